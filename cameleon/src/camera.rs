@@ -58,6 +58,7 @@
 //! ```
 
 use auto_impl::auto_impl;
+use futures::future::BoxFuture;
 use tracing::info;
 
 use super::{
@@ -235,6 +236,9 @@ impl<Ctrl, Strm, Ctxt> Camera<Ctrl, Strm, Ctxt> {
     /// // Loads context. This enables you to edit parameters of the camera and start payload streaming.
     /// camera.load_context().unwrap();
     ///
+    /// // Optionally, reads all cacheable registers at once to speed up subsequent node accesses.
+    /// camera.params_ctxt().unwrap().warm_up_cache();
+    ///
     /// // Closes the camera.
     /// camera.close().unwrap();
     /// ```
@@ -521,6 +525,15 @@ pub struct CameraInfo {
     pub model_name: String,
     ///Serial number of the camera.
     pub serial_number: String,
+    /// User defined name of the camera, `None` if the camera doesn't support it.
+    pub user_defined_name: Option<String>,
+    /// Bus number the camera is connected to, `None` if the protocol doesn't have the concept.
+    pub bus_number: Option<u8>,
+    /// Port chain from the root hub to the camera, empty if the protocol doesn't have the
+    /// concept.
+    pub port_numbers: Vec<u8>,
+    /// Link speed between the host and the camera in bits per second, `None` if unknown.
+    pub link_speed_bps: Option<u64>,
 }
 
 /// This trait provides operations on the device's memory.
@@ -543,6 +556,39 @@ pub trait DeviceControl {
     /// Writes data to the device's memory.
     fn write(&mut self, address: u64, data: &[u8]) -> ControlResult<()>;
 
+    /// Reads multiple regions of the device's memory.
+    ///
+    /// Each entry is a pair of an address and a length to read, returned data are in the same
+    /// order as `entries`.
+    ///
+    /// The default implementation calls [`DeviceControl::read`] for each entry. Implementors
+    /// should override this method if the device can read multiple regions in a single
+    /// transaction.
+    fn read_stacked(&mut self, entries: &[(u64, usize)]) -> ControlResult<Vec<Vec<u8>>> {
+        entries
+            .iter()
+            .map(|&(address, len)| {
+                let mut buf = vec![0; len];
+                self.read(address, &mut buf)?;
+                Ok(buf)
+            })
+            .collect()
+    }
+
+    /// Writes data to multiple regions of the device's memory.
+    ///
+    /// Each entry is a pair of an address and data to write.
+    ///
+    /// The default implementation calls [`DeviceControl::write`] for each entry. Implementors
+    /// should override this method if the device can write multiple regions in a single
+    /// transaction.
+    fn write_stacked(&mut self, entries: &[(u64, &[u8])]) -> ControlResult<()> {
+        for &(address, data) in entries {
+            self.write(address, data)?;
+        }
+        Ok(())
+    }
+
     /// Returns `GenICam` xml string.
     fn genapi(&mut self) -> ControlResult<String>;
 
@@ -553,6 +599,62 @@ pub trait DeviceControl {
     fn disable_streaming(&mut self) -> ControlResult<()>;
 }
 
+/// Asynchronous counterpart of [`DeviceControl`].
+///
+/// Waiting for acknowledges and pending timeouts of the device doesn't block the async executor.
+/// Operations which are usually done only once, e.g. opening the device or retrieving `GenApi`
+/// xml, are only provided by [`DeviceControl`].
+///
+/// See [`ParamsCtxt::enter_async`] for asynchronous access to `GenApi` nodes, e.g.
+/// [`IntegerNode::value_async`](crate::genapi::IntegerNode::value_async).
+#[auto_impl(&mut, Box)]
+pub trait AsyncDeviceControl: Send {
+    /// Reads data from the device's memory.
+    ///
+    /// Reads length is same as `buf.len()`.
+    fn read<'a>(&'a mut self, address: u64, buf: &'a mut [u8]) -> BoxFuture<'a, ControlResult<()>>;
+
+    /// Writes data to the device's memory.
+    fn write<'a>(&'a mut self, address: u64, data: &'a [u8]) -> BoxFuture<'a, ControlResult<()>>;
+
+    /// Reads multiple regions of the device's memory.
+    ///
+    /// See [`DeviceControl::read_stacked`] for details.
+    fn read_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, usize)],
+    ) -> BoxFuture<'a, ControlResult<Vec<Vec<u8>>>> {
+        Box::pin(async move {
+            let mut bufs = Vec::with_capacity(entries.len());
+            for &(address, len) in entries {
+                let mut buf = vec![0; len];
+                self.read(address, &mut buf).await?;
+                bufs.push(buf);
+            }
+            Ok(bufs)
+        })
+    }
+
+    /// Writes data to multiple regions of the device's memory.
+    ///
+    /// See [`DeviceControl::write_stacked`] for details.
+    fn write_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, &'a [u8])],
+    ) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(async move {
+            for &(address, data) in entries {
+                self.write(address, data).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// A [`DeviceControl`] whose clones share the same device and can be sent to other threads,
+/// e.g. [`SharedControlHandle`](crate::u3v::SharedControlHandle).
+pub trait SharedDeviceControl: DeviceControl + Clone + Send + 'static {}
+
 /// This trait provides streaming capability.
 #[auto_impl(&mut, Box)]
 pub trait PayloadStream {
@@ -0,0 +1,351 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module provides emulated U3V cameras.
+//!
+//! Emulated cameras are controlled via [`ControlHandle`] and [`StreamHandle`] in the same way as
+//! real U3V cameras, so they are useful to test applications without cameras connected to the
+//! host.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use cameleon::emulator::{self, EmulatorBuilder};
+//!
+//! // Build an emulator and pass it to the device pool.
+//! EmulatorBuilder::new().serial_number("CAM1984").unwrap().build();
+//!
+//! // Enumerate emulated cameras in the device pool.
+//! let mut cameras = emulator::enumerate_cameras().unwrap();
+//! let mut camera = cameras.pop().unwrap();
+//! camera.open().unwrap();
+//!
+//! // Read serial number from ABRM.
+//! let ctrl = &mut camera.ctrl;
+//! let abrm = ctrl.abrm().unwrap();
+//! assert_eq!(abrm.serial_number(ctrl).unwrap(), "CAM1984");
+//!
+//! // Loads `GenApi` context from the emulator.
+//! camera.load_context().unwrap();
+//! let params_ctxt = camera.params_ctxt().unwrap();
+//! assert!(params_ctxt.node("AcquisitionStart").is_some());
+//!
+//! camera.close().unwrap();
+//! ```
+//!
+//! Emulated cameras generate test pattern images while streaming.
+//!
+//! ```rust
+//! use cameleon::{
+//!     emulator::{self, EmulatorBuilder, TestPattern},
+//!     payload::PixelFormat,
+//! };
+//!
+//! EmulatorBuilder::new()
+//!     .image_size(320, 240)
+//!     .unwrap()
+//!     .pixel_format(PixelFormat::Mono16)
+//!     .unwrap()
+//!     .test_pattern(TestPattern::Counter)
+//!     .build();
+//!
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! camera.load_context().unwrap();
+//!
+//! let payload_rx = camera.start_streaming(3).unwrap();
+//! let payload = async_std::task::block_on(payload_rx.recv()).unwrap();
+//! let image_info = payload.image_info().unwrap();
+//! assert_eq!((image_info.width, image_info.height), (320, 240));
+//! assert_eq!(image_info.pixel_format, PixelFormat::Mono16);
+//! assert_eq!(payload.image().unwrap().len(), 320 * 240 * 2);
+//! payload_rx.send_back(payload);
+//!
+//! camera.close().unwrap();
+//! ```
+//!
+//! Emulated cameras provide `SFNC` features, and they have side effects on the device memory as
+//! real cameras do.
+//!
+//! ```rust
+//! use cameleon::emulator::{self, EmulatorBuilder};
+//!
+//! EmulatorBuilder::new().build();
+//!
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! camera.load_context().unwrap();
+//!
+//! let mut params_ctxt = camera.params_ctxt().unwrap();
+//! let width = params_ctxt.node("Width").unwrap().as_integer(&params_ctxt).unwrap();
+//! let payload_size = params_ctxt.node("PayloadSize").unwrap().as_integer(&params_ctxt).unwrap();
+//! assert_eq!(payload_size.value(&mut params_ctxt).unwrap(), 640 * 480);
+//!
+//! // `PayloadSize` is recomputed when `Width` is changed.
+//! width.set_value(&mut params_ctxt, 320).unwrap();
+//! assert_eq!(payload_size.value(&mut params_ctxt).unwrap(), 320 * 480);
+//!
+//! // Save the current settings to `UserSet1`, then load `Default` user set.
+//! let selector = params_ctxt.node("UserSetSelector").unwrap().as_enumeration(&params_ctxt).unwrap();
+//! let save = params_ctxt.node("UserSetSave").unwrap().as_command(&params_ctxt).unwrap();
+//! let load = params_ctxt.node("UserSetLoad").unwrap().as_command(&params_ctxt).unwrap();
+//! selector.set_entry_by_symbolic(&mut params_ctxt, "UserSet1").unwrap();
+//! save.execute(&mut params_ctxt).unwrap();
+//! selector.set_entry_by_symbolic(&mut params_ctxt, "Default").unwrap();
+//! load.execute(&mut params_ctxt).unwrap();
+//! assert_eq!(width.value(&mut params_ctxt).unwrap(), 640);
+//! assert_eq!(payload_size.value(&mut params_ctxt).unwrap(), 640 * 480);
+//!
+//! // Restore the settings saved in `UserSet1`.
+//! selector.set_entry_by_symbolic(&mut params_ctxt, "UserSet1").unwrap();
+//! load.execute(&mut params_ctxt).unwrap();
+//! assert_eq!(width.value(&mut params_ctxt).unwrap(), 320);
+//!
+//! camera.close().unwrap();
+//! ```
+//!
+//! Faults can be injected into emulated cameras to test error handling of applications.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use cameleon::{
+//!     emulator::{self, ControlFault, EmulatorBuilder, GenCpStatus},
+//!     ControlError, DeviceStatus,
+//! };
+//!
+//! let builder = EmulatorBuilder::new();
+//! let faults = builder.fault_injector();
+//! builder.build();
+//!
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! let ctrl = &mut camera.ctrl;
+//! let abrm = ctrl.abrm().unwrap();
+//!
+//! // The device replies with an error status.
+//! faults.inject_control(ControlFault::NakGenCp(GenCpStatus::AccessDenied));
+//! assert!(matches!(
+//!     abrm.serial_number(ctrl),
+//!     Err(ControlError::DeviceStatus(DeviceStatus::AccessDenied))
+//! ));
+//!
+//! // Pending acknowledges are handled by the control handle.
+//! faults.inject_control(ControlFault::Pending(Duration::from_millis(100)));
+//! assert!(abrm.serial_number(ctrl).is_ok());
+//!
+//! // Requests fail while the device is disconnected.
+//! faults.disconnect();
+//! assert!(abrm.serial_number(ctrl).is_err());
+//! faults.reconnect();
+//!
+//! camera.close().unwrap();
+//! ```
+//!
+//! Emulated cameras can also be customized with an arbitrary `GenApi` XML and memory layout to
+//! emulate specific cameras. See [`EmulatorBuilder::on_write`] to emulate device behavior when
+//! registers are written.
+//!
+//! ```rust
+//! use cameleon::emulator::{self, AccessRight, EmulatorBuilder, MemoryFragment};
+//!
+//! let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+//! <RegisterDescription ModelName="MyCamera" VendorName="Vendor" StandardNameSpace="None"
+//! SchemaMajorVersion="1" SchemaMinorVersion="1" SchemaSubMinorVersion="0"
+//! MajorVersion="1" MinorVersion="0" SubMinorVersion="0"
+//! ProductGuid="00000000-0000-0000-0000-000000000000"
+//! VersionGuid="00000000-0000-0000-0000-000000000000"
+//! xmlns="http://www.genicam.org/GenApi/Version_1_1">
+//!     <Category Name="Root" NameSpace="Standard">
+//!         <pFeature>VendorGain</pFeature>
+//!     </Category>
+//!     <IntReg Name="VendorGain">
+//!         <Address>0x100000</Address>
+//!         <Length>4</Length>
+//!         <AccessMode>RO</AccessMode>
+//!         <pPort>Device</pPort>
+//!         <Sign>Unsigned</Sign>
+//!         <Endianess>LittleEndian</Endianess>
+//!     </IntReg>
+//!     <Port Name="Device" NameSpace="Standard">
+//!     </Port>
+//! </RegisterDescription>"#;
+//!
+//! let image = MemoryFragment::new(0x10_0000, 42_u32.to_le_bytes().to_vec(), AccessRight::RO);
+//! EmulatorBuilder::new()
+//!     .memory_fragment(image)
+//!     .unwrap()
+//!     .genapi_xml(xml)
+//!     .build();
+//!
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! camera.load_context().unwrap();
+//!
+//! let mut params_ctxt = camera.params_ctxt().unwrap();
+//! let gain = params_ctxt.node("VendorGain").unwrap().as_integer(&params_ctxt).unwrap();
+//! assert_eq!(gain.value(&mut params_ctxt).unwrap(), 42);
+//!
+//! camera.close().unwrap();
+//! ```
+//!
+//! [`EmulatorHandle`] returned from [`EmulatorBuilder::build`] controls the emulator at runtime.
+//!
+//! ```rust
+//! use cameleon::emulator::{self, EmulatorBuilder};
+//!
+//! let handle = EmulatorBuilder::new().build();
+//!
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! let ctrl = &mut camera.ctrl;
+//! let abrm = ctrl.abrm().unwrap();
+//!
+//! // Observe writes from the host.
+//! handle.clear_command_log();
+//! abrm.set_user_defined_name(ctrl, "My Camera").unwrap();
+//! let writes = handle.host_writes();
+//! assert_eq!(writes.len(), 1);
+//! assert!(writes[0].1.starts_with(b"My Camera"));
+//!
+//! // Change the device memory from the device side.
+//! let (address, _) = writes[0];
+//! handle.write_memory(address as usize, b"Renamed\0").unwrap();
+//! assert_eq!(abrm.user_defined_name(ctrl).unwrap().unwrap(), "Renamed");
+//!
+//! // Unplugged devices can't be accessed nor enumerated.
+//! handle.unplug().unwrap();
+//! assert!(abrm.user_defined_name(ctrl).is_err());
+//! assert!(emulator::enumerate_cameras().unwrap().is_empty());
+//! camera.close().ok();
+//!
+//! handle.replug().unwrap();
+//! let mut camera = emulator::enumerate_cameras().unwrap().pop().unwrap();
+//! camera.open().unwrap();
+//! camera.close().unwrap();
+//! ```
+//!
+//! Emulated cameras respect `DeviceLinkThroughputLimit`, so they are useful to try
+//! [`BandwidthManager`](crate::u3v::BandwidthManager). They aren't connected to a USB bus, so
+//! each of them is regarded as connected to its own bus whose bandwidth is computed from the
+//! link speed.
+//!
+//! ```rust
+//! use cameleon::{
+//!     emulator::{self, EmulatorBuilder},
+//!     u3v::{BandwidthManager, BandwidthRequest},
+//! };
+//!
+//! EmulatorBuilder::new().build();
+//!
+//! let mut cameras = emulator::enumerate_cameras().unwrap();
+//! for camera in &mut cameras {
+//!     camera.open().unwrap();
+//!     camera.load_context().unwrap();
+//! }
+//!
+//! // Stream 640x480 Mono8 images at 100 fps.
+//! let manager = BandwidthManager::new();
+//! let allocations = manager
+//!     .allocate(&mut cameras, &[BandwidthRequest::FrameRate(100.0)])
+//!     .unwrap();
+//! assert_eq!(allocations[0].bus_number, None);
+//! assert_eq!(allocations[0].throughput_limit, 640 * 480 * 100);
+//! assert!(allocations[0].satisfied);
+//!
+//! // A million fps doesn't fit in the bandwidth.
+//! let allocations = manager
+//!     .allocate(&mut cameras, &[BandwidthRequest::FrameRate(1_000_000.0)])
+//!     .unwrap();
+//! assert!(!allocations[0].satisfied);
+//! ```
+
+pub use cameleon_device::{
+    emulator::{
+        AccessRight, BuilderError, BuilderResult, ControlFault, DeviceMemory, EmulatorBuilder,
+        EmulatorHandle, FaultInjector, LoggedCommand, MemoryFragment, StreamFault, TestPattern,
+    },
+    u3v::{
+        protocol::ack::{GenCpStatus, UsbSpecificStatus},
+        DeviceFilter,
+    },
+};
+
+use cameleon_device::emulator;
+
+use super::{
+    u3v::{self, ControlHandle, StreamHandle},
+    CameleonResult, Camera, ControlError,
+};
+
+/// Enumerate all emulated cameras in the device pool.
+///
+/// Emulators must be built by [`EmulatorBuilder`] in advance.
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon::emulator::{self, EmulatorBuilder};
+///
+/// EmulatorBuilder::new().build();
+/// let cameras = emulator::enumerate_cameras().unwrap();
+/// assert_eq!(cameras.len(), 1);
+/// ```
+pub fn enumerate_cameras() -> CameleonResult<Vec<Camera<ControlHandle, StreamHandle>>> {
+    let devices = emulator::enumerate_devices().map_err(ControlError::from)?;
+    u3v::into_cameras(devices)
+}
+
+/// Enumerate emulated cameras in the device pool which match the `filter`.
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon::emulator::{self, DeviceFilter, EmulatorBuilder};
+///
+/// EmulatorBuilder::new().user_defined_name("left").unwrap().build();
+/// EmulatorBuilder::new().user_defined_name("right").unwrap().build();
+///
+/// let filter = DeviceFilter::new().user_defined_name("right");
+/// let cameras = emulator::enumerate_cameras_with_filter(&filter).unwrap();
+/// assert_eq!(cameras.len(), 1);
+///
+/// let info = cameras[0].info();
+/// assert_eq!(info.user_defined_name.as_deref(), Some("right"));
+/// assert_eq!(info.bus_number, None);
+/// assert!(info.link_speed_bps.is_some());
+/// ```
+pub fn enumerate_cameras_with_filter(
+    filter: &DeviceFilter,
+) -> CameleonResult<Vec<Camera<ControlHandle, StreamHandle>>> {
+    let devices = emulator::enumerate_devices_with_filter(filter).map_err(ControlError::from)?;
+    u3v::into_cameras(devices)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Build an emulator with a unique serial number and open it.
+    ///
+    /// Emulators are shared by all tests in the process, so tests must not pick up emulators
+    /// built by other tests.
+    pub(crate) fn open_camera(
+        builder: EmulatorBuilder,
+    ) -> (Camera<ControlHandle, StreamHandle>, EmulatorHandle) {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let serial_number = format!("TEST{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        let handle = builder.serial_number(&serial_number).unwrap().build();
+        let filter = DeviceFilter::new().serial_number(serial_number);
+        let mut camera = enumerate_cameras_with_filter(&filter)
+            .unwrap()
+            .pop()
+            .unwrap();
+        camera.open().unwrap();
+        (camera, handle)
+    }
+}
@@ -0,0 +1,236 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module contains a [`Device`] which lets synchronous `GenApi` node operations await
+//! [`AsyncDeviceControl`].
+//!
+//! `GenApi` nodes access the device synchronously, so a node operation can't await the device in
+//! the middle of it. Instead, the operation is suspended at the first access which has not been
+//! done yet, the access is awaited outside of the operation, then the operation is evaluated
+//! again from the start. Accesses which have already been done are replayed from the log.
+
+use cameleon_genapi::Device;
+
+use super::into_device_address;
+use crate::{AsyncDeviceControl, ControlError, ControlResult};
+
+/// A [`Device`] passed to the closure of
+/// [`ParamsCtxt::enter_async`](super::ParamsCtxt::enter_async).
+///
+/// An access which is not done yet fails with an error, and the access is done before the closure
+/// is called again.
+pub struct AsyncGenApiDevice<'a> {
+    log: &'a mut AccessLog,
+    /// The number of writes which are replayed in the current evaluation.
+    write_count: usize,
+}
+
+impl<'a> AsyncGenApiDevice<'a> {
+    pub(super) fn new(log: &'a mut AccessLog) -> Self {
+        Self {
+            log,
+            write_count: 0,
+        }
+    }
+
+    fn access(&mut self, access: Access) -> ControlResult<&[Vec<u8>]> {
+        if self.log.pending.is_some() {
+            return Err(suspended());
+        }
+
+        // A read is looked up by the number of preceding writes, because the evaluation may skip
+        // reads with the cache, and a read after a write must observe the written data.
+        let write_count = self.write_count;
+        let position = if access.is_write() {
+            self.write_count += 1;
+            let position = self
+                .log
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.access.is_write())
+                .nth(write_count)
+                .map(|(i, _)| i);
+            if let Some(i) = position {
+                if self.log.entries[i].access != access {
+                    return Err(ControlError::InvalidData(
+                        "a node operation writes different data in re-evaluation".into(),
+                    ));
+                }
+            }
+            position
+        } else {
+            self.log
+                .entries
+                .iter()
+                .position(|entry| entry.write_count == write_count && entry.access == access)
+        };
+
+        match position {
+            Some(i) => match &mut self.log.entries[i].result {
+                Ok(data) => Ok(data.as_slice()),
+                Err(err) => Err(err.replay()),
+            },
+            None => {
+                self.log.pending = Some(Entry {
+                    write_count,
+                    access,
+                    result: Ok(vec![]),
+                });
+                Err(suspended())
+            }
+        }
+    }
+}
+
+impl<'a> Device for AsyncGenApiDevice<'a> {
+    fn read_mem(
+        &mut self,
+        address: i64,
+        buf: &mut [u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let data = self.access(Access::Read {
+            address,
+            len: buf.len(),
+        })?;
+        buf.copy_from_slice(&data[0]);
+        Ok(())
+    }
+
+    fn write_mem(
+        &mut self,
+        address: i64,
+        data: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.access(Access::Write {
+            address,
+            data: data.to_vec(),
+        })?;
+        Ok(())
+    }
+
+    fn read_mem_stacked(
+        &mut self,
+        entries: &mut [(i64, &mut [u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let regions = entries.iter().map(|(address, buf)| (*address, buf.len()));
+        let data = self.access(Access::ReadStacked(regions.collect()))?;
+        for ((_, buf), data) in entries.iter_mut().zip(data) {
+            buf.copy_from_slice(data);
+        }
+        Ok(())
+    }
+
+    fn write_mem_stacked(
+        &mut self,
+        entries: &[(i64, &[u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let entries = entries
+            .iter()
+            .map(|&(address, data)| (address, data.to_vec()));
+        self.access(Access::WriteStacked(entries.collect()))?;
+        Ok(())
+    }
+}
+
+/// Accesses done by a node operation.
+#[derive(Default)]
+pub(super) struct AccessLog {
+    entries: Vec<Entry>,
+    /// The first access which is not done yet in the last evaluation.
+    pending: Option<Entry>,
+}
+
+impl AccessLog {
+    /// Does the pending access and returns `true`, or returns `false` if there is no pending
+    /// access, i.e. the last evaluation is complete.
+    pub(super) async fn resume(&mut self, ctrl: &mut impl AsyncDeviceControl) -> bool {
+        match self.pending.take() {
+            Some(mut entry) => {
+                entry.result = entry.access.run(ctrl).await.map_err(LoggedError::new);
+                self.entries.push(entry);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+struct Entry {
+    /// The number of writes which precede the access.
+    write_count: usize,
+    access: Access,
+    result: Result<Vec<Vec<u8>>, LoggedError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Access {
+    Read { address: i64, len: usize },
+    Write { address: i64, data: Vec<u8> },
+    ReadStacked(Vec<(i64, usize)>),
+    WriteStacked(Vec<(i64, Vec<u8>)>),
+}
+
+impl Access {
+    fn is_write(&self) -> bool {
+        matches!(self, Self::Write { .. } | Self::WriteStacked(..))
+    }
+
+    async fn run(&self, ctrl: &mut impl AsyncDeviceControl) -> ControlResult<Vec<Vec<u8>>> {
+        match self {
+            Self::Read { address, len } => {
+                let mut buf = vec![0; *len];
+                ctrl.read(into_device_address(*address)?, &mut buf).await?;
+                Ok(vec![buf])
+            }
+            Self::Write { address, data } => {
+                ctrl.write(into_device_address(*address)?, data).await?;
+                Ok(vec![])
+            }
+            Self::ReadStacked(entries) => {
+                let regions = entries
+                    .iter()
+                    .map(|&(address, len)| Ok((into_device_address(address)?, len)))
+                    .collect::<ControlResult<Vec<_>>>()?;
+                ctrl.read_stacked(&regions).await
+            }
+            Self::WriteStacked(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(address, data)| Ok((into_device_address(*address)?, data.as_slice())))
+                    .collect::<ControlResult<Vec<_>>>()?;
+                ctrl.write_stacked(&entries).await?;
+                Ok(vec![])
+            }
+        }
+    }
+}
+
+/// An error of an access, which may be replayed more than once.
+struct LoggedError {
+    /// The original error, which is returned when the error is replayed for the first time.
+    err: Option<ControlError>,
+    message: String,
+}
+
+impl LoggedError {
+    fn new(err: ControlError) -> Self {
+        Self {
+            message: err.to_string(),
+            err: Some(err),
+        }
+    }
+
+    fn replay(&mut self) -> ControlError {
+        self.err
+            .take()
+            .unwrap_or_else(|| ControlError::Io(anyhow::Error::msg(self.message.clone())))
+    }
+}
+
+fn suspended() -> ControlError {
+    ControlError::Io(anyhow::Error::msg(
+        "the node operation is suspended to await the device",
+    ))
+}
@@ -0,0 +1,617 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module contains functions to access features by name without knowing their interfaces.
+
+use std::{fmt, net::Ipv4Addr};
+
+use cameleon_genapi::{elem_type::IntegerRepresentation, GenApiError, GenApiResult};
+
+use super::{
+    BooleanNode, DeviceControl, EnumerationNode, FloatNode, GenApiCtxt, IntegerNode, Node,
+    ParamsCtxt, StringNode,
+};
+
+/// A value of a feature returned by [`ParamsCtxt::get_value`] and passed to
+/// [`ParamsCtxt::set_value`].
+///
+/// When the value doesn't match the interface of the feature, it's converted on
+/// [`ParamsCtxt::set_value`]. Especially, [`FeatureValue::String`] is parsed according to the
+/// interface, so values read from config files or command lines can be written as is.
+///
+/// | Interface     | Accepted string                                                         |
+/// |---------------|-------------------------------------------------------------------------|
+/// | `IInteger`    | Decimal or `0x` prefixed hexadecimal number, see below                  |
+/// | `IFloat`      | Floating point number                                                   |
+/// | `IEnumeration`| Symbolic name of an entry                                               |
+/// | `IBoolean`    | `true`, `false`, `1`, `0`, `on`, `off`, `yes` or `no`, case insensitive |
+/// | `IString`     | Any string                                                              |
+/// | `ICommand`    | Same as `IBoolean`, or `Execute`. The command is executed if `true`     |
+///
+/// `IInteger` additionally accepts a string in the format of its [`IntegerRepresentation`],
+/// i.e. `192.168.0.1` for `IpV4Address`, `00:11:22:AA:BB:CC` for `MacAddress`, the same strings
+/// as `IBoolean` for `Boolean`, and a hexadecimal number without prefix for `HexNumber`.
+///
+/// # Examples
+/// ```
+/// use cameleon::genapi::FeatureValue;
+///
+/// assert_eq!(FeatureValue::from(10), FeatureValue::Integer(10));
+/// assert_eq!(FeatureValue::from("Mono8"), FeatureValue::String("Mono8".into()));
+/// assert_eq!(FeatureValue::Float(0.5).to_string(), "0.5");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureValue {
+    /// A value of `IInteger`.
+    Integer(i64),
+    /// A value of `IFloat`.
+    Float(f64),
+    /// Symbolic name of the current entry of `IEnumeration`.
+    Enumeration(String),
+    /// A value of `IBoolean`.
+    Boolean(bool),
+    /// A value of `IString`.
+    String(String),
+}
+
+impl fmt::Display for FeatureValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::Enumeration(value) | Self::String(value) => f.write_str(value),
+        }
+    }
+}
+
+impl From<i64> for FeatureValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for FeatureValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for FeatureValue {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<String> for FeatureValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for FeatureValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    /// Returns the value of the feature with the given name.
+    ///
+    /// Returns an error if the feature doesn't have any of `IInteger`, `IFloat`,
+    /// `IEnumeration`, `IBoolean` or `IString` interface.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use cameleon::u3v;
+    /// # let mut cameras = u3v::enumerate_cameras().unwrap();
+    /// # let mut camera = cameras.pop().unwrap();
+    /// # camera.open().unwrap();
+    /// # camera.load_context().unwrap();
+    /// let mut params_ctxt = camera.params_ctxt().unwrap();
+    /// for name in ["Width", "Gain", "PixelFormat", "DeviceUserID"] {
+    ///     println!("{}: {}", name, params_ctxt.get_value(name).unwrap());
+    /// }
+    /// ```
+    pub fn get_value(&mut self, name: &str) -> GenApiResult<FeatureValue> {
+        self.feature(name)?.read(self)
+    }
+
+    /// Returns the value of the feature with the given name as a string.
+    ///
+    /// Unlike `get_value(name)?.to_string()`, an integer value is formatted according to its
+    /// [`IntegerRepresentation`], e.g. `192.168.0.1` for `IpV4Address`. The returned string can
+    /// be passed to [`Self::set_value`] as is.
+    pub fn get_value_string(&mut self, name: &str) -> GenApiResult<String> {
+        self.feature(name)?.read_string(self)
+    }
+
+    /// Sets the value to the feature with the given name.
+    ///
+    /// The value is converted according to the interface of the feature, see [`FeatureValue`]
+    /// for details. If the feature has `ICommand` interface, the command is executed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use cameleon::u3v;
+    /// # let mut cameras = u3v::enumerate_cameras().unwrap();
+    /// # let mut camera = cameras.pop().unwrap();
+    /// # camera.open().unwrap();
+    /// # camera.load_context().unwrap();
+    /// let mut params_ctxt = camera.params_ctxt().unwrap();
+    /// params_ctxt.set_value("Gain", 0.1).unwrap();
+    /// params_ctxt.set_value("PixelFormat", "Mono8").unwrap();
+    ///
+    /// // Strings are parsed according to the interface of the feature.
+    /// params_ctxt.set_value("Width", "640").unwrap();
+    /// params_ctxt.set_value("ReverseX", "on").unwrap();
+    /// ```
+    pub fn set_value(&mut self, name: &str, value: impl Into<FeatureValue>) -> GenApiResult<()> {
+        let value = value.into();
+        let node = self.node_by_name(name)?;
+        if let Some(node) = node.as_command(self) {
+            return if parse_command(&value)? {
+                node.execute(self)
+            } else {
+                Ok(())
+            };
+        }
+
+        Feature::new(node, self)
+            .ok_or_else(|| not_feature(name))?
+            .write(self, value)
+    }
+
+    /// Executes the command with the given name.
+    pub fn execute(&mut self, name: &str) -> GenApiResult<()> {
+        self.node_by_name(name)?
+            .as_command(self)
+            .ok_or_else(|| GenApiError::InvalidNode(format!("`{}` is not a command", name).into()))?
+            .execute(self)
+    }
+
+    fn feature(&self, name: &str) -> GenApiResult<Feature> {
+        let node = self.node_by_name(name)?;
+        Feature::new(node, self).ok_or_else(|| not_feature(name))
+    }
+
+    fn node_by_name(&self, name: &str) -> GenApiResult<Node> {
+        self.node(name)
+            .ok_or_else(|| GenApiError::InvalidNode(format!("no node named `{}`", name).into()))
+    }
+}
+
+/// Typed view of a node whose value can be represented as [`FeatureValue`] or a string.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Feature {
+    Enumeration(EnumerationNode),
+    Boolean(BooleanNode),
+    Integer(IntegerNode),
+    Float(FloatNode),
+    String(StringNode),
+}
+
+impl Feature {
+    pub(super) fn new<Ctrl, Ctxt>(node: Node, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> Option<Self>
+    where
+        Ctxt: GenApiCtxt,
+    {
+        // `Enumeration` must be checked first because it also has `IInteger` interface.
+        if let Some(node) = node.as_enumeration(ctxt) {
+            Some(Self::Enumeration(node))
+        } else if let Some(node) = node.as_boolean(ctxt) {
+            Some(Self::Boolean(node))
+        } else if let Some(node) = node.as_integer(ctxt) {
+            Some(Self::Integer(node))
+        } else if let Some(node) = node.as_float(ctxt) {
+            Some(Self::Float(node))
+        } else {
+            node.as_string(ctxt).map(Self::String)
+        }
+    }
+
+    fn read<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<FeatureValue>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        Ok(match self {
+            Self::Enumeration(node) => {
+                FeatureValue::Enumeration(node.current_entry(ctxt)?.symbolic(ctxt).to_string())
+            }
+            Self::Boolean(node) => FeatureValue::Boolean(node.value(ctxt)?),
+            Self::Integer(node) => FeatureValue::Integer(node.value(ctxt)?),
+            Self::Float(node) => FeatureValue::Float(node.value(ctxt)?),
+            Self::String(node) => FeatureValue::String(node.value(ctxt)?),
+        })
+    }
+
+    /// Returns the value of the feature as a string. An integer value is formatted according to
+    /// its [`IntegerRepresentation`].
+    pub(super) fn read_string<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+    ) -> GenApiResult<String>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        Ok(match self {
+            Self::Enumeration(node) => node.current_entry(ctxt)?.symbolic(ctxt).to_string(),
+            Self::Boolean(node) => node.value(ctxt)?.to_string(),
+            Self::Integer(node) => format_integer(node.value(ctxt)?, node.representation(ctxt)),
+            Self::Float(node) => node.value(ctxt)?.to_string(),
+            Self::String(node) => node.value(ctxt)?,
+        })
+    }
+
+    /// Parses the string according to the interface of the feature, then writes it.
+    pub(super) fn write_str<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+        value: &str,
+    ) -> GenApiResult<()>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        match self {
+            Self::Enumeration(node) => node.set_entry_by_symbolic(ctxt, value.trim()),
+            Self::Boolean(node) => {
+                let value = parse_bool(value.trim()).ok_or_else(|| invalid(value, "boolean"))?;
+                node.set_value(ctxt, value)
+            }
+            Self::Integer(node) => {
+                let value = parse_integer(value, node.representation(ctxt))?;
+                node.set_value(ctxt, value)
+            }
+            Self::Float(node) => {
+                let value = value.trim().parse().map_err(|_| invalid(value, "float"))?;
+                node.set_value(ctxt, value)
+            }
+            Self::String(node) => node.set_value(ctxt, value.to_string()),
+        }
+    }
+
+    /// Writes the value after converting it according to the interface of the feature.
+    fn write<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+        value: FeatureValue,
+    ) -> GenApiResult<()>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        match (self, value) {
+            (feature, FeatureValue::String(value)) => feature.write_str(ctxt, &value),
+
+            (Self::Enumeration(node), FeatureValue::Enumeration(value)) => {
+                node.set_entry_by_symbolic(ctxt, &value)
+            }
+            (Self::Enumeration(node), FeatureValue::Integer(value)) => {
+                node.set_entry_by_value(ctxt, value)
+            }
+
+            (Self::Boolean(node), FeatureValue::Boolean(value)) => node.set_value(ctxt, value),
+            (Self::Boolean(node), FeatureValue::Integer(value)) if value == 0 || value == 1 => {
+                node.set_value(ctxt, value == 1)
+            }
+
+            (Self::Integer(node), FeatureValue::Integer(value)) => node.set_value(ctxt, value),
+            (Self::Integer(node), FeatureValue::Boolean(value)) => {
+                node.set_value(ctxt, value.into())
+            }
+            (Self::Integer(node), FeatureValue::Float(value)) => {
+                let value = float_to_integer(value).ok_or_else(|| {
+                    GenApiError::InvalidData(format!("`{}` is not an integer", value).into())
+                })?;
+                node.set_value(ctxt, value)
+            }
+
+            (Self::Float(node), FeatureValue::Float(value)) => node.set_value(ctxt, value),
+            #[allow(clippy::cast_precision_loss)]
+            (Self::Float(node), FeatureValue::Integer(value)) => node.set_value(ctxt, value as f64),
+
+            (Self::String(node), value) => node.set_value(ctxt, value.to_string()),
+
+            (feature, value) => Err(GenApiError::InvalidData(
+                format!(
+                    "`{:?}` can't be written to `{}`",
+                    value,
+                    feature.as_node().name(ctxt)
+                )
+                .into(),
+            )),
+        }
+    }
+
+    fn as_node(self) -> Node {
+        match self {
+            Self::Enumeration(node) => node.as_node(),
+            Self::Boolean(node) => node.as_node(),
+            Self::Integer(node) => node.as_node(),
+            Self::Float(node) => node.as_node(),
+            Self::String(node) => node.as_node(),
+        }
+    }
+}
+
+/// Returns `true` if the command should be executed.
+fn parse_command(value: &FeatureValue) -> GenApiResult<bool> {
+    match value {
+        FeatureValue::Boolean(value) => Ok(*value),
+        FeatureValue::Integer(value) if *value == 0 || *value == 1 => Ok(*value == 1),
+        FeatureValue::String(value) if value.trim().eq_ignore_ascii_case("execute") => Ok(true),
+        FeatureValue::String(value) => {
+            parse_bool(value.trim()).ok_or_else(|| invalid(value, "command value"))
+        }
+        _ => Err(invalid(&value.to_string(), "command value")),
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    const TRUE: [&str; 4] = ["true", "1", "on", "yes"];
+    const FALSE: [&str; 4] = ["false", "0", "off", "no"];
+
+    if TRUE.iter().any(|s| value.eq_ignore_ascii_case(s)) {
+        Some(true)
+    } else if FALSE.iter().any(|s| value.eq_ignore_ascii_case(s)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_integer(value: &str, repr: IntegerRepresentation) -> GenApiResult<i64> {
+    let s = value.trim();
+    let parsed = match repr {
+        IntegerRepresentation::Boolean => parse_bool(s).map(i64::from),
+        IntegerRepresentation::HexNumber => parse_hex(strip_hex_prefix(s).unwrap_or(s)),
+        IntegerRepresentation::IpV4Address => s
+            .parse::<Ipv4Addr>()
+            .ok()
+            .map(|addr| u32::from(addr).into()),
+        IntegerRepresentation::MacAddress => parse_mac(s),
+        IntegerRepresentation::Linear
+        | IntegerRepresentation::Logarithmic
+        | IntegerRepresentation::PureNumber => None,
+    };
+
+    parsed
+        .or_else(|| match strip_hex_prefix(s) {
+            Some(hex) => parse_hex(hex),
+            None => s.parse().ok(),
+        })
+        .ok_or_else(|| invalid(value, "integer"))
+}
+
+pub(super) fn format_integer(value: i64, repr: IntegerRepresentation) -> String {
+    match repr {
+        IntegerRepresentation::Boolean if value == 0 || value == 1 => (value == 1).to_string(),
+        IntegerRepresentation::HexNumber => format!("0x{:X}", value),
+        IntegerRepresentation::IpV4Address if (0..=0xffff_ffff).contains(&value) => {
+            Ipv4Addr::from(value as u32).to_string()
+        }
+        IntegerRepresentation::MacAddress if (0..=0xffff_ffff_ffff).contains(&value) => value
+            .to_be_bytes()[2..]
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+        _ => value.to_string(),
+    }
+}
+
+/// Returns `None` if the value is not integral or out of the range of `i64`.
+#[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+fn float_to_integer(value: f64) -> Option<i64> {
+    // `i64::MAX as f64` is rounded up to 2^63, which is out of the range.
+    if value.trunc() == value && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+        Some(value as i64)
+    } else {
+        None
+    }
+}
+
+fn strip_hex_prefix(s: &str) -> Option<&str> {
+    s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))
+}
+
+fn parse_hex(s: &str) -> Option<i64> {
+    u64::from_str_radix(s, 16).ok().map(|value| value as i64)
+}
+
+fn parse_mac(s: &str) -> Option<i64> {
+    let octets: Vec<_> = s.split([':', '-']).collect();
+    if octets.len() != 6 {
+        return None;
+    }
+    octets.into_iter().try_fold(0, |acc, octet| {
+        if octet.len() == 2 {
+            u8::from_str_radix(octet, 16)
+                .ok()
+                .map(|b| acc << 8 | i64::from(b))
+        } else {
+            None
+        }
+    })
+}
+
+fn invalid(value: &str, ty: &str) -> GenApiError {
+    GenApiError::InvalidData(format!("`{}` is not a valid {}", value, ty).into())
+}
+
+fn not_feature(name: &str) -> GenApiError {
+    GenApiError::InvalidNode(format!("`{}` doesn't have a value", name).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_number() {
+        let repr = IntegerRepresentation::HexNumber;
+        for s in ["ff", "FF", "0xff", "0XFF", " 0xFf "] {
+            assert_eq!(parse_integer(s, repr).unwrap(), 0xff, "{}", s);
+        }
+        assert_eq!(format_integer(0xff, repr), "0xFF");
+        assert!(parse_integer("0xfg", repr).is_err());
+
+        // Other representations also accept prefixed hexadecimal numbers.
+        let repr = IntegerRepresentation::PureNumber;
+        assert_eq!(parse_integer("0x10", repr).unwrap(), 16);
+        assert_eq!(parse_integer("10", repr).unwrap(), 10);
+        assert!(parse_integer("ff", repr).is_err());
+    }
+
+    #[test]
+    fn test_negative_hex_number() {
+        let repr = IntegerRepresentation::HexNumber;
+        assert_eq!(format_integer(-1, repr), "0xFFFFFFFFFFFFFFFF");
+        for value in [-1, i64::MIN, i64::MAX] {
+            let s = format_integer(value, repr);
+            assert_eq!(parse_integer(&s, repr).unwrap(), value, "{}", s);
+        }
+        assert!(parse_integer("0x1FFFFFFFFFFFFFFFF", repr).is_err());
+    }
+
+    #[test]
+    fn test_ipv4_address() {
+        let repr = IntegerRepresentation::IpV4Address;
+        assert_eq!(format_integer(0xC0A8_0001, repr), "192.168.0.1");
+        assert_eq!(parse_integer("192.168.0.1", repr).unwrap(), 0xC0A8_0001);
+        for value in [0, 0xC0A8_0001, 0xFFFF_FFFF] {
+            let s = format_integer(value, repr);
+            assert_eq!(parse_integer(&s, repr).unwrap(), value, "{}", s);
+        }
+
+        for s in ["192.168.0", "192.168.0.1.2", "192.168.0.256"] {
+            assert!(parse_integer(s, repr).is_err(), "{}", s);
+        }
+        // Values out of the range of IPv4 addresses are formatted as decimal numbers.
+        assert_eq!(format_integer(-1, repr), "-1");
+    }
+
+    #[test]
+    fn test_mac_address() {
+        let repr = IntegerRepresentation::MacAddress;
+        let value = 0x0011_22AA_BBCC;
+        assert_eq!(format_integer(value, repr), "00:11:22:AA:BB:CC");
+        for s in [
+            "00:11:22:AA:BB:CC",
+            "00-11-22-aa-bb-cc",
+            "00:11:22-AA-BB:CC",
+        ] {
+            assert_eq!(parse_integer(s, repr).unwrap(), value, "{}", s);
+        }
+        assert_eq!(
+            parse_integer(&format_integer(0xFFFF_FFFF_FFFF, repr), repr).unwrap(),
+            0xFFFF_FFFF_FFFF
+        );
+
+        for s in [
+            "00:11:22:AA:BB",
+            "00:11:22:AA:BB:CC:DD",
+            "0:11:22:AA:BB:CC",
+            "00:11:22:AA:BB:GG",
+        ] {
+            assert!(parse_integer(s, repr).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_boolean_words() {
+        for s in ["true", "TRUE", "1", "on", "On", "yes", "YES"] {
+            assert_eq!(parse_bool(s), Some(true), "{}", s);
+        }
+        for s in ["false", "False", "0", "off", "OFF", "no", "No"] {
+            assert_eq!(parse_bool(s), Some(false), "{}", s);
+        }
+        for s in ["", "2", "enable", "y"] {
+            assert_eq!(parse_bool(s), None, "{}", s);
+        }
+
+        let repr = IntegerRepresentation::Boolean;
+        assert_eq!(parse_integer("on", repr).unwrap(), 1);
+        assert_eq!(parse_integer("No", repr).unwrap(), 0);
+        assert_eq!(format_integer(1, repr), "true");
+        assert_eq!(format_integer(2, repr), "2");
+
+        assert!(parse_command(&"Execute".into()).unwrap());
+        assert!(!parse_command(&"off".into()).unwrap());
+        assert!(parse_command(&FeatureValue::Integer(1)).unwrap());
+        assert!(parse_command(&FeatureValue::Integer(2)).is_err());
+        assert!(parse_command(&FeatureValue::Float(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_float_to_integer() {
+        assert_eq!(float_to_integer(10.0), Some(10));
+        assert_eq!(float_to_integer(-10.0), Some(-10));
+        assert_eq!(float_to_integer(i64::MIN as f64), Some(i64::MIN));
+        for value in [0.5, -1.5, f64::NAN, f64::INFINITY, i64::MAX as f64] {
+            assert_eq!(float_to_integer(value), None, "{}", value);
+        }
+    }
+
+    #[cfg(feature = "emulator")]
+    #[test]
+    fn test_dispatch() {
+        use crate::emulator::{tests::open_camera, EmulatorBuilder};
+
+        let (mut camera, _handle) = open_camera(EmulatorBuilder::new());
+        camera.load_context().unwrap();
+        let mut ctxt = camera.params_ctxt().unwrap();
+
+        // `IInteger`.
+        ctxt.set_value("Width", "320").unwrap();
+        assert_eq!(ctxt.get_value("Width").unwrap(), FeatureValue::Integer(320));
+        ctxt.set_value("Width", 256.0).unwrap();
+        assert_eq!(ctxt.get_value("Width").unwrap(), FeatureValue::Integer(256));
+        assert!(ctxt.set_value("Width", 320.5).is_err());
+        assert_eq!(ctxt.get_value_string("Width").unwrap(), "256");
+
+        // `IFloat`.
+        ctxt.set_value("Gain", 2_i64).unwrap();
+        assert_eq!(ctxt.get_value("Gain").unwrap(), FeatureValue::Float(2.0));
+        ctxt.set_value("Gain", " 1.5 ").unwrap();
+        assert_eq!(ctxt.get_value("Gain").unwrap(), FeatureValue::Float(1.5));
+
+        // `IEnumeration`.
+        let entry = |name: &str| FeatureValue::Enumeration(name.into());
+        ctxt.set_value("TestPattern", "Checkerboard").unwrap();
+        assert_eq!(
+            ctxt.get_value("TestPattern").unwrap(),
+            entry("Checkerboard")
+        );
+        ctxt.set_value("TestPattern", 2_i64).unwrap();
+        assert_eq!(
+            ctxt.get_value("TestPattern").unwrap(),
+            entry("FrameCounter")
+        );
+        assert!(ctxt.set_value("TestPattern", "Unknown").is_err());
+
+        // `IString`.
+        ctxt.set_value("DeviceUserID", "camera").unwrap();
+        assert_eq!(
+            ctxt.get_value("DeviceUserID").unwrap(),
+            FeatureValue::String("camera".into())
+        );
+
+        // `ICommand`.
+        ctxt.set_value("TimestampLatch", "Execute").unwrap();
+        ctxt.execute("TimestampLatch").unwrap();
+        assert!(ctxt.get_value("TimestampLatch").is_err());
+        assert!(ctxt.execute("Width").is_err());
+
+        assert!(ctxt.get_value("Missing").is_err());
+        assert!(ctxt.set_value("Missing", 0_i64).is_err());
+    }
+}
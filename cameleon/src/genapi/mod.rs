@@ -35,12 +35,20 @@
 //! }
 //! ```
 
+mod async_device;
+mod feature;
 mod node_kind;
+mod persistence;
+mod poller;
 
+pub use async_device::AsyncGenApiDevice;
+pub use feature::FeatureValue;
 pub use node_kind::{
     BooleanNode, CategoryNode, CommandNode, EnumEntryNode, EnumerationNode, FloatNode, IntegerNode,
     Node, PortNode, RegisterNode, StringNode,
 };
+pub use persistence::{FeatureError, SavedFeatures};
+pub use poller::{PollEvent, PolledValue, Poller};
 
 use std::{
     convert::TryInto,
@@ -50,9 +58,12 @@ use std::{
 use auto_impl::auto_impl;
 use cameleon_genapi::{builder::GenApiBuilder, store};
 
-use super::{ControlError, ControlResult, DeviceControl};
+use super::{AsyncDeviceControl, ControlError, ControlResult, DeviceControl, SharedDeviceControl};
+
+use async_device::AccessLog;
 
 pub use cameleon_genapi::{
+    dependency::{DependencyGraph, DependencyKind},
     elem_type::{AccessMode, NameSpace, Visibility},
     store::{
         CacheSink, CacheStore, DefaultCacheStore, DefaultNodeStore, DefaultValueStore, NodeId,
@@ -115,6 +126,38 @@ where
     pub fn node_store(&self) -> &Ctxt::NS {
         self.ctxt.node_store()
     }
+
+    /// Returns a receiver of nodes whose value, access mode or range may have changed.
+    ///
+    /// Nodes are sent when they are written through the context, or when nodes they depend on
+    /// are written, e.g. `WidthMax` is sent after `BinningHorizontal` is written. Changes found
+    /// by [`Poller`] are also sent.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use cameleon::u3v;
+    /// # let mut cameras = u3v::enumerate_cameras().unwrap();
+    /// # let mut camera = cameras.pop().unwrap();
+    /// # camera.open().unwrap();
+    /// # camera.load_context().unwrap();
+    /// let mut params_ctxt = camera.params_ctxt().unwrap();
+    /// let changes = params_ctxt.subscribe();
+    ///
+    /// let binning = params_ctxt
+    ///     .node("BinningHorizontal")
+    ///     .unwrap()
+    ///     .as_integer(&params_ctxt)
+    ///     .unwrap();
+    /// binning.set_value(&mut params_ctxt, 2).unwrap();
+    ///
+    /// for nid in changes.try_iter() {
+    ///     let node = cameleon::genapi::Node::from(nid);
+    ///     println!("{} may have changed", node.name(&params_ctxt));
+    /// }
+    /// ```
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<NodeId> {
+        self.ctxt.enter(|_, value_ctxt| value_ctxt.subscribe())
+    }
 }
 
 impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt>
@@ -123,11 +166,16 @@ where
     Ctxt: GenApiCtxt,
 {
     /// Enters the context.
+    ///
+    /// Changes made in `f` are notified to subscribers registered by [`Self::subscribe`] when
+    /// `f` returns.
     pub fn enter<F, R>(&mut self, f: F) -> R
     where
         F: FnOnce(&mut Ctrl, &mut Ctxt) -> R,
     {
-        f(&mut self.ctrl, &mut self.ctxt)
+        let result = f(&mut self.ctrl, &mut self.ctxt);
+        self.ctxt.notify_changes();
+        result
     }
 
     /// Enters the context and then enters `GenApiCtxt`.
@@ -139,6 +187,85 @@ where
             ctxt.enter(|node_store, value_ctxt| f(ctrl, node_store, value_ctxt))
         })
     }
+
+    /// Reads all cacheable registers of the device with as few transactions as possible, then
+    /// populates the cache of the context with them.
+    ///
+    /// This is useful to reduce the number of transactions when many nodes are read in a row,
+    /// e.g. right after [`Camera::load_context`](crate::Camera::load_context).
+    /// Registers that fail to be read are skipped, they are read on demand as usual.
+    ///
+    /// NOTE: This method does nothing meaningful if the context doesn't cache values, e.g.
+    /// [`NoCacheGenApiCtxt`].
+    pub fn warm_up_cache(&mut self) {
+        self.enter2(|ctrl, ns, vc| {
+            let mut device = GenApiDevice::new(ctrl);
+            vc.warm_up_cache(&mut device, ns);
+        })
+    }
+
+    /// Reads cacheable registers which the features of `category` depend on with as few
+    /// transactions as possible, then populates the cache of the context with them.
+    ///
+    /// This reads a whole feature page in a few transactions, see [`Self::warm_up_cache`] for
+    /// details.
+    pub fn warm_up_category(&mut self, category: CategoryNode) {
+        self.enter2(|ctrl, ns, vc| {
+            let mut device = GenApiDevice::new(ctrl);
+            vc.warm_up_category(&mut device, ns, category.as_node().0);
+        })
+    }
+}
+
+impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt>
+where
+    Ctrl: AsyncDeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    /// Asynchronous version of [`Self::enter2`], `f` accesses the device through
+    /// [`AsyncGenApiDevice`].
+    ///
+    /// Accesses to the device are awaited without blocking the async executor. To do so, `f` is
+    /// called again whenever it needs an access which is not done yet, and accesses which have
+    /// already been done are replayed. So `f` should only operate `GenApi` nodes, and may be
+    /// called several times.
+    ///
+    /// Changes made in `f` are notified to subscribers registered by [`Self::subscribe`] when
+    /// `f` returns.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use cameleon::u3v;
+    /// use cameleon_genapi::{prelude::*, NodeStore};
+    ///
+    /// # let mut cameras = u3v::enumerate_cameras().unwrap();
+    /// # let mut camera = cameras.pop().unwrap();
+    /// # camera.open().unwrap();
+    /// # camera.load_context().unwrap();
+    /// let mut params_ctxt = camera.params_ctxt().unwrap();
+    /// let gain = params_ctxt.node_store().id_by_name("Gain").unwrap();
+    ///
+    /// let value = async_std::task::block_on(params_ctxt.enter_async(|device, ns, vc| {
+    ///     gain.expect_ifloat_kind(ns).unwrap().value(device, ns, vc)
+    /// }));
+    /// println!("{}", value.unwrap());
+    /// ```
+    pub async fn enter_async<F, R>(&mut self, mut f: F) -> R
+    where
+        F: FnMut(&mut AsyncGenApiDevice, &Ctxt::NS, &mut ValueCtxt<Ctxt::VS, Ctxt::CS>) -> R,
+    {
+        let mut log = AccessLog::default();
+        loop {
+            let result = self.ctxt.enter(|ns, vc| {
+                let mut device = AsyncGenApiDevice::new(&mut log);
+                f(&mut device, ns, vc)
+            });
+            if !log.resume(&mut self.ctrl).await {
+                self.ctxt.notify_changes();
+                return result;
+            }
+        }
+    }
 }
 
 impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt> {
@@ -191,8 +318,21 @@ pub trait GenApiCtxt {
     fn clear_cache(&mut self) {
         self.enter(|_, value_ctxt| value_ctxt.clear_cache())
     }
+
+    /// Sends changes recorded in the context to subscribers.
+    fn notify_changes(&mut self) {
+        self.enter(|node_store, value_ctxt| value_ctxt.notify_changes(node_store))
+    }
 }
 
+/// A [`GenApiCtxt`] whose clones share the node store and the value context, e.g.
+/// [`SharedDefaultGenApiCtxt`].
+pub trait SharedGenApiCtxt: GenApiCtxt + Clone + Send + 'static {}
+
+impl SharedGenApiCtxt for SharedDefaultGenApiCtxt {}
+
+impl SharedGenApiCtxt for SharedNoCacheGenApiCtxt {}
+
 /// A trait that provides directly conversion from `GenApi` string to a `GenApi` context.
 pub trait FromXml {
     /// Parse `GenApi` context and build `
@@ -408,6 +548,37 @@ impl From<DefaultGenApiCtxt> for SharedNoCacheGenApiCtxt {
     }
 }
 
+/// Returns [`ControlError`] that caused `err` if the `GenApi` operation failed due to device
+/// control, e.g. the device returned [`DeviceStatus::WriteProtect`](crate::DeviceStatus::WriteProtect).
+///
+/// # Examples
+/// ```no_run
+/// # use cameleon::u3v;
+/// use cameleon::{genapi, ControlError, DeviceStatus};
+///
+/// # let mut cameras = u3v::enumerate_cameras().unwrap();
+/// # let mut camera = cameras.pop().unwrap();
+/// # camera.open().unwrap();
+/// # camera.load_context().unwrap();
+/// let mut params_ctxt = camera.params_ctxt().unwrap();
+/// let gain = params_ctxt.node("Gain").unwrap().as_float(&params_ctxt).unwrap();
+/// if let Err(err) = gain.set_value(&mut params_ctxt, 0.1) {
+///     match genapi::control_error(&err) {
+///         Some(ControlError::DeviceStatus(DeviceStatus::WriteProtect)) => {
+///             println!("`Gain` is write protected now");
+///         }
+///         _ => println!("failed to set `Gain`: {}", err),
+///     }
+/// }
+/// ```
+#[must_use]
+pub fn control_error(err: &GenApiError) -> Option<&ControlError> {
+    match err {
+        GenApiError::Device(inner) => inner.downcast_ref(),
+        _ => None,
+    }
+}
+
 /// Represents `CompressionType` of `GenICam` XML file on the device's memory.
 #[derive(Debug, Clone, Copy)]
 pub enum CompressionType {
@@ -427,6 +598,12 @@ impl<'a, T> GenApiDevice<'a, T> {
     }
 }
 
+fn into_device_address(address: i64) -> ControlResult<u64> {
+    address.try_into().map_err(|_| {
+        ControlError::InvalidData("invalid address: the given address has negative value".into())
+    })
+}
+
 impl<'a, T> cameleon_genapi::Device for GenApiDevice<'a, T>
 where
     T: DeviceControl,
@@ -435,12 +612,8 @@ where
         &mut self,
         address: i64,
         data: &mut [u8],
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let address: u64 = address.try_into().map_err(|_| {
-            ControlError::InvalidData(
-                "invalid address: the given address has negative value".into(),
-            )
-        })?;
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let address = into_device_address(address)?;
         Ok(self.inner.read(address, data)?)
     }
 
@@ -448,12 +621,116 @@ where
         &mut self,
         address: i64,
         data: &[u8],
-    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
-        let address: u64 = address.try_into().map_err(|_| {
-            ControlError::InvalidData(
-                "invalid address: the given address has negative value".into(),
-            )
-        })?;
+    ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let address = into_device_address(address)?;
         Ok(self.inner.write(address, data)?)
     }
+
+    fn read_mem_stacked(
+        &mut self,
+        entries: &mut [(i64, &mut [u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let regions = entries
+            .iter()
+            .map(|(address, buf)| Ok((into_device_address(*address)?, buf.len())))
+            .collect::<ControlResult<Vec<_>>>()?;
+        let data = self.inner.read_stacked(&regions)?;
+        for ((_, buf), data) in entries.iter_mut().zip(data) {
+            buf.copy_from_slice(&data);
+        }
+        Ok(())
+    }
+
+    fn write_mem_stacked(
+        &mut self,
+        entries: &[(i64, &[u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let entries = entries
+            .iter()
+            .map(|&(address, data)| Ok((into_device_address(address)?, data)))
+            .collect::<ControlResult<Vec<_>>>()?;
+        Ok(self.inner.write_stacked(&entries)?)
+    }
+}
+
+#[cfg(all(test, feature = "emulator"))]
+mod tests {
+    use async_std::task;
+
+    use super::*;
+    use crate::{
+        emulator::{tests::open_camera, EmulatorBuilder, LoggedCommand},
+        u3v::{SharedControlHandle, StreamHandle},
+        Camera,
+    };
+
+    #[test]
+    fn test_enter_async() {
+        let (mut camera, handle) = open_camera(EmulatorBuilder::new());
+        camera.load_context().unwrap();
+        let mut params_ctxt = camera.params_ctxt().unwrap();
+        let width = params_ctxt
+            .node("Width")
+            .unwrap()
+            .as_integer(&params_ctxt)
+            .unwrap();
+
+        // The same value is read as the synchronous access.
+        let value = width.value(&mut params_ctxt).unwrap();
+        params_ctxt.ctxt.clear_cache();
+        handle.clear_command_log();
+        let async_value = task::block_on(width.value_async(&mut params_ctxt));
+        assert_eq!(async_value.unwrap(), value);
+        assert!(!handle.command_log().is_empty());
+
+        // The value is written only once, and the change is visible to synchronous accesses.
+        handle.clear_command_log();
+        task::block_on(width.set_value_async(&mut params_ctxt, value / 2)).unwrap();
+        let writes = handle
+            .command_log()
+            .into_iter()
+            .filter(|cmd| matches!(cmd, LoggedCommand::WriteMem { .. }))
+            .count();
+        assert_eq!(writes, 1);
+        assert_eq!(width.value(&mut params_ctxt).unwrap(), value / 2);
+
+        // Errors are returned to the caller.
+        let result = task::block_on(width.set_value_async(&mut params_ctxt, -1));
+        assert!(result.is_err());
+
+        // Device errors can be retrieved as usual.
+        params_ctxt.ctxt.clear_cache();
+        handle.unplug().unwrap();
+        let err = task::block_on(width.value_async(&mut params_ctxt)).unwrap_err();
+        assert!(control_error(&err).is_some());
+    }
+
+    #[test]
+    fn test_enter_async_shared() {
+        let (mut camera, _handle) = open_camera(EmulatorBuilder::new());
+        camera.load_context().unwrap();
+        let camera: Camera<SharedControlHandle, StreamHandle, SharedDefaultGenApiCtxt> =
+            camera.convert_into();
+        let mut params_ctxt = ParamsCtxt {
+            ctrl: camera.ctrl.clone(),
+            ctxt: camera.ctxt.clone().unwrap(),
+        };
+        let node = |name| params_ctxt.node(name).unwrap();
+        let gain = node("Gain").as_float(&params_ctxt).unwrap();
+        let trigger_mode = node("TriggerMode").as_enumeration(&params_ctxt).unwrap();
+
+        // Accesses of other clones wait for the async access and vice versa.
+        let mut cloned = params_ctxt.clone();
+        let set_gain = task::spawn(async move { gain.set_value_async(&mut cloned, 2.0).await });
+        task::block_on(set_gain).unwrap();
+        assert!((gain.value(&mut params_ctxt).unwrap() - 2.0).abs() < f64::EPSILON);
+        assert!(
+            (task::block_on(gain.value_async(&mut params_ctxt)).unwrap() - 2.0).abs()
+                < f64::EPSILON
+        );
+
+        task::block_on(trigger_mode.set_entry_by_symbolic_async(&mut params_ctxt, "On")).unwrap();
+        let entry = trigger_mode.current_entry(&mut params_ctxt).unwrap();
+        assert_eq!(entry.symbolic(&params_ctxt), "On");
+    }
 }
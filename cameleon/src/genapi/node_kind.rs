@@ -12,7 +12,7 @@ use cameleon_genapi::{
     GenApiError, GenApiResult, NodeId,
 };
 
-use super::{DeviceControl, GenApiCtxt, GenApiDevice, ParamsCtxt};
+use super::{AsyncDeviceControl, DeviceControl, GenApiCtxt, GenApiDevice, ParamsCtxt};
 
 /// A node that has `IInteger` interface
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -87,6 +87,29 @@ macro_rules! delegate {
         )*
     };
 
+    (
+        async,
+        $expect_kind:ident,
+        $(
+            $(#[$meta:meta])*
+            $vis:vis async fn $method:ident<$Ctrl:ident, $Ctxt:ident>($self:ident, ctxt: &mut ParamsCtxt<Ctrl, Ctxt> $(,$arg:ident: $arg_ty:ty)*) -> $ret_ty:ty => $inner:ident,)*) => {
+        $(
+            $(#[$meta])*
+            $vis async fn $method<$Ctrl, $Ctxt>($self, ctxt: &mut ParamsCtxt<$Ctrl, $Ctxt> $(,$arg: $arg_ty)*) -> $ret_ty
+            where $Ctrl: AsyncDeviceControl,
+                  $Ctxt: GenApiCtxt
+            {
+                ctxt.enter_async(|device, ns, vc| {
+                    $self.0
+                        .$expect_kind(ns)
+                        .unwrap()
+                        .$inner($($arg,)* device, ns, vc)
+                })
+                .await
+            }
+        )*
+    };
+
     (
         no_vc,
         $expect_kind:ident,
@@ -132,6 +155,23 @@ impl IntegerNode {
         /// Returns `true` if the node is writable.
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
+
+    delegate! {
+        async,
+        expect_iinteger_kind,
+        /// Asynchronous version of [`Self::value`].
+        pub async fn value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<i64> => value,
+        /// Asynchronous version of [`Self::set_value`].
+        pub async fn set_value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, value: i64) -> GenApiResult<()> => set_value,
+        /// Asynchronous version of [`Self::min`].
+        pub async fn min_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<i64> => min,
+        /// Asynchronous version of [`Self::max`].
+        pub async fn max_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<i64> => max,
+        /// Asynchronous version of [`Self::is_readable`].
+        pub async fn is_readable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_readable,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
     delegate! {
        no_vc,
        expect_iinteger_kind,
@@ -154,6 +194,20 @@ impl IntegerNode {
             .map(String::from)
     }
 
+    /// Returns valid values if `inc_mode` returns `IncrementMode::ListIncrement`, otherwise returns
+    /// an empty vector. The value to set must be one of them.
+    pub fn valid_value_set<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> Vec<i64>
+    where
+        Ctxt: GenApiCtxt,
+    {
+        let ns = ctxt.node_store();
+        self.0
+            .expect_iinteger_kind(ns)
+            .unwrap()
+            .valid_value_set(ns)
+            .to_vec()
+    }
+
     /// Upcast to [`Node`].
     pub fn as_node(self) -> Node {
         Node(self.0)
@@ -183,6 +237,23 @@ impl FloatNode {
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
 
+    delegate! {
+        async,
+        expect_ifloat_kind,
+        /// Asynchronous version of [`Self::value`].
+        pub async fn value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<f64> => value,
+        /// Asynchronous version of [`Self::set_value`].
+        pub async fn set_value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, value: f64) -> GenApiResult<()> => set_value,
+        /// Asynchronous version of [`Self::min`].
+        pub async fn min_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<f64> => min,
+        /// Asynchronous version of [`Self::max`].
+        pub async fn max_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<f64> => max,
+        /// Asynchronous version of [`Self::is_readable`].
+        pub async fn is_readable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_readable,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
+
     delegate! {
        no_vc,
        expect_ifloat_kind,
@@ -207,6 +278,20 @@ impl FloatNode {
             .map(String::from)
     }
 
+    /// Returns valid values if `inc_mode` returns `IncrementMode::ListIncrement`, otherwise returns
+    /// an empty vector. The value to set must be one of them.
+    pub fn valid_value_set<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> Vec<f64>
+    where
+        Ctxt: GenApiCtxt,
+    {
+        let ns = ctxt.node_store();
+        self.0
+            .expect_ifloat_kind(ns)
+            .unwrap()
+            .valid_value_set(ns)
+            .to_vec()
+    }
+
     /// Upcast to [`Node`].
     pub fn as_node(self) -> Node {
         Node(self.0)
@@ -228,6 +313,36 @@ impl StringNode {
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
 
+    delegate! {
+        async,
+        expect_istring_kind,
+        /// Asynchronous version of [`Self::value`].
+        pub async fn value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<String> => value,
+        /// Asynchronous version of [`Self::is_readable`].
+        pub async fn is_readable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_readable,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
+
+    /// Asynchronous version of [`Self::set_value`].
+    pub async fn set_value_async<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+        value: String,
+    ) -> GenApiResult<()>
+    where
+        Ctrl: AsyncDeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        ctxt.enter_async(|device, ns, vc| {
+            self.0
+                .expect_istring_kind(ns)
+                .unwrap()
+                .set_value(value.clone(), device, ns, vc)
+        })
+        .await
+    }
+
     /// Upcast to [`Node`].
     pub fn as_node(self) -> Node {
         Node(self.0)
@@ -247,6 +362,19 @@ impl EnumerationNode {
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
 
+    delegate! {
+        async,
+        expect_ienumeration_kind,
+        /// Asynchronous version of [`Self::set_entry_by_symbolic`].
+        pub async fn set_entry_by_symbolic_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, name: &str) -> GenApiResult<()> => set_entry_by_symbolic,
+        /// Asynchronous version of [`Self::set_entry_by_value`].
+        pub async fn set_entry_by_value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, value: i64) -> GenApiResult<()> => set_entry_by_value,
+        /// Asynchronous version of [`Self::is_readable`].
+        pub async fn is_readable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_readable,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
+
     /// Returns entries of the node.
     pub fn entries<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> Vec<EnumEntryNode>
     where
@@ -346,6 +474,17 @@ impl CommandNode {
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
 
+    delegate! {
+        async,
+        expect_icommand_kind,
+        /// Asynchronous version of [`Self::execute`].
+        pub async fn execute_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<()> => execute,
+        /// Asynchronous version of [`Self::is_done`].
+        pub async fn is_done_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_done,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
+
     /// Upcast to [`Node`].
     pub fn as_node(self) -> Node {
         Node(self.0)
@@ -365,6 +504,19 @@ impl BooleanNode {
         pub fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>,
     }
 
+    delegate! {
+        async,
+        expect_iboolean_kind,
+        /// Asynchronous version of [`Self::value`].
+        pub async fn value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => value,
+        /// Asynchronous version of [`Self::set_value`].
+        pub async fn set_value_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, value: bool) -> GenApiResult<()> => set_value,
+        /// Asynchronous version of [`Self::is_readable`].
+        pub async fn is_readable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_readable,
+        /// Asynchronous version of [`Self::is_writable`].
+        pub async fn is_writable_async<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool> => is_writable,
+    }
+
     /// Upcast to [`Node`].
     pub fn as_node(self) -> Node {
         Node(self.0)
@@ -502,6 +654,31 @@ impl Node {
         self.0.as_inode_kind(ns).unwrap().name(ns)
     }
 
+    /// Returns `true` if the node value should be saved and restored as a part of the device
+    /// configuration.
+    pub fn streamable<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> bool
+    where
+        Ctxt: GenApiCtxt,
+    {
+        let ns = ctxt.node_store();
+        self.0.as_inode_kind(ns).unwrap().streamable()
+    }
+
+    /// Returns nodes selected by the node, or an empty vector if the node is not a selector.
+    pub fn selecting_nodes<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> Vec<Node>
+    where
+        Ctxt: GenApiCtxt,
+    {
+        let ns = ctxt.node_store();
+        match self.0.as_iselector_kind(ns) {
+            Some(selector) => selector
+                .selecting_nodes(ns)
+                .map(|nodes| nodes.iter().map(|nid| Node(*nid)).collect())
+                .unwrap_or_default(),
+            None => vec![],
+        }
+    }
+
     /// Returns display name of the node. This method is mainly for GUI.
     pub fn display_name<Ctrl, Ctxt>(self, ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> &str
     where
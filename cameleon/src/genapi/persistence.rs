@@ -0,0 +1,599 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module contains functions to save and restore device configurations.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use cameleon_genapi::{GenApiError, GenApiResult};
+use tracing::warn;
+
+use super::{
+    feature::{format_integer, Feature},
+    DependencyGraph, DependencyKind, DeviceControl, GenApiCtxt, Node, NodeId, ParamsCtxt,
+};
+
+/// Maximum number of values iterated for a selector that has `IInteger` interface.
+const MAX_SELECTOR_VALUES: usize = 1 << 16;
+
+/// Feature values saved by [`ParamsCtxt::save_features`].
+///
+/// The text representation consists of lines of a feature name and its value separated by a
+/// tab. `\`, tab, LF and CR in values are escaped as `\\`, `\t`, `\n` and `\r` respectively.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// # Examples
+/// ```
+/// use cameleon::genapi::SavedFeatures;
+///
+/// let text = "# Saved features\nGainSelector\tAnalogAll\nDeviceUserID\tleft\\tcamera\n";
+/// let saved: SavedFeatures = text.parse().unwrap();
+/// assert_eq!(
+///     saved.entries()[1],
+///     ("DeviceUserID".to_string(), "left\tcamera".to_string())
+/// );
+///
+/// let restored: SavedFeatures = saved.to_string().parse().unwrap();
+/// assert_eq!(saved, restored);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedFeatures {
+    entries: Vec<(String, String)>,
+}
+
+impl SavedFeatures {
+    /// Returns empty saved features.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a pair of a feature name and its value.
+    pub fn push(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.entries.push((name.into(), value.into()));
+    }
+
+    /// Returns pairs of a feature name and its value in the order they are loaded.
+    #[must_use]
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for SavedFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.entries {
+            writeln!(f, "{}\t{}", name, escape(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SavedFeatures {
+    type Err = GenApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut saved = Self::new();
+        for (i, line) in s.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('\t')
+                .ok_or_else(|| invalid_line(i, "missing tab after feature name"))?;
+            let value = unescape(value).ok_or_else(|| invalid_line(i, "invalid escape"))?;
+            saved.push(name, value);
+        }
+        Ok(saved)
+    }
+}
+
+/// An error occurred while loading a feature value.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to load `{value}` to `{feature}`: {error}")]
+pub struct FeatureError {
+    /// Name of the feature.
+    pub feature: String,
+    /// The value which failed to be loaded.
+    pub value: String,
+    /// The cause of the failure.
+    #[source]
+    pub error: GenApiError,
+}
+
+impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    /// Saves values of all streamable, readable and writable features under the `Root`
+    /// category.
+    ///
+    /// Features are saved after features they depend on. Features selected by a selector are
+    /// saved for each value of the selector, and the selector is set back to its original value
+    /// afterwards. Features which fail to be read are skipped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use cameleon::u3v;
+    /// # let mut cameras = u3v::enumerate_cameras().unwrap();
+    /// # let mut camera = cameras.pop().unwrap();
+    /// # camera.open().unwrap();
+    /// # camera.load_context().unwrap();
+    /// use cameleon::genapi::SavedFeatures;
+    ///
+    /// let mut params_ctxt = camera.params_ctxt().unwrap();
+    /// let saved = params_ctxt.save_features().unwrap();
+    /// std::fs::write("camera.txt", saved.to_string()).unwrap();
+    ///
+    /// let saved: SavedFeatures = std::fs::read_to_string("camera.txt")
+    ///     .unwrap()
+    ///     .parse()
+    ///     .unwrap();
+    /// for error in params_ctxt.load_features(&saved) {
+    ///     println!("{}", error);
+    /// }
+    /// ```
+    pub fn save_features(&mut self) -> GenApiResult<SavedFeatures> {
+        let root = self
+            .node("Root")
+            .and_then(|node| node.as_category(self))
+            .ok_or_else(|| GenApiError::InvalidNode("`Root` category is missing".into()))?;
+
+        let mut visited = HashSet::new();
+        let mut features = vec![];
+        let mut categories = vec![root];
+        while let Some(category) = categories.pop() {
+            for node in category.nodes(self) {
+                if !visited.insert(node.0) {
+                    continue;
+                }
+                if let Some(category) = node.as_category(self) {
+                    categories.push(category);
+                } else if node.streamable(self) && Feature::new(node, self).is_some() {
+                    features.push(node);
+                }
+            }
+        }
+
+        let graph = DependencyGraph::new(self.node_store());
+        let features = sort_by_dependency(&graph, &features);
+
+        // Features selected by a saved selector are saved in the block of the selector. A
+        // selector only takes features following it so that blocks never form a cycle.
+        let mut owned = HashSet::new();
+        let mut blocks: HashMap<NodeId, Vec<Node>> = HashMap::new();
+        for (i, selector) in features.iter().enumerate() {
+            let selecting_nodes = selector.selecting_nodes(self);
+            for node in &features[i + 1..] {
+                if selecting_nodes.contains(node) && owned.insert(node.0) {
+                    blocks.entry(selector.0).or_default().push(*node);
+                }
+            }
+        }
+
+        let mut saved = SavedFeatures::new();
+        for node in features.into_iter().filter(|node| !owned.contains(&node.0)) {
+            self.save_feature(node, &blocks, &mut saved);
+        }
+        Ok(saved)
+    }
+
+    /// Loads feature values saved by [`Self::save_features`].
+    ///
+    /// Values are written in the saved order. Features which fail to be written, e.g. because
+    /// they are locked until another feature is written, are retried after the rest together
+    /// with the selector values they were saved with, until no more features succeed. Each
+    /// selector is finally set to the last saved value.
+    ///
+    /// Returns errors of features which could not be loaded.
+    pub fn load_features(&mut self, saved: &SavedFeatures) -> Vec<FeatureError> {
+        let entries = saved.entries();
+        let graph = DependencyGraph::new(self.node_store());
+        let nodes: Vec<_> = entries.iter().map(|(name, _)| self.node(name)).collect();
+
+        // Indices of the entries of the selectors that were in effect when each entry was saved.
+        let mut latest = HashMap::new();
+        let mut contexts = Vec::with_capacity(entries.len());
+        for (i, node) in nodes.iter().enumerate() {
+            let context: Vec<usize> = node.map_or_else(Vec::new, |node| {
+                graph
+                    .dependencies(node.0)
+                    .iter()
+                    .filter(|(kind, _)| *kind == DependencyKind::Selector)
+                    .filter_map(|(_, selector)| latest.get(selector).copied())
+                    .collect()
+            });
+            contexts.push(context);
+            if let Some(node) = node {
+                latest.insert(node.0, i);
+            }
+        }
+
+        let mut failed = vec![];
+        for i in 0..entries.len() {
+            if let Err(e) = self.load_entry(&entries[i], nodes[i]) {
+                failed.push((i, e));
+            }
+        }
+
+        while !failed.is_empty() {
+            let mut still_failed = vec![];
+            for &(i, _) in &failed {
+                if let Err(e) = self.load_entry_in_context(i, entries, &nodes, &contexts) {
+                    still_failed.push((i, e));
+                }
+            }
+            let progressed = still_failed.len() < failed.len();
+            failed = still_failed;
+            if !progressed {
+                break;
+            }
+        }
+
+        let mut selectors: Vec<_> = latest
+            .into_iter()
+            .filter(|(nid, _)| !Node(*nid).selecting_nodes(self).is_empty())
+            .map(|(_, i)| i)
+            .collect();
+        selectors.sort_unstable();
+        for i in selectors {
+            if let Err(e) = self.load_entry(&entries[i], nodes[i]) {
+                warn!(?e, "failed to restore selector `{}`", entries[i].0);
+            }
+        }
+
+        failed
+            .into_iter()
+            .map(|(i, error)| FeatureError {
+                feature: entries[i].0.clone(),
+                value: entries[i].1.clone(),
+                error,
+            })
+            .collect()
+    }
+
+    fn save_feature(
+        &mut self,
+        node: Node,
+        blocks: &HashMap<NodeId, Vec<Node>>,
+        saved: &mut SavedFeatures,
+    ) {
+        let feature = Feature::new(node, self).unwrap();
+        let value = match feature.read_if_accessible(self) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(?e, "failed to save `{}`", node.name(self));
+                None
+            }
+        };
+        let (value, selected) = match (value, blocks.get(&node.0)) {
+            (Some(value), Some(selected)) => (value, selected),
+            (Some(value), None) => {
+                saved.push(node.name(self), value);
+                return;
+            }
+            // Selected features can only be saved with the current selector value.
+            (None, Some(selected)) => {
+                for node in selected {
+                    self.save_feature(*node, blocks, saved);
+                }
+                return;
+            }
+            (None, None) => return,
+        };
+
+        let selector_values = feature.selector_values(self).unwrap_or_else(|e| {
+            warn!(?e, "failed to get values of selector `{}`", node.name(self));
+            vec![]
+        });
+        for selector_value in selector_values {
+            if let Err(e) = feature.write_str(self, &selector_value) {
+                warn!(
+                    ?e,
+                    "failed to set `{}` to `{}`",
+                    node.name(self),
+                    selector_value
+                );
+                continue;
+            }
+            saved.push(node.name(self), selector_value);
+            for node in selected {
+                self.save_feature(*node, blocks, saved);
+            }
+        }
+
+        if let Err(e) = feature.write_str(self, &value) {
+            warn!(?e, "failed to restore `{}` to `{}`", node.name(self), value);
+        }
+        saved.push(node.name(self), value);
+    }
+
+    fn load_entry_in_context(
+        &mut self,
+        i: usize,
+        entries: &[(String, String)],
+        nodes: &[Option<Node>],
+        contexts: &[Vec<usize>],
+    ) -> GenApiResult<()> {
+        for &j in &contexts[i] {
+            self.load_entry_in_context(j, entries, nodes, contexts)?;
+        }
+        self.load_entry(&entries[i], nodes[i])
+    }
+
+    fn load_entry(
+        &mut self,
+        (name, value): &(String, String),
+        node: Option<Node>,
+    ) -> GenApiResult<()> {
+        let node = node
+            .ok_or_else(|| GenApiError::InvalidNode(format!("no node named `{}`", name).into()))?;
+        let feature = Feature::new(node, self).ok_or_else(|| {
+            GenApiError::InvalidNode(format!("`{}` can't be loaded from text", name).into())
+        })?;
+        // Devices don't necessarily reject writes to locked features, so check it in advance to
+        // retry them.
+        if !feature.is_writable(self)? {
+            return Err(GenApiError::NotWritable);
+        }
+        feature.write_str(self, value)
+    }
+}
+
+impl Feature {
+    /// Returns `None` if the node is not readable or not writable.
+    fn read_if_accessible<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+    ) -> GenApiResult<Option<String>>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        let is_readable = match self {
+            Self::Enumeration(node) => node.is_readable(ctxt)?,
+            Self::Boolean(node) => node.is_readable(ctxt)?,
+            Self::Integer(node) => node.is_readable(ctxt)?,
+            Self::Float(node) => node.is_readable(ctxt)?,
+            Self::String(node) => node.is_readable(ctxt)?,
+        };
+        if !is_readable || !self.is_writable(ctxt)? {
+            return Ok(None);
+        }
+
+        self.read_string(ctxt).map(Some)
+    }
+
+    fn is_writable<Ctrl, Ctxt>(self, ctxt: &mut ParamsCtxt<Ctrl, Ctxt>) -> GenApiResult<bool>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        match self {
+            Self::Enumeration(node) => node.is_writable(ctxt),
+            Self::Boolean(node) => node.is_writable(ctxt),
+            Self::Integer(node) => node.is_writable(ctxt),
+            Self::Float(node) => node.is_writable(ctxt),
+            Self::String(node) => node.is_writable(ctxt),
+        }
+    }
+
+    /// Returns values the feature can take as a selector.
+    fn selector_values<Ctrl, Ctxt>(
+        self,
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+    ) -> GenApiResult<Vec<String>>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        match self {
+            Self::Enumeration(node) => {
+                let mut values = vec![];
+                for entry in node.entries(ctxt) {
+                    if entry.is_implemented(ctxt)? && entry.is_available(ctxt)? {
+                        values.push(entry.symbolic(ctxt).to_string());
+                    }
+                }
+                Ok(values)
+            }
+            Self::Boolean(_) => Ok(vec![false.to_string(), true.to_string()]),
+            Self::Integer(node) => {
+                let repr = node.representation(ctxt);
+                let valid_value_set = node.valid_value_set(ctxt);
+                if !valid_value_set.is_empty() {
+                    return Ok(valid_value_set
+                        .into_iter()
+                        .map(|value| format_integer(value, repr))
+                        .collect());
+                }
+
+                let (min, max) = (node.min(ctxt)?, node.max(ctxt)?);
+                let inc = node.inc(ctxt)?.unwrap_or(1).max(1);
+                let values: Vec<_> = (min..=max)
+                    .step_by(inc as usize)
+                    .take(MAX_SELECTOR_VALUES + 1)
+                    .map(|value| format_integer(value, repr))
+                    .collect();
+                if values.len() > MAX_SELECTOR_VALUES {
+                    warn!(
+                        "only the first {} values of selector `{}` are iterated",
+                        MAX_SELECTOR_VALUES,
+                        node.as_node().name(ctxt)
+                    );
+                    return Ok(values[..MAX_SELECTOR_VALUES].to_vec());
+                }
+                Ok(values)
+            }
+            Self::Float(_) | Self::String(_) => Ok(vec![]),
+        }
+    }
+}
+
+/// Orders `features` so that every feature follows features it depends on.
+fn sort_by_dependency(graph: &DependencyGraph, features: &[Node]) -> Vec<Node> {
+    fn visit(
+        graph: &DependencyGraph,
+        nid: NodeId,
+        features: &HashSet<NodeId>,
+        visited: &mut HashSet<NodeId>,
+        sorted: &mut Vec<Node>,
+    ) {
+        if !visited.insert(nid) {
+            return;
+        }
+        for &(kind, dependency) in graph.dependencies(nid) {
+            if kind.propagates_change() {
+                visit(graph, dependency, features, visited, sorted);
+            }
+        }
+        if features.contains(&nid) {
+            sorted.push(Node(nid));
+        }
+    }
+
+    let targets = features.iter().map(|node| node.0).collect();
+    let mut visited = HashSet::new();
+    let mut sorted = Vec::with_capacity(features.len());
+    for node in features {
+        visit(graph, node.0, &targets, &mut visited, &mut sorted);
+    }
+    sorted
+}
+
+fn invalid_line(i: usize, reason: &str) -> GenApiError {
+    GenApiError::InvalidData(format!("line {}: {}", i + 1, reason).into())
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => unescaped.push('\\'),
+            't' => unescaped.push('\t'),
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
+
+#[cfg(all(test, feature = "emulator"))]
+mod tests {
+    use super::{super::FeatureValue, *};
+    use crate::{
+        emulator::{tests::open_camera, EmulatorBuilder},
+        u3v::{ControlHandle, StreamHandle},
+        Camera,
+    };
+
+    fn camera() -> Camera<ControlHandle, StreamHandle> {
+        let (mut camera, _) = open_camera(EmulatorBuilder::new());
+        camera.load_context().unwrap();
+        camera
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut camera = camera();
+        let mut ctxt = camera.params_ctxt().unwrap();
+        let saved = ctxt.save_features().unwrap();
+        assert!(saved.entries().iter().any(|(name, _)| name == "Width"));
+
+        ctxt.set_value("Width", 320_i64).unwrap();
+        ctxt.set_value("Gain", 6.0).unwrap();
+        ctxt.set_value("TestPattern", "Checkerboard").unwrap();
+        ctxt.set_value("DeviceUserID", "changed").unwrap();
+        assert_ne!(ctxt.save_features().unwrap(), saved);
+
+        let text: SavedFeatures = saved.to_string().parse().unwrap();
+        assert!(ctxt.load_features(&text).is_empty());
+        assert_eq!(ctxt.save_features().unwrap(), saved);
+        assert_eq!(ctxt.get_value("Width").unwrap(), FeatureValue::Integer(640));
+    }
+
+    #[test]
+    fn test_locked_feature_is_retried() {
+        let mut camera = camera();
+        let mut ctxt = camera.params_ctxt().unwrap();
+        ctxt.set_value("TLParamsLocked", 1_i64).unwrap();
+
+        // `Width` is locked until `TLParamsLocked` following it is loaded.
+        let mut saved = SavedFeatures::new();
+        saved.push("Width", "320");
+        saved.push("TLParamsLocked", "0");
+        assert!(ctxt.load_features(&saved).is_empty());
+        assert_eq!(ctxt.get_value("Width").unwrap(), FeatureValue::Integer(320));
+
+        // Features which stay locked are reported.
+        ctxt.set_value("TLParamsLocked", 1_i64).unwrap();
+        let mut saved = SavedFeatures::new();
+        saved.push("Width", "160");
+        let errors = ctxt.load_features(&saved);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].feature, "Width");
+        assert_eq!(errors[0].value, "160");
+        assert_eq!(ctxt.get_value("Width").unwrap(), FeatureValue::Integer(320));
+    }
+
+    #[test]
+    fn test_selector_is_restored() {
+        let mut camera = camera();
+        let mut ctxt = camera.params_ctxt().unwrap();
+        let selector = || FeatureValue::Enumeration("FrameStart".into());
+        let saved = ctxt.save_features().unwrap();
+        assert_eq!(ctxt.get_value("TriggerSelector").unwrap(), selector());
+
+        // Selected features are saved for each selector value, and the selector value follows.
+        let names: Vec<_> = saved
+            .entries()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .skip_while(|name| *name != "TriggerSelector")
+            .take(4)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "TriggerSelector",
+                "TriggerMode",
+                "TriggerSource",
+                "TriggerSelector"
+            ]
+        );
+
+        ctxt.set_value("TriggerMode", "On").unwrap();
+        assert!(ctxt.load_features(&saved).is_empty());
+        assert_eq!(
+            ctxt.get_value("TriggerMode").unwrap(),
+            FeatureValue::Enumeration("Off".into())
+        );
+        assert_eq!(ctxt.get_value("TriggerSelector").unwrap(), selector());
+    }
+}
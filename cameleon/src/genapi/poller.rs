@@ -0,0 +1,487 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module contains a poller which re-reads nodes that declare `PollingTime`.
+
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use async_std::channel::{Receiver, Sender};
+use cameleon_genapi::{elem_type::ImmOrPNode, store::NodeData, GenApiResult};
+use tracing::{debug, error};
+
+use super::{
+    DeviceControl, GenApiCtxt, Node, NodeStore, ParamsCtxt, SharedDeviceControl, SharedGenApiCtxt,
+};
+
+/// A value of a polled node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolledValue {
+    /// A value of a node that has `IInteger` interface.
+    Integer(i64),
+    /// A value of a node that has `IFloat` interface.
+    Float(f64),
+    /// A value of a node that has `IString` interface.
+    String(String),
+    /// A value of a node that has `IBoolean` interface.
+    Boolean(bool),
+    /// Symbolic name of the current entry of a node that has `IEnumeration` interface.
+    Enumeration(String),
+    /// Whether the previous command of a node that has `ICommand` interface is done.
+    CommandDone(bool),
+    /// Bytes of a node that has `IRegister` interface.
+    Register(Vec<u8>),
+}
+
+/// An event sent by [`Poller`] when the value of a polled node changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollEvent {
+    /// The node whose value has changed.
+    pub node: Node,
+    /// The new value of the node.
+    pub value: PolledValue,
+}
+
+/// A handle of a background thread which invalidates and re-reads nodes at their `PollingTime`.
+///
+/// Nodes are re-read at the interval declared by their `PollingTime`, and [`PollEvent`] is sent
+/// to subscribers whenever a value differs from the previously read one. The changed node and
+/// nodes depending on it are also sent to receivers returned by [`ParamsCtxt::subscribe`].
+/// The thread is stopped when the handle is dropped.
+///
+/// # Examples
+/// ```no_run
+/// # use cameleon::u3v;
+/// use cameleon::{
+///     genapi::{ParamsCtxt, SharedDefaultGenApiCtxt},
+///     u3v::{SharedControlHandle, StreamHandle},
+///     Camera,
+/// };
+///
+/// # let mut cameras = u3v::enumerate_cameras().unwrap();
+/// # let camera = cameras.pop().unwrap();
+/// let mut camera: Camera<SharedControlHandle, StreamHandle, SharedDefaultGenApiCtxt> =
+///     camera.convert_into();
+/// camera.open().unwrap();
+/// camera.load_context().unwrap();
+///
+/// // The poller needs its own handles, which share the device and the cache with the camera.
+/// let params_ctxt = ParamsCtxt {
+///     ctrl: camera.ctrl.clone(),
+///     ctxt: camera.ctxt.clone().unwrap(),
+/// };
+/// let poller = params_ctxt.start_polling();
+/// let events = poller.subscribe();
+///
+/// while let Ok(event) = async_std::task::block_on(events.recv()) {
+///     println!("{}: {:?}", event.node.name(&params_ctxt), event.value);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Poller {
+    nodes: Vec<Node>,
+    subscribers: Arc<Mutex<Vec<Sender<PollEvent>>>>,
+    stop_tx: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Poller {
+    /// Returns a receiver of [`PollEvent`]s sent after this call.
+    ///
+    /// The receiver is closed when the poller is dropped.
+    pub fn subscribe(&self) -> Receiver<PollEvent> {
+        let (tx, rx) = async_std::channel::unbounded();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Returns nodes that are polled.
+    #[must_use]
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the thread up and stops it.
+        drop(self.stop_tx.take());
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                error!("polling thread panicked");
+            }
+        }
+    }
+}
+
+impl<Ctrl, Ctxt> ParamsCtxt<Ctrl, Ctxt>
+where
+    Ctrl: SharedDeviceControl,
+    Ctxt: SharedGenApiCtxt,
+{
+    /// Starts a background thread which invalidates and re-reads nodes at their `PollingTime`.
+    ///
+    /// The thread works on clones of the control handle and the `GenApi` context, which share
+    /// the device and the cache with `self`, e.g. [`SharedControlHandle`] and
+    /// [`SharedDefaultGenApiCtxt`]. See [`Poller`] for more details.
+    ///
+    /// [`SharedControlHandle`]: crate::u3v::SharedControlHandle
+    /// [`SharedDefaultGenApiCtxt`]: super::SharedDefaultGenApiCtxt
+    pub fn start_polling(&self) -> Poller {
+        let now = Instant::now();
+        let mut targets = vec![];
+        self.node_store()
+            .visit_nodes(|node| match node.polling_time() {
+                Some(time) if time > 0 => targets.push(PollTarget {
+                    node: Node(node.node_base().id()),
+                    interval: Duration::from_millis(time),
+                    deadline: now,
+                    last: None,
+                }),
+                _ => {}
+            });
+
+        let nodes = targets.iter().map(|target| target.node).collect();
+        let subscribers = Arc::new(Mutex::new(vec![]));
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let polling_loop = PollingLoop {
+            params_ctxt: self.clone(),
+            targets,
+            subscribers: subscribers.clone(),
+            stop_rx,
+        };
+        let handle = std::thread::spawn(|| polling_loop.run());
+
+        Poller {
+            nodes,
+            subscribers,
+            stop_tx: Some(stop_tx),
+            handle: Some(handle),
+        }
+    }
+}
+
+struct PollTarget {
+    node: Node,
+    interval: Duration,
+    deadline: Instant,
+    last: Option<PolledValue>,
+}
+
+struct PollingLoop<Ctrl, Ctxt> {
+    params_ctxt: ParamsCtxt<Ctrl, Ctxt>,
+    targets: Vec<PollTarget>,
+    subscribers: Arc<Mutex<Vec<Sender<PollEvent>>>>,
+    stop_rx: mpsc::Receiver<()>,
+}
+
+impl<Ctrl, Ctxt> PollingLoop<Ctrl, Ctxt>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    fn run(mut self) {
+        loop {
+            // Stop the loop when `stop_tx` sends signal or is dropped.
+            let signal = match self.targets.iter().map(|target| target.deadline).min() {
+                Some(deadline) => self
+                    .stop_rx
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self
+                    .stop_rx
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            if signal != Err(RecvTimeoutError::Timeout) {
+                break;
+            }
+
+            let now = Instant::now();
+            for i in 0..self.targets.len() {
+                if self.targets[i].deadline <= now {
+                    self.targets[i].deadline = now + self.targets[i].interval;
+                    self.poll(i);
+                }
+            }
+        }
+    }
+
+    fn poll(&mut self, i: usize) {
+        let node = self.targets[i].node;
+        self.invalidate(node);
+        let value = match read_value(node, &mut self.params_ctxt) {
+            Ok(Some(value)) => value,
+            Ok(None) => return,
+            Err(e) => {
+                debug!(?e, "failed to poll `{}`", node.name(&self.params_ctxt));
+                return;
+            }
+        };
+
+        let target = &mut self.targets[i];
+        if target.last.as_ref() == Some(&value) {
+            return;
+        }
+        // The first read value is just recorded because there is nothing to compare it with.
+        if target.last.replace(value.clone()).is_some() {
+            // Dependents are notified to subscribers of the context when `enter2` returns.
+            self.params_ctxt.enter2(|_, _, vc| vc.mark_changed(node.0));
+            let event = PollEvent { node, value };
+            self.subscribers
+                .lock()
+                .unwrap()
+                .retain(|tx| tx.try_send(event.clone()).is_ok());
+        }
+    }
+
+    fn invalidate(&mut self, node: Node) {
+        self.params_ctxt.enter2(|_, ns, vc| {
+            vc.invalidate_cache_of(node.0);
+            // `Command` and `Enumeration` have no register of their own, their values come from
+            // `pValue`.
+            let p_value = match ns.node(node.0) {
+                NodeData::Command(n) => n.value_elem(),
+                NodeData::Enumeration(n) => n.value_elem(),
+                _ => return,
+            };
+            if let ImmOrPNode::PNode(nid) = p_value {
+                vc.invalidate_cache_of(nid);
+            }
+        });
+    }
+}
+
+fn read_value<Ctrl, Ctxt>(
+    node: Node,
+    ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+) -> GenApiResult<Option<PolledValue>>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    let value = if let Some(node) = node.as_command(ctxt) {
+        PolledValue::CommandDone(node.is_done(ctxt)?)
+    } else if let Some(node) = node.as_enumeration(ctxt) {
+        let entry = node.current_entry(ctxt)?;
+        PolledValue::Enumeration(entry.symbolic(ctxt).to_string())
+    } else if let Some(node) = node.as_integer(ctxt) {
+        PolledValue::Integer(node.value(ctxt)?)
+    } else if let Some(node) = node.as_float(ctxt) {
+        PolledValue::Float(node.value(ctxt)?)
+    } else if let Some(node) = node.as_string(ctxt) {
+        PolledValue::String(node.value(ctxt)?)
+    } else if let Some(node) = node.as_boolean(ctxt) {
+        PolledValue::Boolean(node.value(ctxt)?)
+    } else if let Some(node) = node.as_register(ctxt) {
+        let mut buf = vec![0; node.length(ctxt)? as usize];
+        node.read(ctxt, &mut buf)?;
+        PolledValue::Register(buf)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(value))
+}
+
+#[cfg(all(test, feature = "emulator"))]
+mod tests {
+    use async_std::{future::timeout, task};
+
+    use super::{super::SharedDefaultGenApiCtxt, *};
+    use crate::{
+        emulator::{
+            tests::open_camera, AccessRight, EmulatorBuilder, EmulatorHandle, LoggedCommand,
+            MemoryFragment,
+        },
+        u3v::{SharedControlHandle, StreamHandle},
+        Camera,
+    };
+
+    const XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<RegisterDescription ModelName="PollerTest" VendorName="Cameleon" StandardNameSpace="None"
+SchemaMajorVersion="1" SchemaMinorVersion="1" SchemaSubMinorVersion="0"
+MajorVersion="1" MinorVersion="0" SubMinorVersion="0"
+ProductGuid="00000000-0000-0000-0000-000000000000"
+VersionGuid="00000000-0000-0000-0000-000000000000"
+xmlns="http://www.genicam.org/GenApi/Version_1_1">
+    <IntReg Name="Counter">
+        <Address>0x100000</Address>
+        <Length>4</Length>
+        <AccessMode>RO</AccessMode>
+        <pPort>Device</pPort>
+        <Cachable>WriteThrough</Cachable>
+        <PollingTime>10</PollingTime>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+    <Command Name="Start">
+        <pValue>StartReg</pValue>
+        <CommandValue>1</CommandValue>
+        <PollingTime>10</PollingTime>
+    </Command>
+    <IntReg Name="StartReg">
+        <Address>0x100004</Address>
+        <Length>4</Length>
+        <AccessMode>RW</AccessMode>
+        <pPort>Device</pPort>
+        <Cachable>WriteThrough</Cachable>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+    <Enumeration Name="Mode">
+        <EnumEntry Name="Off">
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="On">
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>ModeReg</pValue>
+        <PollingTime>10</PollingTime>
+    </Enumeration>
+    <IntReg Name="ModeReg">
+        <Address>0x100008</Address>
+        <Length>4</Length>
+        <AccessMode>RW</AccessMode>
+        <pPort>Device</pPort>
+        <Cachable>WriteThrough</Cachable>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+    <Port Name="Device" NameSpace="Standard">
+    </Port>
+</RegisterDescription>"#;
+
+    type SharedParamsCtxt = ParamsCtxt<SharedControlHandle, SharedDefaultGenApiCtxt>;
+
+    /// Open an emulator whose `Counter` is 1, and `StartReg` and `ModeReg` are 0.
+    fn open() -> (SharedParamsCtxt, EmulatorHandle) {
+        let mut data = vec![0; 12];
+        data[0] = 1;
+        let image = MemoryFragment::new(0x10_0000, data, AccessRight::RW);
+        let builder = EmulatorBuilder::new()
+            .memory_fragment(image)
+            .unwrap()
+            .genapi_xml(XML);
+        let (mut camera, handle) = open_camera(builder);
+        camera.load_context().unwrap();
+        let camera: Camera<SharedControlHandle, StreamHandle, SharedDefaultGenApiCtxt> =
+            camera.convert_into();
+        let params_ctxt = ParamsCtxt {
+            ctrl: camera.ctrl.clone(),
+            ctxt: camera.ctxt.clone().unwrap(),
+        };
+        handle.clear_command_log();
+        (params_ctxt, handle)
+    }
+
+    /// Wait until the poller reads the register at `address`, so that the initial value of the
+    /// node is recorded.
+    fn wait_for_read(handle: &EmulatorHandle, address: u64) {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !handle.command_log().iter().any(
+            |command| matches!(command, LoggedCommand::ReadMem { address: a, .. } if *a == address),
+        ) {
+            assert!(Instant::now() < deadline, "{:#x} is never read", address);
+            std::thread::yield_now();
+        }
+    }
+
+    fn recv(events: &Receiver<PollEvent>) -> PollEvent {
+        task::block_on(timeout(Duration::from_secs(1), events.recv()))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_poller() {
+        let (params_ctxt, handle) = open();
+        let counter = params_ctxt.node("Counter").unwrap();
+
+        let poller = params_ctxt.start_polling();
+        assert_eq!(
+            poller.nodes(),
+            &[
+                counter,
+                params_ctxt.node("Start").unwrap(),
+                params_ctxt.node("Mode").unwrap()
+            ]
+        );
+        let events = poller.subscribe();
+
+        wait_for_read(&handle, 0x10_0000);
+        handle
+            .write_memory(0x10_0000, &2_u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(
+            recv(&events),
+            PollEvent {
+                node: counter,
+                value: PolledValue::Integer(2),
+            }
+        );
+
+        // Dropping the poller joins the thread, which releases the senders of the events.
+        drop(poller);
+        assert!(events.is_closed());
+        handle.clear_command_log();
+        assert!(handle.command_log().is_empty());
+    }
+
+    #[test]
+    fn test_poll_command() {
+        let (params_ctxt, handle) = open();
+        let start = params_ctxt.node("Start").unwrap();
+        let poller = params_ctxt.start_polling();
+        let events = poller.subscribe();
+
+        // The command is done while `StartReg` differs from `CommandValue`.
+        wait_for_read(&handle, 0x10_0004);
+        handle
+            .write_memory(0x10_0004, &1_u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(
+            recv(&events),
+            PollEvent {
+                node: start,
+                value: PolledValue::CommandDone(false),
+            }
+        );
+
+        handle
+            .write_memory(0x10_0004, &0_u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(
+            recv(&events),
+            PollEvent {
+                node: start,
+                value: PolledValue::CommandDone(true),
+            }
+        );
+    }
+
+    #[test]
+    fn test_poll_enumeration() {
+        let (params_ctxt, handle) = open();
+        let mode = params_ctxt.node("Mode").unwrap();
+        let poller = params_ctxt.start_polling();
+        let events = poller.subscribe();
+
+        wait_for_read(&handle, 0x10_0008);
+        handle
+            .write_memory(0x10_0008, &1_u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(
+            recv(&events),
+            PollEvent {
+                node: mode,
+                value: PolledValue::Enumeration("On".into()),
+            }
+        );
+    }
+}
@@ -82,6 +82,15 @@
 //!
 //! More examples can be found [here][cameleon-example].
 //!
+//! ### Emulated cameras
+//! `emulator` feature provides emulated U3V cameras, which are useful to test applications
+//! without cameras connected to the host. See [`emulator`](crate::emulator) module for details.
+//! The feature doesn't require `libusb`, enable `libusb` feature as well to use real cameras.
+//! ```toml
+//! [dependencies]
+//! cameleon = { version = "0.1", features = ["emulator"] }
+//! ```
+//!
 //! [libusb-url]: https://libusb.info
 //! [cameleon-example]: https://github.com/cameleon-rs/cameleon/tree/main/cameleon/examples
 //!
@@ -121,6 +130,7 @@
 //!
 //! In this case, it's recommended to allocate the equal throughput limit to the connected cameras,
 //! making sure that the total throughput does not exceed the maximum bandwidth of the host controller.
+//! [`BandwidthManager`](crate::u3v::BandwidthManager) does this for you.
 //!
 //! 3. `usbfs_memory_mb` is set to low value
 //!
@@ -144,12 +154,16 @@
 )]
 
 pub mod camera;
+#[cfg(feature = "emulator")]
+pub mod emulator;
 pub mod genapi;
 pub mod payload;
-#[cfg(feature = "libusb")]
+#[cfg(any(feature = "libusb", feature = "emulator"))]
 pub mod u3v;
 
-pub use camera::{Camera, CameraInfo, DeviceControl, PayloadStream};
+pub use camera::{
+    AsyncDeviceControl, Camera, CameraInfo, DeviceControl, PayloadStream, SharedDeviceControl,
+};
 
 use std::{borrow::Cow, num::TryFromIntError};
 
@@ -167,6 +181,10 @@ pub enum CameleonError {
     #[error("stream error: {0}")]
     StreamError(#[from] StreamError),
 
+    /// No camera matches the condition.
+    #[error("camera not found: {0}")]
+    CameraNotFound(Cow<'static, str>),
+
     /// `GenApi` context is not laoded yet.
     #[error("`GenApi` context is missing")]
     GenApiContextMissing,
@@ -184,6 +202,9 @@ pub enum CameleonError {
 pub type ControlResult<T> = std::result::Result<T, ControlError>;
 
 /// An error type for device control.
+///
+/// When a `GenApi` node operation fails due to device control, the error can be retrieved with
+/// [`genapi::control_error`].
 #[derive(Debug, thiserror::Error)]
 pub enum ControlError {
     /// The device is busy, may be opened by another application.
@@ -217,7 +238,83 @@ pub enum ControlError {
     /// Try to write invalid data to the device, or received data from the device is semantically invalid.
     /// e.g. try to write too large data that will overrun register.
     #[error("try to write invalid data to the device: {0}")]
-    InvalidData(Box<dyn std::error::Error>),
+    InvalidData(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The device received the command, but returned an error status.
+    #[error("device returned an error status: {0}")]
+    DeviceStatus(DeviceStatus),
+}
+
+/// An error status returned from the device in response to a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DeviceStatus {
+    /// The command is not implemented in the device.
+    #[error("command not implemented")]
+    NotImplemented,
+
+    /// A parameter of the command is invalid.
+    #[error("invalid parameter")]
+    InvalidParameter,
+
+    /// Attempt to access an address that doesn't exist.
+    #[error("invalid address")]
+    InvalidAddress,
+
+    /// Attempt to write to a read only address.
+    #[error("write protected")]
+    WriteProtect,
+
+    /// Attempt to access an address with bad alignment.
+    #[error("bad alignment")]
+    BadAlignment,
+
+    /// Attempt to read an unreadable address or write to an unwritable address.
+    #[error("access denied")]
+    AccessDenied,
+
+    /// The device is busy.
+    #[error("device is busy")]
+    Busy,
+
+    /// The device timed out while processing the command.
+    #[error("timeout")]
+    Timeout,
+
+    /// Header of the command is inconsistent with its data.
+    #[error("invalid header")]
+    InvalidHeader,
+
+    /// Current configuration of the device doesn't allow the execution of the command.
+    #[error("wrong configuration")]
+    WrongConfig,
+
+    /// Generic error.
+    #[error("generic error")]
+    GenericError,
+
+    /// `U3V` specific: Resend command is not supported by the device.
+    #[error("resend command not supported")]
+    ResendNotSupported,
+
+    /// `U3V` specific: Stream endpoint is halted when stream flag is set.
+    #[error("stream endpoint halted")]
+    StreamEndpointHalted,
+
+    /// `U3V` specific: Payload size to be set is not aligned.
+    #[error("payload size not aligned")]
+    PayloadSizeNotAligned,
+
+    /// `U3V` specific: Event endpoint is halted when event enable flag is set.
+    #[error("event endpoint halted")]
+    EventEndpointHalted,
+
+    /// `U3V` specific: Streaming interface is in invalid state to enable stream.
+    #[error("invalid streaming interface state")]
+    InvalidSiState,
+
+    /// Device specific error status.
+    #[error("device specific error: {0:#X}")]
+    DeviceSpecific(u16),
 }
 
 /// A specialized `Result` type for streaming.
@@ -0,0 +1,648 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module provides [`BandwidthManager`] which shares the bandwidth of USB host controllers
+//! among cameras.
+//!
+//! Cameras connected to the same host controller share its bandwidth, and streaming could be
+//! unstable when the total throughput of the cameras exceeds the bandwidth.
+//! [`BandwidthManager`] groups cameras by the bus they are connected to, splits the usable
+//! bandwidth of the bus among the cameras, and then programs `DeviceLinkThroughputLimitMode` and
+//! `DeviceLinkThroughputLimit` of each camera.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cameleon::u3v::{self, BandwidthManager, BandwidthRequest};
+//!
+//! let mut cameras = u3v::enumerate_cameras().unwrap();
+//! for camera in &mut cameras {
+//!     camera.open().unwrap();
+//!     camera.load_context().unwrap();
+//! }
+//!
+//! // Share the bandwidth equally.
+//! let requests = vec![BandwidthRequest::Weight(1.0); cameras.len()];
+//! let allocations = BandwidthManager::new()
+//!     .allocate(&mut cameras, &requests)
+//!     .unwrap();
+//!
+//! for (camera, allocation) in cameras.iter().zip(&allocations) {
+//!     println!(
+//!         "{}: {} bytes/s",
+//!         camera.info().serial_number,
+//!         allocation.throughput_limit
+//!     );
+//! }
+//! ```
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use tracing::warn;
+
+use super::{register_map::Abrm, BusSpeed};
+use crate::{
+    genapi::{EnumerationNode, GenApiCtxt, IntegerNode, ParamsCtxt},
+    CameleonError, CameleonResult, Camera, DeviceControl, PayloadStream,
+};
+
+/// Default ratio of the usable bandwidth to the signaling rate of the bus, see
+/// [`BandwidthManager::efficiency`].
+const DEFAULT_EFFICIENCY: f64 = 0.8;
+
+/// A request of bandwidth for a camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandwidthRequest {
+    /// Share the bandwidth left after [`BandwidthRequest::FrameRate`] requests are fulfilled in
+    /// proportion to the weight.
+    Weight(f64),
+
+    /// Reserve the bandwidth required to stream payloads at the frame rate in Hz.
+    /// The required bandwidth is computed from `PayloadSize` of the camera.
+    FrameRate(f64),
+}
+
+/// The result of bandwidth allocation for a camera.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BandwidthAllocation {
+    /// Bus number the camera is connected to, see [`crate::CameraInfo::bus_number`].
+    pub bus_number: Option<u8>,
+
+    /// Usable bandwidth of the bus in bytes per second.
+    pub bus_bandwidth: u64,
+
+    /// The value programmed to `DeviceLinkThroughputLimit` in bytes per second.
+    pub throughput_limit: i64,
+
+    /// `false` if the request of the camera couldn't be fulfilled.
+    pub satisfied: bool,
+}
+
+/// Splits the bandwidth of USB host controllers among cameras.
+///
+/// See the [module level documentation](self) for details.
+#[derive(Clone, Debug)]
+pub struct BandwidthManager {
+    efficiency: f64,
+    bus_bandwidth: HashMap<u8, u64>,
+}
+
+impl Default for BandwidthManager {
+    fn default() -> Self {
+        Self {
+            efficiency: DEFAULT_EFFICIENCY,
+            bus_bandwidth: HashMap::new(),
+        }
+    }
+}
+
+impl BandwidthManager {
+    /// Constructs a manager with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the ratio of the usable bandwidth to the signaling rate of the bus, `0.8` by
+    /// default.
+    ///
+    /// The usable bandwidth is lower than the signaling rate because of the line encoding and
+    /// the protocol overhead, the ratio is clamped to `[0.0, 1.0]`.
+    #[must_use]
+    pub fn efficiency(mut self, ratio: f64) -> Self {
+        self.efficiency = if ratio.is_nan() {
+            DEFAULT_EFFICIENCY
+        } else {
+            ratio.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /// Set the usable bandwidth of the bus in bytes per second explicitly.
+    ///
+    /// This is useful when the bus is also used by other devices.
+    #[must_use]
+    pub fn bus_bandwidth(mut self, bus_number: u8, bytes_per_second: u64) -> Self {
+        self.bus_bandwidth.insert(bus_number, bytes_per_second);
+        self
+    }
+
+    /// Split the bandwidth among `cameras` according to `requests`, and then program
+    /// `DeviceLinkThroughputLimitMode` and `DeviceLinkThroughputLimit` of the cameras.
+    ///
+    /// `requests[i]` is the request of `cameras[i]`, and the returned allocations are in the same
+    /// order as `cameras`. Cameras must be opened and their `GenApi` contexts must be loaded in
+    /// advance.
+    ///
+    /// The usable bandwidth of a bus is computed from the fastest `CurrentSpeed` of SBRM among
+    /// the cameras connected to the bus. Cameras whose bus is unknown are regarded as connected
+    /// to their own bus.
+    ///
+    /// When requests can't be fulfilled, e.g. the total of requested frame rates exceeds the
+    /// bandwidth, a warning is emitted and the bandwidth is split in proportion to the requests
+    /// as far as the limits of the cameras allow. [`BandwidthAllocation::satisfied`] of such
+    /// cameras is set to `false`.
+    ///
+    /// The ranges of the limits and the payload sizes of all the cameras are read before any
+    /// camera is programmed, so the cameras are left untouched if reading fails. The range of
+    /// the limit is read in the current `DeviceLinkThroughputLimitMode`.
+    ///
+    /// # Errors
+    /// [`CameleonError::InvalidGenApiXml`] is returned if a camera doesn't have
+    /// `DeviceLinkThroughputLimitMode` with `On` entry or `DeviceLinkThroughputLimit`, or if a
+    /// camera requesting a frame rate doesn't have `PayloadSize`.
+    ///
+    /// # Panics
+    /// Panics if the length of `cameras` and `requests` differs.
+    pub fn allocate<Ctrl, Strm, Ctxt>(
+        &self,
+        cameras: &mut [Camera<Ctrl, Strm, Ctxt>],
+        requests: &[BandwidthRequest],
+    ) -> CameleonResult<Vec<BandwidthAllocation>>
+    where
+        Ctrl: DeviceControl,
+        Strm: PayloadStream,
+        Ctxt: GenApiCtxt,
+    {
+        assert_eq!(cameras.len(), requests.len());
+
+        let mut demands = Vec::with_capacity(cameras.len());
+        let mut speeds = Vec::with_capacity(cameras.len());
+        for (camera, request) in cameras.iter_mut().zip(requests) {
+            let abrm = Abrm::new(&mut camera.ctrl)?;
+            let sbrm = abrm.sbrm(&mut camera.ctrl)?;
+            speeds.push(sbrm.current_speed(&mut camera.ctrl)?);
+
+            let mut ctxt = camera.params_ctxt()?;
+            demands.push(Demand::read(&mut ctxt, *request)?);
+        }
+
+        // Group cameras by the bus they are connected to.
+        let mut groups: Vec<(Option<u8>, Vec<usize>)> = vec![];
+        for (i, camera) in cameras.iter().enumerate() {
+            let bus_number = camera.info().bus_number;
+            match groups
+                .iter_mut()
+                .find(|(bus, _)| bus.is_some() && *bus == bus_number)
+            {
+                Some((_, members)) => members.push(i),
+                None => groups.push((bus_number, vec![i])),
+            }
+        }
+
+        let mut allocations = vec![None; cameras.len()];
+        for (bus_number, members) in groups {
+            let speed = members.iter().map(|&i| speeds[i]).max().unwrap();
+            let bus_bandwidth = self.usable_bandwidth(bus_number, speed);
+            let group_demands: Vec<_> = members.iter().map(|&i| demands[i]).collect();
+            let (limits, feasible) = split(bus_bandwidth, &group_demands);
+            if !feasible {
+                warn!(
+                    ?bus_number,
+                    bus_bandwidth, "requested bandwidth exceeds the bandwidth of the bus"
+                );
+            }
+
+            for ((&i, demand), limit) in members.iter().zip(&group_demands).zip(limits) {
+                let satisfied = match demand.required {
+                    Some(required) if required > limit => {
+                        warn!(
+                            serial_number = %cameras[i].info().serial_number,
+                            required,
+                            limit,
+                            "throughput limit is lower than required for the frame rate"
+                        );
+                        false
+                    }
+                    _ => feasible,
+                };
+                allocations[i] = Some(BandwidthAllocation {
+                    bus_number,
+                    bus_bandwidth,
+                    throughput_limit: limit,
+                    satisfied,
+                });
+            }
+        }
+
+        let allocations: Vec<_> = allocations.into_iter().map(Option::unwrap).collect();
+        for (camera, allocation) in cameras.iter_mut().zip(&allocations) {
+            let mut ctxt = camera.params_ctxt()?;
+            program(&mut ctxt, allocation.throughput_limit)?;
+        }
+
+        Ok(allocations)
+    }
+
+    fn usable_bandwidth(&self, bus_number: Option<u8>, speed: BusSpeed) -> u64 {
+        if let Some(bandwidth) = bus_number.and_then(|bus| self.bus_bandwidth.get(&bus)) {
+            return *bandwidth;
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let bandwidth = (speed.bits_per_second() as f64 / 8.0 * self.efficiency) as u64;
+        bandwidth
+    }
+}
+
+/// Bandwidth demand of a camera.
+#[derive(Clone, Copy, Debug)]
+struct Demand {
+    /// Weight to share the bandwidth, `None` for a frame rate request.
+    weight: Option<f64>,
+    /// Required bandwidth for a frame rate request.
+    required: Option<i64>,
+    min: i64,
+    max: i64,
+    inc: i64,
+}
+
+impl Demand {
+    fn read<Ctrl, Ctxt>(
+        ctxt: &mut ParamsCtxt<Ctrl, Ctxt>,
+        request: BandwidthRequest,
+    ) -> CameleonResult<Self>
+    where
+        Ctrl: DeviceControl,
+        Ctxt: GenApiCtxt,
+    {
+        // Validate the mode here, it's enabled only after the demands of all cameras are read.
+        limit_mode(ctxt)?;
+        let limit = integer_node(ctxt, "DeviceLinkThroughputLimit")?;
+        let min = limit.min(ctxt)?;
+        let max = limit.max(ctxt)?;
+        let inc = limit.inc(ctxt)?.unwrap_or(1).max(1);
+
+        let (weight, required) = match request {
+            BandwidthRequest::Weight(weight) => (Some(weight.max(0.0)), None),
+            BandwidthRequest::FrameRate(frame_rate) => {
+                let payload_size = integer_node(ctxt, "PayloadSize")?.value(ctxt)?;
+                #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+                let required = (payload_size as f64 * frame_rate.max(0.0)).ceil() as i64;
+                (None, Some(required))
+            }
+        };
+
+        Ok(Self {
+            weight,
+            required,
+            min,
+            max,
+            inc,
+        })
+    }
+
+    /// Clamp the value to the range of the limit, the value is aligned to the increment.
+    fn clamp(&self, value: i64, round_up: bool) -> i64 {
+        let value = value.clamp(self.min, self.max);
+        let rem = (value - self.min) % self.inc;
+        if rem == 0 {
+            value
+        } else if round_up && value - rem + self.inc <= self.max {
+            value - rem + self.inc
+        } else {
+            value - rem
+        }
+    }
+}
+
+/// Split `bandwidth` among `demands`, returns the limits and whether the demands are feasible.
+fn split(bandwidth: u64, demands: &[Demand]) -> (Vec<i64>, bool) {
+    let bandwidth = i64::try_from(bandwidth).unwrap_or(i64::MAX);
+
+    // Every camera consumes at least the minimum of its limit.
+    let reserved: i64 = demands
+        .iter()
+        .map(|d| match d.required {
+            Some(required) => d.clamp(required, true),
+            None => d.min,
+        })
+        .sum();
+    let mut feasible = reserved <= bandwidth;
+
+    let mut limits: Vec<Option<i64>> = vec![None; demands.len()];
+    if reserved <= bandwidth {
+        for (limit, d) in limits.iter_mut().zip(demands) {
+            if let Some(required) = d.required {
+                *limit = Some(d.clamp(required, true));
+            }
+        }
+    } else {
+        // Scale frame rate requests down in proportion to the requests.
+        let weight_mins: i64 = demands
+            .iter()
+            .filter(|d| d.required.is_none())
+            .map(|d| d.min)
+            .sum();
+        let requested: i64 = demands.iter().filter_map(|d| d.required).sum();
+        let available = (bandwidth - weight_mins).max(0);
+        for (limit, d) in limits.iter_mut().zip(demands) {
+            if let Some(required) = d.required {
+                #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+                let scaled = if requested == 0 {
+                    0
+                } else {
+                    (required as f64 * available as f64 / requested as f64) as i64
+                };
+                *limit = Some(d.clamp(scaled, false));
+            }
+        }
+    }
+
+    // Share the rest among weight requests. Cameras reaching their maximum pass the excess to
+    // the others.
+    let mut rest = bandwidth - limits.iter().flatten().sum::<i64>();
+    loop {
+        let pending: Vec<_> = (0..demands.len())
+            .filter(|&i| limits[i].is_none())
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+
+        let total_weight: f64 = pending.iter().map(|&i| demands[i].weight.unwrap()).sum();
+        let share = |i: usize| -> i64 {
+            if total_weight > 0.0 {
+                #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+                let share = (rest.max(0) as f64 * demands[i].weight.unwrap() / total_weight) as i64;
+                share
+            } else {
+                0
+            }
+        };
+
+        let saturated: Vec<_> = pending
+            .iter()
+            .copied()
+            .filter(|&i| share(i) >= demands[i].max)
+            .collect();
+        if saturated.is_empty() {
+            for &i in &pending {
+                limits[i] = Some(demands[i].clamp(share(i), false));
+            }
+            break;
+        }
+        for i in saturated {
+            limits[i] = Some(demands[i].max);
+            rest -= demands[i].max;
+        }
+    }
+
+    let limits: Vec<i64> = limits.into_iter().map(Option::unwrap).collect();
+    feasible &= limits.iter().sum::<i64>() <= bandwidth;
+    (limits, feasible)
+}
+
+/// Returns `DeviceLinkThroughputLimitMode` which has `On` entry.
+fn limit_mode<Ctrl, Ctxt>(ctxt: &ParamsCtxt<Ctrl, Ctxt>) -> CameleonResult<EnumerationNode>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    let name = "DeviceLinkThroughputLimitMode";
+    let mode = ctxt
+        .node(name)
+        .and_then(|node| node.as_enumeration(ctxt))
+        .ok_or_else(|| missing_node(name))?;
+    if mode
+        .entries(ctxt)
+        .into_iter()
+        .any(|entry| entry.symbolic(ctxt) == "On")
+    {
+        Ok(mode)
+    } else {
+        Err(CameleonError::InvalidGenApiXml(
+            format!("`{}` doesn't have `On` entry", name).into(),
+        ))
+    }
+}
+
+fn program<Ctrl, Ctxt>(ctxt: &mut ParamsCtxt<Ctrl, Ctxt>, limit: i64) -> CameleonResult<()>
+where
+    Ctrl: DeviceControl,
+    Ctxt: GenApiCtxt,
+{
+    limit_mode(ctxt)?.set_entry_by_symbolic(ctxt, "On")?;
+    integer_node(ctxt, "DeviceLinkThroughputLimit")?.set_value(ctxt, limit)?;
+    Ok(())
+}
+
+fn integer_node<Ctrl, Ctxt>(
+    ctxt: &ParamsCtxt<Ctrl, Ctxt>,
+    name: &'static str,
+) -> CameleonResult<IntegerNode>
+where
+    Ctxt: GenApiCtxt,
+{
+    ctxt.node(name)
+        .and_then(|node| node.as_integer(ctxt))
+        .ok_or_else(|| missing_node(name))
+}
+
+fn missing_node(name: &'static str) -> CameleonError {
+    CameleonError::InvalidGenApiXml(
+        format!("`{}` is missing or has a wrong interface", name).into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(weight: f64, min: i64, max: i64, inc: i64) -> Demand {
+        Demand {
+            weight: Some(weight),
+            required: None,
+            min,
+            max,
+            inc,
+        }
+    }
+
+    fn frame_rate(required: i64, min: i64, max: i64, inc: i64) -> Demand {
+        Demand {
+            weight: None,
+            required: Some(required),
+            min,
+            max,
+            inc,
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let cases = vec![
+            // No camera.
+            (1000, vec![], vec![], true),
+            // Under-subscription.
+            (
+                1000,
+                vec![weight(1.0, 0, 1000, 1), weight(3.0, 0, 1000, 1)],
+                vec![250, 750],
+                true,
+            ),
+            (
+                1000,
+                vec![frame_rate(300, 0, 1000, 1), weight(1.0, 0, 1000, 1)],
+                vec![300, 700],
+                true,
+            ),
+            (
+                1000,
+                vec![frame_rate(900, 0, 1000, 1), weight(1.0, 100, 1000, 1)],
+                vec![900, 100],
+                true,
+            ),
+            // Over-subscription.
+            (
+                1000,
+                vec![frame_rate(600, 0, 1000, 1), frame_rate(600, 0, 1000, 1)],
+                vec![500, 500],
+                false,
+            ),
+            (
+                1000,
+                vec![frame_rate(1000, 0, 1000, 1), weight(1.0, 100, 1000, 1)],
+                vec![900, 100],
+                false,
+            ),
+            // Zero demand.
+            (
+                1000,
+                vec![weight(0.0, 10, 1000, 1), weight(0.0, 10, 1000, 1)],
+                vec![10, 10],
+                true,
+            ),
+            (
+                1000,
+                vec![frame_rate(0, 10, 1000, 1), weight(1.0, 10, 1000, 1)],
+                vec![10, 990],
+                true,
+            ),
+            // Min clamp.
+            (
+                1000,
+                vec![weight(1.0, 600, 1000, 1), weight(1.0, 600, 1000, 1)],
+                vec![600, 600],
+                false,
+            ),
+            // Max clamp, the excess is passed to the others.
+            (
+                1000,
+                vec![weight(1.0, 0, 200, 1), weight(1.0, 0, 1000, 1)],
+                vec![200, 800],
+                true,
+            ),
+            (
+                2000,
+                vec![frame_rate(5000, 0, 1000, 1), weight(1.0, 0, 500, 1)],
+                vec![1000, 500],
+                true,
+            ),
+            // Limits are aligned to the increment.
+            (
+                1000,
+                vec![frame_rate(250, 0, 1000, 100), weight(1.0, 0, 1000, 300)],
+                vec![300, 600],
+                true,
+            ),
+        ];
+
+        for (bandwidth, demands, limits, feasible) in cases {
+            assert_eq!(
+                split(bandwidth, &demands),
+                (limits, feasible),
+                "bandwidth: {}, demands: {:?}",
+                bandwidth,
+                demands
+            );
+        }
+    }
+
+    #[cfg(feature = "emulator")]
+    #[test]
+    fn test_allocate() {
+        use crate::{
+            emulator::{tests::open_camera, EmulatorBuilder},
+            u3v::{ControlHandle, StreamHandle},
+            CameraInfo,
+        };
+
+        // Two cameras share bus 1, and the other is connected to an unknown bus.
+        let mut cameras = vec![];
+        let mut handles = vec![];
+        for bus_number in [Some(1), Some(1), None] {
+            let (mut camera, handle) = open_camera(EmulatorBuilder::new());
+            camera.load_context().unwrap();
+            let info = CameraInfo {
+                bus_number,
+                ..camera.info().clone()
+            };
+            cameras.push(Camera::<ControlHandle, StreamHandle>::new(
+                camera.ctrl,
+                camera.strm,
+                camera.ctxt,
+                info,
+            ));
+            handle.set_speed(BusSpeed::HighSpeed).unwrap();
+            handles.push(handle);
+        }
+
+        let programmed = |camera: &mut Camera<ControlHandle, StreamHandle>| {
+            let mut ctxt = camera.params_ctxt().unwrap();
+            let mode = limit_mode(&ctxt).unwrap().current_entry(&mut ctxt).unwrap();
+            let limit = integer_node(&ctxt, "DeviceLinkThroughputLimit").unwrap();
+            (
+                mode.symbolic(&ctxt).to_string(),
+                limit.value(&mut ctxt).unwrap(),
+            )
+        };
+
+        let manager = BandwidthManager::new().bus_bandwidth(1, 100_000_000);
+        let requests = [
+            BandwidthRequest::Weight(1.0),
+            BandwidthRequest::Weight(3.0),
+            BandwidthRequest::Weight(1.0),
+        ];
+        let allocations = manager.allocate(&mut cameras, &requests).unwrap();
+        let expected = [
+            (Some(1), 100_000_000, 25_000_000),
+            (Some(1), 100_000_000, 75_000_000),
+            // 480 Mbps * 0.8 / 8.
+            (None, 48_000_000, 48_000_000),
+        ];
+        for ((camera, allocation), (bus_number, bus_bandwidth, limit)) in
+            cameras.iter_mut().zip(&allocations).zip(expected)
+        {
+            assert_eq!(
+                allocation,
+                &BandwidthAllocation {
+                    bus_number,
+                    bus_bandwidth,
+                    throughput_limit: limit,
+                    satisfied: true,
+                }
+            );
+            assert_eq!(programmed(camera), ("On".to_string(), limit));
+        }
+
+        // 640 * 480 bytes * 200 Hz exceeds the half of the bus bandwidth.
+        let requests = [
+            BandwidthRequest::FrameRate(200.0),
+            BandwidthRequest::FrameRate(200.0),
+            BandwidthRequest::Weight(1.0),
+        ];
+        let allocations = manager.allocate(&mut cameras, &requests).unwrap();
+        for (camera, allocation) in cameras.iter_mut().zip(&allocations).take(2) {
+            assert_eq!(allocation.throughput_limit, 50_000_000);
+            assert!(!allocation.satisfied);
+            assert_eq!(programmed(camera), ("On".to_string(), 50_000_000));
+        }
+        assert!(allocations[2].satisfied);
+    }
+}
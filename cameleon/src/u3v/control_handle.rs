@@ -7,15 +7,17 @@
 use std::{
     convert::TryInto,
     io::Read,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard},
     time::Duration,
 };
 
+use async_std::task;
 use cameleon_device::{
     u3v,
     u3v::protocol::{ack, cmd},
 };
-use tracing::error;
+use futures::future::BoxFuture;
+use tracing::{error, warn};
 
 use super::register_map::{self, Abrm, ManifestTable, Sbrm, Sirm};
 
@@ -35,6 +37,13 @@ const INITIAL_MAXIMUM_ACK_LENGTH: u32 = 128;
 
 const PAYLOAD_TRANSFER_SIZE: u32 = 1024 * 64;
 
+/// Timeout duration used to drain stale acknowledges from the control channel when the channel is
+/// resynchronized.
+const DRAIN_TIMEOUT_DURATION: Duration = Duration::from_millis(10);
+
+/// Maximum number of stale acknowledges drained in a single resynchronization.
+const MAXIMUM_DRAIN_COUNT: usize = 32;
+
 /// This handle provides low level API to read and write data from the device.  
 /// See [`ControlHandle::abrm`] and [`register_map`](super::register_map) which provide more
 /// convenient way to communicate with `u3v` specific registers.
@@ -64,10 +73,13 @@ const PAYLOAD_TRANSFER_SIZE: u32 = 1024 * 64;
 /// camera.ctrl.read(address, &mut buffer).unwrap();
 /// ```
 pub struct ControlHandle {
-    inner: u3v::ControlChannel,
+    /// Control channel, shared with blocking tasks spawned by asynchronous operations.
+    inner: Arc<Mutex<u3v::ControlChannel>>,
     config: ConnectionConfig,
     /// Request id of the next packet.
     next_req_id: u16,
+    /// `true` if the control channel must be resynchronized before sending the next command.
+    needs_resync: bool,
     /// Buffer for serializing/deserializing a packet.
     buffer: Vec<u8>,
 
@@ -84,6 +96,18 @@ pub struct ControlHandle {
     manifest_table: Option<ManifestTable>,
 }
 
+macro_rules! unwrap_or_log {
+    ($expr:expr) => {{
+        match $expr {
+            Ok(v) => v,
+            Err(error) => {
+                error!(?error);
+                return Err(error.into());
+            }
+        }
+    }};
+}
+
 impl ControlHandle {
     /// Capacity of the buffer inside [`ControlHandle`], the buffer is used for
     /// serializing/deserializing packet. This buffer automatically extend according to packet
@@ -119,19 +143,37 @@ impl ControlHandle {
         self.config.timeout_duration = duration;
     }
 
-    /// The value determines how many times to retry when pending acknowledge is returned from the
-    /// device.
+    /// The value determines how many times to retry when pending or stale acknowledge is returned
+    /// from the device.
     #[must_use]
     pub fn retry_count(&self) -> u16 {
         self.config.retry_count
     }
 
-    /// Set the value determines how many times to retry when pending acknowledge is returned from the
-    /// device.
+    /// Set the value determines how many times to retry when pending or stale acknowledge is
+    /// returned from the device.
     pub fn set_retry_count(&mut self, count: u16) {
         self.config.retry_count = count;
     }
 
+    /// The value determines how many times to resend an idempotent command, i.e. `ReadMem` and
+    /// `ReadMemStacked`, after the control channel is resynchronized due to a timeout, a stall,
+    /// or a broken acknowledge.
+    #[must_use]
+    pub fn recovery_count(&self) -> u16 {
+        self.config.recovery_count
+    }
+
+    /// Set the value determines how many times to resend an idempotent command, i.e. `ReadMem`
+    /// and `ReadMemStacked`, after the control channel is resynchronized due to a timeout, a
+    /// stall, or a broken acknowledge.
+    ///
+    /// Non-idempotent commands are never resent, but the control channel is resynchronized
+    /// anyway so that following commands work.
+    pub fn set_recovery_count(&mut self, count: u16) {
+        self.config.recovery_count = count;
+    }
+
     /// Returns the device info of the handle.
     pub fn device_info(&self) -> &u3v::DeviceInfo {
         &self.info
@@ -190,9 +232,10 @@ impl ControlHandle {
         let inner = device.control_channel()?;
 
         Ok(Self {
-            inner,
+            inner: Arc::new(Mutex::new(inner)),
             config: ConnectionConfig::default(),
             next_req_id: 0,
+            needs_resync: false,
             buffer: Vec::new(),
             info: device.device_info.clone(),
             abrm: None,
@@ -202,6 +245,10 @@ impl ControlHandle {
         })
     }
 
+    fn channel(&self) -> MutexGuard<'_, u3v::ControlChannel> {
+        self.inner.lock().unwrap()
+    }
+
     fn assert_open(&self) -> ControlResult<()> {
         if self.is_opened() {
             Ok(())
@@ -227,74 +274,381 @@ impl ControlHandle {
 
     fn send_cmd<'a, T, U>(&'a mut self, cmd: T) -> ControlResult<U>
     where
-        T: cmd::CommandScd,
+        T: cmd::CommandScd + Clone,
         U: ack::ParseScd<'a>,
     {
-        let cmd = cmd.finalize(self.next_req_id);
-        let cmd_len = cmd.cmd_len();
-        let ack_len = cmd.maximum_ack_len();
-        if self.buffer.len() < std::cmp::max(cmd_len, ack_len) {
-            self.buffer.resize(std::cmp::max(cmd_len, ack_len), 0);
+        let mut recovery_count = self.recovery_count_of(&cmd);
+        let recv_len = loop {
+            if self.needs_resync {
+                self.resync()?;
+            }
+
+            match self.transact(cmd.clone()) {
+                Ok(recv_len) => break recv_len,
+                Err(TransactionError::Desync(err)) => {
+                    // The channel is in an unknown state, resynchronize it before sending any
+                    // other commands.
+                    warn!(?err, "control channel is out of sync");
+                    self.needs_resync = true;
+                    if recovery_count == 0 {
+                        return Err(err);
+                    }
+                    recovery_count -= 1;
+                }
+                Err(TransactionError::Fatal(err)) => return Err(err),
+            }
+        };
+
+        self.parse_ack(recv_len)
+    }
+
+    /// Asynchronous version of [`ControlHandle::send_cmd`], returns the length of the received
+    /// acknowledge. Use [`ControlHandle::parse_ack`] to interpret the acknowledge.
+    async fn send_cmd_async<T>(&mut self, cmd: T) -> ControlResult<usize>
+    where
+        T: cmd::CommandScd + Clone,
+    {
+        let mut recovery_count = self.recovery_count_of(&cmd);
+        loop {
+            if self.needs_resync {
+                self.resync_async().await?;
+            }
+
+            match self.transact_async(cmd.clone()).await {
+                Ok(recv_len) => return Ok(recv_len),
+                Err(TransactionError::Desync(err)) => {
+                    warn!(?err, "control channel is out of sync");
+                    self.needs_resync = true;
+                    if recovery_count == 0 {
+                        return Err(err);
+                    }
+                    recovery_count -= 1;
+                }
+                Err(TransactionError::Fatal(err)) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns how many times the command can be resent after the channel is resynchronized.
+    fn recovery_count_of(&self, cmd: &impl cmd::CommandScd) -> u16 {
+        if is_idempotent(cmd) {
+            self.config.recovery_count
+        } else {
+            0
         }
+    }
+
+    fn parse_ack<'a, U>(&'a self, recv_len: usize) -> ControlResult<U>
+    where
+        U: ack::ParseScd<'a>,
+    {
+        // `ack::AckPacket::parse` is a fast operation, so it's ok to call it repeatedly.
+        Ok(ack::AckPacket::parse(&self.buffer[0..recv_len])
+            .unwrap()
+            .scd_as()?)
+    }
 
-        // Serialize and send command.
-        cmd.serialize(self.buffer.as_mut_slice())?;
+    /// Sends a command and receives the corresponding acknowledge, then returns the length of the
+    /// received acknowledge.
+    fn transact<T>(&mut self, cmd: T) -> Result<usize, TransactionError>
+    where
+        T: cmd::CommandScd,
+    {
+        let is_idempotent = is_idempotent(&cmd);
+        let cmd_len = self.serialize_cmd(cmd)?;
         self.inner
-            .send(&self.buffer[..cmd_len], self.config.timeout_duration)?;
+            .lock()
+            .unwrap()
+            .send(&self.buffer[..cmd_len], self.config.timeout_duration)
+            .map_err(TransactionError::from_io)?;
 
         // Receive ack and interpret the packet.
         let mut retry_count = self.config.retry_count;
-        let mut ok = None;
         while retry_count > 0 {
             let recv_len = self
                 .inner
-                .recv(&mut self.buffer, self.config.timeout_duration)?;
+                .lock()
+                .unwrap()
+                .recv(&mut self.buffer, self.config.timeout_duration)
+                .map_err(TransactionError::from_io)?;
+
+            match self.process_ack(recv_len)? {
+                AckState::Completed => return Ok(recv_len),
+                AckState::Pending(timeout) => {
+                    // Retry up to retry count.
+                    std::thread::sleep(timeout);
+                    retry_count -= 1;
+                }
+                AckState::Stale => retry_count -= 1,
+            }
+        }
 
-            let ack = ack::AckPacket::parse(&self.buffer[0..recv_len])?;
-            self.verify_ack(&ack)?;
+        Err(self.retry_exceeded(is_idempotent))
+    }
 
-            // Retry up to retry count.
-            if ack.scd_kind() == ack::ScdKind::Pending {
-                let pending_ack: ack::Pending = ack.scd_as()?;
-                std::thread::sleep(pending_ack.timeout);
-                retry_count -= 1;
-                continue;
+    /// Asynchronous version of [`ControlHandle::transact`].
+    async fn transact_async<T>(&mut self, cmd: T) -> Result<usize, TransactionError>
+    where
+        T: cmd::CommandScd,
+    {
+        let is_idempotent = is_idempotent(&cmd);
+        let cmd_len = self.serialize_cmd(cmd)?;
+        // The future may be dropped at any await point, then the acknowledge of the command may
+        // be left in the channel. The channel is regarded as out of sync until the acknowledge is
+        // received.
+        self.needs_resync = true;
+        let channel = self.inner.clone();
+        let data = self.buffer[..cmd_len].to_vec();
+        let timeout = self.config.timeout_duration;
+        task::spawn_blocking(move || channel.lock().unwrap().send(&data, timeout))
+            .await
+            .map_err(TransactionError::from_io)?;
+
+        let mut retry_count = self.config.retry_count;
+        while retry_count > 0 {
+            let recv_len = self
+                .recv_async(self.config.timeout_duration)
+                .await
+                .map_err(TransactionError::from_io)?;
+
+            let state = self.process_ack(recv_len)?;
+            match state {
+                AckState::Completed => return Ok(recv_len),
+                AckState::Pending(timeout) => {
+                    task::sleep(timeout).await;
+                    retry_count -= 1;
+                }
+                AckState::Stale => retry_count -= 1,
             }
+        }
 
-            self.next_req_id = self.next_req_id.wrapping_add(1);
-            ok = Some(recv_len);
-            break;
+        Err(self.retry_exceeded(is_idempotent))
+    }
+
+    /// Serializes the command into the internal buffer, then returns the length of the command.
+    fn serialize_cmd<T>(&mut self, cmd: T) -> Result<usize, TransactionError>
+    where
+        T: cmd::CommandScd,
+    {
+        let cmd = cmd.finalize(self.next_req_id);
+        let cmd_len = cmd.cmd_len();
+        let ack_len = cmd.maximum_ack_len();
+        if self.buffer.len() < std::cmp::max(cmd_len, ack_len) {
+            self.buffer.resize(std::cmp::max(cmd_len, ack_len), 0);
         }
 
-        // This codes seems weird due to a lifetime problem.
-        // `ack::AckPacket::parse` is a fast operation, so it's ok to call it repeatedly.
-        if let Some(recv_len) = ok {
-            Ok(ack::AckPacket::parse(&self.buffer[0..recv_len])
-                .unwrap()
-                .scd_as()?)
+        cmd.serialize(self.buffer.as_mut_slice())
+            .map_err(|e| TransactionError::Fatal(e.into()))?;
+        Ok(cmd_len)
+    }
+
+    /// Returns an error for a command whose acknowledge didn't arrive within the retry count.
+    ///
+    /// The device may have already processed the command, so a non idempotent command fails
+    /// without being resent. The channel is resynchronized before the next command in either
+    /// case.
+    fn retry_exceeded(&mut self, is_idempotent: bool) -> TransactionError {
+        let err = ControlError::Io(anyhow::Error::msg(
+            "the number of pending or stale acknowledges exceeds the retry_count",
+        ));
+        if is_idempotent {
+            TransactionError::Desync(err)
         } else {
-            Err(ControlError::Io(anyhow::Error::msg(
-                "the number of times pending was returned exceeds the retry_count.",
-            )))
+            self.needs_resync = true;
+            TransactionError::Fatal(err)
         }
     }
 
-    fn verify_ack(&self, ack: &ack::AckPacket) -> ControlResult<()> {
-        let status = ack.status().kind();
-        if status != ack::StatusKind::GenCp(ack::GenCpStatus::Success) {
-            return Err(ControlError::Io(anyhow::Error::msg(format!(
-                "invalid status: {:?}",
-                ack.status().kind()
-            ))));
-        }
+    /// Interprets the acknowledge received in the internal buffer.
+    fn process_ack(&mut self, recv_len: usize) -> Result<AckState, TransactionError> {
+        use TransactionError::{Desync, Fatal};
+
+        let ack = ack::AckPacket::parse(&self.buffer[0..recv_len]).map_err(|e| Desync(e.into()))?;
 
+        // Discard a stale acknowledge which corresponds to a previous command.
         if ack.request_id() != self.next_req_id {
-            return Err(ControlError::Io(anyhow::Error::msg("request id mismatch")));
+            warn!(
+                request_id = ack.request_id(),
+                expected = self.next_req_id,
+                "discard stale acknowledge"
+            );
+            return Ok(AckState::Stale);
+        }
+
+        if ack.scd_kind() == ack::ScdKind::Pending {
+            let pending_ack: ack::Pending = ack.scd_as().map_err(|e| Desync(e.into()))?;
+            return Ok(AckState::Pending(pending_ack.timeout));
+        }
+
+        // The last acknowledge of the command is received, so the channel is in sync and the
+        // request id must advance even if the device reports an error.
+        self.needs_resync = false;
+        self.next_req_id = self.next_req_id.wrapping_add(1);
+        self.verify_ack(&ack).map_err(Fatal)?;
+
+        Ok(AckState::Completed)
+    }
+
+    /// Receives a packet into the internal buffer without blocking the async executor.
+    ///
+    /// The packet is received into a temporary buffer, so the internal buffer is left intact even
+    /// if the future is dropped.
+    async fn recv_async(&mut self, timeout: Duration) -> u3v::Result<usize> {
+        let channel = self.inner.clone();
+        let mut buf = vec![0; self.buffer.len()];
+        let (result, buf) = task::spawn_blocking(move || {
+            let result = channel.lock().unwrap().recv(&mut buf, timeout);
+            (result, buf)
+        })
+        .await;
+        if let Ok(len) = result {
+            self.buffer[..len].copy_from_slice(&buf[..len]);
+        }
+        result
+    }
+
+    /// Resynchronizes the control channel.
+    ///
+    /// This drains stale acknowledges left in the channel, clears halts of the endpoints, and
+    /// realigns the request id so that a late acknowledge of the failed command is never taken
+    /// as the acknowledge of a following command.
+    fn resync(&mut self) -> ControlResult<()> {
+        for _ in 0..MAXIMUM_DRAIN_COUNT {
+            if self
+                .inner
+                .lock()
+                .unwrap()
+                .recv(&mut self.buffer, DRAIN_TIMEOUT_DURATION)
+                .is_err()
+            {
+                break;
+            }
         }
 
+        unwrap_or_log!(self.channel().clear_halt());
+        self.next_req_id = self.next_req_id.wrapping_add(1);
+        self.needs_resync = false;
+
         Ok(())
     }
 
+    /// Asynchronous version of [`ControlHandle::resync`].
+    async fn resync_async(&mut self) -> ControlResult<()> {
+        for _ in 0..MAXIMUM_DRAIN_COUNT {
+            if self.recv_async(DRAIN_TIMEOUT_DURATION).await.is_err() {
+                break;
+            }
+        }
+
+        let channel = self.inner.clone();
+        unwrap_or_log!(task::spawn_blocking(move || channel.lock().unwrap().clear_halt()).await);
+        self.next_req_id = self.next_req_id.wrapping_add(1);
+        self.needs_resync = false;
+
+        Ok(())
+    }
+
+    /// Returns the maximum length of data read by a single `ReadMem` command.
+    ///
+    /// Returns an error if the maximum acknowledge length of the device is too small to carry any
+    /// data, otherwise reading would never make progress.
+    fn maximum_read_length(&self) -> ControlResult<usize> {
+        match cmd::ReadMem::maximum_read_length(self.config.maximum_ack_length as usize) {
+            0 => Err(ControlError::InvalidDevice(
+                "maximum acknowledge length is too small to receive `ReadMem`".into(),
+            )),
+            len => Ok(len as usize),
+        }
+    }
+
+    /// Splits `WriteMem` command so that each chunk fits into a single command.
+    fn write_mem_chunks<'a>(
+        &self,
+        cmd: &cmd::WriteMem<'a>,
+    ) -> ControlResult<cmd::WriteMemChunks<'a>> {
+        cmd.chunks(self.config.maximum_cmd_length as usize)
+            .map_err(|_| {
+                ControlError::InvalidDevice(
+                    "maximum command length is too small to send `WriteMem`".into(),
+                )
+            })
+    }
+
+    /// Splits each entry into regions so that each region fits into a single ack, then packs
+    /// them into `ReadMemStacked` commands.
+    ///
+    fn pack_read_stacked(
+        &self,
+        entries: &[(u64, usize)],
+    ) -> ControlResult<(Vec<ReadRegion>, Vec<cmd::ReadMemStacked>)> {
+        let maximum_read_length = self.maximum_read_length()?;
+        let mut regions = vec![];
+        let mut read_mems = vec![];
+        for (idx, &(address, len)) in entries.iter().enumerate() {
+            let mut offset = 0;
+            while offset < len {
+                let read_len = std::cmp::min(len - offset, maximum_read_length);
+                read_mems.push(cmd::ReadMem::new(
+                    address + offset as u64,
+                    read_len.try_into().unwrap(),
+                ));
+                regions.push((idx, offset, read_len));
+                offset += read_len;
+            }
+        }
+
+        let packed = cmd::ReadMemStacked::pack(
+            read_mems,
+            self.config.maximum_cmd_length as usize,
+            self.config.maximum_ack_length as usize,
+        )?;
+        Ok((regions, packed))
+    }
+
+    /// Splits each entry so that each entry fits into a single command, then packs them into
+    /// `WriteMemStacked` commands.
+    fn pack_write_stacked<'a>(
+        &self,
+        entries: &[(u64, &'a [u8])],
+    ) -> ControlResult<Vec<cmd::WriteMemStacked<'a>>> {
+        let maximum_cmd_length = self.config.maximum_cmd_length as usize;
+        let maximum_data_length = cmd::WriteMemStacked::maximum_data_length(maximum_cmd_length);
+        if maximum_data_length == 0 {
+            return Err(ControlError::InvalidDevice(
+                "maximum command length is too small to send `WriteMemStacked`".into(),
+            ));
+        }
+        let mut write_mems = vec![];
+        for &(address, data) in entries {
+            for (i, chunk) in data.chunks(maximum_data_length).enumerate() {
+                let chunk_address = address + (i * maximum_data_length) as u64;
+                write_mems.push(cmd::WriteMem::new(chunk_address, chunk)?);
+            }
+        }
+
+        Ok(cmd::WriteMemStacked::pack(
+            write_mems,
+            maximum_cmd_length,
+            self.config.maximum_ack_length as usize,
+        )?)
+    }
+
+    fn verify_ack(&self, ack: &ack::AckPacket) -> ControlResult<()> {
+        let status = *ack.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(ControlError::DeviceStatus(status.into()))
+        }
+    }
+
+    fn is_stacked_commands_supported(&mut self) -> ControlResult<bool> {
+        Ok(self
+            .abrm()?
+            .device_capability()?
+            .is_stacked_commands_supported())
+    }
+
     fn verify_xml(&mut self, xml: &[u8], ent: register_map::ManifestEntry) -> ControlResult<()> {
         use sha1::Digest;
 
@@ -313,40 +667,28 @@ impl ControlHandle {
     }
 }
 
-macro_rules! unwrap_or_log {
-    ($expr:expr) => {{
-        match $expr {
-            Ok(v) => v,
-            Err(error) => {
-                error!(?error);
-                return Err(error.into());
-            }
-        }
-    }};
-}
-
 impl DeviceControl for ControlHandle {
     fn open(&mut self) -> ControlResult<()> {
         if self.is_opened() {
             return Ok(());
         }
 
-        unwrap_or_log!(self.inner.open());
+        unwrap_or_log!(self.channel().open());
         // Clean up control channel state.
-        unwrap_or_log!(self.inner.set_halt(self.config.timeout_duration));
-        unwrap_or_log!(self.inner.clear_halt());
+        unwrap_or_log!(self.channel().set_halt(self.config.timeout_duration));
+        unwrap_or_log!(self.channel().clear_halt());
         unwrap_or_log!(self.initialize_config());
 
         Ok(())
     }
 
     fn is_opened(&self) -> bool {
-        self.inner.is_opened()
+        self.channel().is_opened()
     }
 
     fn close(&mut self) -> ControlResult<()> {
         if self.is_opened() {
-            unwrap_or_log!(self.inner.close());
+            unwrap_or_log!(self.channel().close());
         }
         Ok(())
     }
@@ -355,16 +697,11 @@ impl DeviceControl for ControlHandle {
         unwrap_or_log!(self.assert_open());
 
         let cmd = unwrap_or_log!(cmd::WriteMem::new(address, data));
-        let maximum_cmd_length = self.config.maximum_cmd_length;
-
-        for chunk in cmd.chunks(maximum_cmd_length as usize).unwrap() {
+        let chunks = unwrap_or_log!(self.write_mem_chunks(&cmd));
+        for chunk in chunks {
             let chunk_data_len = chunk.data_len();
             let ack: ack::WriteMem = unwrap_or_log!(self.send_cmd(chunk));
-
-            if ack.length as usize != chunk_data_len {
-                let err_msg = "write mem failed: written length mismatch";
-                return Err(ControlError::Io(anyhow::Error::msg(err_msg)));
-            }
+            verify_written_lengths(&[ack.length], &[chunk_data_len])?;
         }
 
         Ok(())
@@ -375,10 +712,8 @@ impl DeviceControl for ControlHandle {
 
         // Chunks buffer if buffer length is larger than maximum read length calculated from
         // maximum ack length.
-        for buf_chunk in buf.chunks_mut(cmd::ReadMem::maximum_read_length(
-            self.config.maximum_ack_length as usize,
-        ) as usize)
-        {
+        let maximum_read_length = unwrap_or_log!(self.maximum_read_length());
+        for buf_chunk in buf.chunks_mut(maximum_read_length) {
             let read_len: u16 = buf_chunk.len().try_into().unwrap();
 
             let cmd = cmd::ReadMem::new(address, read_len);
@@ -390,6 +725,47 @@ impl DeviceControl for ControlHandle {
         Ok(())
     }
 
+    fn read_stacked(&mut self, entries: &[(u64, usize)]) -> ControlResult<Vec<Vec<u8>>> {
+        unwrap_or_log!(self.assert_open());
+
+        let mut bufs: Vec<Vec<u8>> = entries.iter().map(|&(_, len)| vec![0; len]).collect();
+        if !unwrap_or_log!(self.is_stacked_commands_supported()) {
+            for (&(address, _), buf) in entries.iter().zip(bufs.iter_mut()) {
+                unwrap_or_log!(self.read(address, buf));
+            }
+            return Ok(bufs);
+        }
+
+        let (regions, packed) = unwrap_or_log!(self.pack_read_stacked(entries));
+        let mut regions = regions.into_iter();
+        for cmd in packed {
+            let entry_num = cmd.entries().len();
+            let ack: ack::ReadMemStacked = unwrap_or_log!(self.send_cmd(cmd));
+            scatter_read_data(&mut bufs, regions.by_ref().take(entry_num), ack.data)?;
+        }
+
+        Ok(bufs)
+    }
+
+    fn write_stacked(&mut self, entries: &[(u64, &[u8])]) -> ControlResult<()> {
+        unwrap_or_log!(self.assert_open());
+
+        if !unwrap_or_log!(self.is_stacked_commands_supported()) {
+            for &(address, data) in entries {
+                unwrap_or_log!(self.write(address, data));
+            }
+            return Ok(());
+        }
+
+        for cmd in unwrap_or_log!(self.pack_write_stacked(entries)) {
+            let data_lens: Vec<usize> = cmd.entries().iter().map(cmd::WriteMem::data_len).collect();
+            let ack: ack::WriteMemStacked = unwrap_or_log!(self.send_cmd(cmd));
+            verify_written_lengths(&ack.lengths, &data_lens)?;
+        }
+
+        Ok(())
+    }
+
     fn genapi(&mut self) -> ControlResult<String> {
         fn zip_err(err: impl std::fmt::Debug) -> ControlError {
             ControlError::InvalidDevice(format!("zipped xml file is broken: {:?}", err).into())
@@ -494,6 +870,111 @@ impl DeviceControl for ControlHandle {
     }
 }
 
+impl ControlHandle {
+    async fn read_async(&mut self, mut address: u64, buf: &mut [u8]) -> ControlResult<()> {
+        unwrap_or_log!(self.assert_open());
+
+        let maximum_read_length = unwrap_or_log!(self.maximum_read_length());
+        for buf_chunk in buf.chunks_mut(maximum_read_length) {
+            let read_len: u16 = buf_chunk.len().try_into().unwrap();
+
+            let cmd = cmd::ReadMem::new(address, read_len);
+            let recv_len = unwrap_or_log!(self.send_cmd_async(cmd).await);
+            let ack: ack::ReadMem = unwrap_or_log!(self.parse_ack(recv_len));
+            buf_chunk.copy_from_slice(ack.data);
+            address += read_len as u64;
+        }
+
+        Ok(())
+    }
+
+    async fn write_async(&mut self, address: u64, data: &[u8]) -> ControlResult<()> {
+        unwrap_or_log!(self.assert_open());
+
+        let cmd = unwrap_or_log!(cmd::WriteMem::new(address, data));
+        let chunks = unwrap_or_log!(self.write_mem_chunks(&cmd));
+        for chunk in chunks {
+            let chunk_data_len = chunk.data_len();
+            let recv_len = unwrap_or_log!(self.send_cmd_async(chunk).await);
+            let ack: ack::WriteMem = unwrap_or_log!(self.parse_ack(recv_len));
+            verify_written_lengths(&[ack.length], &[chunk_data_len])?;
+        }
+
+        Ok(())
+    }
+
+    async fn read_stacked_async(
+        &mut self,
+        entries: &[(u64, usize)],
+    ) -> ControlResult<Vec<Vec<u8>>> {
+        unwrap_or_log!(self.assert_open());
+
+        let mut bufs: Vec<Vec<u8>> = entries.iter().map(|&(_, len)| vec![0; len]).collect();
+        if !unwrap_or_log!(self.is_stacked_commands_supported()) {
+            for (&(address, _), buf) in entries.iter().zip(bufs.iter_mut()) {
+                unwrap_or_log!(self.read_async(address, buf).await);
+            }
+            return Ok(bufs);
+        }
+
+        let (regions, packed) = unwrap_or_log!(self.pack_read_stacked(entries));
+        let mut regions = regions.into_iter();
+        for cmd in packed {
+            let entry_num = cmd.entries().len();
+            let recv_len = unwrap_or_log!(self.send_cmd_async(cmd).await);
+            let ack: ack::ReadMemStacked = unwrap_or_log!(self.parse_ack(recv_len));
+            scatter_read_data(&mut bufs, regions.by_ref().take(entry_num), ack.data)?;
+        }
+
+        Ok(bufs)
+    }
+
+    async fn write_stacked_async(&mut self, entries: &[(u64, &[u8])]) -> ControlResult<()> {
+        unwrap_or_log!(self.assert_open());
+
+        if !unwrap_or_log!(self.is_stacked_commands_supported()) {
+            for &(address, data) in entries {
+                unwrap_or_log!(self.write_async(address, data).await);
+            }
+            return Ok(());
+        }
+
+        let packed = unwrap_or_log!(self.pack_write_stacked(entries));
+        for cmd in packed {
+            let data_lens: Vec<usize> = cmd.entries().iter().map(cmd::WriteMem::data_len).collect();
+            let recv_len = unwrap_or_log!(self.send_cmd_async(cmd).await);
+            let ack: ack::WriteMemStacked = unwrap_or_log!(self.parse_ack(recv_len));
+            verify_written_lengths(&ack.lengths, &data_lens)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl crate::AsyncDeviceControl for ControlHandle {
+    fn read<'a>(&'a mut self, address: u64, buf: &'a mut [u8]) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(self.read_async(address, buf))
+    }
+
+    fn write<'a>(&'a mut self, address: u64, data: &'a [u8]) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(self.write_async(address, data))
+    }
+
+    fn read_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, usize)],
+    ) -> BoxFuture<'a, ControlResult<Vec<Vec<u8>>>> {
+        Box::pin(self.read_stacked_async(entries))
+    }
+
+    fn write_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, &'a [u8])],
+    ) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(self.write_stacked_async(entries))
+    }
+}
+
 impl Drop for ControlHandle {
     fn drop(&mut self) {
         if let Err(e) = self.close() {
@@ -503,8 +984,11 @@ impl Drop for ControlHandle {
 }
 
 /// Thread safe version of [`ControlHandle`].
+///
+/// The inner handle is guarded by an asynchronous mutex, so that asynchronous operations wait for
+/// the handle without blocking the async executor.
 #[derive(Clone)]
-pub struct SharedControlHandle(Arc<Mutex<ControlHandle>>);
+pub struct SharedControlHandle(Arc<async_std::sync::Mutex<ControlHandle>>);
 
 macro_rules! impl_shared_control_handle {
     ($(
@@ -513,7 +997,7 @@ macro_rules! impl_shared_control_handle {
         $(
             $(#[$meta])*
             $vis fn $method(&$self, $($arg: $arg_ty),*) -> $ret_ty {
-                $self.0.lock().unwrap().$method($($arg),*)
+                task::block_on($self.0.lock()).$method($($arg),*)
             }
         )*
     };
@@ -524,7 +1008,7 @@ macro_rules! impl_shared_control_handle {
         $(
             $(#[$meta])*
             $vis fn $method(&mut $self, $($arg: $arg_ty),*) -> $ret_ty {
-                $self.0.lock().unwrap().$method($($arg),*)
+                task::block_on($self.0.lock()).$method($($arg),*)
             }
         )*
     }
@@ -532,7 +1016,7 @@ macro_rules! impl_shared_control_handle {
 
 impl From<ControlHandle> for SharedControlHandle {
     fn from(handle: ControlHandle) -> Self {
-        Self(Arc::new(Mutex::new(handle)))
+        Self(Arc::new(async_std::sync::Mutex::new(handle)))
     }
 }
 
@@ -552,12 +1036,17 @@ impl SharedControlHandle {
         #[must_use]
         pub fn retry_count(&self) -> u16,
         /// Thread safe version of [`ControlHandle::set_retry_count`].
-        pub fn set_retry_count(&self, count: u16) -> ()
+        pub fn set_retry_count(&self, count: u16) -> (),
+        /// Thread safe version of [`ControlHandle::recovery_count`].
+        #[must_use]
+        pub fn recovery_count(&self) -> u16,
+        /// Thread safe version of [`ControlHandle::set_recovery_count`].
+        pub fn set_recovery_count(&self, count: u16) -> ()
     );
 
     /// Returns the device info of the handle.
     pub fn device_info(&self) -> u3v::DeviceInfo {
-        self.0.lock().unwrap().device_info().clone()
+        task::block_on(self.0.lock()).device_info().clone()
     }
 }
 
@@ -571,12 +1060,40 @@ impl DeviceControl for SharedControlHandle {
         fn close(&mut self) -> ControlResult<()>,
         fn read(&mut self, address: u64, buf: &mut [u8]) -> ControlResult<()>,
         fn write(&mut self, address: u64, data: &[u8]) -> ControlResult<()>,
+        fn read_stacked(&mut self, entries: &[(u64, usize)]) -> ControlResult<Vec<Vec<u8>>>,
+        fn write_stacked(&mut self, entries: &[(u64, &[u8])]) -> ControlResult<()>,
         fn genapi(&mut self) -> ControlResult<String>,
         fn enable_streaming(&mut self) -> ControlResult<()>,
         fn disable_streaming(&mut self) -> ControlResult<()>
     }
 }
 
+impl crate::SharedDeviceControl for SharedControlHandle {}
+
+impl crate::AsyncDeviceControl for SharedControlHandle {
+    fn read<'a>(&'a mut self, address: u64, buf: &'a mut [u8]) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(async move { self.0.lock().await.read_async(address, buf).await })
+    }
+
+    fn write<'a>(&'a mut self, address: u64, data: &'a [u8]) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(async move { self.0.lock().await.write_async(address, data).await })
+    }
+
+    fn read_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, usize)],
+    ) -> BoxFuture<'a, ControlResult<Vec<Vec<u8>>>> {
+        Box::pin(async move { self.0.lock().await.read_stacked_async(entries).await })
+    }
+
+    fn write_stacked<'a>(
+        &'a mut self,
+        entries: &'a [(u64, &'a [u8])],
+    ) -> BoxFuture<'a, ControlResult<()>> {
+        Box::pin(async move { self.0.lock().await.write_stacked_async(entries).await })
+    }
+}
+
 struct ConnectionConfig {
     /// Timeout duration of each transaction between device.
     timeout_duration: Duration,
@@ -585,6 +1102,10 @@ struct ConnectionConfig {
     /// device.
     retry_count: u16,
 
+    /// The value determines how many times to resend an idempotent command after the control
+    /// channel is resynchronized.
+    recovery_count: u16,
+
     /// Maximum length of a command sent to device from host. Unit is byte.
     maximum_cmd_length: u32,
 
@@ -597,12 +1118,86 @@ impl Default for ConnectionConfig {
         Self {
             timeout_duration: INITIAL_TIMEOUT_DURATION,
             retry_count: 3,
+            recovery_count: 3,
             maximum_cmd_length: INITIAL_MAXIMUM_CMD_LENGTH,
             maximum_ack_length: INITIAL_MAXIMUM_ACK_LENGTH,
         }
     }
 }
 
+/// A region of a `ReadMemStacked` entry, represented as
+/// `(index of the entry, offset in the entry, length)`.
+type ReadRegion = (usize, usize, usize);
+
+/// Copies data returned by a `ReadMemStacked` command into the buffers of the corresponding
+/// regions.
+fn scatter_read_data(
+    bufs: &mut [Vec<u8>],
+    regions: impl Iterator<Item = ReadRegion>,
+    mut data: &[u8],
+) -> ControlResult<()> {
+    for (idx, offset, read_len) in regions {
+        if data.len() < read_len {
+            let err_msg = "read mem stacked failed: returned data is too short";
+            return Err(ControlError::Io(anyhow::Error::msg(err_msg)));
+        }
+        bufs[idx][offset..offset + read_len].copy_from_slice(&data[..read_len]);
+        data = &data[read_len..];
+    }
+    Ok(())
+}
+
+fn verify_written_lengths(written: &[u16], expected: &[usize]) -> ControlResult<()> {
+    if written.len() != expected.len()
+        || written
+            .iter()
+            .zip(expected)
+            .any(|(&written, &expected)| written as usize != expected)
+    {
+        let err_msg = "write mem failed: written length mismatch";
+        Err(ControlError::Io(anyhow::Error::msg(err_msg)))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns `true` if the command can be resent without side effects.
+fn is_idempotent(cmd: &impl cmd::CommandScd) -> bool {
+    matches!(
+        cmd.scd_kind(),
+        cmd::ScdKind::ReadMem | cmd::ScdKind::ReadMemStacked
+    )
+}
+
+/// A state of the control channel after an acknowledge is received.
+enum AckState {
+    /// The command is completed.
+    Completed,
+    /// The device needs more time to complete the command.
+    Pending(Duration),
+    /// The acknowledge corresponds to a previous command.
+    Stale,
+}
+
+/// An error occurred in a single transaction.
+enum TransactionError {
+    /// The control channel may be out of sync, e.g. the device didn't respond in time, the
+    /// endpoint is stalled, or the acknowledge is broken.
+    Desync(ControlError),
+    /// The command must not be resent, e.g. the device returned an error status.
+    Fatal(ControlError),
+}
+
+impl TransactionError {
+    fn from_io(err: u3v::Error) -> Self {
+        let err: ControlError = err.into();
+        match err {
+            ControlError::Timeout | ControlError::Io(_) => Self::Desync(err),
+            _ => Self::Fatal(err),
+        }
+    }
+}
+
 impl From<SharedControlHandle> for Box<dyn DeviceControl> {
     fn from(ctrl: SharedControlHandle) -> Self {
         Box::new(ctrl)
@@ -614,3 +1209,184 @@ impl From<ControlHandle> for Box<dyn DeviceControl> {
         Box::new(ctrl)
     }
 }
+
+#[cfg(all(test, feature = "emulator"))]
+mod tests {
+    use super::*;
+    use crate::emulator::{
+        tests::open_camera, ControlFault, EmulatorBuilder, EmulatorHandle, FaultInjector,
+        LoggedCommand,
+    };
+
+    fn setup() -> (ControlHandle, EmulatorHandle, FaultInjector) {
+        let builder = EmulatorBuilder::new();
+        let faults = builder.fault_injector();
+        let (camera, handle) = open_camera(builder);
+        let mut ctrl = camera.ctrl;
+        ctrl.set_timeout_duration(Duration::from_millis(200));
+        (ctrl, handle, faults)
+    }
+
+    fn read_mem_count(handle: &EmulatorHandle) -> usize {
+        handle
+            .command_log()
+            .iter()
+            .filter(|cmd| matches!(cmd, LoggedCommand::ReadMem { .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_recover_from_dropped_ack() {
+        let (mut ctrl, handle, faults) = setup();
+        let abrm = ctrl.abrm().unwrap();
+        let serial_number = abrm.serial_number(&mut ctrl).unwrap();
+
+        handle.clear_command_log();
+        faults.inject_control(ControlFault::DropAck);
+        assert_eq!(abrm.serial_number(&mut ctrl).unwrap(), serial_number);
+        // `ReadMem` is resent after the channel is resynchronized.
+        assert_eq!(read_mem_count(&handle), 2);
+        assert!(!ctrl.needs_resync);
+    }
+
+    #[test]
+    fn test_skip_stale_ack() {
+        let (mut ctrl, handle, faults) = setup();
+        let abrm = ctrl.abrm().unwrap();
+        let serial_number = abrm.serial_number(&mut ctrl).unwrap();
+
+        // The acknowledge with a wrong request id is discarded, then `ReadMem` is resent.
+        handle.clear_command_log();
+        faults.inject_control(ControlFault::CorruptRequestId);
+        assert_eq!(abrm.serial_number(&mut ctrl).unwrap(), serial_number);
+        assert_eq!(read_mem_count(&handle), 2);
+    }
+
+    #[test]
+    fn test_error_ack_advances_request_id() {
+        let (mut ctrl, _handle, _faults) = setup();
+        let abrm = ctrl.abrm().unwrap();
+        let serial_number = abrm.serial_number(&mut ctrl).unwrap();
+
+        // The device rejects a read from an unmapped address.
+        let req_id = ctrl.next_req_id;
+        let mut buf = [0; 4];
+        assert!(ctrl.read(0xffff_ffff_0000, &mut buf).is_err());
+        assert_eq!(ctrl.next_req_id, req_id.wrapping_add(1));
+        let error_ack = ctrl.buffer.clone();
+
+        // The next command succeeds.
+        assert_eq!(abrm.serial_number(&mut ctrl).unwrap(), serial_number);
+
+        // A replayed acknowledge of the rejected command is discarded.
+        let len = error_ack.len();
+        ctrl.buffer = error_ack;
+        assert!(matches!(ctrl.process_ack(len), Ok(AckState::Stale)));
+    }
+
+    #[test]
+    fn test_stale_ack_consumes_retry_count() {
+        let (mut ctrl, handle, faults) = setup();
+        ctrl.set_retry_count(1);
+        let abrm = ctrl.abrm().unwrap();
+
+        // `ReadMem` is resent immediately without waiting for the timeout.
+        handle.clear_command_log();
+        faults.inject_control(ControlFault::CorruptRequestId);
+        let now = std::time::Instant::now();
+        assert!(abrm.serial_number(&mut ctrl).is_ok());
+        assert!(now.elapsed() < ctrl.timeout_duration());
+        assert_eq!(read_mem_count(&handle), 2);
+
+        // `WriteMem` fails without being resent.
+        handle.clear_command_log();
+        faults.inject_control(ControlFault::CorruptRequestId);
+        assert!(abrm.set_user_defined_name(&mut ctrl, "stale").is_err());
+        assert!(matches!(
+            handle.command_log().as_slice(),
+            [LoggedCommand::WriteMem { .. }]
+        ));
+
+        // The channel is resynchronized before the next command.
+        assert!(ctrl.needs_resync);
+        assert_eq!(
+            abrm.user_defined_name(&mut ctrl).unwrap().as_deref(),
+            Some("stale")
+        );
+    }
+
+    #[test]
+    fn test_no_write_mem_retry() {
+        let (mut ctrl, handle, faults) = setup();
+        let abrm = ctrl.abrm().unwrap();
+
+        for fault in [ControlFault::DropAck, ControlFault::Stall] {
+            handle.clear_command_log();
+            faults.inject_control(fault);
+            assert!(abrm.set_user_defined_name(&mut ctrl, "retry").is_err());
+            let write_count = handle
+                .command_log()
+                .iter()
+                .filter(|cmd| matches!(cmd, LoggedCommand::WriteMem { .. }))
+                .count();
+            // A stalled command is never processed by the device.
+            let expected = if fault == ControlFault::Stall { 0 } else { 1 };
+            assert_eq!(write_count, expected);
+
+            // The channel is recovered for the following commands.
+            assert!(abrm.serial_number(&mut ctrl).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_cancel_async_transaction() {
+        let (mut ctrl, handle, faults) = setup();
+        let abrm = ctrl.abrm().unwrap();
+        let serial_number = abrm.serial_number(&mut ctrl).unwrap();
+        let address = handle.command_log().pop().unwrap();
+        let (address, len) = match address {
+            LoggedCommand::ReadMem { address, length } => (address, length as usize),
+            cmd => panic!("unexpected command {:?}", cmd),
+        };
+
+        // Drop the future while it's waiting for the acknowledge.
+        faults.inject_control(ControlFault::DropAck);
+        let mut buf = vec![0; len];
+        let read = crate::AsyncDeviceControl::read(&mut ctrl, address, &mut buf);
+        let timeout = Duration::from_millis(50);
+        assert!(task::block_on(async_std::future::timeout(timeout, read)).is_err());
+        assert!(ctrl.needs_resync);
+
+        // The channel is resynchronized before the next command.
+        assert_eq!(abrm.serial_number(&mut ctrl).unwrap(), serial_number);
+        assert!(!ctrl.needs_resync);
+        task::block_on(crate::AsyncDeviceControl::read(
+            &mut ctrl, address, &mut buf,
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_shared_async_access() {
+        let (ctrl, _handle, _faults) = setup();
+        let mut ctrl = SharedControlHandle::from(ctrl);
+        let abrm = Abrm::new(&mut ctrl).unwrap();
+        let (address, _) = cameleon_device::u3v::register_map::abrm::USER_DEFINED_NAME;
+
+        task::block_on(crate::AsyncDeviceControl::write(
+            &mut ctrl, address, b"async\0",
+        ))
+        .unwrap();
+        assert_eq!(
+            abrm.user_defined_name(&mut ctrl).unwrap().as_deref(),
+            Some("async")
+        );
+
+        let mut buf = [0; 6];
+        task::block_on(crate::AsyncDeviceControl::read(
+            &mut ctrl, address, &mut buf,
+        ))
+        .unwrap();
+        assert_eq!(&buf, b"async\0");
+    }
+}
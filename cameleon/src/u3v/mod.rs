@@ -45,19 +45,27 @@
 //! ```
 #![allow(clippy::missing_panics_doc)]
 
+pub mod bandwidth;
 pub mod control_handle;
 pub mod register_map;
 pub mod stream_handle;
 
+pub use bandwidth::{BandwidthAllocation, BandwidthManager, BandwidthRequest};
 pub use control_handle::{ControlHandle, SharedControlHandle};
 pub use stream_handle::{StreamHandle, StreamParams};
 
-pub use cameleon_device::u3v::DeviceInfo;
+pub use cameleon_device::u3v::{
+    loopback, BusSpeed, ControlIfaceInfo, Device, DeviceFilter, DeviceInfo, LoopbackDevice,
+    LoopbackTransport, ReceiveIfaceInfo, Transport, TransportProvider,
+};
 
-use cameleon_device::u3v;
+use cameleon_device::{u3v, u3v::protocol::ack};
 
+#[cfg(feature = "libusb")]
+use super::CameleonError;
 use super::{
-    genapi::DefaultGenApiCtxt, CameleonResult, Camera, CameraInfo, ControlError, StreamError,
+    genapi::DefaultGenApiCtxt, CameleonResult, Camera, CameraInfo, ControlError, DeviceStatus,
+    StreamError,
 };
 
 /// Enumerate all U3V compatible cameras connected to the host.
@@ -72,9 +80,146 @@ use super::{
 /// // Enumerate cameras connected to the host.
 /// let mut cameras = u3v::enumerate_cameras().unwrap();
 /// ```
+#[cfg(feature = "libusb")]
 pub fn enumerate_cameras() -> CameleonResult<Vec<Camera<ControlHandle, StreamHandle>>> {
     let devices = u3v::enumerate_devices().map_err(ControlError::from)?;
+    into_cameras(devices)
+}
+
+/// Enumerate U3V compatible cameras connected to the host which match the `filter`.
+///
+/// Devices are filtered by [`DeviceFilter::bus_number`] and [`DeviceFilter::port_numbers`] before
+/// they are opened. The other filters need information read from the device, so they are applied
+/// after the device is opened.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cameleon::u3v::{self, BusSpeed, DeviceFilter};
+///
+/// // Enumerate cameras which are connected to the host with USB3.
+/// let filter = DeviceFilter::new().min_speed(BusSpeed::SuperSpeed);
+/// let cameras = u3v::enumerate_cameras_with_filter(&filter).unwrap();
+/// ```
+#[cfg(feature = "libusb")]
+pub fn enumerate_cameras_with_filter(
+    filter: &DeviceFilter,
+) -> CameleonResult<Vec<Camera<ControlHandle, StreamHandle>>> {
+    let devices = u3v::enumerate_devices_with_filter(filter).map_err(ControlError::from)?;
+    into_cameras(devices)
+}
+
+/// Open the camera with the serial number.
+///
+/// # Errors
+/// [`CameleonError::CameraNotFound`] is returned if no camera has the serial number.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cameleon::u3v;
+///
+/// let mut camera = u3v::open_by_serial("CAM1984").unwrap();
+/// camera.load_context().unwrap();
+/// ```
+#[cfg(feature = "libusb")]
+pub fn open_by_serial(serial_number: &str) -> CameleonResult<Camera<ControlHandle, StreamHandle>> {
+    let filter = DeviceFilter::new().serial_number(serial_number);
+    open_first(
+        enumerate_cameras_with_filter(&filter)?,
+        format!("no camera has the serial number `{}`", serial_number),
+    )
+}
 
+/// Open the camera with the user defined name.
+///
+/// # Errors
+/// [`CameleonError::CameraNotFound`] is returned if no camera has the user defined name.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cameleon::u3v;
+///
+/// let mut camera = u3v::open_by_user_name("left").unwrap();
+/// camera.load_context().unwrap();
+/// ```
+#[cfg(feature = "libusb")]
+pub fn open_by_user_name(name: &str) -> CameleonResult<Camera<ControlHandle, StreamHandle>> {
+    let filter = DeviceFilter::new().user_defined_name(name);
+    open_first(
+        enumerate_cameras_with_filter(&filter)?,
+        format!("no camera has the user defined name `{}`", name),
+    )
+}
+
+/// Open the first camera in `cameras`, returns [`CameleonError::CameraNotFound`] with `msg` if
+/// `cameras` is empty.
+#[cfg(feature = "libusb")]
+fn open_first(
+    cameras: Vec<Camera<ControlHandle, StreamHandle>>,
+    msg: String,
+) -> CameleonResult<Camera<ControlHandle, StreamHandle>> {
+    let mut camera = cameras
+        .into_iter()
+        .next()
+        .ok_or(CameleonError::CameraNotFound(msg.into()))?;
+    camera.open()?;
+    Ok(camera)
+}
+
+/// Converts U3V devices into cameras, devices without a stream channel are skipped.
+///
+/// This is useful to drive cameras over custom transports, see [`Device::with_transport`].
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon::u3v::{self, ControlIfaceInfo, Device, ReceiveIfaceInfo};
+/// # use cameleon::u3v::{BusSpeed, DeviceInfo};
+/// # use semver::Version;
+/// # let device_info = DeviceInfo {
+/// #     gencp_version: Version::new(1, 0, 0),
+/// #     u3v_version: Version::new(1, 0, 0),
+/// #     guid: "000000000000".into(),
+/// #     vendor_name: "vendor".into(),
+/// #     model_name: "model".into(),
+/// #     family_name: None,
+/// #     device_version: "1.0".into(),
+/// #     manufacturer_info: "none".into(),
+/// #     serial_number: "0".into(),
+/// #     user_defined_name: None,
+/// #     supported_speed: BusSpeed::SuperSpeed,
+/// #     bus_number: None,
+/// #     port_numbers: vec![],
+/// #     current_speed: None,
+/// # };
+///
+/// // Drive the host stack with a device implemented on top of the loopback transport.
+/// let (transport, _device) = u3v::loopback();
+/// let ctrl_iface_info = ControlIfaceInfo {
+///     iface_number: 0,
+///     bulk_in_ep: 0x81,
+///     bulk_out_ep: 0x01,
+/// };
+/// let stream_iface_info = ReceiveIfaceInfo {
+///     iface_number: 2,
+///     bulk_in_ep: 0x83,
+/// };
+/// let device = Device::with_transport(
+///     transport,
+///     ctrl_iface_info,
+///     None,
+///     Some(stream_iface_info),
+///     device_info,
+/// );
+///
+/// let cameras = u3v::into_cameras(vec![device]).unwrap();
+/// assert_eq!(cameras.len(), 1);
+/// ```
+pub fn into_cameras(
+    devices: Vec<u3v::Device>,
+) -> CameleonResult<Vec<Camera<ControlHandle, StreamHandle>>> {
     let mut cameras: Vec<Camera<ControlHandle, StreamHandle>> = Vec::with_capacity(devices.len());
 
     for dev in devices {
@@ -91,6 +236,10 @@ pub fn enumerate_cameras() -> CameleonResult<Vec<Camera<ControlHandle, StreamHan
             vendor_name: dev_info.vendor_name,
             model_name: dev_info.model_name,
             serial_number: dev_info.serial_number,
+            user_defined_name: dev_info.user_defined_name,
+            bus_number: dev_info.bus_number,
+            port_numbers: dev_info.port_numbers,
+            link_speed_bps: dev_info.current_speed.map(BusSpeed::bits_per_second),
         };
 
         let camera: Camera<ControlHandle, StreamHandle, DefaultGenApiCtxt> =
@@ -125,6 +274,37 @@ impl From<u3v::Error> for ControlError {
     }
 }
 
+impl From<ack::Status> for DeviceStatus {
+    fn from(status: ack::Status) -> Self {
+        use ack::{GenCpStatus, StatusKind, UsbSpecificStatus};
+
+        match status.kind() {
+            StatusKind::GenCp(status) => match status {
+                GenCpStatus::NotImplemented => Self::NotImplemented,
+                GenCpStatus::InvalidParameter => Self::InvalidParameter,
+                GenCpStatus::InvalidAddress => Self::InvalidAddress,
+                GenCpStatus::WriteProtect => Self::WriteProtect,
+                GenCpStatus::BadAlignment => Self::BadAlignment,
+                GenCpStatus::AccessDenied => Self::AccessDenied,
+                GenCpStatus::Busy => Self::Busy,
+                GenCpStatus::Timeout => Self::Timeout,
+                GenCpStatus::InvalidHeader => Self::InvalidHeader,
+                GenCpStatus::WrongConfig => Self::WrongConfig,
+                // `Success` is never converted into an error status.
+                GenCpStatus::GenericError | GenCpStatus::Success => Self::GenericError,
+            },
+            StatusKind::UsbSpecific(status) => match status {
+                UsbSpecificStatus::ResendNotSupported => Self::ResendNotSupported,
+                UsbSpecificStatus::StreamEndpointHalted => Self::StreamEndpointHalted,
+                UsbSpecificStatus::PayloadSizeNotAligned => Self::PayloadSizeNotAligned,
+                UsbSpecificStatus::EventEndpointHalted => Self::EventEndpointHalted,
+                UsbSpecificStatus::InvalidSiState => Self::InvalidSiState,
+            },
+            StatusKind::DeviceSpecific => Self::DeviceSpecific(status.code()),
+        }
+    }
+}
+
 impl From<u3v::Error> for StreamError {
     fn from(err: u3v::Error) -> Self {
         use u3v::Error::LibUsb;
@@ -144,3 +324,67 @@ impl From<u3v::Error> for StreamError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genapi::{self, GenApiError};
+
+    fn status(code: u16) -> ack::Status {
+        let mut buf = vec![];
+        buf.extend_from_slice(&0x4356_3355_u32.to_le_bytes());
+        buf.extend_from_slice(&code.to_le_bytes());
+        buf.extend_from_slice(&0x0803_u16.to_le_bytes());
+        buf.extend_from_slice(&0_u16.to_le_bytes());
+        buf.extend_from_slice(&1_u16.to_le_bytes());
+        *ack::AckPacket::parse(&buf).unwrap().status()
+    }
+
+    #[test]
+    fn test_device_status_from_ack_status() {
+        let expected = [
+            (0x8001, DeviceStatus::NotImplemented),
+            (0x8002, DeviceStatus::InvalidParameter),
+            (0x8003, DeviceStatus::InvalidAddress),
+            (0x8004, DeviceStatus::WriteProtect),
+            (0x8005, DeviceStatus::BadAlignment),
+            (0x8006, DeviceStatus::AccessDenied),
+            (0x8007, DeviceStatus::Busy),
+            (0x800B, DeviceStatus::Timeout),
+            (0x800E, DeviceStatus::InvalidHeader),
+            (0x800F, DeviceStatus::WrongConfig),
+            (0x8FFF, DeviceStatus::GenericError),
+            (0xA001, DeviceStatus::ResendNotSupported),
+            (0xA002, DeviceStatus::StreamEndpointHalted),
+            (0xA003, DeviceStatus::PayloadSizeNotAligned),
+            (0xA004, DeviceStatus::InvalidSiState),
+            (0xA005, DeviceStatus::EventEndpointHalted),
+        ];
+
+        for (code, device_status) in expected {
+            assert_eq!(DeviceStatus::from(status(code)), device_status);
+        }
+    }
+
+    #[test]
+    fn test_control_error() {
+        let err = GenApiError::Device(Box::new(ControlError::DeviceStatus(
+            DeviceStatus::WriteProtect,
+        )));
+        assert!(matches!(
+            genapi::control_error(&err),
+            Some(ControlError::DeviceStatus(DeviceStatus::WriteProtect))
+        ));
+
+        let err = GenApiError::Device(Box::new(ControlError::Timeout));
+        assert!(matches!(
+            genapi::control_error(&err),
+            Some(ControlError::Timeout)
+        ));
+
+        // Errors not caused by device control.
+        let err = GenApiError::Device(Box::new(std::io::Error::from(std::io::ErrorKind::Other)));
+        assert!(genapi::control_error(&err).is_none());
+        assert!(genapi::control_error(&GenApiError::NotWritable).is_none());
+    }
+}
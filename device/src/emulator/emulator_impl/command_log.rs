@@ -0,0 +1,88 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Maximum number of commands kept in the log, the oldest command is discarded when exceeded.
+const COMMAND_LOG_CAPACITY: usize = 1024;
+
+/// A command processed by an emulated device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoggedCommand {
+    /// `ReadMem` command.
+    ReadMem { address: u64, length: u16 },
+
+    /// `WriteMem` command.
+    WriteMem { address: u64, data: Vec<u8> },
+
+    /// `ReadMemStacked` command, each entry is a pair of an address and a length.
+    ReadMemStacked(Vec<(u64, u16)>),
+
+    /// `WriteMemStacked` command, each entry is a pair of an address and data.
+    WriteMemStacked(Vec<(u64, Vec<u8>)>),
+}
+
+/// Log of commands processed by an emulated device.
+#[derive(Debug, Clone, Default)]
+pub(super) struct CommandLog {
+    inner: Arc<Mutex<VecDeque<LoggedCommand>>>,
+}
+
+impl CommandLog {
+    pub(super) fn push(&self, command: LoggedCommand) {
+        self.with(|log| {
+            if log.len() == COMMAND_LOG_CAPACITY {
+                log.pop_front();
+            }
+            log.push_back(command);
+        });
+    }
+
+    pub(super) fn commands(&self) -> Vec<LoggedCommand> {
+        self.with(|log| log.iter().cloned().collect())
+    }
+
+    pub(super) fn clear(&self) {
+        self.with(VecDeque::clear);
+    }
+
+    fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut VecDeque<LoggedCommand>) -> R,
+    {
+        f(&mut crate::lock(&self.inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity() {
+        let log = CommandLog::default();
+        for i in 0..=COMMAND_LOG_CAPACITY as u64 {
+            log.push(LoggedCommand::ReadMem {
+                address: i,
+                length: 4,
+            });
+        }
+
+        let commands = log.commands();
+        assert_eq!(commands.len(), COMMAND_LOG_CAPACITY);
+        assert_eq!(
+            commands[0],
+            LoggedCommand::ReadMem {
+                address: 1,
+                length: 4
+            }
+        );
+
+        log.clear();
+        assert!(log.commands().is_empty());
+    }
+}
@@ -16,13 +16,18 @@ use async_std::{
 
 use cameleon_impl::memory::{prelude::*, MemoryError};
 
+use crate::u3v::protocol::cmd::CommandCcd;
+
 use super::{
+    command_log::{CommandLog, LoggedCommand},
     device::Timestamp,
+    fault::{ControlFault, FaultInjector},
     interface::IfaceState,
-    memory::{Memory, SBRM},
+    memory::{Memory, EIRM, SBRM, SIRM},
     memory_event_handler::MemoryEventHandler,
     shared_queue::SharedQueue,
     signal::{ControlSignal, InterfaceSignal},
+    write_hook::WriteHooks,
     IfaceKind,
 };
 
@@ -32,6 +37,9 @@ pub(super) struct ControlModule {
     iface_state: IfaceState,
     memory: Arc<Mutex<Memory>>,
     timestamp: Timestamp,
+    faults: FaultInjector,
+    hooks: WriteHooks,
+    log: CommandLog,
     queue: SharedQueue<Vec<u8>>,
 }
 
@@ -40,12 +48,18 @@ impl ControlModule {
         iface_state: IfaceState,
         memory: Arc<Mutex<Memory>>,
         timestamp: Timestamp,
+        faults: FaultInjector,
+        hooks: WriteHooks,
+        log: CommandLog,
         queue: SharedQueue<Vec<u8>>,
     ) -> Self {
         Self {
             iface_state,
             memory,
             timestamp,
+            faults,
+            hooks,
+            log,
             queue,
         }
     }
@@ -55,12 +69,15 @@ impl ControlModule {
         signal_tx: Sender<InterfaceSignal>,
         mut signal_rx: Receiver<ControlSignal>,
     ) {
-        let event_handler = MemoryEventHandler::new(&mut *self.memory.lock().await).await;
+        let event_handler =
+            MemoryEventHandler::new(&mut *self.memory.lock().await, self.hooks.clone()).await;
 
         let mut worker_manager = WorkerManager::new(
             self.iface_state.clone(),
             self.memory.clone(),
             self.timestamp.clone(),
+            self.faults.clone(),
+            self.log.clone(),
             event_handler,
             self.queue.clone(),
             signal_tx,
@@ -76,9 +93,18 @@ impl ControlModule {
 
                 ControlSignal::CancelJobs(_completed) => worker_manager.wait_completion().await,
 
-                ControlSignal::ClearSiRegister | ControlSignal::ClearEiRegister => {
-                    // TODO:
-                    todo!()
+                ControlSignal::ClearSiRegister => {
+                    let mut memory = self.memory.lock().await;
+                    if let Err(e) = memory.write::<SIRM::Control>(0) {
+                        log::error!("failed to clear SIRM: {}", e);
+                    }
+                }
+
+                ControlSignal::ClearEiRegister => {
+                    let mut memory = self.memory.lock().await;
+                    if let Err(e) = memory.write::<EIRM::Control>(0) {
+                        log::error!("failed to clear EIRM: {}", e);
+                    }
                 }
 
                 ControlSignal::Shutdown => {
@@ -94,6 +120,8 @@ struct WorkerManager {
     iface_state: IfaceState,
     memory: Arc<Mutex<Memory>>,
     timestamp: Timestamp,
+    faults: FaultInjector,
+    log: CommandLog,
 
     queue: SharedQueue<Vec<u8>>,
     signal_tx: Sender<InterfaceSignal>,
@@ -114,10 +142,13 @@ struct WorkerManager {
 }
 
 impl WorkerManager {
+    #[allow(clippy::too_many_arguments)]
     async fn new(
         iface_state: IfaceState,
         memory: Arc<Mutex<Memory>>,
         timestamp: Timestamp,
+        faults: FaultInjector,
+        log: CommandLog,
         memory_event_handler: MemoryEventHandler,
         queue: SharedQueue<Vec<u8>>,
         signal_tx: Sender<InterfaceSignal>,
@@ -138,6 +169,8 @@ impl WorkerManager {
             iface_state,
             memory,
             timestamp,
+            faults,
+            log,
 
             queue,
             signal_tx,
@@ -159,6 +192,9 @@ impl WorkerManager {
             iface_state: self.iface_state.clone(),
             memory: self.memory.clone(),
             timestamp: self.timestamp.clone(),
+            faults: self.faults.clone(),
+            fault: None,
+            log: self.log.clone(),
 
             queue: self.queue.clone(),
             signal_tx: self.signal_tx.clone(),
@@ -190,6 +226,10 @@ pub(super) struct Worker {
     iface_state: IfaceState,
     pub(super) memory: Arc<Mutex<Memory>>,
     pub(super) timestamp: Timestamp,
+    faults: FaultInjector,
+    /// A fault applied to the command processed by the worker.
+    fault: Option<ControlFault>,
+    log: CommandLog,
 
     queue: SharedQueue<Vec<u8>>,
     signal_tx: Sender<InterfaceSignal>,
@@ -205,14 +245,21 @@ pub(super) struct Worker {
 }
 
 impl Worker {
-    // TODO: Emulate pending situation.
-    async fn run(self, command: Vec<u8>) {
+    async fn run(mut self, command: Vec<u8>) {
         let cmd_packet = match self.try_parse_command(&command) {
             Some(packet) => packet,
             None => return,
         };
         let ccd = cmd_packet.ccd();
 
+        self.fault = self.faults.next_control();
+        if let Some(fault) = self.fault {
+            log::info!("inject {:?} into the command", fault);
+            if !self.inject_fault(fault, ccd).await {
+                return;
+            }
+        }
+
         // If sent command length is larger than SBRM::MaximumCommandTransferLength, return error.
         if (self.maximum_cmd_length) < command.len() {
             let ack = ack::ErrorAck::new(ack::GenCpStatus::InvalidParameter, ccd.scd_kind())
@@ -239,8 +286,8 @@ impl Worker {
         // If another thread is processing command simultaneously, return busy error ack.
         if self
             .on_processing
-            .compare_exchange(true, false, Ordering::Relaxed, Ordering::Relaxed)
-            .is_ok()
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
         {
             let ack = ack::ErrorAck::new(ack::GenCpStatus::Busy, ccd.scd_kind())
                 .finalize(ccd.request_id());
@@ -258,6 +305,47 @@ impl Worker {
         self.on_processing.store(false, Ordering::Relaxed);
     }
 
+    /// Apply the fault before processing the command, returns `false` if the command must not be
+    /// processed.
+    async fn inject_fault(&self, fault: ControlFault, ccd: &CommandCcd) -> bool {
+        let scd_kind = ccd.scd_kind();
+        let req_id = ccd.request_id();
+
+        match fault {
+            ControlFault::Pending(timeout) => {
+                let ack = ack::Pending::new(timeout).finalize(req_id);
+                self.enqueue_or_halt(&ack);
+                task::sleep(timeout / 2).await;
+                true
+            }
+
+            ControlFault::NakGenCp(status) => {
+                let ack = ack::ErrorAck::new(status, scd_kind).finalize(req_id);
+                self.enqueue_or_halt(&ack);
+                false
+            }
+
+            ControlFault::NakUsb(status) => {
+                let ack = ack::ErrorAck::new(status, scd_kind).finalize(req_id);
+                self.enqueue_or_halt(&ack);
+                false
+            }
+
+            ControlFault::DelayAck(delay) => {
+                task::sleep(delay).await;
+                true
+            }
+
+            ControlFault::Stall => {
+                self.try_send_signal(InterfaceSignal::Halt(IfaceKind::Control));
+                false
+            }
+
+            // These faults are applied when the acknowledge is sent.
+            ControlFault::DropAck | ControlFault::CorruptRequestId => true,
+        }
+    }
+
     fn try_parse_command<'a>(&self, command: &'a [u8]) -> Option<cmd::CommandPacket<'a>> {
         match cmd::CommandPacket::parse(command) {
             Ok(packet) => Some(packet),
@@ -280,6 +368,10 @@ impl Worker {
             Some(scd) => scd,
             None => return,
         };
+        self.log.push(LoggedCommand::ReadMem {
+            address: scd.address,
+            length: scd.read_length,
+        });
         let ccd = command.ccd();
         let req_id = ccd.request_id();
         let scd_kind = ccd.scd_kind();
@@ -317,6 +409,10 @@ impl Worker {
             Some(scd) => scd,
             None => return,
         };
+        self.log.push(LoggedCommand::WriteMem {
+            address: scd.address,
+            data: scd.data.to_vec(),
+        });
         let ccd = command.ccd();
         let req_id = ccd.request_id();
         let scd_kind = ccd.scd_kind();
@@ -358,10 +454,16 @@ impl Worker {
     }
 
     async fn process_read_mem_stacked(&self, command: cmd::CommandPacket<'_>) {
-        let _scd: cmd::WriteMemStacked = match self.try_extract_scd(&command) {
+        let scd: cmd::ReadMemStacked = match self.try_extract_scd(&command) {
             Some(scd) => scd,
             None => return,
         };
+        self.log.push(LoggedCommand::ReadMemStacked(
+            scd.entries
+                .iter()
+                .map(|entry| (entry.address, entry.read_length))
+                .collect(),
+        ));
         let ccd = command.ccd();
         let req_id = ccd.request_id();
         let scd_kind = ccd.scd_kind();
@@ -372,10 +474,16 @@ impl Worker {
     }
 
     async fn process_write_mem_stacked(&self, command: cmd::CommandPacket<'_>) {
-        let _scd: cmd::WriteMemStacked = match self.try_extract_scd(&command) {
+        let scd: cmd::WriteMemStacked = match self.try_extract_scd(&command) {
             Some(scd) => scd,
             None => return,
         };
+        self.log.push(LoggedCommand::WriteMemStacked(
+            scd.entries
+                .iter()
+                .map(|entry| (entry.address, entry.data.to_vec()))
+                .collect(),
+        ));
         let ccd = command.ccd();
         let req_id = ccd.request_id();
         let scd_kind = ccd.scd_kind();
@@ -404,9 +512,18 @@ impl Worker {
     where
         T: AckSerialize,
     {
+        let request_id = match self.fault {
+            Some(ControlFault::DropAck) if ack.ccd.scd_kind != ack::ScdKind::Pending => {
+                log::info!("drop the acknowledge packet");
+                return;
+            }
+            Some(ControlFault::CorruptRequestId) => ack.ccd.request_id.wrapping_add(1),
+            _ => ack.ccd.request_id,
+        };
+
         let mut buf = vec![];
 
-        if let Err(e) = ack.serialize(&mut buf) {
+        if let Err(e) = ack.serialize_with_request_id(request_id, &mut buf) {
             log::error!("{}", e);
             return;
         }
@@ -25,14 +25,11 @@ pub(super) mod cmd {
 
     use std::io::Cursor;
 
-    use crate::u3v::protocol::{
-        cmd::{CommandCcd, CommandFlag},
-        util,
-    };
+    use cameleon_impl::bytes_io::ReadBytes;
 
-    use super::{ProtocolError, ProtocolResult};
+    use crate::u3v::protocol::cmd::{CommandCcd, CommandFlag};
 
-    use crate::u3v::protocol::util::ReadBytes;
+    use super::{ProtocolError, ProtocolResult};
 
     pub(in super::super) struct CommandPacket<'a> {
         ccd: CommandCcd,
@@ -62,7 +59,7 @@ pub(super) mod cmd {
         }
 
         fn parse_prefix(cursor: &mut Cursor<&[u8]>) -> ProtocolResult<()> {
-            let magic: u32 = cursor.read_bytes()?;
+            let magic: u32 = cursor.read_bytes_le()?;
             if magic == Self::PREFIX_MAGIC {
                 Ok(())
             } else {
@@ -75,8 +72,8 @@ pub(super) mod cmd {
         fn parse(cursor: &mut Cursor<&[u8]>) -> ProtocolResult<Self> {
             let flag = CommandFlag::parse(cursor)?;
             let scd_kind = ScdKind::parse(cursor)?;
-            let scd_len = cursor.read_bytes()?;
-            let request_id = cursor.read_bytes()?;
+            let scd_len = cursor.read_bytes_le()?;
+            let request_id = cursor.read_bytes_le()?;
 
             Ok(Self::new(flag, scd_kind, scd_len, request_id))
         }
@@ -84,7 +81,7 @@ pub(super) mod cmd {
 
     impl CommandFlag {
         fn parse(cursor: &mut Cursor<&[u8]>) -> ProtocolResult<Self> {
-            let raw: u16 = cursor.read_bytes()?;
+            let raw: u16 = cursor.read_bytes_le()?;
             if raw == 1 << 14 {
                 Ok(Self::RequestAck)
             } else if raw == 1 << 15 {
@@ -97,7 +94,7 @@ pub(super) mod cmd {
 
     impl ScdKind {
         fn parse(cursor: &mut Cursor<&[u8]>) -> ProtocolResult<Self> {
-            let raw: u16 = cursor.read_bytes()?;
+            let raw: u16 = cursor.read_bytes_le()?;
             match raw {
                 0x0800 => Ok(Self::ReadMem),
                 0x0802 => Ok(Self::WriteMem),
@@ -115,14 +112,14 @@ pub(super) mod cmd {
     impl<'a> ParseScd<'a> for ReadMem {
         fn parse(buf: &'a [u8], _ccd: &CommandCcd) -> ProtocolResult<Self> {
             let mut cursor = Cursor::new(buf);
-            let address = cursor.read_bytes()?;
-            let reserved: u16 = cursor.read_bytes()?;
+            let address = cursor.read_bytes_le()?;
+            let reserved: u16 = cursor.read_bytes_le()?;
             if reserved != 0 {
                 return Err(ProtocolError::InvalidPacket(
                     "the reserved field of Read command must be zero".into(),
                 ));
             }
-            let read_length = cursor.read_bytes()?;
+            let read_length = cursor.read_bytes_le()?;
             Ok(Self::new(address, read_length))
         }
     }
@@ -130,8 +127,8 @@ pub(super) mod cmd {
     impl<'a> ParseScd<'a> for WriteMem<'a> {
         fn parse(buf: &'a [u8], ccd: &CommandCcd) -> ProtocolResult<Self> {
             let mut cursor = Cursor::new(buf);
-            let address = cursor.read_bytes()?;
-            let data = util::read_bytes(&mut cursor, ccd.scd_len() - 8)?;
+            let address = cursor.read_bytes_le()?;
+            let data = read_raw(&mut cursor, ccd.scd_len() - 8)?;
             Self::new(address, data)
                 .map_err(|err| ProtocolError::InvalidPacket(err.to_string().into()))
         }
@@ -143,14 +140,14 @@ pub(super) mod cmd {
             let mut len = ccd.scd_len();
             let mut entries = Vec::with_capacity(len as usize / 12);
             while len > 0 {
-                let address = cursor.read_bytes()?;
-                let reserved: u16 = cursor.read_bytes()?;
+                let address = cursor.read_bytes_le()?;
+                let reserved: u16 = cursor.read_bytes_le()?;
                 if reserved != 0 {
                     return Err(ProtocolError::InvalidPacket(
                         "the reserved field of ReadMemStacked command must be zero".into(),
                     ));
                 }
-                let read_length = cursor.read_bytes()?;
+                let read_length = cursor.read_bytes_le()?;
                 entries.push(ReadMem::new(address, read_length));
 
                 len -= 12;
@@ -167,15 +164,15 @@ pub(super) mod cmd {
             let mut len = ccd.scd_len();
 
             while len > 0 {
-                let address = cursor.read_bytes()?;
-                let reserved: u16 = cursor.read_bytes()?;
+                let address = cursor.read_bytes_le()?;
+                let reserved: u16 = cursor.read_bytes_le()?;
                 if reserved != 0 {
                     return Err(ProtocolError::InvalidPacket(
                         "the reserved field of WriteMemStacked command must be zero".into(),
                     ));
                 }
-                let data_length = cursor.read_bytes()?;
-                let data = util::read_bytes(&mut cursor, data_length)?;
+                let data_length: u16 = cursor.read_bytes_le()?;
+                let data = read_raw(&mut cursor, data_length)?;
                 regs.push(
                     WriteMem::new(address, data)
                         .map_err(|err| ProtocolError::InvalidPacket(err.to_string().into()))?,
@@ -188,6 +185,23 @@ pub(super) mod cmd {
         }
     }
 
+    /// Reads `len` bytes from the cursor without copying them.
+    fn read_raw<'a>(
+        cursor: &mut Cursor<&'a [u8]>,
+        len: impl Into<usize>,
+    ) -> ProtocolResult<&'a [u8]> {
+        let start = cursor.position() as usize;
+        let end = start + len.into();
+        let buf = *cursor.get_ref();
+        if buf.len() < end {
+            return Err(ProtocolError::InvalidPacket(
+                "the packet is shorter than the length specified in the packet".into(),
+            ));
+        }
+        cursor.set_position(end as u64);
+        Ok(&buf[start..end])
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -277,10 +291,11 @@ pub(super) mod cmd {
 pub(super) mod ack {
     use std::{convert::TryFrom, io::Write, time};
 
+    use cameleon_impl::bytes_io::WriteBytes;
+
     use crate::u3v::protocol::{
         ack::{AckCcd, Status, StatusKind},
         cmd,
-        util::WriteBytes,
     };
 
     use super::ProtocolResult;
@@ -300,9 +315,22 @@ pub(super) mod ack {
     {
         const PREFIX_MAGIC: u32 = 0x4356_3355;
 
-        pub(in super::super) fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_bytes(Self::PREFIX_MAGIC)?;
-            self.ccd.serialize(&mut buf)?;
+        pub(in super::super) fn serialize(&self, buf: impl Write) -> ProtocolResult<()> {
+            self.serialize_with_request_id(self.ccd.request_id, buf)
+        }
+
+        /// Serialize the packet with `request_id` instead of the request id of the CCD.
+        pub(in super::super) fn serialize_with_request_id(
+            &self,
+            request_id: u16,
+            mut buf: impl Write,
+        ) -> ProtocolResult<()> {
+            let ccd = AckCcd {
+                request_id,
+                ..self.ccd.clone()
+            };
+            buf.write_bytes_le(Self::PREFIX_MAGIC)?;
+            ccd.serialize(&mut buf)?;
             self.scd.serialize(&mut buf)?;
             Ok(())
         }
@@ -324,10 +352,10 @@ pub(super) mod ack {
         }
 
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_bytes(self.status().code())?;
+            buf.write_bytes_le(self.status().code())?;
             self.scd_kind().serialize(&mut buf)?;
-            buf.write_bytes(self.scd_len())?;
-            buf.write_bytes(self.request_id())?;
+            buf.write_bytes_le(self.scd_len())?;
+            buf.write_bytes_le(self.request_id())?;
             Ok(())
         }
     }
@@ -342,7 +370,7 @@ pub(super) mod ack {
                 Self::WriteMemStacked => 0x0809,
             };
 
-            buf.write_bytes(raw)?;
+            buf.write_bytes_le(raw)?;
             Ok(())
         }
     }
@@ -370,7 +398,7 @@ pub(super) mod ack {
 
     impl<'a> AckSerialize for ReadMem<'a> {
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_all(&self.data)?;
+            buf.write_all(self.data)?;
             Ok(())
         }
 
@@ -389,10 +417,10 @@ pub(super) mod ack {
         }
     }
 
-    impl<'a> AckSerialize for WriteMem {
+    impl AckSerialize for WriteMem {
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_bytes(0_u16)?;
-            buf.write_bytes(self.length)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(self.length)?;
             Ok(())
         }
 
@@ -406,16 +434,16 @@ pub(super) mod ack {
     }
 
     impl Pending {
-        pub(in super::super) fn _new(timeout: time::Duration) -> Self {
-            debug_assert!(timeout.as_millis() <= u128::from(std::u16::MAX));
+        pub(in super::super) fn new(timeout: time::Duration) -> Self {
+            debug_assert!(timeout.as_millis() <= u128::from(u16::MAX));
             Self { timeout }
         }
     }
 
     impl AckSerialize for Pending {
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_bytes(0_u16)?;
-            buf.write_bytes(self.timeout.as_millis() as u16)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(self.timeout.as_millis() as u16)?;
             Ok(())
         }
 
@@ -437,7 +465,7 @@ pub(super) mod ack {
 
     impl<'a> AckSerialize for ReadMemStacked<'a> {
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_all(&self.data)?;
+            buf.write_all(self.data)?;
             Ok(())
         }
 
@@ -464,8 +492,8 @@ pub(super) mod ack {
     impl AckSerialize for WriteMemStacked {
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
             for len in &self.lengths {
-                buf.write_bytes(0_u16)?;
-                buf.write_bytes(*len)?;
+                buf.write_bytes_le(0_u16)?;
+                buf.write_bytes_le(*len)?;
             }
 
             Ok(())
@@ -620,7 +648,7 @@ pub(super) mod ack {
         #[test]
         fn test_pending() {
             let timeout = time::Duration::from_millis(700);
-            let command = Pending::_new(timeout).finalize(1);
+            let command = Pending::new(timeout).finalize(1);
             let mut buf = vec![];
             command.serialize(&mut buf).unwrap();
 
@@ -11,12 +11,16 @@ use async_std::{
 };
 use futures::channel::oneshot;
 
-use crate::u3v::DeviceInfo;
+use crate::u3v::{BusSpeed, DeviceInfo};
 
 use super::{
+    command_log::CommandLog,
+    event_module::Event,
     fake_protocol::{FakeAckPacket, FakeReqPacket},
+    fault::FaultInjector,
     interface::Interface,
     memory::Memory,
+    write_hook::WriteHooks,
 };
 
 const REQ_PACKET_CHANNEL_CAPACITY: usize = 1;
@@ -28,16 +32,31 @@ pub(super) struct Device {
     shutdown_tx: Option<oneshot::Sender<()>>,
     completion_rx: Option<oneshot::Receiver<()>>,
     device_info: DeviceInfo,
+    faults: FaultInjector,
+    hooks: WriteHooks,
+    log: CommandLog,
+    injected_events: Receiver<Event>,
 }
 
 impl Device {
-    pub(super) fn new(memory: Memory, device_info: DeviceInfo) -> Self {
+    pub(super) fn new(
+        memory: Memory,
+        device_info: DeviceInfo,
+        faults: FaultInjector,
+        hooks: WriteHooks,
+        log: CommandLog,
+        injected_events: Receiver<Event>,
+    ) -> Self {
         Self {
             timestamp: Timestamp::new(),
             memory: Arc::new(Mutex::new(memory)),
             shutdown_tx: None,
             completion_rx: None,
             device_info,
+            faults,
+            hooks,
+            log,
+            injected_events,
         }
     }
 
@@ -53,12 +72,15 @@ impl Device {
         self.completion_rx = Some(completion_rx);
 
         task::spawn(
-            Interface::new(self.memory.clone(), self.timestamp.clone()).run(
-                ack_tx,
-                req_rx,
-                shutdown_rx,
-                completion_tx,
-            ),
+            Interface::new(
+                self.memory.clone(),
+                self.timestamp.clone(),
+                self.faults.clone(),
+                self.hooks.clone(),
+                self.log.clone(),
+                self.injected_events.clone(),
+            )
+            .run(ack_tx, req_rx, shutdown_rx, completion_tx),
         );
 
         (req_tx, ack_rx)
@@ -77,6 +99,19 @@ impl Device {
     pub(super) fn device_info(&self) -> &DeviceInfo {
         &self.device_info
     }
+
+    pub(super) fn set_speed(&mut self, speed: BusSpeed) {
+        self.device_info.supported_speed = self.device_info.supported_speed.max(speed);
+        self.device_info.current_speed = Some(speed);
+    }
+
+    pub(super) fn set_port_numbers(&mut self, port_numbers: Vec<u8>) {
+        self.device_info.port_numbers = port_numbers;
+    }
+
+    pub(super) fn memory(&self) -> Arc<Mutex<Memory>> {
+        self.memory.clone()
+    }
 }
 
 impl Drop for Device {
@@ -2,8 +2,6 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-pub(crate) use super::fake_protocol::IfaceKind;
-
 use std::{
     io::Write,
     time::{Duration, Instant},
@@ -11,16 +9,28 @@ use std::{
 
 use async_std::{channel::TrySendError, task};
 
-use crate::u3v::{LibUsbError, Result};
+use crate::u3v::{LibUsbError, Result, Transport};
 
 use super::{
     device_pool::{DevicePipe, DevicePool},
     fake_protocol::{
-        FakeAckKind::{ClearHaltAck, IfaceHalted, RecvAck, RecvNak, SendAck, SetHaltAck},
-        FakeAckPacket, FakeReqKind, FakeReqPacket,
+        FakeAckKind::{
+            ClearHaltAck, Disconnected, IfaceHalted, RecvAck, RecvNak, SendAck, SetHaltAck,
+        },
+        FakeAckPacket, FakeReqKind, FakeReqPacket, IfaceKind,
     },
 };
 
+/// Interface number of the control interface of emulated devices.
+pub(crate) const CONTROL_IFACE: u8 = 0;
+/// Interface number of the event interface of emulated devices.
+pub(crate) const EVENT_IFACE: u8 = 1;
+/// Interface number of the stream interface of emulated devices.
+pub(crate) const STREAM_IFACE: u8 = 2;
+
+/// A handle to an emulated device.
+///
+/// Like a libusb device handle, the handle is bound to an interface when the interface is claimed.
 #[derive(Debug)]
 pub(crate) struct DeviceHandle {
     device_id: u32,
@@ -29,15 +39,15 @@ pub(crate) struct DeviceHandle {
 }
 
 impl DeviceHandle {
-    pub(crate) fn new(device_id: u32, iface_kind: IfaceKind) -> Self {
+    pub(crate) fn new(device_id: u32) -> Self {
         Self {
             device_id,
             channel: None,
-            iface_kind,
+            iface_kind: IfaceKind::Control,
         }
     }
 
-    pub(crate) fn read_bulk(&self, mut buf: &mut [u8], timeout: Duration) -> Result<usize> {
+    fn read_bulk(&self, mut buf: &mut [u8], timeout: Duration) -> Result<usize> {
         let start = Instant::now();
 
         while timeout.checked_sub(start.elapsed()).is_some() {
@@ -62,28 +72,22 @@ impl DeviceHandle {
         Err(LibUsbError::Timeout.into())
     }
 
-    pub(crate) fn write_bulk(&self, buf: &[u8], timeout: Duration) -> Result<usize> {
-        let start = Instant::now();
+    fn write_bulk(&self, buf: &[u8], timeout: Duration) -> Result<usize> {
+        if timeout.is_zero() {
+            return Err(LibUsbError::Timeout.into());
+        }
 
-        while timeout.checked_sub(start.elapsed()).is_some() {
-            let req = FakeReqPacket::new(self.iface_kind, FakeReqKind::Send(buf.to_vec()));
-            let ack = self.send_packet(req)?;
+        let req = FakeReqPacket::new(self.iface_kind, FakeReqKind::Send(buf.to_vec()));
+        let ack = self.send_packet(req)?;
 
-            match ack.kind {
-                SendAck => {
-                    return Ok(buf.len());
-                }
-                IfaceHalted => {
-                    return Err(LibUsbError::Pipe.into());
-                }
-                _ => unreachable!(),
-            }
+        match ack.kind {
+            SendAck => Ok(buf.len()),
+            IfaceHalted => Err(LibUsbError::Pipe.into()),
+            _ => unreachable!(),
         }
-
-        Err(LibUsbError::Timeout.into())
     }
 
-    pub(crate) fn set_halt(&self) -> Result<()> {
+    fn set_halt(&self) -> Result<()> {
         let req = FakeReqPacket::new(self.iface_kind, FakeReqKind::SetHalt);
         let ack = self.send_packet(req)?;
         match ack.kind {
@@ -92,7 +96,7 @@ impl DeviceHandle {
         }
     }
 
-    pub(crate) fn clear_halt(&self) -> Result<()> {
+    fn clear_halt(&self) -> Result<()> {
         let req = FakeReqPacket::new(self.iface_kind, FakeReqKind::ClearHalt);
         let ack = self.send_packet(req)?;
         match ack.kind {
@@ -101,18 +105,24 @@ impl DeviceHandle {
         }
     }
 
-    pub(crate) fn claim_interface(&mut self) -> Result<()> {
+    fn claim_interface(&mut self, iface: u8) -> Result<()> {
         if self.channel.is_some() {
             return Ok(());
         }
 
-        let channel =
-            DevicePool::with(|pool| pool.claim_interface(self.device_id, self.iface_kind))?;
+        let iface_kind = match iface {
+            CONTROL_IFACE => IfaceKind::Control,
+            EVENT_IFACE => IfaceKind::Event,
+            STREAM_IFACE => IfaceKind::Stream,
+            _ => return Err(LibUsbError::NotFound.into()),
+        };
+        let channel = DevicePool::with(|pool| pool.claim_interface(self.device_id, iface_kind))?;
+        self.iface_kind = iface_kind;
         self.channel = Some(channel);
         Ok(())
     }
 
-    pub(crate) fn release_interface(&mut self) -> Result<()> {
+    fn release_interface(&mut self) -> Result<()> {
         if self.channel.is_none() {
             return Ok(());
         }
@@ -126,7 +136,14 @@ impl DeviceHandle {
     /// It's necessary to make send/recv operation atomic because fake protocol doesn't have state.
     /// An emulated device immediately returns acknowledge packet, so no need to worry about
     /// latency.
+    ///
+    /// A request fails with [`LibUsbError::NoDevice`] if the device is unplugged from the device
+    /// pool, or if the device is disconnected by the fault injector.
     fn send_packet(&self, packet: FakeReqPacket) -> Result<FakeAckPacket> {
+        if !DevicePool::with(|pool| pool.is_plugged(self.device_id)).unwrap_or(false) {
+            return Err(LibUsbError::NoDevice.into());
+        }
+
         task::block_on(async {
             let channel = self.channel()?.lock().await;
 
@@ -139,6 +156,9 @@ impl DeviceHandle {
             let ack = channel.1.recv().await.map_err(|_| LibUsbError::NoDevice)?;
 
             debug_assert!(ack.iface == self.iface_kind);
+            if ack.kind == Disconnected {
+                return Err(LibUsbError::NoDevice.into());
+            }
             Ok(ack)
         })
     }
@@ -151,6 +171,34 @@ impl DeviceHandle {
     }
 }
 
+/// Endpoint numbers are ignored because the handle is bound to the claimed interface.
+impl Transport for DeviceHandle {
+    fn claim_interface(&mut self, iface: u8) -> Result<()> {
+        DeviceHandle::claim_interface(self, iface)
+    }
+
+    fn release_interface(&mut self, _iface: u8) -> Result<()> {
+        DeviceHandle::release_interface(self)
+    }
+
+    fn read_bulk(&self, _endpoint: u8, buf: &mut [u8], timeout: Duration) -> Result<usize> {
+        DeviceHandle::read_bulk(self, buf, timeout)
+    }
+
+    fn write_bulk(&self, _endpoint: u8, buf: &[u8], timeout: Duration) -> Result<usize> {
+        DeviceHandle::write_bulk(self, buf, timeout)
+    }
+
+    // Set halt timeout isn't suppoted.
+    fn set_halt(&self, _endpoint: u8, _timeout: Duration) -> Result<()> {
+        DeviceHandle::set_halt(self)
+    }
+
+    fn clear_halt(&mut self, _endpoint: u8) -> Result<()> {
+        DeviceHandle::clear_halt(self)
+    }
+}
+
 impl Drop for DeviceHandle {
     fn drop(&mut self) {
         self.release_interface().ok();
@@ -11,7 +11,7 @@ use async_std::{
 };
 use lazy_static::lazy_static;
 
-use crate::u3v::{DeviceInfo, LibUsbError, Result};
+use crate::u3v::{BusSpeed, DeviceInfo, LibUsbError, Result};
 
 use super::{
     device::Device,
@@ -35,8 +35,13 @@ impl DevicePool {
         Ok(ctx.device_info())
     }
 
+    /// Returns ids of devices which are plugged in.
     pub(crate) fn device_ids(&self) -> Vec<u32> {
-        self.contexts.iter().map(|ctx| ctx.device_id).collect()
+        self.contexts
+            .iter()
+            .filter(|ctx| ctx.plugged)
+            .map(|ctx| ctx.device_id)
+            .collect()
     }
 
     pub(crate) fn with<F, R>(f: F) -> R
@@ -44,7 +49,7 @@ impl DevicePool {
         F: FnOnce(&mut DevicePool) -> R,
     {
         let mut pool = task::block_on(DEVICE_POOL.lock());
-        f(&mut *pool)
+        f(&mut pool)
     }
 
     pub(super) fn claim_interface(
@@ -52,7 +57,11 @@ impl DevicePool {
         device_id: u32,
         iface: IfaceKind,
     ) -> Result<DevicePipe> {
-        self.ctx_mut(device_id)?.claim_interface(iface)
+        let ctx = self.ctx_mut(device_id)?;
+        if !ctx.plugged {
+            return Err(LibUsbError::NoDevice.into());
+        }
+        ctx.claim_interface(iface)
     }
 
     pub(super) fn release_interface(&mut self, device_id: u32, iface: IfaceKind) -> Result<()> {
@@ -61,11 +70,40 @@ impl DevicePool {
         Ok(())
     }
 
-    pub(super) fn pool_and_run(&mut self, device: Device) {
-        let ctx = Context::run(device, self.next_id);
+    /// Pool the device and run it, returns the id assigned to the device.
+    pub(super) fn pool_and_run(&mut self, mut device: Device) -> u32 {
+        let device_id = self.next_id;
+        // Each emulated device is connected to its own port.
+        // Port number wraps around if more than 256 devices are pooled.
+        #[allow(clippy::cast_possible_truncation)]
+        device.set_port_numbers(vec![device_id as u8]);
+        let ctx = Context::run(device, device_id);
 
         self.next_id += 1;
         self.contexts.push(ctx);
+        device_id
+    }
+
+    /// Set the plugged state of the device. Unplugged devices are hidden from enumeration and
+    /// can't be opened, and all interfaces claimed by the host are released.
+    pub(super) fn set_plugged(&mut self, device_id: u32, plugged: bool) -> Result<()> {
+        let ctx = self.ctx_mut(device_id)?;
+        ctx.plugged = plugged;
+        if !plugged {
+            for claimed in ctx.iface_state.values_mut() {
+                *claimed = false;
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn is_plugged(&self, device_id: u32) -> Result<bool> {
+        Ok(self.ctx(device_id)?.plugged)
+    }
+
+    pub(super) fn set_speed(&mut self, device_id: u32, speed: BusSpeed) -> Result<()> {
+        self.ctx_mut(device_id)?.device.set_speed(speed);
+        Ok(())
     }
 
     fn ctx_mut(&mut self, id: u32) -> Result<&mut Context> {
@@ -95,6 +133,7 @@ struct Context {
     device: Device,
     device_id: u32,
     channel: DevicePipe,
+    plugged: bool,
 
     /// Hold interface state.
     /// Currently just holds claimed state.
@@ -116,6 +155,7 @@ impl Context {
             device,
             device_id,
             channel: Arc::new(Mutex::new(channel)),
+            plugged: true,
             iface_state,
         }
     }
@@ -2,24 +2,44 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use async_std::channel::Receiver;
 use rand::seq::SliceRandom;
 use semver::Version;
 use thiserror::Error;
 
-use crate::u3v::{BusSpeed, DeviceInfo};
+use crate::{
+    u3v::{BusSpeed, DeviceInfo},
+    PixelFormat,
+};
 
 use super::{
+    command_log::CommandLog,
     device::Device,
     device_pool::DevicePool,
-    memory::{Memory, ABRM, SBRM},
+    emulator_handle::EmulatorHandle,
+    event_module::{Event, EventSender},
+    fault::{ControlFault, FaultInjector, StreamFault},
+    genapi::{GenApiReg, HEIGHT_INC, HEIGHT_MIN, USER_SET_LENGTH, WIDTH_INC, WIDTH_MIN},
+    image::{self, TestPattern},
+    memory::{ManifestTable, Memory, ABRM, SBRM},
+    stream_module,
+    write_hook::{DeviceMemory, WriteHooks},
 };
 
-use cameleon_impl::memory::prelude::*;
+use cameleon_impl::memory::{prelude::*, AccessRight, MemoryFragment};
+
+use crate::u3v::protocol::ack::GenCpStatus;
 
 #[derive(Debug, Error)]
 pub enum BuilderError {
     #[error("invalid string: {0}")]
     InvalidString(String),
+
+    #[error("invalid stream setting: {0}")]
+    InvalidStreamSetting(String),
+
+    #[error("invalid memory fragment: {0}")]
+    InvalidMemoryFragment(String),
 }
 
 pub type BuilderResult<T> = std::result::Result<T, BuilderError>;
@@ -27,15 +47,16 @@ pub type BuilderResult<T> = std::result::Result<T, BuilderError>;
 /// USB3 emulated device builder.
 /// All initial configuration of the device must be done via this builder.
 ///
-/// An emulator is passed to the device pool and user can't control the emulator itself directly
-/// once build process is finished by calling [`EmulatorBuilder::build`].
+/// An emulator is passed to the device pool once build process is finished by calling
+/// [`EmulatorBuilder::build`], then the emulator can be controlled at runtime via
+/// [`EmulatorHandle`] returned from the method.
 ///
-/// Emulators in the device pool can be found by [`crate::u3v::enumerate_devices`] and controlled via
+/// Emulators in the device pool can be found by [`crate::emulator::enumerate_devices`] and controlled via
 /// [`crate::u3v::Device`] in the same way as real device.
 ///
 /// # Example
 /// ```rust
-/// use cameleon_device::u3v::{EmulatorBuilder, enumerate_devices};
+/// use cameleon_device::emulator::{EmulatorBuilder, enumerate_devices};
 ///
 /// // Build device with default configuration and pass it to the device pool.
 /// // Now the device pool has one device.
@@ -51,6 +72,10 @@ pub type BuilderResult<T> = std::result::Result<T, BuilderError>;
 /// ```
 pub struct EmulatorBuilder {
     memory: Memory,
+    faults: FaultInjector,
+    hooks: WriteHooks,
+    event_sender: EventSender,
+    injected_events: Receiver<Event>,
 }
 
 impl EmulatorBuilder {
@@ -67,18 +92,26 @@ impl EmulatorBuilder {
             .collect();
         memory.write::<ABRM::SerialNumber>(serial_number).unwrap();
 
-        Self { memory }
+        let (event_sender, injected_events) = EventSender::new();
+
+        Self {
+            memory,
+            faults: FaultInjector::default(),
+            hooks: WriteHooks::default(),
+            event_sender,
+            injected_events,
+        }
     }
 
-    /// Build an emulator and pass it to the device pool. User can't control the emulator itself
-    /// directly once call this method.
+    /// Build an emulator and pass it to the device pool, returns a handle to control the emulator
+    /// at runtime.
     ///
-    /// Emulators in the device pool can be found by [`crate::u3v::enumerate_devices`] and controlled via
+    /// Emulators in the device pool can be found by [`crate::emulator::enumerate_devices`] and controlled via
     /// [`crate::u3v::Device`] in the same way as real device.
     ///
     /// # Example
     /// ```rust
-    /// use cameleon_device::u3v::EmulatorBuilder;
+    /// use cameleon_device::emulator::EmulatorBuilder;
     ///
     /// // Build device with default configuration and pass it to the device pool.
     /// // Now the device pool has one device.
@@ -86,13 +119,39 @@ impl EmulatorBuilder {
     ///
     /// // Set model name and serial number, then build device.
     /// // Now the device pool has two devices.
-    /// EmulatorBuilder::new().user_defined_name("My Camera").unwrap().serial_number("CAM1984").unwrap().build();
-    ///
+    /// let handle = EmulatorBuilder::new().user_defined_name("My Camera").unwrap().serial_number("CAM1984").unwrap().build();
+    /// assert!(handle.is_plugged().unwrap());
     /// ```
-    pub fn build(self) {
+    pub fn build(mut self) -> EmulatorHandle {
+        // Settings at this point are used as `Default` user set, and `UserSet1` is initialized
+        // with the same settings.
+        let start = GenApiReg::Width::ADDRESS;
+        let user_set = self
+            .memory
+            .read_raw(start..start + USER_SET_LENGTH)
+            .unwrap()
+            .to_vec();
+        self.memory
+            .write::<GenApiReg::DefaultUserSet>(user_set.clone())
+            .unwrap();
+        self.memory.write::<GenApiReg::UserSet1>(user_set).unwrap();
+        // Writes done while building the device must not trigger hooks.
+        self.hooks.reset();
+
         let device_info = self.build_device_info();
-        let device = Device::new(self.memory, device_info);
-        DevicePool::with(|pool| pool.pool_and_run(device));
+        let log = CommandLog::default();
+        let device = Device::new(
+            self.memory,
+            device_info,
+            self.faults.clone(),
+            self.hooks,
+            log.clone(),
+            self.injected_events,
+        );
+        let memory = device.memory();
+        let device_id = DevicePool::with(|pool| pool.pool_and_run(device));
+
+        EmulatorHandle::new(device_id, memory, self.faults, self.event_sender, log)
     }
 
     /// Setter of serial number of the device. The data is flushed to ABRM segment of the device memory.
@@ -108,14 +167,14 @@ impl EmulatorBuilder {
     /// # Examples
     ///
     /// ```rust
-    /// use cameleon_device::u3v::EmulatorBuilder;
+    /// use cameleon_device::emulator::EmulatorBuilder;
     ///
     /// assert!(EmulatorBuilder::new().serial_number("CAM1984").is_ok());
     /// assert!(EmulatorBuilder::new().serial_number("カム1984年").is_err());
     /// ```
     pub fn serial_number(mut self, serial: &str) -> BuilderResult<Self> {
         self.memory
-            .write::<ABRM::UserDefinedName>(serial.into())
+            .write::<ABRM::SerialNumber>(serial.into())
             .map_err(|e| BuilderError::InvalidString(format! {"{}", e}))?;
         Ok(self)
     }
@@ -133,7 +192,7 @@ impl EmulatorBuilder {
     /// # Examples
     ///
     /// ```rust
-    /// use cameleon_device::u3v::EmulatorBuilder;
+    /// use cameleon_device::emulator::EmulatorBuilder;
     ///
     /// assert!(EmulatorBuilder::new().user_defined_name("user define name").is_ok());
     /// assert!(EmulatorBuilder::new().user_defined_name("使用者が定義した名前").is_err());
@@ -145,6 +204,336 @@ impl EmulatorBuilder {
         Ok(self)
     }
 
+    /// Setter of the sensor size of the device. The default size is 640x480.
+    ///
+    /// `Width` and `Height` features are also set to the sensor size, i.e. images are captured
+    /// with full resolution by default.
+    ///
+    /// NOTE: `width` must be a multiple of 4 and `height` must be a multiple of 2, and both of
+    /// them must be 16 or greater.
+    ///
+    /// # Errors
+    /// If `width` or `height` doesn't satisfy the constraints, then
+    /// [`BuilderError::InvalidStreamSetting`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::EmulatorBuilder;
+    ///
+    /// assert!(EmulatorBuilder::new().image_size(1920, 1080).is_ok());
+    /// assert!(EmulatorBuilder::new().image_size(0, 1080).is_err());
+    /// assert!(EmulatorBuilder::new().image_size(1921, 1080).is_err());
+    /// ```
+    pub fn image_size(mut self, width: u32, height: u32) -> BuilderResult<Self> {
+        if !image::is_valid_roi(width, 0, width, WIDTH_MIN, WIDTH_INC)
+            || !image::is_valid_roi(height, 0, height, HEIGHT_MIN, HEIGHT_INC)
+        {
+            return Err(BuilderError::InvalidStreamSetting(format!(
+                "image size {}x{} doesn't satisfy the constraints",
+                width, height
+            )));
+        }
+        self.memory.write::<GenApiReg::SensorWidth>(width).unwrap();
+        self.memory
+            .write::<GenApiReg::SensorHeight>(height)
+            .unwrap();
+        self.memory.write::<GenApiReg::Width>(width).unwrap();
+        self.memory.write::<GenApiReg::Height>(height).unwrap();
+        self.memory.write::<GenApiReg::OffsetX>(0).unwrap();
+        self.memory.write::<GenApiReg::OffsetY>(0).unwrap();
+        self.update_payload_size();
+        Ok(self)
+    }
+
+    /// Setter of the pixel format of images generated by the device. The default format is
+    /// [`PixelFormat::Mono8`].
+    ///
+    /// NOTE: Only `Mono8`, `Mono16`, `BayerGR8`, `BayerRG8`, `BayerGB8`, `BayerBG8`, and `RGB8` are
+    /// supported.
+    ///
+    /// # Errors
+    /// If `format` is not supported, then [`BuilderError::InvalidStreamSetting`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::{emulator::EmulatorBuilder, PixelFormat};
+    ///
+    /// assert!(EmulatorBuilder::new().pixel_format(PixelFormat::RGB8).is_ok());
+    /// assert!(EmulatorBuilder::new().pixel_format(PixelFormat::YUV422_8).is_err());
+    /// ```
+    pub fn pixel_format(mut self, format: PixelFormat) -> BuilderResult<Self> {
+        if image::bytes_per_pixel(format).is_none() {
+            return Err(BuilderError::InvalidStreamSetting(format!(
+                "{:?} is not supported",
+                format
+            )));
+        }
+        self.memory
+            .write::<GenApiReg::PixelFormat>(format.into())
+            .unwrap();
+        self.update_payload_size();
+        Ok(self)
+    }
+
+    /// Setter of the test pattern of images generated by the device. The default pattern is
+    /// [`TestPattern::Gradient`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::{EmulatorBuilder, TestPattern};
+    ///
+    /// EmulatorBuilder::new().test_pattern(TestPattern::Checkerboard).build();
+    /// ```
+    #[must_use]
+    pub fn test_pattern(mut self, pattern: TestPattern) -> Self {
+        self.memory
+            .write::<GenApiReg::TestPattern>(pattern.as_num())
+            .unwrap();
+        self
+    }
+
+    /// Setter of the frame rate of the device in Hz. The default frame rate is 30 Hz.
+    ///
+    /// # Errors
+    /// If `frame_rate` is not a positive finite number, then
+    /// [`BuilderError::InvalidStreamSetting`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::EmulatorBuilder;
+    ///
+    /// assert!(EmulatorBuilder::new().frame_rate(60.0).is_ok());
+    /// assert!(EmulatorBuilder::new().frame_rate(0.0).is_err());
+    /// ```
+    pub fn frame_rate(mut self, frame_rate: f64) -> BuilderResult<Self> {
+        if !(frame_rate.is_finite() && frame_rate > 0.0) {
+            return Err(BuilderError::InvalidStreamSetting(format!(
+                "frame rate must be a positive number: {}",
+                frame_rate
+            )));
+        }
+        self.memory
+            .write::<GenApiReg::AcquisitionFrameRate>(frame_rate)
+            .unwrap();
+        Ok(self)
+    }
+
+    /// Inject a fault into the control channel of the device. The fault is applied to the first
+    /// command which the device receives after it's built.
+    ///
+    /// Faults can be also injected at runtime via [`FaultInjector`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::{
+    ///     emulator::{ControlFault, EmulatorBuilder},
+    ///     u3v::protocol::ack::GenCpStatus,
+    /// };
+    ///
+    /// EmulatorBuilder::new()
+    ///     .control_fault(ControlFault::NakGenCp(GenCpStatus::Busy))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn control_fault(self, fault: ControlFault) -> Self {
+        self.faults.inject_control(fault);
+        self
+    }
+
+    /// Inject a fault into the stream channel of the device. The fault is applied to the first
+    /// frame which the device generates after it's built.
+    ///
+    /// Faults can be also injected at runtime via [`FaultInjector`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::{EmulatorBuilder, StreamFault};
+    ///
+    /// EmulatorBuilder::new()
+    ///     .stream_fault(StreamFault::DataOverrun)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn stream_fault(self, fault: StreamFault) -> Self {
+        self.faults.inject_stream(fault);
+        self
+    }
+
+    /// Returns a handle to inject faults into the device at runtime.
+    ///
+    /// The handle remains valid after [`EmulatorBuilder::build`] is called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::{ControlFault, EmulatorBuilder};
+    ///
+    /// let builder = EmulatorBuilder::new();
+    /// let faults = builder.fault_injector();
+    /// builder.build();
+    ///
+    /// faults.inject_control(ControlFault::CorruptRequestId);
+    /// ```
+    #[must_use]
+    pub fn fault_injector(&self) -> FaultInjector {
+        self.faults.clone()
+    }
+
+    /// Returns a handle to send arbitrary events from the device to the host.
+    ///
+    /// The handle remains valid after [`EmulatorBuilder::build`] is called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::EmulatorBuilder;
+    ///
+    /// let builder = EmulatorBuilder::new();
+    /// let events = builder.event_sender();
+    /// builder.build();
+    ///
+    /// events.send(0x8001, vec![0xde, 0xad]);
+    /// ```
+    #[must_use]
+    pub fn event_sender(&self) -> EventSender {
+        self.event_sender.clone()
+    }
+
+    /// Map a memory fragment into the device memory. The memory is extended if the fragment
+    /// exceeds the end of the memory.
+    ///
+    /// A fragment can be built from an initial memory image by [`MemoryFragment::new`], or from a
+    /// register map defined by [`cameleon_impl::memory::register_map`] via the generated
+    /// `fragment` function.
+    ///
+    /// # Errors
+    /// If the fragment overlaps with registers which are already mapped, then
+    /// [`BuilderError::InvalidMemoryFragment`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::{AccessRight, EmulatorBuilder, MemoryFragment};
+    /// use cameleon_impl::memory::register_map;
+    ///
+    /// #[register_map(base = 0x10_0000, endianness = LE)]
+    /// pub enum VendorReg {
+    ///     #[register(len = 4, access = RW, ty = u32)]
+    ///     Gain = 10,
+    /// }
+    ///
+    /// let image = MemoryFragment::new(0x20_0000, vec![0; 16], AccessRight::RO);
+    /// let builder = EmulatorBuilder::new()
+    ///     .memory_fragment(VendorReg::fragment())
+    ///     .unwrap()
+    ///     .memory_fragment(image)
+    ///     .unwrap();
+    ///
+    /// // ABRM is already mapped.
+    /// let abrm = MemoryFragment::new(0, vec![0; 4], AccessRight::RW);
+    /// assert!(builder.memory_fragment(abrm).is_err());
+    /// ```
+    pub fn memory_fragment(mut self, fragment: MemoryFragment) -> BuilderResult<Self> {
+        if self.memory.map_fragment(&fragment) {
+            Ok(self)
+        } else {
+            Err(BuilderError::InvalidMemoryFragment(format!(
+                "{:#X}..{:#X} overlaps with mapped registers",
+                fragment.range().start,
+                fragment.range().end
+            )))
+        }
+    }
+
+    /// Replace the GenApi XML of the device with `xml`.
+    ///
+    /// The XML is placed at the end of the device memory, so this method should be called after
+    /// all memory fragments which the XML refers to are mapped by
+    /// [`EmulatorBuilder::memory_fragment`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::emulator::EmulatorBuilder;
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+    /// <RegisterDescription ModelName="MyCamera" VendorName="Vendor"></RegisterDescription>"#;
+    /// EmulatorBuilder::new().genapi_xml(xml).build();
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn genapi_xml(mut self, xml: &str) -> Self {
+        // Keep the XML address aligned to 8 bytes as the default XML is.
+        let address = (self.memory.size() + 7) & !7;
+        let fragment = MemoryFragment::new(address, xml.as_bytes().to_vec(), AccessRight::RO);
+        // The fragment is placed after the end of the memory, so it never overlaps.
+        assert!(self.memory.map_fragment(&fragment));
+        self.memory
+            .write::<ManifestTable::RegisterAddress>(address as u64)
+            .unwrap();
+        self.memory
+            .write::<ManifestTable::FileSize>(xml.len() as u64)
+            .unwrap();
+        self
+    }
+
+    /// Register a hook which is run when the host writes to the register `R`.
+    ///
+    /// Hooks are run after the written data is stored in the device memory, and before the
+    /// device sends an acknowledge. If a hook returns an error, the device responds with the
+    /// error status instead of success.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cameleon_device::{emulator::EmulatorBuilder, u3v::protocol::ack::GenCpStatus};
+    /// use cameleon_impl::memory::register_map;
+    ///
+    /// #[register_map(base = 0x10_0000, endianness = LE)]
+    /// pub enum VendorReg {
+    ///     #[register(len = 4, access = RW, ty = u32)]
+    ///     Gain = 0,
+    ///
+    ///     #[register(len = 4, access = RO, ty = u32)]
+    ///     GainRaw = 0,
+    /// }
+    ///
+    /// EmulatorBuilder::new()
+    ///     .memory_fragment(VendorReg::fragment())
+    ///     .unwrap()
+    ///     .on_write::<VendorReg::Gain, _>(|memory| {
+    ///         let gain = memory.read::<VendorReg::Gain>().unwrap();
+    ///         // Emulate a quirk that the device rejects odd gain values.
+    ///         if gain % 2 == 1 {
+    ///             return Err(GenCpStatus::InvalidParameter);
+    ///         }
+    ///         memory.write::<VendorReg::GainRaw>(gain * 16).unwrap();
+    ///         Ok(())
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn on_write<R, F>(mut self, hook: F) -> Self
+    where
+        R: Register,
+        F: FnMut(&mut DeviceMemory<'_>) -> Result<(), GenCpStatus> + Send + 'static,
+    {
+        self.hooks.register::<R, _>(&mut self.memory, hook);
+        self
+    }
+
+    /// Update `PayloadSize` and `RequiredPayloadSize` of SIRM with the current image settings.
+    fn update_payload_size(&mut self) {
+        // Image settings are verified when they're written.
+        assert!(stream_module::update_payload_size(&mut self.memory));
+    }
+
     fn build_device_info(&self) -> DeviceInfo {
         use ABRM::{
             DeviceVersion, FamilyName, GenCpVersionMajor, GenCpVersionMinor, ManufacturerInfo,
@@ -165,7 +554,7 @@ impl EmulatorBuilder {
         let device_version = self.memory.read::<DeviceVersion>().unwrap();
         let manufacturer_info = self.memory.read::<ManufacturerInfo>().unwrap();
         let user_defined_name = Some(self.memory.read::<UserDefinedName>().unwrap());
-        let supported_speed = self.current_speed();
+        let current_speed = self.current_speed();
         let serial_number = self.memory.read::<SerialNumber>().unwrap();
 
         // TODO: Read from SBRM.
@@ -178,7 +567,7 @@ impl EmulatorBuilder {
         let guid = if serial_len > 8 {
             format!("EMU-{}", &serial_number[serial_len - 8..])
         } else {
-            let pad: String = std::iter::repeat('0').take(8 - serial_len).collect();
+            let pad = "0".repeat(8 - serial_len);
             format!("EMU-{}{}", pad, serial_number)
         };
 
@@ -193,7 +582,10 @@ impl EmulatorBuilder {
             manufacturer_info,
             serial_number,
             user_defined_name,
-            supported_speed,
+            supported_speed: current_speed,
+            bus_number: None,
+            port_numbers: vec![],
+            current_speed: Some(current_speed),
         }
     }
 
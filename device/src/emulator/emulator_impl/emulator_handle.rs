@@ -0,0 +1,351 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::{ops::Range, sync::Arc};
+
+use async_std::{sync::Mutex, task};
+
+use cameleon_impl::memory::{prelude::*, MemoryResult};
+
+use crate::u3v::{BusSpeed, Result};
+
+use super::{
+    command_log::{CommandLog, LoggedCommand},
+    device_pool::DevicePool,
+    event_module::EventSender,
+    fault::FaultInjector,
+    memory::{Memory, SBRM},
+};
+
+/// A handle to control an emulated device at runtime.
+///
+/// The handle is returned from [`super::EmulatorBuilder::build`], and remains valid while the
+/// emulator lives in the device pool.
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon_device::{emulator::EmulatorBuilder, u3v::BusSpeed};
+///
+/// let handle = EmulatorBuilder::new().build();
+///
+/// // Read the serial number from ABRM.
+/// let serial_number = handle.read_memory(0x0144..0x0184).unwrap();
+///
+/// // Emulate a device connected to a USB2 port.
+/// handle.set_speed(BusSpeed::HighSpeed).unwrap();
+///
+/// // Emulate unplugging and replugging the device.
+/// handle.unplug().unwrap();
+/// assert!(!handle.is_plugged().unwrap());
+/// handle.replug().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct EmulatorHandle {
+    device_id: u32,
+    memory: Arc<Mutex<Memory>>,
+    faults: FaultInjector,
+    events: EventSender,
+    log: CommandLog,
+}
+
+impl EmulatorHandle {
+    pub(super) fn new(
+        device_id: u32,
+        memory: Arc<Mutex<Memory>>,
+        faults: FaultInjector,
+        events: EventSender,
+        log: CommandLog,
+    ) -> Self {
+        Self {
+            device_id,
+            memory,
+            faults,
+            events,
+            log,
+        }
+    }
+
+    /// Read the device memory. Access rights of the memory are not checked.
+    ///
+    /// # Errors
+    /// If the range is out of the device memory, then [`MemoryError::InvalidAddress`] is
+    /// returned.
+    ///
+    /// [`MemoryError::InvalidAddress`]: cameleon_impl::memory::MemoryError::InvalidAddress
+    pub fn read_memory(&self, range: Range<usize>) -> MemoryResult<Vec<u8>> {
+        let memory = task::block_on(self.memory.lock());
+        memory.peek(range).map(<[u8]>::to_vec)
+    }
+
+    /// Write `data` to the device memory starting from `address`. Access rights of the memory
+    /// are not checked.
+    ///
+    /// NOTE: Unlike writes from the host, the write doesn't cause any side effects on the device,
+    /// e.g. hooks registered by [`super::EmulatorBuilder::on_write`] are not run.
+    ///
+    /// # Errors
+    /// If the range is out of the device memory, then [`MemoryError::InvalidAddress`] is
+    /// returned.
+    ///
+    /// [`MemoryError::InvalidAddress`]: cameleon_impl::memory::MemoryError::InvalidAddress
+    pub fn write_memory(&self, address: usize, data: &[u8]) -> MemoryResult<()> {
+        let mut memory = task::block_on(self.memory.lock());
+        memory.poke(address, data)
+    }
+
+    /// Read the value of the register. Access rights of the register are not checked.
+    ///
+    /// # Errors
+    /// If the register is out of the device memory, then [`MemoryError::InvalidAddress`] is
+    /// returned.
+    ///
+    /// [`MemoryError::InvalidAddress`]: cameleon_impl::memory::MemoryError::InvalidAddress
+    pub fn read<R: Register>(&self) -> MemoryResult<R::Ty> {
+        R::parse(&self.read_memory(R::range())?)
+    }
+
+    /// Write `data` to the register. Access rights of the register are not checked.
+    ///
+    /// See [`EmulatorHandle::write_memory`] for side effects of the write.
+    ///
+    /// # Errors
+    /// If the register is out of the device memory, then [`MemoryError::InvalidAddress`] is
+    /// returned. If `data` is invalid for the register, then
+    /// [`MemoryError::InvalidRegisterData`] is returned.
+    ///
+    /// [`MemoryError::InvalidAddress`]: cameleon_impl::memory::MemoryError::InvalidAddress
+    /// [`MemoryError::InvalidRegisterData`]: cameleon_impl::memory::MemoryError::InvalidRegisterData
+    pub fn write<R: Register>(&self, data: R::Ty) -> MemoryResult<()> {
+        self.write_memory(R::ADDRESS, &R::serialize(data)?)
+    }
+
+    /// Returns commands processed by the device in the received order.
+    ///
+    /// The log holds only the latest 1024 commands.
+    #[must_use]
+    pub fn command_log(&self) -> Vec<LoggedCommand> {
+        self.log.commands()
+    }
+
+    /// Clear the command log.
+    pub fn clear_command_log(&self) {
+        self.log.clear();
+    }
+
+    /// Returns `(address, data)` pairs written by the host in the written order.
+    ///
+    /// Writes by stacked commands are flattened.
+    #[must_use]
+    pub fn host_writes(&self) -> Vec<(u64, Vec<u8>)> {
+        self.command_log()
+            .into_iter()
+            .flat_map(|command| match command {
+                LoggedCommand::WriteMem { address, data } => vec![(address, data)],
+                LoggedCommand::WriteMemStacked(entries) => entries,
+                LoggedCommand::ReadMem { .. } | LoggedCommand::ReadMemStacked(..) => vec![],
+            })
+            .collect()
+    }
+
+    /// Unplug the device. The device disappears from the device pool, and all requests from the
+    /// host fail with [`crate::u3v::LibUsbError::NoDevice`] until [`EmulatorHandle::replug`] is
+    /// called.
+    ///
+    /// The plugged state is independent of [`super::FaultInjector::disconnect`].
+    ///
+    /// # Errors
+    /// If the device is already removed from the device pool, then
+    /// [`crate::u3v::LibUsbError::NotFound`] is returned.
+    pub fn unplug(&self) -> Result<()> {
+        DevicePool::with(|pool| pool.set_plugged(self.device_id, false))
+    }
+
+    /// Replug the device unplugged by [`EmulatorHandle::unplug`]. The device state, e.g. the
+    /// device memory, is preserved, but the host needs to open the device again.
+    ///
+    /// A disconnection injected by [`super::FaultInjector::disconnect`] is kept.
+    ///
+    /// # Errors
+    /// If the device is already removed from the device pool, then
+    /// [`crate::u3v::LibUsbError::NotFound`] is returned.
+    pub fn replug(&self) -> Result<()> {
+        DevicePool::with(|pool| pool.set_plugged(self.device_id, true))
+    }
+
+    /// Returns `true` if the device is plugged.
+    ///
+    /// # Errors
+    /// If the device is already removed from the device pool, then
+    /// [`crate::u3v::LibUsbError::NotFound`] is returned.
+    pub fn is_plugged(&self) -> Result<bool> {
+        DevicePool::with(|pool| pool.is_plugged(self.device_id))
+    }
+
+    /// Change the link speed of the device. The speed is reflected in both
+    /// [`crate::u3v::DeviceInfo::current_speed`] of newly enumerated devices and `CurrentSpeed`
+    /// register of SBRM.
+    ///
+    /// # Errors
+    /// If the device is already removed from the device pool, then
+    /// [`crate::u3v::LibUsbError::NotFound`] is returned.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn set_speed(&self, speed: BusSpeed) -> Result<()> {
+        DevicePool::with(|pool| pool.set_speed(self.device_id, speed))?;
+        let speed = match speed {
+            BusSpeed::LowSpeed => 0b00001,
+            BusSpeed::FullSpeed => 0b00010,
+            BusSpeed::HighSpeed => 0b00100,
+            BusSpeed::SuperSpeed => 0b01000,
+            BusSpeed::SuperSpeedPlus => 0b10000,
+        };
+        let mut memory = task::block_on(self.memory.lock());
+        memory.write::<SBRM::CurrentSpeed>(speed).unwrap();
+        Ok(())
+    }
+
+    /// Returns a handle to inject faults into the device.
+    #[must_use]
+    pub fn fault_injector(&self) -> FaultInjector {
+        self.faults.clone()
+    }
+
+    /// Returns a handle to send events from the device to the host.
+    #[must_use]
+    pub fn event_sender(&self) -> EventSender {
+        self.events.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        emulator::{self, EmulatorBuilder},
+        u3v::{
+            prelude::*,
+            protocol::{ack, cmd},
+            register_map::abrm,
+            ControlChannel, DeviceFilter, Error, LibUsbError,
+        },
+    };
+
+    use super::{super::memory::ABRM, *};
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    fn build(serial_number: &str) -> EmulatorHandle {
+        EmulatorBuilder::new()
+            .serial_number(serial_number)
+            .unwrap()
+            .build()
+    }
+
+    fn open(serial_number: &str) -> Result<ControlChannel> {
+        let filter = DeviceFilter::new().serial_number(serial_number);
+        let devices = emulator::enumerate_devices_with_filter(&filter)?;
+        let mut channel = devices
+            .into_iter()
+            .next()
+            .ok_or(LibUsbError::NoDevice)?
+            .control_channel()?;
+        channel.open()?;
+        Ok(channel)
+    }
+
+    fn read_serial_number(channel: &ControlChannel, request_id: u16) -> Result<String> {
+        let (address, len) = abrm::SERIAL_NUMBER;
+        let command = cmd::ReadMem::new(address, len).finalize(request_id);
+        let mut buf = vec![];
+        command.serialize(&mut buf)?;
+        channel.send(&buf, TIMEOUT)?;
+
+        let mut buf = vec![0; command.maximum_ack_len()];
+        channel.recv(&mut buf, TIMEOUT)?;
+        let ack = ack::AckPacket::parse(&buf)?;
+        assert!(ack.status().is_success());
+        let data = ack.scd_as::<ack::ReadMem>()?.data;
+        let len = data.iter().position(|c| *c == 0).unwrap();
+        Ok(String::from_utf8(data[..len].to_vec()).unwrap())
+    }
+
+    fn is_no_device<T>(result: Result<T>) -> bool {
+        matches!(result, Err(Error::LibUsb(LibUsbError::NoDevice)))
+    }
+
+    #[test]
+    fn test_unplug() {
+        let handle = build("EMH0001");
+        let channel = open("EMH0001").unwrap();
+        assert_eq!(read_serial_number(&channel, 0).unwrap(), "EMH0001");
+
+        handle.unplug().unwrap();
+        assert!(!handle.is_plugged().unwrap());
+        // Requests through the opened channel fail, and the device can't be found.
+        assert!(is_no_device(read_serial_number(&channel, 1)));
+        assert!(is_no_device(open("EMH0001")));
+        // The device state is still accessible from the handle.
+        assert_eq!(handle.read::<ABRM::SerialNumber>().unwrap(), "EMH0001");
+    }
+
+    #[test]
+    fn test_replug() {
+        let handle = build("EMH0002");
+        handle.unplug().unwrap();
+        handle.replug().unwrap();
+        assert!(handle.is_plugged().unwrap());
+        let channel = open("EMH0002").unwrap();
+        assert_eq!(read_serial_number(&channel, 0).unwrap(), "EMH0002");
+
+        // Replugging doesn't clear a disconnection injected as a fault.
+        let faults = handle.fault_injector();
+        faults.disconnect();
+        handle.unplug().unwrap();
+        drop(channel);
+        handle.replug().unwrap();
+        assert!(faults.is_disconnected());
+        let channel = open("EMH0002").unwrap();
+        assert!(is_no_device(read_serial_number(&channel, 1)));
+
+        faults.reconnect();
+        assert_eq!(read_serial_number(&channel, 2).unwrap(), "EMH0002");
+    }
+
+    #[test]
+    fn test_set_speed() {
+        let handle = build("EMH0003");
+        handle.set_speed(BusSpeed::HighSpeed).unwrap();
+        assert_eq!(handle.read::<SBRM::CurrentSpeed>().unwrap(), 0b00100);
+
+        let filter = DeviceFilter::new().serial_number("EMH0003");
+        let devices = emulator::enumerate_devices_with_filter(&filter).unwrap();
+        assert_eq!(
+            devices[0].device_info().current_speed,
+            Some(BusSpeed::HighSpeed)
+        );
+    }
+
+    #[test]
+    fn test_command_log() {
+        let handle = build("EMH0004");
+        let channel = open("EMH0004").unwrap();
+        read_serial_number(&channel, 0).unwrap();
+
+        let (address, length) = abrm::SERIAL_NUMBER;
+        assert_eq!(
+            handle.command_log(),
+            vec![LoggedCommand::ReadMem { address, length }]
+        );
+        // Reads are not host writes, and accesses from the handle are not logged.
+        assert!(handle.host_writes().is_empty());
+        handle
+            .write::<ABRM::UserDefinedName>("name".into())
+            .unwrap();
+        assert_eq!(handle.command_log().len(), 1);
+
+        handle.clear_command_log();
+        assert!(handle.command_log().is_empty());
+    }
+}
@@ -2,98 +2,244 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::sync::Arc;
+
 use async_std::{
-    channel::{Receiver, Sender},
-    prelude::*,
+    channel::{self, Receiver, Sender},
+    sync::Mutex,
 };
+use futures::{select, FutureExt, StreamExt};
+
+use cameleon_impl::memory::prelude::*;
 
 use super::{
+    device::Timestamp,
+    memory::{Memory, ABRM, EIRM},
     shared_queue::SharedQueue,
     signal::{EventSignal, InterfaceSignal},
     IfaceKind,
 };
 
+/// Event ID of the test event which is sent when 1 is written to `EIRM::EventTestControl`.
+pub(super) const TEST_EVENT_ID: u16 = 0x4FFF;
+
+/// Size of the prefix and CCD of an event command.
+const EVENT_CCD_SIZE: usize = 12;
+/// Size of the header of each event in SCD, which consists of event size, event ID and timestamp.
+const EVENT_HEADER_SIZE: usize = 12;
+
+/// An event sent to the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Event {
+    pub(super) event_id: u16,
+    pub(super) data: Vec<u8>,
+}
+
+impl Event {
+    pub(super) fn new(event_id: u16, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            event_id,
+            data: data.into(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        EVENT_HEADER_SIZE + self.data.len()
+    }
+}
+
+/// A handle to send arbitrary events from an emulated device to the host.
+///
+/// The handle is obtained by [`super::EmulatorBuilder::event_sender`] and remains valid after the
+/// emulator is built.
+///
+/// Events are sent only while the host enables the event interface of the device via `EIRM`,
+/// otherwise they are discarded. Timestamps of events are filled with the device timestamp when
+/// the events are sent.
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon_device::emulator::EmulatorBuilder;
+///
+/// let builder = EmulatorBuilder::new();
+/// let events = builder.event_sender();
+/// builder.build();
+///
+/// // Send an event with ID `0x8001` and its payload.
+/// events.send(0x8001, vec![1, 2, 3, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    tx: Sender<Event>,
+}
+
+impl EventSender {
+    pub(super) fn new() -> (Self, Receiver<Event>) {
+        let (tx, rx) = channel::unbounded();
+        (Self { tx }, rx)
+    }
+
+    /// Send an event with `event_id` and `data` to the host.
+    pub fn send(&self, event_id: u16, data: impl Into<Vec<u8>>) {
+        if self.tx.try_send(Event::new(event_id, data)).is_err() {
+            log::warn!("the emulator is already dropped, the event is discarded");
+        }
+    }
+}
+
 pub(super) struct EventModule {
+    memory: Arc<Mutex<Memory>>,
     queue: SharedQueue<Vec<u8>>,
-    timestamp: u64,
+    timestamp: Timestamp,
+    /// Events sent via [`EventSender`].
+    injected_rx: Receiver<Event>,
 
     enabled: bool,
+    request_id: u16,
 }
 
 impl EventModule {
-    pub(super) fn new(queue: SharedQueue<Vec<u8>>, timestamp: u64) -> Self {
+    pub(super) fn new(
+        memory: Arc<Mutex<Memory>>,
+        timestamp: Timestamp,
+        injected_rx: Receiver<Event>,
+        queue: SharedQueue<Vec<u8>>,
+    ) -> Self {
         Self {
+            memory,
             queue,
             timestamp,
+            injected_rx,
             enabled: false,
+            request_id: 0,
         }
     }
 
+    // FIXME: Remove this when <https://github.com/rust-lang/rust-clippy/issues/6922> is fixed.
+    #[allow(clippy::mut_mut)]
     pub(super) async fn run(
         mut self,
         signal_tx: Sender<InterfaceSignal>,
-        mut signal_rx: Receiver<EventSignal>,
+        signal_rx: Receiver<EventSignal>,
     ) {
-        while let Some(signal) = signal_rx.next().await {
-            match signal {
-                EventSignal::_EventData {
-                    event_id,
-                    data,
-                    request_id,
-                } => {
-                    if self.enabled {
-                        self.enqueue_or_halt(event_id, &data, request_id, &signal_tx)
-                    } else {
-                        log::warn! {"receive event data signal, but event module is currently disabled"}
+        let mut signal_rx = signal_rx.fuse();
+        let mut injected_rx = self.injected_rx.clone();
+
+        loop {
+            let events = select! {
+                signal = signal_rx.next().fuse() => match signal {
+                    Some(EventSignal::Events(events)) => events,
+
+                    Some(EventSignal::Enable) => {
+                        if self.enabled {
+                            log::warn! {"receive event enable signal, but event module is already enabled"}
+                        } else {
+                            self.enabled = true;
+                            log::info! {"event module is enabled"};
+                        }
+                        continue;
                     }
-                }
 
-                EventSignal::UpdateTimestamp(timestamp) => {
-                    self.timestamp = timestamp;
-                }
-
-                EventSignal::_Enable => {
-                    if self.enabled {
-                        log::warn! {"receive event enable signal, but event module is already enabled"}
-                    } else {
-                        self.enabled = true;
-                        log::info! {"event module is enabled"};
+                    Some(EventSignal::Disable(_completed)) => {
+                        if self.enabled {
+                            self.enabled = false;
+                            log::info! {"event module is disabled"};
+                        } else {
+                            log::warn! {"receive event disable signal, but event module is already disabled"}
+                        }
+                        continue;
                     }
-                }
 
-                EventSignal::Disable(_completed) => {
-                    if self.enabled {
-                        self.enabled = false;
-                        log::info! {"event module is disabled"};
-                    } else {
-                        log::warn! {"receive event disable signal, but event module is already disabled"}
-                    }
-                }
+                    Some(EventSignal::Shutdown) | None => break,
+                },
+
+                event = injected_rx.select_next_some() => vec![event],
+            };
 
-                EventSignal::Shutdown => {
-                    break;
+            if self.enabled {
+                self.send_events(events, &signal_tx).await;
+            } else {
+                log::warn! {"receive events, but event module is currently disabled"}
+            }
+        }
+    }
+
+    /// Send events to the host. Multiple events are packed into an event command if multi event
+    /// is enabled in `ABRM::DeviceConfiguration`.
+    async fn send_events(&mut self, mut events: Vec<Event>, signal_tx: &Sender<InterfaceSignal>) {
+        let (multi_event, max_len) = {
+            let memory = self.memory.lock().await;
+            let multi_event = matches!(
+                memory.read::<ABRM::DeviceConfiguration>(),
+                Ok(config) if config.first().copied().unwrap_or_default() & 0b10 != 0
+            );
+            let max_len = memory
+                .read::<EIRM::MaximumEventTransferLength>()
+                .unwrap_or_default() as usize;
+            (multi_event, max_len)
+        };
+        let timestamp = self.timestamp.as_nanos().await;
+
+        // Pack pending injected events together if possible.
+        if multi_event {
+            while let Ok(event) = self.injected_rx.try_recv() {
+                events.push(event);
+            }
+        }
+
+        let mut start = 0;
+        while start < events.len() {
+            let mut end = start + 1;
+            let mut len = EVENT_CCD_SIZE + events[start].size();
+            if multi_event {
+                while end < events.len() && len + events[end].size() <= max_len {
+                    len += events[end].size();
+                    end += 1;
                 }
             }
+
+            if len > max_len {
+                log::error!(
+                    "event {:#X} is larger than the maximum event transfer length, the event is discarded",
+                    events[start].event_id
+                );
+            } else {
+                self.enqueue_or_halt(&events[start..end], multi_event, timestamp, signal_tx);
+            }
+            start = end;
         }
     }
 
     fn enqueue_or_halt(
         &mut self,
-        event_id: u16,
-        data: &[u8],
-        request_id: u16,
+        events: &[Event],
+        multi_event: bool,
+        timestamp: u64,
         signal_tx: &Sender<InterfaceSignal>,
     ) {
-        let scd = match event_packet::EventScd::single_event(event_id, data, self.timestamp) {
-            Ok(scd) => scd,
-            Err(e) => {
-                log::error!("can't generate event packet: cause {}", e);
-                return;
-            }
-        };
+        let scds: event_packet::ProtocolResult<Vec<_>> = events
+            .iter()
+            .map(|event| {
+                if multi_event {
+                    event_packet::EventScd::multi_event(event.event_id, &event.data, timestamp)
+                } else {
+                    event_packet::EventScd::single_event(event.event_id, &event.data, timestamp)
+                }
+            })
+            .collect();
+        let packet =
+            match scds.and_then(|scds| event_packet::EventPacket::new(scds, self.request_id)) {
+                Ok(packet) => packet,
+                Err(e) => {
+                    log::error!("can't generate event packet: cause {}", e);
+                    return;
+                }
+            };
+        self.request_id = self.request_id.wrapping_add(1);
 
         let mut bytes = vec![];
-        if let Err(e) = scd.finalize(request_id).serialize(&mut bytes) {
+        if let Err(e) = packet.serialize(&mut bytes) {
             log::error!("cant't serialize event packet: cause {}", e);
             return;
         }
@@ -106,7 +252,7 @@ impl EventModule {
             match signal_tx.try_send(signal) {
                 Ok(()) => {}
                 Err(_) => {
-                    log::error!("Control module -> Interface channel is full");
+                    log::error!("Event module -> Interface channel is full");
                 }
             }
         }
@@ -122,7 +268,7 @@ mod event_packet {
 
     use thiserror::Error;
 
-    use crate::u3v::protocol::util::WriteBytes;
+    use cameleon_impl::bytes_io::WriteBytes;
 
     #[derive(Debug, Error)]
     pub(super) enum ProtocolError {
@@ -136,9 +282,9 @@ mod event_packet {
     pub(super) type ProtocolResult<T> = std::result::Result<T, ProtocolError>;
 
     pub(super) struct EventPacket<'a> {
-        _scd_len: u16,
+        scd_len: u16,
         request_id: u16,
-        scd: EventScd<'a>,
+        scds: Vec<EventScd<'a>>,
     }
 
     impl<'a> EventPacket<'a> {
@@ -146,29 +292,39 @@ mod event_packet {
         const COMMAND_FLAG: u16 = 0b1 << 14;
         const COMMAND_ID: u16 = 0x0C00;
 
-        fn from_scd(scd: EventScd<'a>, request_id: u16) -> Self {
-            Self {
-                _scd_len: scd.scd_len_unchecked(),
-                request_id,
-                scd,
+        /// Construct an event packet which contains `scds`. All SCDs must be single events or
+        /// multi events.
+        pub(super) fn new(scds: Vec<EventScd<'a>>, request_id: u16) -> ProtocolResult<Self> {
+            let mut scd_len = 0_u16;
+            for scd in &scds {
+                scd_len = scd_len.checked_add(scd.scd_len_checked()?).ok_or_else(|| {
+                    ProtocolError::InvalidPacket("scd size is larger than u16::MAX".into())
+                })?;
             }
+
+            Ok(Self {
+                scd_len,
+                request_id,
+                scds,
+            })
         }
 
         pub(super) fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
             // Serialize CCD.
-            buf.write_bytes(Self::PREFIX_MAGIC)?;
-            buf.write_bytes(Self::COMMAND_FLAG)?;
-            buf.write_bytes(Self::COMMAND_ID)?;
-            buf.write_bytes(self.scd.scd_len_unchecked())?;
-            buf.write_bytes(self.request_id)?;
+            buf.write_bytes_le(Self::PREFIX_MAGIC)?;
+            buf.write_bytes_le(Self::COMMAND_FLAG)?;
+            buf.write_bytes_le(Self::COMMAND_ID)?;
+            buf.write_bytes_le(self.scd_len)?;
+            buf.write_bytes_le(self.request_id)?;
 
             // Serialize SCD.
-            self.scd.serialize(buf)?;
+            for scd in &self.scds {
+                scd.serialize(&mut buf)?;
+            }
             Ok(())
         }
     }
 
-    // TODO: Implement Multievent.
     impl<'a> EventScd<'a> {
         pub(super) fn single_event(
             event_id: u16,
@@ -185,22 +341,25 @@ mod event_packet {
             Ok(scd)
         }
 
-        pub(super) fn finalize(self, request_id: u16) -> EventPacket<'a> {
-            EventPacket::from_scd(self, request_id)
+        /// Construct an event which is packed into a multi event command with other events.
+        pub(super) fn multi_event(
+            event_id: u16,
+            data: &'a [u8],
+            timestamp: u64,
+        ) -> ProtocolResult<Self> {
+            let mut scd = Self::single_event(event_id, data, timestamp)?;
+            scd.event_size = scd.scd_len_checked()?;
+            Ok(scd)
         }
 
         fn serialize(&self, mut buf: impl Write) -> ProtocolResult<()> {
-            buf.write_bytes(self.event_size)?;
-            buf.write_bytes(self.event_id)?;
-            buf.write_bytes(self.timestamp)?;
+            buf.write_bytes_le(self.event_size)?;
+            buf.write_bytes_le(self.event_id)?;
+            buf.write_bytes_le(self.timestamp)?;
             buf.write_all(self.data)?;
             Ok(())
         }
 
-        fn scd_len_unchecked(&self) -> u16 {
-            self.scd_len_checked().unwrap()
-        }
-
         fn scd_len_checked(&self) -> ProtocolResult<u16> {
             // event_size(2bytes) + event_id(2bytes) + timestamp(8bytes) + data_len
             let data_len: u16 = self.data.len().try_into().map_err(|_| {
@@ -224,9 +383,8 @@ mod event_packet {
             let data = &[1, 2, 3];
             let timestamp = 123_456_789;
             let event_id = 0xff;
-            let event_pacekt = EventScd::single_event(event_id, data, timestamp)
-                .unwrap()
-                .finalize(10);
+            let scd = EventScd::single_event(event_id, data, timestamp).unwrap();
+            let event_pacekt = EventPacket::new(vec![scd], 10).unwrap();
             let mut buf = vec![];
             event_pacekt.serialize(&mut buf).unwrap();
 
@@ -238,6 +396,29 @@ mod event_packet {
             assert_eq!(parsed.scd[0].timestamp, timestamp);
             assert_eq!(parsed.scd[0].data, data);
         }
+
+        #[test]
+        fn test_multi_event() {
+            let data = &[1, 2, 3];
+            let scds = vec![
+                EventScd::multi_event(0x10, data, 1).unwrap(),
+                EventScd::multi_event(0x11, &[], 2).unwrap(),
+            ];
+            let event_packet = EventPacket::new(scds, 10).unwrap();
+            let mut buf = vec![];
+            event_packet.serialize(&mut buf).unwrap();
+
+            let parsed = event::EventPacket::parse(&buf).unwrap();
+
+            assert_eq!(parsed.request_id(), 10);
+            assert_eq!(parsed.scd.len(), 2);
+            assert_eq!(parsed.scd[0].event_id, 0x10);
+            assert_eq!(parsed.scd[0].timestamp, 1);
+            assert_eq!(parsed.scd[0].data, data);
+            assert_eq!(parsed.scd[1].event_id, 0x11);
+            assert_eq!(parsed.scd[1].timestamp, 2);
+            assert!(parsed.scd[1].data.is_empty());
+        }
     }
 }
 
@@ -245,7 +426,7 @@ mod event_packet {
 mod tests {
     use std::time::Duration;
 
-    use async_std::{channel, future::timeout, task};
+    use async_std::{future::timeout, task};
 
     use crate::u3v::protocol::event;
 
@@ -253,18 +434,27 @@ mod tests {
 
     const TO: Duration = Duration::from_millis(100);
 
-    fn spawn_module() -> (
+    fn spawn_module(
+        memory: Memory,
+    ) -> (
         Sender<EventSignal>,
         Receiver<InterfaceSignal>,
         SharedQueue<Vec<u8>>,
+        EventSender,
     ) {
         let (signal_tx, signal_rx) = channel::bounded(10);
         let (iface_signal_tx, iface_signal_rx) = channel::bounded(10);
+        let (event_sender, injected_rx) = EventSender::new();
         let queue = SharedQueue::new(10);
-        let event_module = EventModule::new(queue.clone(), 0);
+        let event_module = EventModule::new(
+            Arc::new(Mutex::new(memory)),
+            Timestamp::new(),
+            injected_rx,
+            queue.clone(),
+        );
         task::spawn(event_module.run(iface_signal_tx, signal_rx));
 
-        (signal_tx, iface_signal_rx, queue)
+        (signal_tx, iface_signal_rx, queue, event_sender)
     }
 
     fn receive_data(queue: &SharedQueue<Vec<u8>>) -> Option<Vec<u8>> {
@@ -278,56 +468,119 @@ mod tests {
         None
     }
 
+    fn shutdown(signal_tx: &Sender<EventSignal>, iface_signal_rx: &mut Receiver<InterfaceSignal>) {
+        assert!(signal_tx.try_send(EventSignal::Shutdown).is_ok());
+        task::block_on(timeout(TO, iface_signal_rx.next())).unwrap();
+    }
+
     #[test]
     fn test_run_and_stop() {
-        let (signal_tx, mut iface_signal_rx, _) = spawn_module();
+        let (signal_tx, mut iface_signal_rx, _, _) = spawn_module(Memory::new());
 
-        assert!(signal_tx.try_send(EventSignal::Shutdown).is_ok());
-        task::block_on(timeout(TO, iface_signal_rx.next())).unwrap();
+        shutdown(&signal_tx, &mut iface_signal_rx);
     }
 
     #[test]
     fn test_signal() {
-        let (signal_tx, mut iface_signal_rx, queue) = spawn_module();
-        signal_tx.try_send(EventSignal::_Enable).unwrap();
+        let (signal_tx, mut iface_signal_rx, queue, _) = spawn_module(Memory::new());
+        signal_tx.try_send(EventSignal::Enable).unwrap();
 
-        // Test EventData signal.
+        // Test Events signal.
         let event_id = 10;
-        let request_id = 20;
         let data = vec![1, 2, 3];
         signal_tx
-            .try_send(EventSignal::_EventData {
+            .try_send(EventSignal::Events(vec![Event::new(
                 event_id,
-                data: data.clone(),
-                request_id,
-            })
+                data.clone(),
+            )]))
             .unwrap();
 
         let received = receive_data(&queue).unwrap();
 
         let event_packet = event::EventPacket::parse(&received).unwrap();
-        assert_eq!(event_packet.request_id(), request_id);
+        assert_eq!(event_packet.request_id(), 0);
         assert_eq!(event_packet.scd.len(), 1);
+        assert_eq!(event_packet.scd[0].event_id, event_id);
         assert_eq!(&event_packet.scd[0].data, &data.as_slice());
+        let timestamp = event_packet.scd[0].timestamp;
 
-        // Test UpdateTimestamp signal.
-        let timestamp = 123_456_789;
+        // Request ID and timestamp are updated.
         signal_tx
-            .try_send(EventSignal::UpdateTimestamp(timestamp))
-            .unwrap();
-        signal_tx
-            .try_send(EventSignal::_EventData {
-                event_id,
-                data,
-                request_id,
-            })
+            .try_send(EventSignal::Events(vec![Event::new(event_id, data)]))
             .unwrap();
         let received = receive_data(&queue).unwrap();
         let event_packet = event::EventPacket::parse(&received).unwrap();
-        assert_eq!(event_packet.scd[0].timestamp, timestamp);
+        assert_eq!(event_packet.request_id(), 1);
+        assert!(event_packet.scd[0].timestamp > timestamp);
 
-        // Clean up.
-        assert!(signal_tx.try_send(EventSignal::Shutdown).is_ok());
-        task::block_on(timeout(TO, iface_signal_rx.next())).unwrap();
+        shutdown(&signal_tx, &mut iface_signal_rx);
+    }
+
+    #[test]
+    fn test_event_sender() {
+        let (signal_tx, mut iface_signal_rx, queue, event_sender) = spawn_module(Memory::new());
+
+        // Events are discarded while the module is disabled.
+        event_sender.send(10, vec![1]);
+        assert!(receive_data(&queue).is_none());
+
+        signal_tx.try_send(EventSignal::Enable).unwrap();
+        event_sender.send(11, vec![2]);
+        let received = receive_data(&queue).unwrap();
+        let event_packet = event::EventPacket::parse(&received).unwrap();
+        assert_eq!(event_packet.scd.len(), 1);
+        assert_eq!(event_packet.scd[0].event_id, 11);
+        assert_eq!(event_packet.scd[0].data, &[2]);
+
+        shutdown(&signal_tx, &mut iface_signal_rx);
+    }
+
+    #[test]
+    fn test_multi_event() {
+        let mut memory = Memory::new();
+        let mut config = memory.read::<ABRM::DeviceConfiguration>().unwrap();
+        config[0] |= 0b10;
+        memory.write::<ABRM::DeviceConfiguration>(config).unwrap();
+        let max_len = memory.read::<EIRM::MaximumEventTransferLength>().unwrap() as usize;
+
+        let (signal_tx, mut iface_signal_rx, queue, _) = spawn_module(memory);
+        signal_tx.try_send(EventSignal::Enable).unwrap();
+
+        let events = vec![
+            Event::new(10, vec![1, 2]),
+            Event::new(11, vec![]),
+            // Doesn't fit into the same packet.
+            Event::new(12, vec![0; max_len - EVENT_CCD_SIZE - EVENT_HEADER_SIZE]),
+        ];
+        signal_tx.try_send(EventSignal::Events(events)).unwrap();
+
+        let received = receive_data(&queue).unwrap();
+        let event_packet = event::EventPacket::parse(&received).unwrap();
+        assert_eq!(event_packet.scd.len(), 2);
+        assert_eq!(event_packet.scd[0].event_id, 10);
+        assert_eq!(event_packet.scd[0].data, &[1, 2]);
+        assert_eq!(event_packet.scd[1].event_id, 11);
+
+        let received = receive_data(&queue).unwrap();
+        assert_eq!(received.len(), max_len);
+        let event_packet = event::EventPacket::parse(&received).unwrap();
+        assert_eq!(event_packet.request_id(), 1);
+        assert_eq!(event_packet.scd.len(), 1);
+        assert_eq!(event_packet.scd[0].event_id, 12);
+
+        shutdown(&signal_tx, &mut iface_signal_rx);
+    }
+
+    #[test]
+    fn test_too_large_event() {
+        let (signal_tx, mut iface_signal_rx, queue, _) = spawn_module(Memory::new());
+        signal_tx.try_send(EventSignal::Enable).unwrap();
+
+        signal_tx
+            .try_send(EventSignal::Events(vec![Event::new(10, vec![0; 2048])]))
+            .unwrap();
+        assert!(receive_data(&queue).is_none());
+
+        shutdown(&signal_tx, &mut iface_signal_rx);
     }
 }
@@ -55,6 +55,7 @@ pub(super) enum FakeAckKind {
     SetHaltAck,
     ClearHaltAck,
     BrokenReq,
+    Disconnected,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
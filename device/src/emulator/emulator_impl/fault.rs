@@ -0,0 +1,152 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::u3v::protocol::ack::{GenCpStatus, UsbSpecificStatus};
+
+/// A fault injected into the control channel of an emulated device.
+///
+/// Each fault affects only one command which is received after the fault is injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFault {
+    /// Send a pending acknowledge which announces the timeout, then send the actual acknowledge
+    /// after half of the timeout elapses.
+    ///
+    /// NOTE: The timeout must be less than or equal to `u16::MAX` milliseconds.
+    Pending(Duration),
+
+    /// Reply with an error acknowledge with the GenCP status without processing the command.
+    NakGenCp(GenCpStatus),
+
+    /// Reply with an error acknowledge with the U3V specific status without processing the
+    /// command.
+    NakUsb(UsbSpecificStatus),
+
+    /// Process the command, then send the acknowledge after the delay.
+    DelayAck(Duration),
+
+    /// Process the command, but never send the acknowledge.
+    DropAck,
+
+    /// Process the command, then send the acknowledge with a request id which doesn't match
+    /// the command.
+    CorruptRequestId,
+
+    /// Halt the control endpoint without processing the command.
+    Stall,
+}
+
+/// A fault injected into the stream channel of an emulated device.
+///
+/// Each fault affects only one frame which is generated after the fault is injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFault {
+    /// Drop the first payload transfer of the frame.
+    DropTransfer,
+
+    /// Truncate the first payload transfer of the frame to the length.
+    TruncateTransfer(usize),
+
+    /// Send the frame with `DataOverrun` status in the trailer.
+    DataOverrun,
+
+    /// Halt the stream endpoint instead of sending the frame.
+    Stall,
+}
+
+/// A handle to inject faults into an emulated device.
+///
+/// The handle is obtained by [`super::EmulatorBuilder::fault_injector`] and remains valid after
+/// the emulator is built, so faults can be injected at runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use cameleon_device::emulator::{ControlFault, EmulatorBuilder, StreamFault};
+///
+/// let builder = EmulatorBuilder::new().control_fault(ControlFault::DropAck);
+/// let faults = builder.fault_injector();
+/// builder.build();
+///
+/// // Inject faults while the emulator is running.
+/// faults.inject_control(ControlFault::DelayAck(Duration::from_millis(100)));
+/// faults.inject_stream(StreamFault::DataOverrun);
+///
+/// // Emulate a device which stops responding while it stays plugged.
+/// faults.disconnect();
+/// assert!(faults.is_disconnected());
+/// faults.reconnect();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+    inner: Arc<Mutex<Faults>>,
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    control: VecDeque<ControlFault>,
+    stream: VecDeque<StreamFault>,
+    disconnected: bool,
+}
+
+impl FaultInjector {
+    /// Inject a fault into the control channel. Injected faults are applied to subsequent
+    /// commands in the injected order.
+    pub fn inject_control(&self, fault: ControlFault) {
+        self.with(|faults| faults.control.push_back(fault));
+    }
+
+    /// Inject a fault into the stream channel. Injected faults are applied to subsequent frames
+    /// in the injected order.
+    pub fn inject_stream(&self, fault: StreamFault) {
+        self.with(|faults| faults.stream.push_back(fault));
+    }
+
+    /// Remove all faults which are not applied yet.
+    pub fn clear(&self) {
+        self.with(|faults| {
+            faults.control.clear();
+            faults.stream.clear();
+        });
+    }
+
+    /// Emulate disconnection of the device. All requests from the host fail with
+    /// [`crate::u3v::LibUsbError::NoDevice`] until [`FaultInjector::reconnect`] is called.
+    pub fn disconnect(&self) {
+        self.with(|faults| faults.disconnected = true);
+    }
+
+    /// Reconnect the device disconnected by [`FaultInjector::disconnect`].
+    pub fn reconnect(&self) {
+        self.with(|faults| faults.disconnected = false);
+    }
+
+    /// Returns `true` if the device is disconnected by [`FaultInjector::disconnect`].
+    #[must_use]
+    pub fn is_disconnected(&self) -> bool {
+        self.with(|faults| faults.disconnected)
+    }
+
+    pub(super) fn next_control(&self) -> Option<ControlFault> {
+        self.with(|faults| faults.control.pop_front())
+    }
+
+    pub(super) fn next_stream(&self) -> Option<StreamFault> {
+        self.with(|faults| faults.stream.pop_front())
+    }
+
+    fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Faults) -> R,
+    {
+        f(&mut crate::lock(&self.inner))
+    }
+}
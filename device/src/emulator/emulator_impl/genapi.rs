@@ -5,7 +5,7 @@
 use cameleon_impl::memory::{prelude::*, register_map};
 use const_format::formatcp;
 
-use super::memory::GENAPI_XML_ADDRESS;
+use super::memory::{ABRM, GENAPI_REG_ADDRESS};
 
 pub(super) const MODEL_NAME: &str = "CameleonU3VEmulator";
 pub(super) const VENDOR_NAME: &str = "CameleonProjectDevelopers";
@@ -25,7 +25,39 @@ pub(super) const PORT_NAME: &str = "Device";
 const PRODUCT_GUID: &str = "eaabe337-2c3b-4e0b-b9b9-e67b347c4da8";
 const VERSION_GUID: &str = "0d29949b-5cd9-4f08-93fb-eea24950de3f";
 
-#[register_map(base=GENAPI_XML_ADDRESS, endianness=LE)]
+/// Default width and height of the sensor, images are captured with full resolution by default.
+pub(super) const DEFAULT_WIDTH: u32 = 640;
+pub(super) const DEFAULT_HEIGHT: u32 = 480;
+/// Default pixel format of images generated by the emulator, `Mono8`.
+pub(super) const DEFAULT_PIXEL_FORMAT: u32 = 0x0108_0001;
+
+/// Minimum value and increment of `Width` and `OffsetX`.
+pub(super) const WIDTH_MIN: u32 = 16;
+pub(super) const WIDTH_INC: u32 = 4;
+/// Minimum value and increment of `Height` and `OffsetY`.
+pub(super) const HEIGHT_MIN: u32 = 16;
+pub(super) const HEIGHT_INC: u32 = 2;
+
+/// Range of `DeviceLinkThroughputLimit` in bytes per second.
+pub(super) const THROUGHPUT_LIMIT_MIN: u32 = 1_000_000;
+pub(super) const THROUGHPUT_LIMIT_MAX: u32 = 400_000_000;
+
+/// Chunk IDs of chunk data appended to the payload when `ChunkModeActive` is set.
+pub(super) const CHUNK_ID_IMAGE: u32 = 0xCA11_0001;
+pub(super) const CHUNK_ID_TIMESTAMP: u32 = 0xCA11_0002;
+pub(super) const CHUNK_ID_FRAME_ID: u32 = 0xCA11_0003;
+
+/// Event IDs of events sent to the host when `EventNotification` of the event is `On`.
+pub(super) const EVENT_ID_EXPOSURE_END: u16 = 0x9001;
+pub(super) const EVENT_ID_FRAME_START: u16 = 0x9002;
+
+/// Length of the user set block which starts from `Width` and ends with
+/// `EventNotificationFrameStart`.
+pub(super) const USER_SET_LENGTH: usize = GenApiReg::EventNotificationFrameStart::ADDRESS
+    + GenApiReg::EventNotificationFrameStart::LENGTH
+    - GenApiReg::Width::ADDRESS;
+
+#[register_map(base=GENAPI_REG_ADDRESS, endianness=LE)]
 pub(super) enum GenApiReg {
     /// Start acquisition of images when the register is set to 1.
     #[register(len = 1, access = WO, ty = u8)]
@@ -34,9 +66,157 @@ pub(super) enum GenApiReg {
     /// Stop the acquisition of images when the register is set to 1.
     #[register(len = 1, access = WO, ty = u8)]
     AcquisitionStop,
+
+    /// Generate a frame start trigger when the register is set to 1.
+    #[register(len = 1, access = WO, ty = u8)]
+    TriggerSoftware,
+
+    /// Load the user set specified by `UserSetSelector` when the register is set to 1.
+    #[register(len = 1, access = WO, ty = u8)]
+    UserSetLoad,
+
+    /// Save the current settings to the user set specified by `UserSetSelector` when the
+    /// register is set to 1.
+    #[register(len = 1, access = WO, ty = u8)]
+    UserSetSave,
+
+    /// Lock transport layer related parameters while streaming when the register is set to 1.
+    #[register(len = 4, access = RW, ty = u32)]
+    TLParamsLocked = 0,
+
+    /// The frame rate is limited so that the throughput doesn't exceed
+    /// `DeviceLinkThroughputLimit` if the register is set to 1.
+    #[register(len = 4, access = RW, ty = u32)]
+    DeviceLinkThroughputLimitMode = 0,
+
+    /// Throughput limit of the link in bytes per second.
+    #[register(len = 4, access = RW, ty = u32)]
+    DeviceLinkThroughputLimit = THROUGHPUT_LIMIT_MAX,
+
+    /// Width of the sensor.
+    #[register(len = 4, access = RO, ty = u32)]
+    SensorWidth = DEFAULT_WIDTH,
+
+    /// Height of the sensor.
+    #[register(len = 4, access = RO, ty = u32)]
+    SensorHeight = DEFAULT_HEIGHT,
+
+    /// Size of a payload in bytes, recomputed when image format or chunk settings are changed.
+    #[register(len = 8, access = RO, ty = u64)]
+    PayloadSize = DEFAULT_WIDTH as u64 * DEFAULT_HEIGHT as u64,
+
+    /// Index of the trigger, only `FrameStart`(0) is supported.
+    #[register(len = 4, access = RW, ty = u32)]
+    TriggerSelector = 0,
+
+    /// Index of the chunk to configure, `Image`(0), `Timestamp`(1) or `FrameID`(2).
+    #[register(len = 4, access = RW, ty = u32)]
+    ChunkSelector = 0,
+
+    /// Index of the event to configure, `ExposureEnd`(0) or `FrameStart`(1).
+    #[register(len = 4, access = RW, ty = u32)]
+    EventSelector = 0,
+
+    /// Index of the user set to load or save, `Default`(0) or `UserSet1`(1).
+    #[register(len = 4, access = RW, ty = u32)]
+    UserSetSelector = 0,
+
+    /// Index of the user set which is loaded when the device is reset.
+    #[register(len = 4, access = RW, ty = u32)]
+    UserSetDefault = 0,
+
+    // User set block start.
+    // All registers in this block are saved to and loaded from user sets.
+    /// Width of images.
+    #[register(len = 4, access = RW, ty = u32)]
+    Width = DEFAULT_WIDTH,
+
+    /// Height of images.
+    #[register(len = 4, access = RW, ty = u32)]
+    Height = DEFAULT_HEIGHT,
+
+    /// Horizontal offset of the region of interest.
+    #[register(len = 4, access = RW, ty = u32)]
+    OffsetX = 0,
+
+    /// Vertical offset of the region of interest.
+    #[register(len = 4, access = RW, ty = u32)]
+    OffsetY = 0,
+
+    /// Pixel format of images, the value is PFNC pixel format code.
+    #[register(len = 4, access = RW, ty = u32)]
+    PixelFormat = DEFAULT_PIXEL_FORMAT,
+
+    /// Test pattern of images, see [`super::image::TestPattern`].
+    #[register(len = 4, access = RW, ty = u32)]
+    TestPattern = 0,
+
+    /// `Continuous`(0), `SingleFrame`(1) or `MultiFrame`(2).
+    #[register(len = 4, access = RW, ty = u32)]
+    AcquisitionMode = 0,
+
+    /// Number of frames to acquire in `MultiFrame` mode.
+    #[register(len = 4, access = RW, ty = u32)]
+    AcquisitionFrameCount = 1,
+
+    /// Frames are generated only when a trigger is issued if the register is set to 1.
+    #[register(len = 4, access = RW, ty = u32)]
+    TriggerMode = 0,
+
+    /// Source of the trigger, only `Software`(0) is supported.
+    #[register(len = 4, access = RW, ty = u32)]
+    TriggerSource = 0,
+
+    /// Exposure time in microseconds.
+    #[register(len = 8, access = RW, ty = f64)]
+    ExposureTime = 10000.0,
+
+    /// Frame rate of acquisition in Hz.
+    #[register(len = 8, access = RW, ty = f64)]
+    AcquisitionFrameRate = 30.0,
+
+    /// Gain in dB.
+    #[register(len = 8, access = RW, ty = f64)]
+    Gain = 0.0,
+
+    #[register(len = 8, access = RW, ty = f64)]
+    BlackLevel = 0.0,
+
+    #[register(len = 8, access = RW, ty = f64)]
+    Gamma = 1.0,
+
+    /// Chunk data are appended to payloads if the register is set to 1.
+    #[register(len = 4, access = RW, ty = u32)]
+    ChunkModeActive = 0,
+
+    // Chunk enable registers are indexed by `ChunkSelector`, so the order must not be changed.
+    /// The image chunk is always enabled.
+    #[register(len = 4, access = RW, ty = u32)]
+    ChunkEnableImage = 1,
+
+    #[register(len = 4, access = RW, ty = u32)]
+    ChunkEnableTimestamp = 0,
+
+    #[register(len = 4, access = RW, ty = u32)]
+    ChunkEnableFrameID = 0,
+
+    // Event notification registers are indexed by `EventSelector`, so the order must not be
+    // changed.
+    #[register(len = 4, access = RW, ty = u32)]
+    EventNotificationExposureEnd = 0,
+
+    #[register(len = 4, access = RW, ty = u32)]
+    EventNotificationFrameStart = 0,
+    // User set block end.
+    /// Storage of `UserSet1`.
+    #[register(len = USER_SET_LENGTH, access = NA, ty = Bytes)]
+    UserSet1,
+
+    /// Storage of `Default` user set, the settings are captured when the emulator is built.
+    #[register(len = USER_SET_LENGTH, access = NA, ty = Bytes)]
+    DefaultUserSet,
 }
 
-// TODO: Add node to this XML.
 pub(super) const GENAPI_XML: &str = formatcp!(
     r#"<?xml version="1.0" encoding="UTF-8"?>
 <RegisterDescription
@@ -59,7 +239,14 @@ xsi:schemaLocation="http://www.genicam.org/GenApi/Version_1_1 http://www.genicam
     <Category Name="Root" NameSpace="Standard">
         <Description>Provides the Root of the GenICam features tree.</Description>
         <Visibility>Beginner</Visibility>
+        <pFeature>DeviceControl</pFeature>
+        <pFeature>ImageFormatControl</pFeature>
         <pFeature>AcquisitionControl</pFeature>
+        <pFeature>AnalogControl</pFeature>
+        <pFeature>TransportLayerControl</pFeature>
+        <pFeature>ChunkDataControl</pFeature>
+        <pFeature>EventControl</pFeature>
+        <pFeature>UserSetControl</pFeature>
     </Category>
 
     <Port Name="{PORT_NAME}" NameSpace="Standard">
@@ -67,12 +254,429 @@ xsi:schemaLocation="http://www.genicam.org/GenApi/Version_1_1 http://www.genicam
         <Visibility>Invisible</Visibility>
     </Port>
 
+    <Category Name="DeviceControl" NameSpace="Standard">
+        <Description>Category for device information and control.</Description>
+        <DisplayName>Device Control</DisplayName>
+        <pFeature>DeviceVendorName</pFeature>
+        <pFeature>DeviceModelName</pFeature>
+        <pFeature>DeviceManufacturerInfo</pFeature>
+        <pFeature>DeviceVersion</pFeature>
+        <pFeature>DeviceSerialNumber</pFeature>
+        <pFeature>DeviceUserID</pFeature>
+        <pFeature>DeviceScanType</pFeature>
+        <pFeature>TimestampLatch</pFeature>
+        <pFeature>TimestampLatchValue</pFeature>
+    </Category>
+
+    <StringReg Name="DeviceVendorName" NameSpace="Standard">
+        <ToolTip>Name of the manufacturer of the device.</ToolTip>
+        <Description>Name of the manufacturer of the device.</Description>
+        <DisplayName>Device Vendor Name</DisplayName>
+        <Address>{manufacturer_name_addr}</Address>
+        <Length>{manufacturer_name_len}</Length>
+        <AccessMode>{manufacturer_name_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <StringReg Name="DeviceModelName" NameSpace="Standard">
+        <ToolTip>Model of the device.</ToolTip>
+        <Description>Model of the device.</Description>
+        <DisplayName>Device Model Name</DisplayName>
+        <Address>{model_name_addr}</Address>
+        <Length>{model_name_len}</Length>
+        <AccessMode>{model_name_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <StringReg Name="DeviceManufacturerInfo" NameSpace="Standard">
+        <ToolTip>Manufacturer information about the device.</ToolTip>
+        <Description>Manufacturer information about the device.</Description>
+        <DisplayName>Device Manufacturer Info</DisplayName>
+        <Address>{manufacturer_info_addr}</Address>
+        <Length>{manufacturer_info_len}</Length>
+        <AccessMode>{manufacturer_info_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <StringReg Name="DeviceVersion" NameSpace="Standard">
+        <ToolTip>Version of the device.</ToolTip>
+        <Description>Version of the device.</Description>
+        <DisplayName>Device Version</DisplayName>
+        <Address>{device_version_addr}</Address>
+        <Length>{device_version_len}</Length>
+        <AccessMode>{device_version_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <StringReg Name="DeviceSerialNumber" NameSpace="Standard">
+        <ToolTip>Device's serial number.</ToolTip>
+        <Description>Device's serial number.</Description>
+        <DisplayName>Device Serial Number</DisplayName>
+        <Address>{serial_number_addr}</Address>
+        <Length>{serial_number_len}</Length>
+        <AccessMode>{serial_number_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <StringReg Name="DeviceUserID" NameSpace="Standard">
+        <ToolTip>User-programmable device identifier.</ToolTip>
+        <Description>User-programmable device identifier.</Description>
+        <DisplayName>Device User ID</DisplayName>
+        <Streamable>Yes</Streamable>
+        <Address>{user_defined_name_addr}</Address>
+        <Length>{user_defined_name_len}</Length>
+        <AccessMode>{user_defined_name_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+    </StringReg>
+
+    <Enumeration Name="DeviceScanType" NameSpace="Standard">
+        <ToolTip>Scan type of the sensor of the device.</ToolTip>
+        <Description>Scan type of the sensor of the device.</Description>
+        <DisplayName>Device Scan Type</DisplayName>
+        <EnumEntry Name="Areascan" NameSpace="Standard">
+            <DisplayName>Areascan</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <Value>0</Value>
+    </Enumeration>
+
+    <Command Name="TimestampLatch" NameSpace="Standard">
+        <ToolTip>Latches the current timestamp counter into TimestampLatchValue.</ToolTip>
+        <Description>Latches the current timestamp counter into TimestampLatchValue.</Description>
+        <DisplayName>Timestamp Latch</DisplayName>
+        <pValue>TimestampLatchReg</pValue>
+        <CommandValue>1</CommandValue>
+    </Command>
+
+    <IntReg Name="TimestampLatchReg" NameSpace="Custom">
+        <Address>{timestamp_latch_addr}</Address>
+        <Length>{timestamp_latch_len}</Length>
+        <AccessMode>{timestamp_latch_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="TimestampLatchValue" NameSpace="Standard">
+        <ToolTip>Returns the latched value of the timestamp counter.</ToolTip>
+        <Description>Returns the latched value of the timestamp counter.</Description>
+        <DisplayName>Timestamp Latch Value</DisplayName>
+        <pValue>TimestampLatchValueReg</pValue>
+        <Unit>ns</Unit>
+    </Integer>
+
+    <IntReg Name="TimestampLatchValueReg" NameSpace="Custom">
+        <Address>{timestamp_addr}</Address>
+        <Length>{timestamp_len}</Length>
+        <AccessMode>{timestamp_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>TimestampLatchReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Category Name="ImageFormatControl" NameSpace="Standard">
+        <Description>Category for image format control features.</Description>
+        <DisplayName>Image Format Control</DisplayName>
+        <pFeature>SensorWidth</pFeature>
+        <pFeature>SensorHeight</pFeature>
+        <pFeature>WidthMax</pFeature>
+        <pFeature>HeightMax</pFeature>
+        <pFeature>Width</pFeature>
+        <pFeature>Height</pFeature>
+        <pFeature>OffsetX</pFeature>
+        <pFeature>OffsetY</pFeature>
+        <pFeature>PixelFormat</pFeature>
+        <pFeature>TestPattern</pFeature>
+    </Category>
+
+    <Integer Name="SensorWidth" NameSpace="Standard">
+        <ToolTip>Effective width of the sensor in pixels.</ToolTip>
+        <Description>Effective width of the sensor in pixels.</Description>
+        <DisplayName>Sensor Width</DisplayName>
+        <pValue>SensorWidthReg</pValue>
+    </Integer>
+
+    <IntReg Name="SensorWidthReg" NameSpace="Custom">
+        <Address>{sensor_width_addr}</Address>
+        <Length>{sensor_width_len}</Length>
+        <AccessMode>{sensor_width_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="SensorHeight" NameSpace="Standard">
+        <ToolTip>Effective height of the sensor in pixels.</ToolTip>
+        <Description>Effective height of the sensor in pixels.</Description>
+        <DisplayName>Sensor Height</DisplayName>
+        <pValue>SensorHeightReg</pValue>
+    </Integer>
+
+    <IntReg Name="SensorHeightReg" NameSpace="Custom">
+        <Address>{sensor_height_addr}</Address>
+        <Length>{sensor_height_len}</Length>
+        <AccessMode>{sensor_height_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="WidthMax" NameSpace="Standard">
+        <ToolTip>Maximum width of the image.</ToolTip>
+        <Description>Maximum width of the image, the dimension is calculated after horizontal offset.</Description>
+        <DisplayName>Width Max</DisplayName>
+        <pValue>WidthMaxExpr</pValue>
+    </Integer>
+
+    <IntSwissKnife Name="WidthMaxExpr" NameSpace="Custom">
+        <Visibility>Invisible</Visibility>
+        <pVariable Name="SENSOR">SensorWidthReg</pVariable>
+        <pVariable Name="OFFSET">OffsetXReg</pVariable>
+        <Formula>SENSOR - OFFSET</Formula>
+    </IntSwissKnife>
+
+    <Integer Name="HeightMax" NameSpace="Standard">
+        <ToolTip>Maximum height of the image.</ToolTip>
+        <Description>Maximum height of the image, the dimension is calculated after vertical offset.</Description>
+        <DisplayName>Height Max</DisplayName>
+        <pValue>HeightMaxExpr</pValue>
+    </Integer>
+
+    <IntSwissKnife Name="HeightMaxExpr" NameSpace="Custom">
+        <Visibility>Invisible</Visibility>
+        <pVariable Name="SENSOR">SensorHeightReg</pVariable>
+        <pVariable Name="OFFSET">OffsetYReg</pVariable>
+        <Formula>SENSOR - OFFSET</Formula>
+    </IntSwissKnife>
+
+    <Integer Name="Width" NameSpace="Standard">
+        <ToolTip>Width of the image provided by the device.</ToolTip>
+        <Description>Width of the image provided by the device in pixels.</Description>
+        <DisplayName>Width</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <Streamable>Yes</Streamable>
+        <pValue>WidthReg</pValue>
+        <Min>{WIDTH_MIN}</Min>
+        <pMax>WidthMax</pMax>
+        <Inc>{WIDTH_INC}</Inc>
+    </Integer>
+
+    <IntReg Name="WidthReg" NameSpace="Custom">
+        <Address>{width_addr}</Address>
+        <Length>{width_len}</Length>
+        <AccessMode>{width_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="Height" NameSpace="Standard">
+        <ToolTip>Height of the image provided by the device.</ToolTip>
+        <Description>Height of the image provided by the device in pixels.</Description>
+        <DisplayName>Height</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <Streamable>Yes</Streamable>
+        <pValue>HeightReg</pValue>
+        <Min>{HEIGHT_MIN}</Min>
+        <pMax>HeightMax</pMax>
+        <Inc>{HEIGHT_INC}</Inc>
+    </Integer>
+
+    <IntReg Name="HeightReg" NameSpace="Custom">
+        <Address>{height_addr}</Address>
+        <Length>{height_len}</Length>
+        <AccessMode>{height_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="OffsetX" NameSpace="Standard">
+        <ToolTip>Horizontal offset from the origin to the region of interest.</ToolTip>
+        <Description>Horizontal offset from the origin to the region of interest in pixels.</Description>
+        <DisplayName>Offset X</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>OffsetXReg</pValue>
+        <Min>0</Min>
+        <pMax>OffsetXMax</pMax>
+        <Inc>{WIDTH_INC}</Inc>
+    </Integer>
+
+    <IntSwissKnife Name="OffsetXMax" NameSpace="Custom">
+        <Visibility>Invisible</Visibility>
+        <pVariable Name="SENSOR">SensorWidthReg</pVariable>
+        <pVariable Name="WIDTH">WidthReg</pVariable>
+        <Formula>SENSOR - WIDTH</Formula>
+    </IntSwissKnife>
+
+    <IntReg Name="OffsetXReg" NameSpace="Custom">
+        <Address>{offset_x_addr}</Address>
+        <Length>{offset_x_len}</Length>
+        <AccessMode>{offset_x_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="OffsetY" NameSpace="Standard">
+        <ToolTip>Vertical offset from the origin to the region of interest.</ToolTip>
+        <Description>Vertical offset from the origin to the region of interest in pixels.</Description>
+        <DisplayName>Offset Y</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>OffsetYReg</pValue>
+        <Min>0</Min>
+        <pMax>OffsetYMax</pMax>
+        <Inc>{HEIGHT_INC}</Inc>
+    </Integer>
+
+    <IntSwissKnife Name="OffsetYMax" NameSpace="Custom">
+        <Visibility>Invisible</Visibility>
+        <pVariable Name="SENSOR">SensorHeightReg</pVariable>
+        <pVariable Name="HEIGHT">HeightReg</pVariable>
+        <Formula>SENSOR - HEIGHT</Formula>
+    </IntSwissKnife>
+
+    <IntReg Name="OffsetYReg" NameSpace="Custom">
+        <Address>{offset_y_addr}</Address>
+        <Length>{offset_y_len}</Length>
+        <AccessMode>{offset_y_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="PixelFormat" NameSpace="Standard">
+        <ToolTip>Format of the pixels provided by the device.</ToolTip>
+        <Description>Format of the pixels provided by the device.</Description>
+        <DisplayName>Pixel Format</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="Mono8" NameSpace="Standard">
+            <DisplayName>Mono 8</DisplayName>
+            <Value>0x01080001</Value>
+        </EnumEntry>
+        <EnumEntry Name="Mono16" NameSpace="Standard">
+            <DisplayName>Mono 16</DisplayName>
+            <Value>0x01100007</Value>
+        </EnumEntry>
+        <EnumEntry Name="BayerGR8" NameSpace="Standard">
+            <DisplayName>Bayer GR 8</DisplayName>
+            <Value>0x01080008</Value>
+        </EnumEntry>
+        <EnumEntry Name="BayerRG8" NameSpace="Standard">
+            <DisplayName>Bayer RG 8</DisplayName>
+            <Value>0x01080009</Value>
+        </EnumEntry>
+        <EnumEntry Name="BayerGB8" NameSpace="Standard">
+            <DisplayName>Bayer GB 8</DisplayName>
+            <Value>0x0108000A</Value>
+        </EnumEntry>
+        <EnumEntry Name="BayerBG8" NameSpace="Standard">
+            <DisplayName>Bayer BG 8</DisplayName>
+            <Value>0x0108000B</Value>
+        </EnumEntry>
+        <EnumEntry Name="RGB8" NameSpace="Standard">
+            <DisplayName>RGB 8</DisplayName>
+            <Value>0x02180014</Value>
+        </EnumEntry>
+        <pValue>PixelFormatReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="PixelFormatReg" NameSpace="Custom">
+        <Address>{pixel_format_addr}</Address>
+        <Length>{pixel_format_len}</Length>
+        <AccessMode>{pixel_format_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="TestPattern" NameSpace="Standard">
+        <ToolTip>Selects the type of test pattern that is generated by the device.</ToolTip>
+        <Description>Selects the type of test pattern that is generated by the device as image source.</Description>
+        <DisplayName>Test Pattern</DisplayName>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="GreyHorizontalRampMoving" NameSpace="Standard">
+            <DisplayName>Grey Horizontal Ramp Moving</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="Checkerboard" NameSpace="Standard">
+            <DisplayName>Checkerboard</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <EnumEntry Name="FrameCounter" NameSpace="Standard">
+            <DisplayName>Frame Counter</DisplayName>
+            <Value>2</Value>
+        </EnumEntry>
+        <EnumEntry Name="Noise" NameSpace="Standard">
+            <DisplayName>Noise</DisplayName>
+            <Value>3</Value>
+        </EnumEntry>
+        <pValue>TestPatternReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="TestPatternReg" NameSpace="Custom">
+        <Address>{test_pattern_addr}</Address>
+        <Length>{test_pattern_len}</Length>
+        <AccessMode>{test_pattern_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
     <Category Name="AcquisitionControl" NameSpace="Standard">
+        <Description>Category for the acquisition and trigger control features.</Description>
         <DisplayName>Acquisition Control</DisplayName>
+        <pFeature>AcquisitionMode</pFeature>
         <pFeature>AcquisitionStart</pFeature>
         <pFeature>AcquisitionStop</pFeature>
+        <pFeature>AcquisitionFrameCount</pFeature>
+        <pFeature>TriggerSelector</pFeature>
+        <pFeature>TriggerMode</pFeature>
+        <pFeature>TriggerSoftware</pFeature>
+        <pFeature>TriggerSource</pFeature>
+        <pFeature>ExposureTime</pFeature>
+        <pFeature>AcquisitionFrameRate</pFeature>
     </Category>
 
+    <Enumeration Name="AcquisitionMode" NameSpace="Standard">
+        <ToolTip>Sets the acquisition mode of the device.</ToolTip>
+        <Description>Sets the acquisition mode of the device. It defines mainly the number of frames to capture during an acquisition.</Description>
+        <DisplayName>Acquisition Mode</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="Continuous" NameSpace="Standard">
+            <DisplayName>Continuous</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="SingleFrame" NameSpace="Standard">
+            <DisplayName>Single Frame</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <EnumEntry Name="MultiFrame" NameSpace="Standard">
+            <DisplayName>Multi Frame</DisplayName>
+            <Value>2</Value>
+        </EnumEntry>
+        <pValue>AcquisitionModeReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="AcquisitionModeReg" NameSpace="Custom">
+        <Address>{acquisition_mode_addr}</Address>
+        <Length>{acquisition_mode_len}</Length>
+        <AccessMode>{acquisition_mode_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
     <Command Name="AcquisitionStart" NameSpace="Standard">
         <ToolTip>Starts the acquisition of images.</ToolTip>
         <Description>This command starts the acquisition of images.</Description>
@@ -90,7 +694,7 @@ xsi:schemaLocation="http://www.genicam.org/GenApi/Version_1_1 http://www.genicam
         <Endianess>LittleEndian</Endianess>
     </IntReg>
 
-   <Command Name="AcquisitionStop" NameSpace="Standard">
+    <Command Name="AcquisitionStop" NameSpace="Standard">
         <ToolTip>Stops the acquisition of images.</ToolTip>
         <Description>This command stop the acquisition of images.</Description>
         <DisplayName>Acquisition Stop</DisplayName>
@@ -107,11 +711,790 @@ xsi:schemaLocation="http://www.genicam.org/GenApi/Version_1_1 http://www.genicam
         <Endianess>LittleEndian</Endianess>
     </IntReg>
 
+    <Integer Name="AcquisitionFrameCount" NameSpace="Standard">
+        <ToolTip>Number of frames to acquire in MultiFrame Acquisition mode.</ToolTip>
+        <Description>Number of frames to acquire in MultiFrame Acquisition mode.</Description>
+        <DisplayName>Acquisition Frame Count</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <Streamable>Yes</Streamable>
+        <pValue>AcquisitionFrameCountReg</pValue>
+        <Min>1</Min>
+        <Max>65535</Max>
+    </Integer>
+
+    <IntReg Name="AcquisitionFrameCountReg" NameSpace="Custom">
+        <Address>{acquisition_frame_count_addr}</Address>
+        <Length>{acquisition_frame_count_len}</Length>
+        <AccessMode>{acquisition_frame_count_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="TriggerSelector" NameSpace="Standard">
+        <ToolTip>Selects the type of trigger to configure.</ToolTip>
+        <Description>Selects the type of trigger to configure.</Description>
+        <DisplayName>Trigger Selector</DisplayName>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="FrameStart" NameSpace="Standard">
+            <DisplayName>Frame Start</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <pValue>TriggerSelectorReg</pValue>
+        <pSelected>TriggerMode</pSelected>
+        <pSelected>TriggerSoftware</pSelected>
+        <pSelected>TriggerSource</pSelected>
+    </Enumeration>
+
+    <IntReg Name="TriggerSelectorReg" NameSpace="Custom">
+        <Address>{trigger_selector_addr}</Address>
+        <Length>{trigger_selector_len}</Length>
+        <AccessMode>{trigger_selector_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="TriggerMode" NameSpace="Standard">
+        <ToolTip>Controls if the selected trigger is active.</ToolTip>
+        <Description>Controls if the selected trigger is active.</Description>
+        <DisplayName>Trigger Mode</DisplayName>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="Off" NameSpace="Standard">
+            <DisplayName>Off</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="On" NameSpace="Standard">
+            <DisplayName>On</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>TriggerModeReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="TriggerModeReg" NameSpace="Custom">
+        <Address>{trigger_mode_addr}</Address>
+        <Length>{trigger_mode_len}</Length>
+        <AccessMode>{trigger_mode_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Command Name="TriggerSoftware" NameSpace="Standard">
+        <ToolTip>Generates an internal trigger.</ToolTip>
+        <Description>Generates an internal trigger. TriggerSource must be set to Software.</Description>
+        <DisplayName>Trigger Software</DisplayName>
+        <pValue>TriggerSoftwareReg</pValue>
+        <CommandValue>1</CommandValue>
+    </Command>
+
+    <IntReg Name="TriggerSoftwareReg" NameSpace="Custom">
+        <Address>{trigger_software_addr}</Address>
+        <Length>{trigger_software_len}</Length>
+        <AccessMode>{trigger_software_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="TriggerSource" NameSpace="Standard">
+        <ToolTip>Specifies the internal signal or physical input Line to use as the trigger source.</ToolTip>
+        <Description>Specifies the internal signal or physical input Line to use as the trigger source.</Description>
+        <DisplayName>Trigger Source</DisplayName>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="Software" NameSpace="Standard">
+            <DisplayName>Software</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <pValue>TriggerSourceReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="TriggerSourceReg" NameSpace="Custom">
+        <Address>{trigger_source_addr}</Address>
+        <Length>{trigger_source_len}</Length>
+        <AccessMode>{trigger_source_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Float Name="ExposureTime" NameSpace="Standard">
+        <ToolTip>Sets the Exposure time.</ToolTip>
+        <Description>Sets the Exposure time when ExposureMode is Timed. The frame period is extended when it's shorter than the exposure time.</Description>
+        <DisplayName>Exposure Time</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>ExposureTimeReg</pValue>
+        <Min>10.0</Min>
+        <Max>1000000.0</Max>
+        <Unit>us</Unit>
+    </Float>
+
+    <FloatReg Name="ExposureTimeReg" NameSpace="Custom">
+        <Address>{exposure_time_addr}</Address>
+        <Length>{exposure_time_len}</Length>
+        <AccessMode>{exposure_time_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Endianess>LittleEndian</Endianess>
+    </FloatReg>
+
+    <Float Name="AcquisitionFrameRate" NameSpace="Standard">
+        <ToolTip>Controls the acquisition rate at which the frames are captured.</ToolTip>
+        <Description>Controls the acquisition rate (in Hertz) at which the frames are captured.</Description>
+        <DisplayName>Acquisition Frame Rate</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>AcquisitionFrameRateReg</pValue>
+        <Min>0.1</Min>
+        <Max>1000.0</Max>
+        <Unit>Hz</Unit>
+    </Float>
+
+    <FloatReg Name="AcquisitionFrameRateReg" NameSpace="Custom">
+        <Address>{acquisition_frame_rate_addr}</Address>
+        <Length>{acquisition_frame_rate_len}</Length>
+        <AccessMode>{acquisition_frame_rate_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Endianess>LittleEndian</Endianess>
+    </FloatReg>
+
+    <Category Name="AnalogControl" NameSpace="Standard">
+        <Description>Category that contains the Analog control features.</Description>
+        <DisplayName>Analog Control</DisplayName>
+        <pFeature>Gain</pFeature>
+        <pFeature>BlackLevel</pFeature>
+        <pFeature>Gamma</pFeature>
+    </Category>
+
+    <Float Name="Gain" NameSpace="Standard">
+        <ToolTip>Controls the selected gain as an absolute physical value.</ToolTip>
+        <Description>Controls the selected gain as an absolute physical value.</Description>
+        <DisplayName>Gain</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>GainReg</pValue>
+        <Min>0.0</Min>
+        <Max>24.0</Max>
+        <Unit>dB</Unit>
+    </Float>
+
+    <FloatReg Name="GainReg" NameSpace="Custom">
+        <Address>{gain_addr}</Address>
+        <Length>{gain_len}</Length>
+        <AccessMode>{gain_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Endianess>LittleEndian</Endianess>
+    </FloatReg>
+
+    <Float Name="BlackLevel" NameSpace="Standard">
+        <ToolTip>Controls the analog black level as an absolute physical value.</ToolTip>
+        <Description>Controls the analog black level as an absolute physical value.</Description>
+        <DisplayName>Black Level</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>BlackLevelReg</pValue>
+        <Min>0.0</Min>
+        <Max>255.0</Max>
+    </Float>
+
+    <FloatReg Name="BlackLevelReg" NameSpace="Custom">
+        <Address>{black_level_addr}</Address>
+        <Length>{black_level_len}</Length>
+        <AccessMode>{black_level_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Endianess>LittleEndian</Endianess>
+    </FloatReg>
+
+    <Float Name="Gamma" NameSpace="Standard">
+        <ToolTip>Controls the gamma correction of pixel intensity.</ToolTip>
+        <Description>Controls the gamma correction of pixel intensity.</Description>
+        <DisplayName>Gamma</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>GammaReg</pValue>
+        <Min>0.25</Min>
+        <Max>4.0</Max>
+    </Float>
+
+    <FloatReg Name="GammaReg" NameSpace="Custom">
+        <Address>{gamma_addr}</Address>
+        <Length>{gamma_len}</Length>
+        <AccessMode>{gamma_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Endianess>LittleEndian</Endianess>
+    </FloatReg>
+
+    <Category Name="TransportLayerControl" NameSpace="Standard">
+        <Description>Category that contains the transport Layer control features.</Description>
+        <DisplayName>Transport Layer Control</DisplayName>
+        <pFeature>PayloadSize</pFeature>
+        <pFeature>DeviceLinkThroughputLimitMode</pFeature>
+        <pFeature>DeviceLinkThroughputLimit</pFeature>
+        <pFeature>TLParamsLocked</pFeature>
+    </Category>
+
+    <Integer Name="PayloadSize" NameSpace="Standard">
+        <ToolTip>Provides the number of bytes transferred for each image or chunk on the stream channel.</ToolTip>
+        <Description>Provides the number of bytes transferred for each image or chunk on the stream channel.</Description>
+        <DisplayName>Payload Size</DisplayName>
+        <pValue>PayloadSizeReg</pValue>
+    </Integer>
+
+    <IntReg Name="PayloadSizeReg" NameSpace="Custom">
+        <Address>{payload_size_addr}</Address>
+        <Length>{payload_size_len}</Length>
+        <AccessMode>{payload_size_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>WidthReg</pInvalidator>
+        <pInvalidator>HeightReg</pInvalidator>
+        <pInvalidator>PixelFormatReg</pInvalidator>
+        <pInvalidator>ChunkModeActiveReg</pInvalidator>
+        <pInvalidator>ChunkEnableReg</pInvalidator>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="DeviceLinkThroughputLimitMode" NameSpace="Standard">
+        <ToolTip>Controls if the DeviceLinkThroughputLimit is active.</ToolTip>
+        <Description>Controls if the DeviceLinkThroughputLimit is active. When disabled, lower level TL specific features are expected to control the throughput.</Description>
+        <DisplayName>Device Link Throughput Limit Mode</DisplayName>
+        <Streamable>Yes</Streamable>
+        <EnumEntry Name="Off" NameSpace="Standard">
+            <DisplayName>Off</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="On" NameSpace="Standard">
+            <DisplayName>On</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>DeviceLinkThroughputLimitModeReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="DeviceLinkThroughputLimitModeReg" NameSpace="Custom">
+        <Address>{device_link_throughput_limit_mode_addr}</Address>
+        <Length>{device_link_throughput_limit_mode_len}</Length>
+        <AccessMode>{device_link_throughput_limit_mode_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="DeviceLinkThroughputLimit" NameSpace="Standard">
+        <ToolTip>Limits the maximum bandwidth of the data that will be streamed out by the device.</ToolTip>
+        <Description>Limits the maximum bandwidth of the data that will be streamed out by the device on the selected Link. If necessary, delays will be uniformly inserted between transport layer packets in order to control the peak bandwidth.</Description>
+        <DisplayName>Device Link Throughput Limit</DisplayName>
+        <Streamable>Yes</Streamable>
+        <pValue>DeviceLinkThroughputLimitReg</pValue>
+        <Min>{THROUGHPUT_LIMIT_MIN}</Min>
+        <Max>{THROUGHPUT_LIMIT_MAX}</Max>
+        <Unit>Bps</Unit>
+    </Integer>
+
+    <IntReg Name="DeviceLinkThroughputLimitReg" NameSpace="Custom">
+        <Address>{device_link_throughput_limit_addr}</Address>
+        <Length>{device_link_throughput_limit_len}</Length>
+        <AccessMode>{device_link_throughput_limit_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="TLParamsLocked" NameSpace="Standard">
+        <ToolTip>Locks transport layer related parameters.</ToolTip>
+        <Description>Used by the transport layer to prevent critical features from changing during acquisition.</Description>
+        <DisplayName>TL Params Locked</DisplayName>
+        <Visibility>Invisible</Visibility>
+        <pValue>TLParamsLockedReg</pValue>
+        <Min>0</Min>
+        <Max>1</Max>
+    </Integer>
+
+    <IntReg Name="TLParamsLockedReg" NameSpace="Custom">
+        <Address>{tl_params_locked_addr}</Address>
+        <Length>{tl_params_locked_len}</Length>
+        <AccessMode>{tl_params_locked_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Category Name="ChunkDataControl" NameSpace="Standard">
+        <Description>Category that contains the Chunk Data control features.</Description>
+        <DisplayName>Chunk Data Control</DisplayName>
+        <pFeature>ChunkModeActive</pFeature>
+        <pFeature>ChunkSelector</pFeature>
+        <pFeature>ChunkEnable</pFeature>
+        <pFeature>ChunkTimestamp</pFeature>
+        <pFeature>ChunkFrameID</pFeature>
+    </Category>
+
+    <Boolean Name="ChunkModeActive" NameSpace="Standard">
+        <ToolTip>Activates the inclusion of Chunk data in the payload of the image.</ToolTip>
+        <Description>Activates the inclusion of Chunk data in the payload of the image.</Description>
+        <DisplayName>Chunk Mode Active</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <pValue>ChunkModeActiveReg</pValue>
+        <OnValue>1</OnValue>
+        <OffValue>0</OffValue>
+    </Boolean>
+
+    <IntReg Name="ChunkModeActiveReg" NameSpace="Custom">
+        <Address>{chunk_mode_active_addr}</Address>
+        <Length>{chunk_mode_active_len}</Length>
+        <AccessMode>{chunk_mode_active_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="ChunkSelector" NameSpace="Standard">
+        <ToolTip>Selects which Chunk to enable or control.</ToolTip>
+        <Description>Selects which Chunk to enable or control.</Description>
+        <DisplayName>Chunk Selector</DisplayName>
+        <EnumEntry Name="Image" NameSpace="Standard">
+            <DisplayName>Image</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="Timestamp" NameSpace="Standard">
+            <DisplayName>Timestamp</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <EnumEntry Name="FrameID" NameSpace="Standard">
+            <DisplayName>Frame ID</DisplayName>
+            <Value>2</Value>
+        </EnumEntry>
+        <pValue>ChunkSelectorReg</pValue>
+        <pSelected>ChunkEnable</pSelected>
+    </Enumeration>
+
+    <IntReg Name="ChunkSelectorReg" NameSpace="Custom">
+        <Address>{chunk_selector_addr}</Address>
+        <Length>{chunk_selector_len}</Length>
+        <AccessMode>{chunk_selector_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Boolean Name="ChunkEnable" NameSpace="Standard">
+        <ToolTip>Enables the inclusion of the selected Chunk data in the payload of the image.</ToolTip>
+        <Description>Enables the inclusion of the selected Chunk data in the payload of the image.</Description>
+        <DisplayName>Chunk Enable</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <pValue>ChunkEnableReg</pValue>
+        <OnValue>1</OnValue>
+        <OffValue>0</OffValue>
+    </Boolean>
+
+    <IntReg Name="ChunkEnableReg" NameSpace="Custom">
+        <Address>{chunk_enable_image_addr}</Address>
+        <pIndex Offset="{chunk_enable_image_len}">ChunkSelectorReg</pIndex>
+        <Length>{chunk_enable_image_len}</Length>
+        <AccessMode>{chunk_enable_image_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Integer Name="ChunkTimestamp" NameSpace="Standard">
+        <ToolTip>Returns the Timestamp of the image.</ToolTip>
+        <Description>Returns the Timestamp of the image.</Description>
+        <DisplayName>Chunk Timestamp</DisplayName>
+        <pValue>ChunkTimestampReg</pValue>
+        <Unit>ns</Unit>
+    </Integer>
+
+    <IntReg Name="ChunkTimestampReg" NameSpace="Custom">
+        <Address>0</Address>
+        <Length>8</Length>
+        <AccessMode>RO</AccessMode>
+        <pPort>ChunkTimestampPort</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Port Name="ChunkTimestampPort" NameSpace="Custom">
+        <ChunkID>{CHUNK_ID_TIMESTAMP:X}</ChunkID>
+    </Port>
+
+    <Integer Name="ChunkFrameID" NameSpace="Standard">
+        <ToolTip>Returns the unique Identifier of the frame.</ToolTip>
+        <Description>Returns the unique Identifier of the frame, it's same as the block ID of the payload.</Description>
+        <DisplayName>Chunk Frame ID</DisplayName>
+        <pValue>ChunkFrameIDReg</pValue>
+    </Integer>
+
+    <IntReg Name="ChunkFrameIDReg" NameSpace="Custom">
+        <Address>0</Address>
+        <Length>8</Length>
+        <AccessMode>RO</AccessMode>
+        <pPort>ChunkFrameIDPort</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Port Name="ChunkFrameIDPort" NameSpace="Custom">
+        <ChunkID>{CHUNK_ID_FRAME_ID:X}</ChunkID>
+    </Port>
+
+    <Category Name="EventControl" NameSpace="Standard">
+        <Description>Category that contains Event control features.</Description>
+        <DisplayName>Event Control</DisplayName>
+        <pFeature>EventSelector</pFeature>
+        <pFeature>EventNotification</pFeature>
+        <pFeature>EventExposureEndData</pFeature>
+        <pFeature>EventFrameStartData</pFeature>
+    </Category>
+
+    <Enumeration Name="EventSelector" NameSpace="Standard">
+        <ToolTip>Selects which Event to signal to the host application.</ToolTip>
+        <Description>Selects which Event to signal to the host application.</Description>
+        <DisplayName>Event Selector</DisplayName>
+        <EnumEntry Name="ExposureEnd" NameSpace="Standard">
+            <DisplayName>Exposure End</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="FrameStart" NameSpace="Standard">
+            <DisplayName>Frame Start</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>EventSelectorReg</pValue>
+        <pSelected>EventNotification</pSelected>
+    </Enumeration>
+
+    <IntReg Name="EventSelectorReg" NameSpace="Custom">
+        <Address>{event_selector_addr}</Address>
+        <Length>{event_selector_len}</Length>
+        <AccessMode>{event_selector_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="EventNotification" NameSpace="Standard">
+        <ToolTip>Activate or deactivate the notification to the host application of the occurrence of the selected Event.</ToolTip>
+        <Description>Activate or deactivate the notification to the host application of the occurrence of the selected Event.</Description>
+        <DisplayName>Event Notification</DisplayName>
+        <EnumEntry Name="Off" NameSpace="Standard">
+            <DisplayName>Off</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="On" NameSpace="Standard">
+            <DisplayName>On</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>EventNotificationReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="EventNotificationReg" NameSpace="Custom">
+        <Address>{event_notification_exposure_end_addr}</Address>
+        <pIndex Offset="{event_notification_exposure_end_len}">EventSelectorReg</pIndex>
+        <Length>{event_notification_exposure_end_len}</Length>
+        <AccessMode>{event_notification_exposure_end_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <pInvalidator>UserSetLoadReg</pInvalidator>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Category Name="EventExposureEndData" NameSpace="Standard">
+        <Description>Category that contains all the data features related to the Exposure End Event.</Description>
+        <DisplayName>Event Exposure End Data</DisplayName>
+        <pFeature>EventExposureEnd</pFeature>
+        <pFeature>EventExposureEndFrameID</pFeature>
+    </Category>
+
+    <Integer Name="EventExposureEnd" NameSpace="Standard">
+        <ToolTip>Returns the unique identifier of the Exposure End type of Event.</ToolTip>
+        <Description>Returns the unique identifier of the Exposure End type of Event.</Description>
+        <DisplayName>Event Exposure End</DisplayName>
+        <ImposedAccessMode>RO</ImposedAccessMode>
+        <Value>{EVENT_ID_EXPOSURE_END}</Value>
+    </Integer>
+
+    <Integer Name="EventExposureEndFrameID" NameSpace="Standard">
+        <ToolTip>Returns the unique Identifier of the Frame that generated the Exposure End Event.</ToolTip>
+        <Description>Returns the unique Identifier of the Frame that generated the Exposure End Event, it's same as the block ID of the payload.</Description>
+        <DisplayName>Event Exposure End Frame ID</DisplayName>
+        <pValue>EventExposureEndFrameIDReg</pValue>
+    </Integer>
+
+    <IntReg Name="EventExposureEndFrameIDReg" NameSpace="Custom">
+        <Address>0</Address>
+        <Length>8</Length>
+        <AccessMode>RO</AccessMode>
+        <pPort>EventExposureEndPort</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Port Name="EventExposureEndPort" NameSpace="Custom">
+        <EventID>{EVENT_ID_EXPOSURE_END:X}</EventID>
+    </Port>
+
+    <Category Name="EventFrameStartData" NameSpace="Standard">
+        <Description>Category that contains all the data features related to the Frame Start Event.</Description>
+        <DisplayName>Event Frame Start Data</DisplayName>
+        <pFeature>EventFrameStart</pFeature>
+        <pFeature>EventFrameStartFrameID</pFeature>
+    </Category>
+
+    <Integer Name="EventFrameStart" NameSpace="Standard">
+        <ToolTip>Returns the unique identifier of the Frame Start type of Event.</ToolTip>
+        <Description>Returns the unique identifier of the Frame Start type of Event.</Description>
+        <DisplayName>Event Frame Start</DisplayName>
+        <ImposedAccessMode>RO</ImposedAccessMode>
+        <Value>{EVENT_ID_FRAME_START}</Value>
+    </Integer>
+
+    <Integer Name="EventFrameStartFrameID" NameSpace="Standard">
+        <ToolTip>Returns the unique Identifier of the Frame that generated the Frame Start Event.</ToolTip>
+        <Description>Returns the unique Identifier of the Frame that generated the Frame Start Event, it's same as the block ID of the payload.</Description>
+        <DisplayName>Event Frame Start Frame ID</DisplayName>
+        <pValue>EventFrameStartFrameIDReg</pValue>
+    </Integer>
+
+    <IntReg Name="EventFrameStartFrameIDReg" NameSpace="Custom">
+        <Address>0</Address>
+        <Length>8</Length>
+        <AccessMode>RO</AccessMode>
+        <pPort>EventFrameStartPort</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Port Name="EventFrameStartPort" NameSpace="Custom">
+        <EventID>{EVENT_ID_FRAME_START:X}</EventID>
+    </Port>
+
+    <Category Name="UserSetControl" NameSpace="Standard">
+        <Description>Category that contains the User Set control features.</Description>
+        <DisplayName>User Set Control</DisplayName>
+        <pFeature>UserSetSelector</pFeature>
+        <pFeature>UserSetLoad</pFeature>
+        <pFeature>UserSetSave</pFeature>
+        <pFeature>UserSetDefault</pFeature>
+    </Category>
+
+    <Enumeration Name="UserSetSelector" NameSpace="Standard">
+        <ToolTip>Selects the feature User Set to load, save or configure.</ToolTip>
+        <Description>Selects the feature User Set to load, save or configure.</Description>
+        <DisplayName>User Set Selector</DisplayName>
+        <EnumEntry Name="Default" NameSpace="Standard">
+            <DisplayName>Default</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="UserSet1" NameSpace="Standard">
+            <DisplayName>User Set 1</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>UserSetSelectorReg</pValue>
+        <pSelected>UserSetLoad</pSelected>
+        <pSelected>UserSetSave</pSelected>
+    </Enumeration>
+
+    <IntReg Name="UserSetSelectorReg" NameSpace="Custom">
+        <Address>{user_set_selector_addr}</Address>
+        <Length>{user_set_selector_len}</Length>
+        <AccessMode>{user_set_selector_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Command Name="UserSetLoad" NameSpace="Standard">
+        <ToolTip>Loads the User Set specified by UserSetSelector to the device and makes it active.</ToolTip>
+        <Description>Loads the User Set specified by UserSetSelector to the device and makes it active.</Description>
+        <DisplayName>User Set Load</DisplayName>
+        <pIsLocked>TLParamsLocked</pIsLocked>
+        <pValue>UserSetLoadReg</pValue>
+        <CommandValue>1</CommandValue>
+    </Command>
+
+    <IntReg Name="UserSetLoadReg" NameSpace="Custom">
+        <Address>{user_set_load_addr}</Address>
+        <Length>{user_set_load_len}</Length>
+        <AccessMode>{user_set_load_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Command Name="UserSetSave" NameSpace="Standard">
+        <ToolTip>Save the User Set specified by UserSetSelector to the non-volatile memory of the device.</ToolTip>
+        <Description>Save the User Set specified by UserSetSelector to the non-volatile memory of the device. The Default user set can't be overwritten.</Description>
+        <DisplayName>User Set Save</DisplayName>
+        <pValue>UserSetSaveReg</pValue>
+        <CommandValue>1</CommandValue>
+    </Command>
+
+    <IntReg Name="UserSetSaveReg" NameSpace="Custom">
+        <Address>{user_set_save_addr}</Address>
+        <Length>{user_set_save_len}</Length>
+        <AccessMode>{user_set_save_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
+    <Enumeration Name="UserSetDefault" NameSpace="Standard">
+        <ToolTip>Selects the feature User Set to load and make active by default when the device is reset.</ToolTip>
+        <Description>Selects the feature User Set to load and make active by default when the device is reset.</Description>
+        <DisplayName>User Set Default</DisplayName>
+        <EnumEntry Name="Default" NameSpace="Standard">
+            <DisplayName>Default</DisplayName>
+            <Value>0</Value>
+        </EnumEntry>
+        <EnumEntry Name="UserSet1" NameSpace="Standard">
+            <DisplayName>User Set 1</DisplayName>
+            <Value>1</Value>
+        </EnumEntry>
+        <pValue>UserSetDefaultReg</pValue>
+    </Enumeration>
+
+    <IntReg Name="UserSetDefaultReg" NameSpace="Custom">
+        <Address>{user_set_default_addr}</Address>
+        <Length>{user_set_default_len}</Length>
+        <AccessMode>{user_set_default_access}</AccessMode>
+        <pPort>{PORT_NAME}</pPort>
+        <Sign>Unsigned</Sign>
+        <Endianess>LittleEndian</Endianess>
+    </IntReg>
+
 </RegisterDescription>"#,
+    manufacturer_name_addr = ABRM::ManufacturerName::ADDRESS,
+    manufacturer_name_len = ABRM::ManufacturerName::LENGTH,
+    manufacturer_name_access = ABRM::ManufacturerName::ACCESS_RIGHT.as_str(),
+    model_name_addr = ABRM::ModelName::ADDRESS,
+    model_name_len = ABRM::ModelName::LENGTH,
+    model_name_access = ABRM::ModelName::ACCESS_RIGHT.as_str(),
+    manufacturer_info_addr = ABRM::ManufacturerInfo::ADDRESS,
+    manufacturer_info_len = ABRM::ManufacturerInfo::LENGTH,
+    manufacturer_info_access = ABRM::ManufacturerInfo::ACCESS_RIGHT.as_str(),
+    device_version_addr = ABRM::DeviceVersion::ADDRESS,
+    device_version_len = ABRM::DeviceVersion::LENGTH,
+    device_version_access = ABRM::DeviceVersion::ACCESS_RIGHT.as_str(),
+    serial_number_addr = ABRM::SerialNumber::ADDRESS,
+    serial_number_len = ABRM::SerialNumber::LENGTH,
+    serial_number_access = ABRM::SerialNumber::ACCESS_RIGHT.as_str(),
+    user_defined_name_addr = ABRM::UserDefinedName::ADDRESS,
+    user_defined_name_len = ABRM::UserDefinedName::LENGTH,
+    user_defined_name_access = ABRM::UserDefinedName::ACCESS_RIGHT.as_str(),
+    timestamp_latch_addr = ABRM::TimestampLatch::ADDRESS,
+    timestamp_latch_len = ABRM::TimestampLatch::LENGTH,
+    timestamp_latch_access = ABRM::TimestampLatch::ACCESS_RIGHT.as_str(),
+    timestamp_addr = ABRM::Timestamp::ADDRESS,
+    timestamp_len = ABRM::Timestamp::LENGTH,
+    timestamp_access = ABRM::Timestamp::ACCESS_RIGHT.as_str(),
+    sensor_width_addr = GenApiReg::SensorWidth::ADDRESS,
+    sensor_width_len = GenApiReg::SensorWidth::LENGTH,
+    sensor_width_access = GenApiReg::SensorWidth::ACCESS_RIGHT.as_str(),
+    sensor_height_addr = GenApiReg::SensorHeight::ADDRESS,
+    sensor_height_len = GenApiReg::SensorHeight::LENGTH,
+    sensor_height_access = GenApiReg::SensorHeight::ACCESS_RIGHT.as_str(),
+    width_addr = GenApiReg::Width::ADDRESS,
+    width_len = GenApiReg::Width::LENGTH,
+    width_access = GenApiReg::Width::ACCESS_RIGHT.as_str(),
+    height_addr = GenApiReg::Height::ADDRESS,
+    height_len = GenApiReg::Height::LENGTH,
+    height_access = GenApiReg::Height::ACCESS_RIGHT.as_str(),
+    offset_x_addr = GenApiReg::OffsetX::ADDRESS,
+    offset_x_len = GenApiReg::OffsetX::LENGTH,
+    offset_x_access = GenApiReg::OffsetX::ACCESS_RIGHT.as_str(),
+    offset_y_addr = GenApiReg::OffsetY::ADDRESS,
+    offset_y_len = GenApiReg::OffsetY::LENGTH,
+    offset_y_access = GenApiReg::OffsetY::ACCESS_RIGHT.as_str(),
+    pixel_format_addr = GenApiReg::PixelFormat::ADDRESS,
+    pixel_format_len = GenApiReg::PixelFormat::LENGTH,
+    pixel_format_access = GenApiReg::PixelFormat::ACCESS_RIGHT.as_str(),
+    test_pattern_addr = GenApiReg::TestPattern::ADDRESS,
+    test_pattern_len = GenApiReg::TestPattern::LENGTH,
+    test_pattern_access = GenApiReg::TestPattern::ACCESS_RIGHT.as_str(),
+    acquisition_mode_addr = GenApiReg::AcquisitionMode::ADDRESS,
+    acquisition_mode_len = GenApiReg::AcquisitionMode::LENGTH,
+    acquisition_mode_access = GenApiReg::AcquisitionMode::ACCESS_RIGHT.as_str(),
     acquisition_start_addr = GenApiReg::AcquisitionStart::ADDRESS,
     acquisition_start_len = GenApiReg::AcquisitionStart::LENGTH,
     acquisition_start_access = GenApiReg::AcquisitionStart::ACCESS_RIGHT.as_str(),
     acquisition_stop_addr = GenApiReg::AcquisitionStop::ADDRESS,
     acquisition_stop_len = GenApiReg::AcquisitionStop::LENGTH,
     acquisition_stop_access = GenApiReg::AcquisitionStop::ACCESS_RIGHT.as_str(),
+    acquisition_frame_count_addr = GenApiReg::AcquisitionFrameCount::ADDRESS,
+    acquisition_frame_count_len = GenApiReg::AcquisitionFrameCount::LENGTH,
+    acquisition_frame_count_access = GenApiReg::AcquisitionFrameCount::ACCESS_RIGHT.as_str(),
+    trigger_selector_addr = GenApiReg::TriggerSelector::ADDRESS,
+    trigger_selector_len = GenApiReg::TriggerSelector::LENGTH,
+    trigger_selector_access = GenApiReg::TriggerSelector::ACCESS_RIGHT.as_str(),
+    trigger_mode_addr = GenApiReg::TriggerMode::ADDRESS,
+    trigger_mode_len = GenApiReg::TriggerMode::LENGTH,
+    trigger_mode_access = GenApiReg::TriggerMode::ACCESS_RIGHT.as_str(),
+    trigger_software_addr = GenApiReg::TriggerSoftware::ADDRESS,
+    trigger_software_len = GenApiReg::TriggerSoftware::LENGTH,
+    trigger_software_access = GenApiReg::TriggerSoftware::ACCESS_RIGHT.as_str(),
+    trigger_source_addr = GenApiReg::TriggerSource::ADDRESS,
+    trigger_source_len = GenApiReg::TriggerSource::LENGTH,
+    trigger_source_access = GenApiReg::TriggerSource::ACCESS_RIGHT.as_str(),
+    exposure_time_addr = GenApiReg::ExposureTime::ADDRESS,
+    exposure_time_len = GenApiReg::ExposureTime::LENGTH,
+    exposure_time_access = GenApiReg::ExposureTime::ACCESS_RIGHT.as_str(),
+    acquisition_frame_rate_addr = GenApiReg::AcquisitionFrameRate::ADDRESS,
+    acquisition_frame_rate_len = GenApiReg::AcquisitionFrameRate::LENGTH,
+    acquisition_frame_rate_access = GenApiReg::AcquisitionFrameRate::ACCESS_RIGHT.as_str(),
+    gain_addr = GenApiReg::Gain::ADDRESS,
+    gain_len = GenApiReg::Gain::LENGTH,
+    gain_access = GenApiReg::Gain::ACCESS_RIGHT.as_str(),
+    black_level_addr = GenApiReg::BlackLevel::ADDRESS,
+    black_level_len = GenApiReg::BlackLevel::LENGTH,
+    black_level_access = GenApiReg::BlackLevel::ACCESS_RIGHT.as_str(),
+    gamma_addr = GenApiReg::Gamma::ADDRESS,
+    gamma_len = GenApiReg::Gamma::LENGTH,
+    gamma_access = GenApiReg::Gamma::ACCESS_RIGHT.as_str(),
+    payload_size_addr = GenApiReg::PayloadSize::ADDRESS,
+    payload_size_len = GenApiReg::PayloadSize::LENGTH,
+    payload_size_access = GenApiReg::PayloadSize::ACCESS_RIGHT.as_str(),
+    device_link_throughput_limit_mode_addr = GenApiReg::DeviceLinkThroughputLimitMode::ADDRESS,
+    device_link_throughput_limit_mode_len = GenApiReg::DeviceLinkThroughputLimitMode::LENGTH,
+    device_link_throughput_limit_mode_access =
+        GenApiReg::DeviceLinkThroughputLimitMode::ACCESS_RIGHT.as_str(),
+    device_link_throughput_limit_addr = GenApiReg::DeviceLinkThroughputLimit::ADDRESS,
+    device_link_throughput_limit_len = GenApiReg::DeviceLinkThroughputLimit::LENGTH,
+    device_link_throughput_limit_access =
+        GenApiReg::DeviceLinkThroughputLimit::ACCESS_RIGHT.as_str(),
+    tl_params_locked_addr = GenApiReg::TLParamsLocked::ADDRESS,
+    tl_params_locked_len = GenApiReg::TLParamsLocked::LENGTH,
+    tl_params_locked_access = GenApiReg::TLParamsLocked::ACCESS_RIGHT.as_str(),
+    chunk_mode_active_addr = GenApiReg::ChunkModeActive::ADDRESS,
+    chunk_mode_active_len = GenApiReg::ChunkModeActive::LENGTH,
+    chunk_mode_active_access = GenApiReg::ChunkModeActive::ACCESS_RIGHT.as_str(),
+    chunk_selector_addr = GenApiReg::ChunkSelector::ADDRESS,
+    chunk_selector_len = GenApiReg::ChunkSelector::LENGTH,
+    chunk_selector_access = GenApiReg::ChunkSelector::ACCESS_RIGHT.as_str(),
+    chunk_enable_image_addr = GenApiReg::ChunkEnableImage::ADDRESS,
+    chunk_enable_image_len = GenApiReg::ChunkEnableImage::LENGTH,
+    chunk_enable_image_access = GenApiReg::ChunkEnableImage::ACCESS_RIGHT.as_str(),
+    event_selector_addr = GenApiReg::EventSelector::ADDRESS,
+    event_selector_len = GenApiReg::EventSelector::LENGTH,
+    event_selector_access = GenApiReg::EventSelector::ACCESS_RIGHT.as_str(),
+    event_notification_exposure_end_addr = GenApiReg::EventNotificationExposureEnd::ADDRESS,
+    event_notification_exposure_end_len = GenApiReg::EventNotificationExposureEnd::LENGTH,
+    event_notification_exposure_end_access =
+        GenApiReg::EventNotificationExposureEnd::ACCESS_RIGHT.as_str(),
+    user_set_selector_addr = GenApiReg::UserSetSelector::ADDRESS,
+    user_set_selector_len = GenApiReg::UserSetSelector::LENGTH,
+    user_set_selector_access = GenApiReg::UserSetSelector::ACCESS_RIGHT.as_str(),
+    user_set_load_addr = GenApiReg::UserSetLoad::ADDRESS,
+    user_set_load_len = GenApiReg::UserSetLoad::LENGTH,
+    user_set_load_access = GenApiReg::UserSetLoad::ACCESS_RIGHT.as_str(),
+    user_set_save_addr = GenApiReg::UserSetSave::ADDRESS,
+    user_set_save_len = GenApiReg::UserSetSave::LENGTH,
+    user_set_save_access = GenApiReg::UserSetSave::ACCESS_RIGHT.as_str(),
+    user_set_default_addr = GenApiReg::UserSetDefault::ADDRESS,
+    user_set_default_len = GenApiReg::UserSetDefault::LENGTH,
+    user_set_default_access = GenApiReg::UserSetDefault::ACCESS_RIGHT.as_str(),
 );
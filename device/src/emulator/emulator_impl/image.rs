@@ -0,0 +1,318 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::convert::TryFrom;
+
+use cameleon_impl::memory::prelude::*;
+use rand::RngCore;
+
+use crate::PixelFormat;
+
+use super::{
+    genapi::{GenApiReg, HEIGHT_INC, HEIGHT_MIN, WIDTH_INC, WIDTH_MIN},
+    memory::Memory,
+};
+
+/// Size of a square of [`TestPattern::Checkerboard`] in pixels.
+const CHECKER_SIZE: u32 = 32;
+
+/// Test pattern of images generated by the emulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    /// Horizontal gradient which scrolls by one pixel every frame.
+    Gradient,
+
+    /// Checkerboard whose black and white squares are swapped every frame.
+    Checkerboard,
+
+    /// Each pixel value is `x + y * width + frame_count`, wrapped at the maximum pixel value.
+    Counter,
+
+    /// Random pixel values.
+    Noise,
+}
+
+impl TestPattern {
+    pub(super) fn as_num(self) -> u32 {
+        match self {
+            Self::Gradient => 0,
+            Self::Checkerboard => 1,
+            Self::Counter => 2,
+            Self::Noise => 3,
+        }
+    }
+
+    pub(super) fn from_num(num: u32) -> Option<Self> {
+        match num {
+            0 => Some(Self::Gradient),
+            1 => Some(Self::Checkerboard),
+            2 => Some(Self::Counter),
+            3 => Some(Self::Noise),
+            _ => None,
+        }
+    }
+}
+
+/// Settings of images generated by the emulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ImageSettings {
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) offset_x: u32,
+    pub(super) offset_y: u32,
+    pub(super) pixel_format: PixelFormat,
+    pub(super) pattern: TestPattern,
+}
+
+impl ImageSettings {
+    /// Read image settings from the memory, returns `None` if the settings are invalid.
+    pub(super) fn read(memory: &Memory) -> Option<Self> {
+        let sensor_width = memory.read::<GenApiReg::SensorWidth>().ok()?;
+        let sensor_height = memory.read::<GenApiReg::SensorHeight>().ok()?;
+        let width = memory.read::<GenApiReg::Width>().ok()?;
+        let height = memory.read::<GenApiReg::Height>().ok()?;
+        let offset_x = memory.read::<GenApiReg::OffsetX>().ok()?;
+        let offset_y = memory.read::<GenApiReg::OffsetY>().ok()?;
+        let pixel_format = memory.read::<GenApiReg::PixelFormat>().ok()?;
+        let pattern = memory.read::<GenApiReg::TestPattern>().ok()?;
+
+        if !is_valid_roi(width, offset_x, sensor_width, WIDTH_MIN, WIDTH_INC)
+            || !is_valid_roi(height, offset_y, sensor_height, HEIGHT_MIN, HEIGHT_INC)
+        {
+            log::error!(
+                "region of interest {}x{}+{}+{} is invalid for the {}x{} sensor",
+                width,
+                height,
+                offset_x,
+                offset_y,
+                sensor_width,
+                sensor_height
+            );
+            return None;
+        }
+        let pixel_format = if let Some(format) = supported_pixel_format(pixel_format) {
+            format
+        } else {
+            log::error!("pixel format {:#x} is not supported", pixel_format);
+            return None;
+        };
+        let pattern = if let Some(pattern) = TestPattern::from_num(pattern) {
+            pattern
+        } else {
+            log::error!("test pattern {} is not supported", pattern);
+            return None;
+        };
+
+        Some(Self {
+            width,
+            height,
+            offset_x,
+            offset_y,
+            pixel_format,
+            pattern,
+        })
+    }
+
+    /// Size of an image in bytes.
+    pub(super) fn image_size(&self) -> u64 {
+        // `pixel_format` is always one of supported formats.
+        let bytes_per_pixel = bytes_per_pixel(self.pixel_format).unwrap();
+        u64::from(self.width) * u64::from(self.height) * bytes_per_pixel as u64
+    }
+
+    /// Render an image of the frame.
+    pub(super) fn render(&self, frame_count: u64) -> Vec<u8> {
+        let bytes_per_pixel = bytes_per_pixel(self.pixel_format).unwrap();
+        let mut image = vec![0; self.image_size() as usize];
+        if self.pattern == TestPattern::Noise {
+            rand::thread_rng().fill_bytes(&mut image);
+            return image;
+        }
+
+        let max = if self.pixel_format == PixelFormat::Mono16 {
+            u32::from(u16::MAX)
+        } else {
+            u32::from(u8::MAX)
+        };
+
+        let pixels = image.chunks_exact_mut(bytes_per_pixel);
+        for (i, pixel) in pixels.enumerate() {
+            let x = (i % self.width as usize) as u32;
+            let y = (i / self.width as usize) as u32;
+            let value = self.pixel_value(x, y, frame_count, max);
+            match self.pixel_format {
+                PixelFormat::Mono16 => pixel.copy_from_slice(&(value as u16).to_le_bytes()),
+                _ => pixel.iter_mut().for_each(|c| *c = value as u8),
+            }
+        }
+
+        image
+    }
+
+    fn pixel_value(&self, x: u32, y: u32, frame_count: u64, max: u32) -> u32 {
+        // Patterns are drawn in the sensor coordinates.
+        let (x, y) = (x + self.offset_x, y + self.offset_y);
+        match self.pattern {
+            TestPattern::Gradient => {
+                let x = (u64::from(x) + frame_count) % u64::from(self.width);
+                (x * u64::from(max) / u64::from(self.width.max(2) - 1)) as u32
+            }
+
+            TestPattern::Checkerboard => {
+                let is_white = (x / CHECKER_SIZE + y / CHECKER_SIZE) & 1 == 0;
+                if is_white ^ (frame_count % 2 == 1) {
+                    max
+                } else {
+                    0
+                }
+            }
+
+            TestPattern::Counter => {
+                let value = u64::from(x) + u64::from(y) * u64::from(self.width) + frame_count;
+                (value % (u64::from(max) + 1)) as u32
+            }
+
+            TestPattern::Noise => unreachable!(),
+        }
+    }
+}
+
+/// Returns `true` if the size and offset of the region of interest satisfy the constraints in one
+/// dimension.
+pub(super) fn is_valid_roi(size: u32, offset: u32, sensor_size: u32, min: u32, inc: u32) -> bool {
+    size >= min
+        && size.checked_rem(inc) == Some(0)
+        && offset.checked_rem(inc) == Some(0)
+        && u64::from(size) + u64::from(offset) <= u64::from(sensor_size)
+}
+
+/// Returns the number of bytes per pixel if the emulator supports the format.
+pub(super) fn bytes_per_pixel(format: PixelFormat) -> Option<usize> {
+    use PixelFormat::{BayerBG8, BayerGB8, BayerGR8, BayerRG8, Mono16, Mono8, RGB8};
+    match format {
+        Mono8 | BayerGR8 | BayerRG8 | BayerGB8 | BayerBG8 => Some(1),
+        Mono16 => Some(2),
+        RGB8 => Some(3),
+        _ => None,
+    }
+}
+
+/// Returns the pixel format if the emulator supports the format code.
+pub(super) fn supported_pixel_format(code: u32) -> Option<PixelFormat> {
+    PixelFormat::try_from(code)
+        .ok()
+        .filter(|format| bytes_per_pixel(*format).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(pixel_format: PixelFormat, pattern: TestPattern) -> ImageSettings {
+        ImageSettings {
+            width: 64,
+            height: 48,
+            offset_x: 0,
+            offset_y: 0,
+            pixel_format,
+            pattern,
+        }
+    }
+
+    #[test]
+    fn test_image_size() {
+        let formats = [
+            (PixelFormat::Mono8, 1),
+            (PixelFormat::BayerRG8, 1),
+            (PixelFormat::Mono16, 2),
+            (PixelFormat::RGB8, 3),
+        ];
+        for &(format, bpp) in &formats {
+            let settings = settings(format, TestPattern::Noise);
+            assert_eq!(settings.image_size(), 64 * 48 * bpp);
+            assert_eq!(settings.render(0).len() as u64, settings.image_size());
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        let settings = settings(PixelFormat::Mono8, TestPattern::Gradient);
+        let image = settings.render(0);
+        assert_eq!(image[0], 0);
+        assert_eq!(image[63], 255);
+        assert!(image[..64].windows(2).all(|w| w[0] <= w[1]));
+
+        // The gradient scrolls by one pixel.
+        let next = settings.render(1);
+        assert_eq!(next[0], image[1]);
+    }
+
+    #[test]
+    fn test_checkerboard() {
+        let settings = settings(PixelFormat::Mono16, TestPattern::Checkerboard);
+        let image = settings.render(0);
+        let pixel = |x: usize, y: usize| {
+            u16::from_le_bytes([image[(y * 64 + x) * 2], image[(y * 64 + x) * 2 + 1]])
+        };
+        assert_eq!(pixel(0, 0), u16::MAX);
+        assert_eq!(pixel(32, 0), 0);
+        assert_eq!(pixel(32, 32), u16::MAX);
+
+        let next = settings.render(1);
+        assert_eq!(&next[..2], &[0, 0]);
+    }
+
+    #[test]
+    fn test_counter() {
+        let settings = settings(PixelFormat::RGB8, TestPattern::Counter);
+        let image = settings.render(10);
+        assert_eq!(&image[..6], &[10, 10, 10, 11, 11, 11]);
+        // Pixel value is wrapped.
+        let offset = (255 - 10 + 1) * 3;
+        assert_eq!(&image[offset..offset + 3], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_offset() {
+        let mut settings = settings(PixelFormat::Mono8, TestPattern::Counter);
+        settings.offset_x = 4;
+        settings.offset_y = 1;
+        let image = settings.render(0);
+        assert_eq!(image[0], 4 + 64);
+    }
+
+    #[test]
+    fn test_read_settings() {
+        let mut memory = Memory::new();
+        let settings = ImageSettings::read(&memory).unwrap();
+        assert_eq!(settings.pixel_format, PixelFormat::Mono8);
+        assert_eq!(settings.pattern, TestPattern::Gradient);
+
+        // Region of interest exceeds the sensor.
+        memory.write::<GenApiReg::OffsetX>(WIDTH_INC).unwrap();
+        assert!(ImageSettings::read(&memory).is_none());
+        memory.write::<GenApiReg::Width>(640 - WIDTH_INC).unwrap();
+        assert!(ImageSettings::read(&memory).is_some());
+
+        // Width must be a multiple of the increment.
+        memory.write::<GenApiReg::Width>(WIDTH_MIN + 1).unwrap();
+        assert!(ImageSettings::read(&memory).is_none());
+        memory.write::<GenApiReg::Width>(WIDTH_MIN).unwrap();
+
+        memory
+            .write::<GenApiReg::PixelFormat>(u32::from(PixelFormat::Mono12))
+            .unwrap();
+        assert!(ImageSettings::read(&memory).is_none());
+    }
+
+    #[test]
+    fn test_supported_pixel_format() {
+        assert_eq!(
+            supported_pixel_format(0x0108_0001),
+            Some(PixelFormat::Mono8)
+        );
+        assert!(supported_pixel_format(u32::from(PixelFormat::Mono12)).is_none());
+        assert!(supported_pixel_format(0xffff_ffff).is_none());
+    }
+}
@@ -13,20 +13,27 @@ use async_std::{
 use futures::{channel::oneshot, select, FutureExt};
 
 use super::{
+    command_log::CommandLog,
     control_module::ControlModule,
     device::Timestamp,
-    event_module::EventModule,
+    event_module::{Event, EventModule},
     fake_protocol::{FakeAckKind, FakeAckPacket, FakeReqKind, FakeReqPacket, IfaceKind},
+    fault::FaultInjector,
     memory::Memory,
     shared_queue::SharedQueue,
     signal::{ControlSignal, EventSignal, InterfaceSignal, StreamSignal},
     stream_module::StreamModule,
+    write_hook::WriteHooks,
 };
 
 pub(super) struct Interface {
     iface_state: IfaceState,
     memory: Arc<Mutex<Memory>>,
     timestamp: Timestamp,
+    faults: FaultInjector,
+    hooks: WriteHooks,
+    log: CommandLog,
+    injected_events: Receiver<Event>,
 
     ctrl_queue: SharedQueue<Vec<u8>>,
     event_queue: SharedQueue<Vec<u8>>,
@@ -37,11 +44,22 @@ const SHARED_QUEUE_SIZE: usize = 32;
 const CHANNEL_CAPACITY: usize = 128;
 
 impl Interface {
-    pub(super) fn new(memory: Arc<Mutex<Memory>>, timestamp: Timestamp) -> Self {
+    pub(super) fn new(
+        memory: Arc<Mutex<Memory>>,
+        timestamp: Timestamp,
+        faults: FaultInjector,
+        hooks: WriteHooks,
+        log: CommandLog,
+        injected_events: Receiver<Event>,
+    ) -> Self {
         Self {
             iface_state: IfaceState::new(),
             memory,
             timestamp,
+            faults,
+            hooks,
+            log,
+            injected_events,
 
             ctrl_queue: SharedQueue::new(SHARED_QUEUE_SIZE),
             event_queue: SharedQueue::new(SHARED_QUEUE_SIZE),
@@ -117,6 +135,9 @@ impl Interface {
             self.iface_state.clone(),
             self.memory.clone(),
             self.timestamp.clone(),
+            self.faults.clone(),
+            self.hooks.clone(),
+            self.log.clone(),
             self.ctrl_queue.clone(),
         );
         task::spawn(control_module.run(signal_tx, ctrl_signal_rx));
@@ -127,8 +148,13 @@ impl Interface {
     fn spawn_event_module(&self, signal_tx: Sender<InterfaceSignal>) -> Sender<EventSignal> {
         let (event_signal_tx, event_signal_rx) = channel::bounded(CHANNEL_CAPACITY);
 
-        // Construct and spawn control module.
-        let event_module = EventModule::new(self.event_queue.clone(), 0);
+        // Construct and spawn event module.
+        let event_module = EventModule::new(
+            self.memory.clone(),
+            self.timestamp.clone(),
+            self.injected_events.clone(),
+            self.event_queue.clone(),
+        );
         task::spawn(event_module.run(signal_tx, event_signal_rx));
 
         event_signal_tx
@@ -138,7 +164,12 @@ impl Interface {
         let (stream_signal_tx, stream_signal_rx) = channel::bounded(CHANNEL_CAPACITY);
 
         // Construct and spawn control module.
-        let stream_module = StreamModule::new(self.timestamp.clone(), self.stream_queue.clone());
+        let stream_module = StreamModule::new(
+            self.memory.clone(),
+            self.timestamp.clone(),
+            self.faults.clone(),
+            self.stream_queue.clone(),
+        );
         task::spawn(stream_module.run(signal_tx, stream_signal_rx));
 
         stream_signal_tx
@@ -154,19 +185,25 @@ impl Interface {
         let iface = packet.iface;
         let req_kind = packet.kind;
 
+        // Disconnected device can't respond to any requests.
+        if self.faults.is_disconnected() {
+            send_ack(ack_tx, iface, FakeAckKind::Disconnected);
+            return;
+        }
+
         // Handle claer halt request.
         if req_kind.is_clear_halt() {
             self.iface_state
                 .set_state(iface, IfaceStateKind::Ready)
                 .await;
-            send_ack(&ack_tx, iface, FakeAckKind::ClearHaltAck);
+            send_ack(ack_tx, iface, FakeAckKind::ClearHaltAck);
             return;
         }
 
         // Handle set halt request.
         if req_kind.is_set_halt() {
             self.set_halt(iface, signal_tx).await;
-            send_ack(&ack_tx, iface, FakeAckKind::SetHaltAck);
+            send_ack(ack_tx, iface, FakeAckKind::SetHaltAck);
             return;
         }
 
@@ -189,12 +226,12 @@ impl Interface {
                     Some(data) => FakeAckKind::RecvAck(data),
                     None => FakeAckKind::RecvNak,
                 };
-                send_ack(&ack_tx, iface, ack_kind);
+                send_ack(ack_tx, iface, ack_kind);
             }
 
             (IfaceKind::Control, FakeReqKind::Send(data)) => {
                 signal_tx.send_ctrl(ControlSignal::ReceiveData(data));
-                send_ack(&ack_tx, iface, FakeAckKind::SendAck);
+                send_ack(ack_tx, iface, FakeAckKind::SendAck);
             }
 
             (iface, req) => {
@@ -203,7 +240,7 @@ impl Interface {
                     iface,
                     req
                 );
-                send_ack(&ack_tx, iface, FakeAckKind::BrokenReq);
+                send_ack(ack_tx, iface, FakeAckKind::BrokenReq);
             }
         };
     }
@@ -2,15 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use cameleon_impl::memory::{memory, register_map, Register};
+use std::ops::Range;
 
-use super::genapi;
+use cameleon_impl::memory::{
+    memory, register_map, AccessRight, MemoryError, MemoryFragment, MemoryResult, Register,
+};
+
+use super::genapi::{self, GenApiReg};
 
 const ABRM_ADDRESS: usize = 0;
 const SBRM_ADDRESS: usize = 0xffff;
 const SIRM_ADDRESS: usize = SBRM::base() + SBRM::size();
-const MANIFEST_TABLE_ADDRESS: usize = SIRM::base() + SIRM::size();
-pub(super) const GENAPI_XML_ADDRESS: usize = ManifestTable::base() + ManifestTable::size();
+const EIRM_ADDRESS: usize = SIRM::base() + SIRM::size();
+const MANIFEST_TABLE_ADDRESS: usize = EIRM::base() + EIRM::size();
+pub(super) const GENAPI_REG_ADDRESS: usize = ManifestTable::base() + ManifestTable::size();
+pub(super) const GENAPI_XML_ADDRESS: usize = GenApiReg::base() + GenApiReg::size();
 const GENAPI_XML_LENGTH: usize = genapi::GENAPI_XML.len();
 
 /// Offset | Value | Description.
@@ -23,13 +29,13 @@ const GENAPI_XML_LENGTH: usize = genapi::GENAPI_XML.len();
 ///      9 |     1 | SBRM is supported.
 ///     10 |     1 | Endianness Register is supported.
 ///     11 |     1 | Written Length Field is supported.
-///     12 |     0 | Multi Event is currently NOT supported.
+///     12 |     1 | Multi Event is supported.
 ///     13 |     0 | Stacked Commands is NOT supported.
 ///     14 |     1 | Device Software Interface Version is supported.
 ///  15-63 |     0 | Reserved. All remained bits are set to 0.
 const DEVICE_CAPABILITY: &[u8] = &[
     0b0000_1001,
-    0b0100_1111,
+    0b0101_1111,
     0b0000_0000,
     0b0000_0000,
     0b0000_0000,
@@ -74,10 +80,68 @@ pub(super) struct Memory {
     abrm: ABRM,
     sbrm: SBRM,
     sirm: SIRM,
+    eirm: EIRM,
     manifest_table: ManifestTable,
+    genapi_reg: GenApiReg,
     genapi_xml: GenApiXml,
 }
 
+impl Memory {
+    /// Returns the size of the memory.
+    pub(super) fn size(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Map `fragment` into the memory, the memory is extended if the fragment exceeds the end of
+    /// the memory.
+    ///
+    /// Returns `false` if the fragment overlaps with registers which are already mapped.
+    pub(super) fn map_fragment(&mut self, fragment: &MemoryFragment) -> bool {
+        let range = fragment.range();
+        let size = self.size();
+        if range
+            .clone()
+            .filter(|addr| *addr < size)
+            .any(|addr| self.protection.access_right(addr) != AccessRight::NA)
+        {
+            return false;
+        }
+
+        if size < range.end {
+            self.raw.resize(range.end, 0);
+            self.protection.resize(range.end);
+        }
+        self.raw[range.clone()].copy_from_slice(fragment.data());
+        for (addr, access_right) in range.zip(fragment.access_rights()) {
+            self.protection.set_access_right(addr, *access_right);
+        }
+        true
+    }
+
+    /// Read the memory without checking access rights.
+    pub(super) fn peek(&self, range: Range<usize>) -> MemoryResult<&[u8]> {
+        self.protection.verify_address_with_range(range.clone())?;
+        Ok(&self.raw[range])
+    }
+
+    /// Write to the memory without checking access rights.
+    ///
+    /// NOTE: Observers of the written registers are NOT notified.
+    pub(super) fn poke(&mut self, addr: usize, data: &[u8]) -> MemoryResult<()> {
+        let range = addr..addr
+            .checked_add(data.len())
+            .ok_or(MemoryError::InvalidAddress)?;
+        self.protection.verify_address_with_range(range.clone())?;
+        self.raw[range].copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Notify observers of registers in the range.
+    pub(super) fn notify(&self, range: Range<usize>) {
+        self.notify_all(range);
+    }
+}
+
 #[register_map(base = ABRM_ADDRESS, endianness = LE)]
 pub(super) enum ABRM {
     #[register(len = 2, access = RO, ty = u16)]
@@ -180,10 +244,10 @@ pub(super) enum SBRM {
     SirmLength = SIRM::size() as u32,
 
     #[register(len = 8, access = RO, ty = u64)]
-    EirmAddress, // TODO: Filled after SIRM register map is implemented.
+    EirmAddress = EIRM_ADDRESS,
 
     #[register(len = 4, access = RO, ty = u32)]
-    EirmLength, // TODO: Filled after EIRM register map is implmeneted.
+    EirmLength = EIRM::size() as u32,
 
     #[register(len = 8, access = NA, ty = u64)]
     Iidc2Address,
@@ -206,7 +270,7 @@ pub(super) enum SIRM {
     Control = 0,
 
     #[register(len = 8, access = RO, ty = u64)]
-    RequiredPayloadSize = 15_151_104,
+    RequiredPayloadSize = genapi::DEFAULT_WIDTH as u64 * genapi::DEFAULT_HEIGHT as u64,
 
     #[register(len = 4, access = RO, ty = u32)]
     RequiredLeaderSize = 1024,
@@ -233,6 +297,18 @@ pub(super) enum SIRM {
     MaximumTrailerSize = 0,
 }
 
+#[register_map(base = EIRM_ADDRESS, endianness = LE)]
+pub(super) enum EIRM {
+    #[register(len = 4, access = RW, ty = u32)]
+    Control = 0,
+
+    #[register(len = 4, access = RO, ty = u32)]
+    MaximumEventTransferLength = 1024,
+
+    #[register(len = 4, access = RW, ty = u32)]
+    EventTestControl = 0,
+}
+
 const MANIFEST_ENTRY0_BF_OFFSET: usize = (ManifestTable::GenICamFileVersionMajor::ADDRESS
     + ManifestTable::GenICamFileVersionMajor::LENGTH)
     - MANIFEST_TABLE_ADDRESS;
@@ -10,8 +10,12 @@ use cameleon_impl::memory::{prelude::*, MemoryObserver};
 use super::{
     control_module::Worker,
     control_protocol::{ack, cmd},
-    memory::{Memory, ABRM, SIRM, SIRM_ALIGNMENT},
+    event_module::{self, Event},
+    genapi::{GenApiReg, USER_SET_LENGTH},
+    memory::{Memory, ABRM, EIRM, SIRM, SIRM_ALIGNMENT},
     signal::{EventSignal, StreamSignal},
+    stream_module,
+    write_hook::WriteHooks,
 };
 
 const MEMORY_EVENT_CHANNEL_CAPACITY: usize = 100;
@@ -19,15 +23,16 @@ const MEMORY_EVENT_CHANNEL_CAPACITY: usize = 100;
 #[derive(Clone)]
 pub(super) struct MemoryEventHandler {
     rx: Receiver<MemoryEvent>,
+    hooks: WriteHooks,
 }
 
 impl MemoryEventHandler {
     /// Construct `MemoryEventHandler` while registering observers to memory.
-    pub(super) async fn new(memory: &mut Memory) -> Self {
+    pub(super) async fn new(memory: &mut Memory, hooks: WriteHooks) -> Self {
         let (tx, rx) = channel::bounded(MEMORY_EVENT_CHANNEL_CAPACITY);
         MemoryEvent::register_events(memory, &tx);
 
-        MemoryEventHandler { rx }
+        MemoryEventHandler { rx, hooks }
     }
 
     /// Handle write events, return `Some(error_ack)` if an error occurs while handling write events.
@@ -36,7 +41,11 @@ impl MemoryEventHandler {
         worker: &Worker,
         scd_kind: cmd::ScdKind,
     ) -> Result<(), ack::ErrorAck> {
-        let mut error_ack = Ok(());
+        // User defined hooks run first so that built-in handlers see registers written by them.
+        let mut error_ack = self
+            .hooks
+            .run(&mut *worker.memory.lock().await)
+            .map_err(|status| ack::ErrorAck::new(status, scd_kind));
 
         while let Ok(event) = self.rx.try_recv() {
             let ack = event.process(worker, scd_kind).await;
@@ -106,12 +115,52 @@ impl TimestampLatchHandler {
         let timestamp_ns = worker.timestamp.as_nanos().await;
         write_memory::<ABRM::Timestamp>(timestamp_ns, &mut memory, scd_kind)?;
 
-        drop(memory);
+        Ok(())
+    }
+}
 
-        // Send signal to [`super::event_module::EventModule`] to notify `TimeStamp` register is updated.
-        let signal = EventSignal::UpdateTimestamp(timestamp_ns);
-        worker.try_send_signal(signal);
+define_handler!(EiControlHandler, EIRM::Control, MemoryEvent::EiControl);
+impl EiControlHandler {
+    /// Handle `MemoryEvent::EiControl`
+    async fn handle_events(worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
+        let value = Self::read(&*worker.memory.lock().await, scd_kind)?;
+
+        if value == 1 {
+            // Send signal to [`super::event_module::EventModule`] to enable it.
+            worker.try_send_signal(EventSignal::Enable);
+            Ok(())
+        } else if value == 0 {
+            let (completed_tx, completed_rx) = oneshot::channel();
+            worker.try_send_signal(EventSignal::Disable(completed_tx));
+            completed_rx.await.ok();
+            Ok(())
+        } else {
+            Err(ack::ErrorAck::new(ack::GenCpStatus::GenericError, scd_kind))
+        }
+    }
+}
+
+define_handler!(
+    EventTestControlHandler,
+    EIRM::EventTestControl,
+    MemoryEvent::EventTestControl
+);
+impl EventTestControlHandler {
+    /// Handle `MemoryEvent::EventTestControl`.
+    ///
+    /// If 1 is written to `EventTestControl`, the device sends a test event to the host, then the
+    /// register is cleared.
+    async fn handle_events(worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
+        let mut memory = worker.memory.lock().await;
+        let value = Self::read(&memory, scd_kind)?;
+        if value != 1 {
+            return Err(ack::ErrorAck::new(ack::GenCpStatus::GenericError, scd_kind));
+        }
+        Self::write(0, &mut memory, scd_kind)?;
+        drop(memory);
 
+        let event = Event::new(event_module::TEST_EVENT_ID, vec![]);
+        worker.try_send_signal(EventSignal::Events(vec![event]));
         Ok(())
     }
 }
@@ -237,6 +286,207 @@ impl SiControlHandler {
     }
 }
 
+/// This macro defines handler for command registers which send a signal to
+/// [`super::stream_module::StreamModule`] when 1 is written.
+macro_rules! define_handler_for_acquisition {
+    ($handler_name:ident, $reg:path, $event:path, $signal:expr) => {
+        define_handler!($handler_name, $reg, $event);
+
+        impl $handler_name {
+            async fn handle_events(
+                worker: &Worker,
+                scd_kind: cmd::ScdKind,
+            ) -> Result<(), ack::ErrorAck> {
+                let value = Self::read(&*worker.memory.lock().await, scd_kind)?;
+                if value == 1 {
+                    worker.try_send_signal($signal);
+                    Ok(())
+                } else {
+                    Err(ack::ErrorAck::new(
+                        ack::GenCpStatus::InvalidParameter,
+                        scd_kind,
+                    ))
+                }
+            }
+        }
+    };
+}
+
+define_handler_for_acquisition!(
+    AcquisitionStartHandler,
+    GenApiReg::AcquisitionStart,
+    MemoryEvent::AcquisitionStart,
+    StreamSignal::StartAcquisition
+);
+define_handler_for_acquisition!(
+    AcquisitionStopHandler,
+    GenApiReg::AcquisitionStop,
+    MemoryEvent::AcquisitionStop,
+    StreamSignal::StopAcquisition
+);
+define_handler_for_acquisition!(
+    TriggerSoftwareHandler,
+    GenApiReg::TriggerSoftware,
+    MemoryEvent::TriggerSoftware,
+    StreamSignal::TriggerSoftware
+);
+
+/// This macro defines handler for registers which affect the payload size.
+///
+/// A handler defined by this macro verifies the image settings and recomputes `PayloadSize` and
+/// `SIRM::RequiredPayloadSize`.
+macro_rules! define_handler_for_payload_size {
+    ($handler_name:ident, $reg:path, $event:path) => {
+        define_handler!($handler_name, $reg, $event);
+
+        impl $handler_name {
+            async fn handle_events(
+                worker: &Worker,
+                scd_kind: cmd::ScdKind,
+            ) -> Result<(), ack::ErrorAck> {
+                if stream_module::update_payload_size(&mut *worker.memory.lock().await) {
+                    Ok(())
+                } else {
+                    Err(ack::ErrorAck::new(
+                        ack::GenCpStatus::InvalidParameter,
+                        scd_kind,
+                    ))
+                }
+            }
+        }
+    };
+}
+
+define_handler_for_payload_size!(WidthHandler, GenApiReg::Width, MemoryEvent::Width);
+define_handler_for_payload_size!(HeightHandler, GenApiReg::Height, MemoryEvent::Height);
+define_handler_for_payload_size!(OffsetXHandler, GenApiReg::OffsetX, MemoryEvent::OffsetX);
+define_handler_for_payload_size!(OffsetYHandler, GenApiReg::OffsetY, MemoryEvent::OffsetY);
+define_handler_for_payload_size!(
+    PixelFormatHandler,
+    GenApiReg::PixelFormat,
+    MemoryEvent::PixelFormat
+);
+define_handler_for_payload_size!(
+    ChunkModeActiveHandler,
+    GenApiReg::ChunkModeActive,
+    MemoryEvent::ChunkModeActive
+);
+define_handler_for_payload_size!(
+    ChunkEnableTimestampHandler,
+    GenApiReg::ChunkEnableTimestamp,
+    MemoryEvent::ChunkEnableTimestamp
+);
+define_handler_for_payload_size!(
+    ChunkEnableFrameIDHandler,
+    GenApiReg::ChunkEnableFrameID,
+    MemoryEvent::ChunkEnableFrameID
+);
+
+define_handler!(
+    ChunkEnableImageHandler,
+    GenApiReg::ChunkEnableImage,
+    MemoryEvent::ChunkEnableImage
+);
+impl ChunkEnableImageHandler {
+    /// Handle `MemoryEvent::ChunkEnableImage`.
+    ///
+    /// The image chunk can't be disabled.
+    async fn handle_events(worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
+        let mut memory = worker.memory.lock().await;
+        if Self::read(&memory, scd_kind)? == 1 {
+            Ok(())
+        } else {
+            Self::write(1, &mut memory, scd_kind)?;
+            Err(ack::ErrorAck::new(
+                ack::GenCpStatus::InvalidParameter,
+                scd_kind,
+            ))
+        }
+    }
+}
+
+/// Index of user sets written to `UserSetSelector`.
+const USER_SET_DEFAULT: u32 = 0;
+const USER_SET_1: u32 = 1;
+
+define_handler!(
+    UserSetLoadHandler,
+    GenApiReg::UserSetLoad,
+    MemoryEvent::UserSetLoad
+);
+impl UserSetLoadHandler {
+    /// Handle `MemoryEvent::UserSetLoad`.
+    ///
+    /// Copy the selected user set to the user set block, then the handlers of each register
+    /// recompute dependent values.
+    async fn handle_events(worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
+        let mut memory = worker.memory.lock().await;
+        if Self::read(&memory, scd_kind)? != 1 {
+            return Err(ack::ErrorAck::new(
+                ack::GenCpStatus::InvalidParameter,
+                scd_kind,
+            ));
+        }
+
+        let user_set = match read_memory::<GenApiReg::UserSetSelector>(&memory, scd_kind)? {
+            USER_SET_DEFAULT => read_memory::<GenApiReg::DefaultUserSet>(&memory, scd_kind)?,
+            USER_SET_1 => read_memory::<GenApiReg::UserSet1>(&memory, scd_kind)?,
+            _ => {
+                return Err(ack::ErrorAck::new(
+                    ack::GenCpStatus::InvalidParameter,
+                    scd_kind,
+                ))
+            }
+        };
+
+        memory
+            .write_raw(GenApiReg::Width::ADDRESS, &user_set)
+            .map_err(|e| {
+                log::error!("failed to load user set: {}", e);
+                ack::ErrorAck::new(ack::GenCpStatus::GenericError, scd_kind)
+            })
+    }
+}
+
+define_handler!(
+    UserSetSaveHandler,
+    GenApiReg::UserSetSave,
+    MemoryEvent::UserSetSave
+);
+impl UserSetSaveHandler {
+    /// Handle `MemoryEvent::UserSetSave`.
+    ///
+    /// Copy the user set block to the selected user set, the `Default` user set is read only.
+    async fn handle_events(worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
+        let mut memory = worker.memory.lock().await;
+        if Self::read(&memory, scd_kind)? != 1 {
+            return Err(ack::ErrorAck::new(
+                ack::GenCpStatus::InvalidParameter,
+                scd_kind,
+            ));
+        }
+
+        match read_memory::<GenApiReg::UserSetSelector>(&memory, scd_kind)? {
+            USER_SET_1 => {
+                let start = GenApiReg::Width::ADDRESS;
+                let user_set = memory
+                    .read_raw(start..start + USER_SET_LENGTH)
+                    .map(<[u8]>::to_vec)
+                    .map_err(|e| {
+                        log::error!("failed to save user set: {}", e);
+                        ack::ErrorAck::new(ack::GenCpStatus::GenericError, scd_kind)
+                    })?;
+                write_memory::<GenApiReg::UserSet1>(user_set, &mut memory, scd_kind)
+            }
+            USER_SET_DEFAULT => Err(ack::ErrorAck::new(ack::GenCpStatus::AccessDenied, scd_kind)),
+            _ => Err(ack::ErrorAck::new(
+                ack::GenCpStatus::InvalidParameter,
+                scd_kind,
+            )),
+        }
+    }
+}
+
 /// This macro defines handler for registers of SIRM which are related to streaming data size.
 ///
 /// A handler defined by this macro works as a verifier which verify the written size has correct
@@ -294,22 +544,43 @@ define_handler_for_sirm!(
 
 enum MemoryEvent {
     TimestampLatch,
+    EiControl,
+    EventTestControl,
     SiControl,
     MaximumLeaderSize,
     PayloadTransferSize,
     PayloadFinalTransferSize1,
     PayloadFinalTransferSize2,
     MaximumTrailerSize,
+    AcquisitionStart,
+    AcquisitionStop,
+    TriggerSoftware,
+    Width,
+    Height,
+    OffsetX,
+    OffsetY,
+    PixelFormat,
+    ChunkModeActive,
+    ChunkEnableImage,
+    ChunkEnableTimestamp,
+    ChunkEnableFrameID,
+    UserSetLoad,
+    UserSetSave,
 }
 
 impl MemoryEvent {
     async fn process(self, worker: &Worker, scd_kind: cmd::ScdKind) -> Result<(), ack::ErrorAck> {
         use MemoryEvent::{
-            MaximumLeaderSize, MaximumTrailerSize, PayloadFinalTransferSize1,
-            PayloadFinalTransferSize2, PayloadTransferSize, SiControl, TimestampLatch,
+            AcquisitionStart, AcquisitionStop, ChunkEnableFrameID, ChunkEnableImage,
+            ChunkEnableTimestamp, ChunkModeActive, EiControl, EventTestControl, Height,
+            MaximumLeaderSize, MaximumTrailerSize, OffsetX, OffsetY, PayloadFinalTransferSize1,
+            PayloadFinalTransferSize2, PayloadTransferSize, PixelFormat, SiControl, TimestampLatch,
+            TriggerSoftware, UserSetLoad, UserSetSave, Width,
         };
         match self {
             TimestampLatch => TimestampLatchHandler::handle_events(worker, scd_kind).await,
+            EiControl => EiControlHandler::handle_events(worker, scd_kind).await,
+            EventTestControl => EventTestControlHandler::handle_events(worker, scd_kind).await,
             SiControl => SiControlHandler::handle_events(worker, scd_kind).await,
             MaximumLeaderSize => MaximumLeaderSizeHandler::handle_events(worker, scd_kind).await,
             PayloadTransferSize => {
@@ -322,17 +593,49 @@ impl MemoryEvent {
                 PayloadFinalTransferSize2Handler::handle_events(worker, scd_kind).await
             }
             MaximumTrailerSize => MaximumTrailerSizeHandler::handle_events(worker, scd_kind).await,
+            AcquisitionStart => AcquisitionStartHandler::handle_events(worker, scd_kind).await,
+            AcquisitionStop => AcquisitionStopHandler::handle_events(worker, scd_kind).await,
+            TriggerSoftware => TriggerSoftwareHandler::handle_events(worker, scd_kind).await,
+            Width => WidthHandler::handle_events(worker, scd_kind).await,
+            Height => HeightHandler::handle_events(worker, scd_kind).await,
+            OffsetX => OffsetXHandler::handle_events(worker, scd_kind).await,
+            OffsetY => OffsetYHandler::handle_events(worker, scd_kind).await,
+            PixelFormat => PixelFormatHandler::handle_events(worker, scd_kind).await,
+            ChunkModeActive => ChunkModeActiveHandler::handle_events(worker, scd_kind).await,
+            ChunkEnableImage => ChunkEnableImageHandler::handle_events(worker, scd_kind).await,
+            ChunkEnableTimestamp => {
+                ChunkEnableTimestampHandler::handle_events(worker, scd_kind).await
+            }
+            ChunkEnableFrameID => ChunkEnableFrameIDHandler::handle_events(worker, scd_kind).await,
+            UserSetLoad => UserSetLoadHandler::handle_events(worker, scd_kind).await,
+            UserSetSave => UserSetSaveHandler::handle_events(worker, scd_kind).await,
         }
     }
 
     fn register_events(memory: &mut Memory, sender: &Sender<Self>) {
         TimestampLatchHandler::register(memory, sender);
+        EiControlHandler::register(memory, sender);
+        EventTestControlHandler::register(memory, sender);
         SiControlHandler::register(memory, sender);
         MaximumLeaderSizeHandler::register(memory, sender);
         PayloadTransferSizeHandler::register(memory, sender);
         PayloadFinalTransferSize1Handler::register(memory, sender);
         PayloadFinalTransferSize2Handler::register(memory, sender);
         MaximumTrailerSizeHandler::register(memory, sender);
+        AcquisitionStartHandler::register(memory, sender);
+        AcquisitionStopHandler::register(memory, sender);
+        TriggerSoftwareHandler::register(memory, sender);
+        WidthHandler::register(memory, sender);
+        HeightHandler::register(memory, sender);
+        OffsetXHandler::register(memory, sender);
+        OffsetYHandler::register(memory, sender);
+        PixelFormatHandler::register(memory, sender);
+        ChunkModeActiveHandler::register(memory, sender);
+        ChunkEnableImageHandler::register(memory, sender);
+        ChunkEnableTimestampHandler::register(memory, sender);
+        ChunkEnableFrameIDHandler::register(memory, sender);
+        UserSetLoadHandler::register(memory, sender);
+        UserSetSaveHandler::register(memory, sender);
     }
 }
 
@@ -2,24 +2,38 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+mod command_log;
 mod control_module;
 mod control_protocol;
 mod device;
 mod device_handle;
 mod device_pool;
 mod emulator_builder;
+mod emulator_handle;
 mod event_module;
 mod fake_protocol;
+mod fault;
 mod genapi;
+mod image;
 mod interface;
 mod memory;
 mod memory_event_handler;
 mod shared_queue;
 mod signal;
 mod stream_module;
+mod write_hook;
 
+pub use command_log::LoggedCommand;
 pub use emulator_builder::*;
+pub use emulator_handle::EmulatorHandle;
+pub use event_module::EventSender;
+pub use fault::{ControlFault, FaultInjector, StreamFault};
+pub use image::TestPattern;
+pub use write_hook::DeviceMemory;
 
-pub(super) use device_handle::*;
+pub use cameleon_impl::memory::{AccessRight, MemoryFragment};
+
+pub(crate) use device_handle::{DeviceHandle, CONTROL_IFACE, EVENT_IFACE, STREAM_IFACE};
 pub(super) use device_pool::DevicePool;
-pub(super) use fake_protocol::IfaceKind;
+
+use fake_protocol::IfaceKind;
@@ -24,6 +24,10 @@ impl<T> SharedQueue<T> {
         }
     }
 
+    pub(super) fn is_full(&self) -> bool {
+        self.inner.lock().unwrap().len() >= self.cap
+    }
+
     pub(super) fn dequeue(&self) -> Option<T> {
         self.inner.lock().unwrap().pop_back()
     }
@@ -4,7 +4,7 @@
 
 use futures::channel::oneshot;
 
-use super::IfaceKind;
+use super::{event_module::Event, IfaceKind};
 
 /// Signal sent to control module.
 pub(super) enum ControlSignal {
@@ -26,18 +26,11 @@ pub(super) enum ControlSignal {
 
 /// Signal sent to event module.
 pub(super) enum EventSignal {
-    /// Signal to send event data to tha host.
-    _EventData {
-        event_id: u16,
-        data: Vec<u8>,
-        request_id: u16,
-    },
-
-    /// Signal to update timestamp
-    UpdateTimestamp(u64),
+    /// Signal to send events to the host.
+    Events(Vec<Event>),
 
     /// signal to enable event module.
-    _Enable,
+    Enable,
 
     /// signal to disable event module.
     Disable(oneshot::Sender<()>),
@@ -54,6 +47,15 @@ pub(super) enum StreamSignal {
     /// Signal to disable stream module.
     Disable(oneshot::Sender<()>),
 
+    /// Signal to start generating frames.
+    StartAcquisition,
+
+    /// Signal to stop generating frames.
+    StopAcquisition,
+
+    /// Signal to generate a frame when trigger mode is on.
+    TriggerSoftware,
+
     /// Signal to shutdown.
     Shutdown,
 }
@@ -2,63 +2,944 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use async_std::{
     channel::{Receiver, Sender},
     prelude::*,
+    sync::Mutex,
+    task,
 };
+use futures::{select, FutureExt};
+
+use cameleon_impl::memory::prelude::*;
 
 use super::{
     device::Timestamp,
+    event_module::Event,
+    fault::{FaultInjector, StreamFault},
+    genapi::{self, GenApiReg},
+    image::ImageSettings,
+    memory::{Memory, SIRM},
     shared_queue::SharedQueue,
-    signal::{InterfaceSignal, StreamSignal},
+    signal::{EventSignal, InterfaceSignal, StreamSignal},
+    IfaceKind,
 };
 
-// TODO: Implement stream module.
+/// Interval to retry enqueueing packets when the queue is full.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
 pub(super) struct StreamModule {
-    _queue: SharedQueue<Vec<u8>>,
-    _timestamp: Timestamp,
+    memory: Arc<Mutex<Memory>>,
+    queue: SharedQueue<Vec<u8>>,
+    timestamp: Timestamp,
+    faults: FaultInjector,
 
     enabled: bool,
+    transfer_sizes: TransferSizes,
+    acquisition: Option<Acquisition>,
+
+    /// Packets of the current frame which are not enqueued yet.
+    pending: VecDeque<Vec<u8>>,
+    block_id: u64,
 }
 
 impl StreamModule {
-    pub(super) fn new(timestamp: Timestamp, queue: SharedQueue<Vec<u8>>) -> Self {
+    pub(super) fn new(
+        memory: Arc<Mutex<Memory>>,
+        timestamp: Timestamp,
+        faults: FaultInjector,
+        queue: SharedQueue<Vec<u8>>,
+    ) -> Self {
         Self {
-            _timestamp: timestamp,
-            _queue: queue,
+            memory,
+            queue,
+            timestamp,
+            faults,
             enabled: false,
+            transfer_sizes: TransferSizes::default(),
+            acquisition: None,
+            pending: VecDeque::new(),
+            block_id: 0,
         }
     }
 
+    // FIXME: Remove this when <https://github.com/rust-lang/rust-clippy/issues/6922> is fixed.
+    #[allow(clippy::mut_mut)]
     pub(super) async fn run(
         mut self,
-        _signal_tx: Sender<InterfaceSignal>,
-        mut signal_rx: Receiver<StreamSignal>,
+        signal_tx: Sender<InterfaceSignal>,
+        signal_rx: Receiver<StreamSignal>,
     ) {
-        while let Some(signal) = signal_rx.next().await {
-            match signal {
-                StreamSignal::Enable => {
-                    if self.enabled {
-                        log::warn! {"receive stream enable signal, but stream module is already enabled"}
-                    } else {
-                        self.enabled = true;
-                        log::info! {"stream module is enabled"};
+        let mut signal_rx = signal_rx.fuse();
+
+        loop {
+            self.flush_pending();
+
+            let signal = match self.next_wake_up() {
+                Some(wait) => {
+                    select! {
+                        signal = signal_rx.next().fuse() => signal,
+                        _ = task::sleep(wait).fuse() => {
+                            self.on_timer(&signal_tx).await;
+                            continue;
+                        }
                     }
                 }
+                None => signal_rx.next().await,
+            };
 
-                StreamSignal::Disable(_completed) => {
-                    if self.enabled {
-                        self.enabled = false;
-                        log::info! {"stream module is disabled"};
-                    } else {
-                        log::warn! {"receive stream disable signal, but stream module is already disabled"}
+            match signal {
+                Some(StreamSignal::Shutdown) | None => break,
+                Some(signal) => self.handle_signal(signal, &signal_tx).await,
+            }
+        }
+    }
+
+    async fn handle_signal(&mut self, signal: StreamSignal, signal_tx: &Sender<InterfaceSignal>) {
+        match signal {
+            StreamSignal::Enable => {
+                if self.enabled {
+                    log::warn! {"receive stream enable signal, but stream module is already enabled"}
+                } else {
+                    self.transfer_sizes = TransferSizes::read(&*self.memory.lock().await);
+                    self.block_id = 0;
+                    self.enabled = true;
+                    log::info! {"stream module is enabled"};
+                }
+            }
+
+            StreamSignal::Disable(_completed) => {
+                if self.enabled {
+                    self.enabled = false;
+                    self.pending.clear();
+                    self.queue.clear();
+                    log::info! {"stream module is disabled"};
+                } else {
+                    log::warn! {"receive stream disable signal, but stream module is already disabled"}
+                }
+            }
+
+            StreamSignal::StartAcquisition => {
+                if self.acquisition.is_some() {
+                    log::warn! {"receive acquisition start signal, but acquisition is already started"}
+                } else {
+                    self.acquisition = Acquisition::read(&*self.memory.lock().await);
+                    if self.acquisition.is_some() {
+                        log::info! {"acquisition is started"};
                     }
                 }
+            }
+
+            StreamSignal::StopAcquisition => {
+                if self.acquisition.take().is_some() {
+                    log::info! {"acquisition is stopped"};
+                } else {
+                    log::warn! {"receive acquisition stop signal, but acquisition is not started"}
+                }
+            }
 
-                StreamSignal::Shutdown => {
-                    break;
+            StreamSignal::TriggerSoftware => match &self.acquisition {
+                Some(acquisition) if self.enabled && acquisition.triggered => {
+                    self.emit_frame(signal_tx).await;
                 }
+                _ => {
+                    log::warn! {"receive software trigger, but the device is not waiting for a trigger"}
+                }
+            },
+
+            StreamSignal::Shutdown => unreachable!(),
+        }
+    }
+
+    /// Returns the duration until the module needs to wake up without signals.
+    fn next_wake_up(&self) -> Option<Duration> {
+        if !self.pending.is_empty() {
+            return Some(QUEUE_POLL_INTERVAL);
+        }
+
+        match &self.acquisition {
+            Some(acquisition) if self.enabled && !acquisition.triggered => Some(
+                acquisition
+                    .next_frame
+                    .saturating_duration_since(Instant::now()),
+            ),
+            _ => None,
+        }
+    }
+
+    async fn on_timer(&mut self, signal_tx: &Sender<InterfaceSignal>) {
+        let acquisition = match &mut self.acquisition {
+            Some(acquisition) if self.enabled && !acquisition.triggered => acquisition,
+            _ => return,
+        };
+
+        let now = Instant::now();
+        if now < acquisition.next_frame {
+            return;
+        }
+        acquisition.next_frame += acquisition.frame_period;
+        if acquisition.next_frame < now {
+            acquisition.next_frame = now + acquisition.frame_period;
+        }
+
+        self.emit_frame(signal_tx).await;
+    }
+
+    /// Generate the next frame of the acquisition, and stop the acquisition if the number of
+    /// frames reaches the limit of the acquisition mode.
+    async fn emit_frame(&mut self, signal_tx: &Sender<InterfaceSignal>) {
+        let acquisition = match &mut self.acquisition {
+            Some(acquisition) => acquisition,
+            None => return,
+        };
+
+        let frame_count = acquisition.frame_count;
+        acquisition.frame_count += 1;
+        let settings = acquisition.settings;
+        let chunks = acquisition.chunks;
+        if matches!(acquisition.frame_limit, Some(limit) if acquisition.frame_count >= limit) {
+            self.acquisition = None;
+            log::info! {"acquisition is stopped because all frames are acquired"};
+        }
+
+        if !self.pending.is_empty() {
+            log::warn!("the host doesn't read the previous frame in time, the frame is dropped");
+            return;
+        }
+
+        let fault = self.faults.next_stream();
+        if let Some(fault) = fault {
+            log::info!("inject {:?} into the frame", fault);
+        }
+        if fault == Some(StreamFault::Stall) {
+            if signal_tx
+                .try_send(InterfaceSignal::Halt(IfaceKind::Stream))
+                .is_err()
+            {
+                log::error!("Stream module -> Interface channel is full");
+            }
+            return;
+        }
+
+        let timestamp = self.timestamp.as_nanos().await;
+        self.send_frame_events(self.block_id, signal_tx).await;
+        self.generate_frame(&settings, chunks.as_ref(), fault, frame_count, timestamp);
+    }
+
+    /// Send `FrameStart` and `ExposureEnd` events of the frame if their notifications are `On`.
+    async fn send_frame_events(&self, frame_id: u64, signal_tx: &Sender<InterfaceSignal>) {
+        let (frame_start, exposure_end) = {
+            let memory = self.memory.lock().await;
+            (
+                matches!(
+                    memory.read::<GenApiReg::EventNotificationFrameStart>(),
+                    Ok(1)
+                ),
+                matches!(
+                    memory.read::<GenApiReg::EventNotificationExposureEnd>(),
+                    Ok(1)
+                ),
+            )
+        };
+
+        let mut events = vec![];
+        if frame_start {
+            events.push(Event::new(
+                genapi::EVENT_ID_FRAME_START,
+                frame_id.to_le_bytes(),
+            ));
+        }
+        if exposure_end {
+            events.push(Event::new(
+                genapi::EVENT_ID_EXPOSURE_END,
+                frame_id.to_le_bytes(),
+            ));
+        }
+        if events.is_empty() {
+            return;
+        }
+
+        if signal_tx
+            .try_send(EventSignal::Events(events).into())
+            .is_err()
+        {
+            log::error!("Stream module -> Interface channel is full");
+        }
+    }
+
+    /// Split a frame into leader, payload, and trailer packets according to SIRM, then push them
+    /// to the pending packets.
+    ///
+    /// `fault` is applied to the packets if specified, [`StreamFault::Stall`] is ignored here.
+    fn generate_frame(
+        &mut self,
+        settings: &ImageSettings,
+        chunks: Option<&ChunkSettings>,
+        fault: Option<StreamFault>,
+        frame_count: u64,
+        timestamp: u64,
+    ) {
+        let block_id = self.block_id;
+        self.block_id += 1;
+
+        let mut image = settings.render(frame_count);
+        if let Some(chunks) = chunks {
+            chunks.append_chunks(&mut image, block_id, timestamp);
+        }
+        self.pending
+            .push_back(packet::leader(block_id, timestamp, settings, chunks));
+
+        let sizes = &self.transfer_sizes;
+        let transfers = (0..sizes.payload_transfer_count)
+            .map(|_| sizes.payload_transfer_size)
+            .chain([
+                sizes.payload_final_transfer1_size,
+                sizes.payload_final_transfer2_size,
+            ]);
+        let mut rest = image.as_slice();
+        let mut is_first = true;
+        for size in transfers {
+            if rest.is_empty() {
+                break;
+            }
+            let (chunk, next) = rest.split_at(size.min(rest.len()));
+            rest = next;
+            if chunk.is_empty() {
+                continue;
+            }
+            match fault {
+                Some(StreamFault::DropTransfer) if is_first => {}
+                Some(StreamFault::TruncateTransfer(len)) if is_first => {
+                    self.pending
+                        .push_back(chunk[..len.min(chunk.len())].to_vec());
+                }
+                _ => self.pending.push_back(chunk.to_vec()),
+            }
+            is_first = false;
+        }
+
+        let status = if fault == Some(StreamFault::DataOverrun) {
+            packet::STATUS_DATA_OVERRUN
+        } else if rest.is_empty() {
+            packet::STATUS_SUCCESS
+        } else {
+            log::warn!(
+                "payload transfer sizes specified in SIRM are too small, some data are discarded"
+            );
+            packet::STATUS_DATA_DISCARDED
+        };
+        let valid_payload_size = (image.len() - rest.len()) as u64;
+        self.pending.push_back(packet::trailer(
+            block_id,
+            status,
+            valid_payload_size,
+            settings.height,
+            chunks,
+        ));
+    }
+
+    fn flush_pending(&mut self) {
+        while !self.pending.is_empty() && !self.queue.is_full() {
+            let packet = self.pending.pop_front().unwrap();
+            self.queue.enqueue(packet);
+        }
+    }
+}
+
+/// Transfer sizes written to SIRM by the host.
+#[derive(Debug, Clone, Copy, Default)]
+struct TransferSizes {
+    payload_transfer_size: usize,
+    payload_transfer_count: usize,
+    payload_final_transfer1_size: usize,
+    payload_final_transfer2_size: usize,
+}
+
+impl TransferSizes {
+    fn read(memory: &Memory) -> Self {
+        use SIRM::{
+            PayloadFinalTransferSize1, PayloadFinalTransferSize2, PayloadTransferCount,
+            PayloadTransferSize,
+        };
+
+        // SIRM is verified before the stream module is enabled.
+        Self {
+            payload_transfer_size: memory.read::<PayloadTransferSize>().unwrap() as usize,
+            payload_transfer_count: memory.read::<PayloadTransferCount>().unwrap() as usize,
+            payload_final_transfer1_size: memory.read::<PayloadFinalTransferSize1>().unwrap()
+                as usize,
+            payload_final_transfer2_size: memory.read::<PayloadFinalTransferSize2>().unwrap()
+                as usize,
+        }
+    }
+}
+
+/// Chunks appended to the image when `ChunkModeActive` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ChunkSettings {
+    timestamp: bool,
+    frame_id: bool,
+}
+
+impl ChunkSettings {
+    /// Size of a chunk ID and a chunk length field.
+    const CHUNK_TAG_SIZE: u64 = 8;
+    /// Size of timestamp and frame ID chunk data.
+    const CHUNK_DATA_SIZE: u64 = 8;
+
+    /// Read chunk settings from the memory, returns `None` if chunk mode is not active.
+    pub(super) fn read(memory: &Memory) -> Option<Self> {
+        if memory.read::<GenApiReg::ChunkModeActive>().ok()? == 0 {
+            return None;
+        }
+
+        Some(Self {
+            timestamp: memory.read::<GenApiReg::ChunkEnableTimestamp>().ok()? != 0,
+            frame_id: memory.read::<GenApiReg::ChunkEnableFrameID>().ok()? != 0,
+        })
+    }
+
+    /// Size of chunks which are appended to the image data.
+    fn chunks_size(self) -> u64 {
+        let data_chunks = u64::from(self.timestamp) + u64::from(self.frame_id);
+        Self::CHUNK_TAG_SIZE + data_chunks * (Self::CHUNK_DATA_SIZE + Self::CHUNK_TAG_SIZE)
+    }
+
+    /// ID which changes when the layout of chunks is changed.
+    fn layout_id(self) -> u32 {
+        1 + u32::from(self.timestamp) + (u32::from(self.frame_id) << 1)
+    }
+
+    /// Convert the image data into chunk data and append enabled chunks.
+    ///
+    /// Each chunk consists of `[data][chunk ID][data length]`, chunk ID and data length are
+    /// big-endian so that the host can parse chunks from the end of the payload.
+    fn append_chunks(self, payload: &mut Vec<u8>, block_id: u64, timestamp: u64) {
+        fn append_tag(payload: &mut Vec<u8>, id: u32, len: usize) {
+            payload.extend_from_slice(&id.to_be_bytes());
+            payload.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+
+        let image_len = payload.len();
+        append_tag(payload, genapi::CHUNK_ID_IMAGE, image_len);
+        if self.timestamp {
+            payload.extend_from_slice(&timestamp.to_le_bytes());
+            append_tag(payload, genapi::CHUNK_ID_TIMESTAMP, 8);
+        }
+        if self.frame_id {
+            payload.extend_from_slice(&block_id.to_le_bytes());
+            append_tag(payload, genapi::CHUNK_ID_FRAME_ID, 8);
+        }
+    }
+}
+
+/// Returns the size of a payload with the current settings, returns `None` if the settings are
+/// invalid.
+pub(super) fn payload_size(memory: &Memory) -> Option<u64> {
+    let image_size = ImageSettings::read(memory)?.image_size();
+    let chunks_size = ChunkSettings::read(memory).map_or(0, ChunkSettings::chunks_size);
+    Some(image_size + chunks_size)
+}
+
+/// Update `PayloadSize` and `SIRM::RequiredPayloadSize` with the current settings, returns
+/// `false` if the settings are invalid.
+pub(super) fn update_payload_size(memory: &mut Memory) -> bool {
+    match payload_size(memory) {
+        Some(size) => {
+            memory.write::<GenApiReg::PayloadSize>(size).unwrap();
+            memory.write::<SIRM::RequiredPayloadSize>(size).unwrap();
+            true
+        }
+        None => false,
+    }
+}
+
+struct Acquisition {
+    settings: ImageSettings,
+    chunks: Option<ChunkSettings>,
+    frame_period: Duration,
+    next_frame: Instant,
+    frame_count: u64,
+    /// The acquisition stops automatically when `frame_count` reaches the limit.
+    frame_limit: Option<u64>,
+    /// Frames are generated only by software triggers if `true`.
+    triggered: bool,
+}
+
+impl Acquisition {
+    /// Read acquisition settings from the memory, returns `None` if the settings are invalid.
+    fn read(memory: &Memory) -> Option<Self> {
+        let settings = ImageSettings::read(memory)?;
+        let chunks = ChunkSettings::read(memory);
+        let mode = memory.read::<GenApiReg::AcquisitionMode>().ok()?;
+        let frame_count = memory.read::<GenApiReg::AcquisitionFrameCount>().ok()?;
+        let trigger_mode = memory.read::<GenApiReg::TriggerMode>().ok()?;
+        let exposure_time = memory.read::<GenApiReg::ExposureTime>().ok()?;
+        let frame_rate = memory.read::<GenApiReg::AcquisitionFrameRate>().ok()?;
+
+        let frame_limit = match mode {
+            0 => None,
+            1 => Some(1),
+            2 if frame_count > 0 => Some(u64::from(frame_count)),
+            _ => {
+                log::error!(
+                    "acquisition mode {} with frame count {} is invalid",
+                    mode,
+                    frame_count
+                );
+                return None;
             }
+        };
+        if !(frame_rate.is_finite() && frame_rate > 0.0) {
+            log::error!("frame rate {} is invalid", frame_rate);
+            return None;
+        }
+        if !(exposure_time.is_finite() && exposure_time >= 0.0) {
+            log::error!("exposure time {} is invalid", exposure_time);
+            return None;
         }
+
+        // The frame period can't be shorter than the exposure time.
+        let mut frame_period = frame_rate.recip().max(exposure_time / 1_000_000.0);
+
+        // The frame period is extended so that the throughput doesn't exceed the limit.
+        if memory
+            .read::<GenApiReg::DeviceLinkThroughputLimitMode>()
+            .ok()?
+            != 0
+        {
+            let limit = memory.read::<GenApiReg::DeviceLinkThroughputLimit>().ok()?;
+            let payload_size = memory.read::<GenApiReg::PayloadSize>().ok()?;
+            if limit == 0 {
+                log::error!("throughput limit must not be zero");
+                return None;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let min_period = payload_size as f64 / f64::from(limit);
+            frame_period = frame_period.max(min_period);
+        }
+
+        Some(Self {
+            settings,
+            chunks,
+            frame_period: Duration::from_secs_f64(frame_period),
+            next_frame: Instant::now(),
+            frame_count: 0,
+            frame_limit,
+            triggered: trigger_mode != 0,
+        })
+    }
+}
+
+mod packet {
+    use cameleon_impl::bytes_io::WriteBytes;
+
+    use super::{ChunkSettings, ImageSettings};
+
+    const LEADER_MAGIC: u32 = 0x4C56_3355;
+    const TRAILER_MAGIC: u32 = 0x5456_3355;
+    const PAYLOAD_TYPE_IMAGE: u16 = 0x0001;
+    const PAYLOAD_TYPE_IMAGE_EXTENDED_CHUNK: u16 = 0x4001;
+    /// Size of the image leader, the image extended chunk leader has the same size.
+    const LEADER_SIZE: u16 = 52;
+    /// Size of the image trailer.
+    const TRAILER_SIZE: u16 = 32;
+    /// Size of the image extended chunk trailer.
+    const CHUNK_TRAILER_SIZE: u16 = 36;
+
+    pub(super) const STATUS_SUCCESS: u16 = 0x0000;
+    pub(super) const STATUS_DATA_DISCARDED: u16 = 0xA100;
+    pub(super) const STATUS_DATA_OVERRUN: u16 = 0xA101;
+
+    pub(super) fn leader(
+        block_id: u64,
+        timestamp: u64,
+        settings: &ImageSettings,
+        chunks: Option<&ChunkSettings>,
+    ) -> Vec<u8> {
+        let payload_type = if chunks.is_some() {
+            PAYLOAD_TYPE_IMAGE_EXTENDED_CHUNK
+        } else {
+            PAYLOAD_TYPE_IMAGE
+        };
+        let mut buf = Vec::with_capacity(LEADER_SIZE as usize);
+        // Writing to `Vec` never fails.
+        (|| -> std::io::Result<()> {
+            buf.write_bytes_le(LEADER_MAGIC)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(LEADER_SIZE)?;
+            buf.write_bytes_le(block_id)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(payload_type)?;
+            // Image leader specific part.
+            buf.write_bytes_le(timestamp)?;
+            buf.write_bytes_le(u32::from(settings.pixel_format))?;
+            buf.write_bytes_le(settings.width)?;
+            buf.write_bytes_le(settings.height)?;
+            buf.write_bytes_le(settings.offset_x)?;
+            buf.write_bytes_le(settings.offset_y)?;
+            // Padding x.
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(0_u16)?;
+            Ok(())
+        })()
+        .unwrap();
+        buf
+    }
+
+    pub(super) fn trailer(
+        block_id: u64,
+        status: u16,
+        valid_payload_size: u64,
+        actual_height: u32,
+        chunks: Option<&ChunkSettings>,
+    ) -> Vec<u8> {
+        let trailer_size = if chunks.is_some() {
+            CHUNK_TRAILER_SIZE
+        } else {
+            TRAILER_SIZE
+        };
+        let mut buf = Vec::with_capacity(trailer_size as usize);
+        // Writing to `Vec` never fails.
+        (|| -> std::io::Result<()> {
+            buf.write_bytes_le(TRAILER_MAGIC)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(trailer_size)?;
+            buf.write_bytes_le(block_id)?;
+            buf.write_bytes_le(status)?;
+            buf.write_bytes_le(0_u16)?;
+            buf.write_bytes_le(valid_payload_size)?;
+            // Image trailer specific part.
+            buf.write_bytes_le(actual_height)?;
+            if let Some(chunks) = chunks {
+                buf.write_bytes_le(chunks.layout_id())?;
+            }
+            Ok(())
+        })()
+        .unwrap();
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        u3v::protocol::stream::{
+            ImageExtendedChunkTrailer, ImageLeader, ImageTrailer, Leader, PayloadStatus,
+            PayloadType, Trailer,
+        },
+        PixelFormat,
+    };
+
+    use async_std::channel;
+
+    use super::super::image::TestPattern;
+
+    fn module(transfer_sizes: TransferSizes) -> StreamModule {
+        let mut module = StreamModule::new(
+            Arc::new(Mutex::new(Memory::new())),
+            Timestamp::new(),
+            FaultInjector::default(),
+            SharedQueue::new(32),
+        );
+        module.transfer_sizes = transfer_sizes;
+        module
+    }
+
+    fn settings() -> ImageSettings {
+        ImageSettings {
+            width: 16,
+            height: 8,
+            offset_x: 0,
+            offset_y: 0,
+            pixel_format: PixelFormat::Mono16,
+            pattern: TestPattern::Counter,
+        }
+    }
+
+    #[test]
+    fn test_generate_frame() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 64,
+            payload_transfer_count: 3,
+            payload_final_transfer1_size: 48,
+            payload_final_transfer2_size: 16,
+        });
+        let settings = settings();
+        module.generate_frame(&settings, None, None, 0, 100);
+
+        let packets: Vec<_> = module.pending.drain(..).collect();
+        let lens: Vec<_> = packets.iter().map(Vec::len).collect();
+        assert_eq!(lens, vec![52, 64, 64, 64, 48, 16, 32]);
+
+        let leader = Leader::parse(&packets[0]).unwrap();
+        assert_eq!(leader.block_id(), 0);
+        let image_leader: ImageLeader = leader.specific_leader_as().unwrap();
+        assert_eq!(image_leader.pixel_format(), PixelFormat::Mono16);
+        assert_eq!(image_leader.width(), 16);
+        assert_eq!(image_leader.height(), 8);
+        assert_eq!(image_leader.timestamp(), Duration::from_nanos(100));
+
+        let payload: Vec<u8> = packets[1..6].concat();
+        assert_eq!(payload, settings.render(0));
+
+        let trailer = Trailer::parse(&packets[6]).unwrap();
+        assert_eq!(trailer.block_id(), 0);
+        assert_eq!(trailer.payload_status(), PayloadStatus::Success);
+        assert_eq!(trailer.valid_payload_size(), 256);
+        let image_trailer: ImageTrailer = trailer.specific_trailer_as().unwrap();
+        assert_eq!(image_trailer.actual_height(), 8);
+
+        // Block ID is incremented.
+        module.generate_frame(&settings, None, None, 1, 200);
+        assert_eq!(Leader::parse(&module.pending[0]).unwrap().block_id(), 1);
+    }
+
+    #[test]
+    fn test_generate_frame_with_small_transfer_size() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 64,
+            payload_transfer_count: 2,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+        module.generate_frame(&settings(), None, None, 0, 0);
+
+        let trailer = module.pending.pop_back().unwrap();
+        let trailer = Trailer::parse(&trailer).unwrap();
+        assert_eq!(trailer.payload_status(), PayloadStatus::DataDiscarded);
+        assert_eq!(trailer.valid_payload_size(), 128);
+    }
+
+    #[test]
+    fn test_generate_frame_with_chunks() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 1024,
+            payload_transfer_count: 1,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+        let chunks = ChunkSettings {
+            timestamp: true,
+            frame_id: false,
+        };
+        module.generate_frame(&settings(), Some(&chunks), None, 0, 100);
+
+        let packets: Vec<_> = module.pending.drain(..).collect();
+        let leader = Leader::parse(&packets[0]).unwrap();
+        assert_eq!(leader.payload_type(), PayloadType::ImageExtendedChunk);
+
+        // Image data, image chunk tag, timestamp data, and timestamp chunk tag.
+        let payload = &packets[1];
+        assert_eq!(payload.len() as u64, 256 + chunks.chunks_size());
+        assert_eq!(&payload[256..260], &genapi::CHUNK_ID_IMAGE.to_be_bytes());
+        assert_eq!(&payload[260..264], &256_u32.to_be_bytes());
+        assert_eq!(&payload[264..272], &100_u64.to_le_bytes());
+        assert_eq!(
+            &payload[272..276],
+            &genapi::CHUNK_ID_TIMESTAMP.to_be_bytes()
+        );
+
+        let trailer = Trailer::parse(&packets[2]).unwrap();
+        let chunk_trailer: ImageExtendedChunkTrailer = trailer.specific_trailer_as().unwrap();
+        assert_eq!(chunk_trailer.actual_height(), 8);
+        assert_eq!(chunk_trailer.chunk_layout_id(), chunks.layout_id());
+    }
+
+    #[test]
+    fn test_generate_frame_with_faults() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 64,
+            payload_transfer_count: 4,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+
+        module.generate_frame(&settings(), None, Some(StreamFault::DropTransfer), 0, 0);
+        let lens: Vec<_> = module.pending.drain(..).map(|p| p.len()).collect();
+        assert_eq!(lens, vec![52, 64, 64, 64, 32]);
+
+        module.generate_frame(
+            &settings(),
+            None,
+            Some(StreamFault::TruncateTransfer(10)),
+            0,
+            0,
+        );
+        let lens: Vec<_> = module.pending.drain(..).map(|p| p.len()).collect();
+        assert_eq!(lens, vec![52, 10, 64, 64, 64, 32]);
+
+        module.generate_frame(&settings(), None, Some(StreamFault::DataOverrun), 0, 0);
+        let trailer = module.pending.pop_back().unwrap();
+        let trailer = Trailer::parse(&trailer).unwrap();
+        assert_eq!(trailer.payload_status(), PayloadStatus::DataOverrun);
+    }
+
+    #[test]
+    fn test_stall() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 640 * 480,
+            payload_transfer_count: 1,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+        module.enabled = true;
+        module.faults.inject_stream(StreamFault::Stall);
+
+        let (signal_tx, signal_rx) = channel::unbounded();
+        task::block_on(async {
+            module
+                .handle_signal(StreamSignal::StartAcquisition, &signal_tx)
+                .await;
+            module.on_timer(&signal_tx).await;
+        });
+        assert!(module.pending.is_empty());
+        assert!(matches!(
+            signal_rx.try_recv(),
+            Ok(InterfaceSignal::Halt(IfaceKind::Stream))
+        ));
+    }
+
+    #[test]
+    fn test_frame_events() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 640 * 480,
+            payload_transfer_count: 1,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+        module.enabled = true;
+        {
+            let mut memory = module.memory.try_lock().unwrap();
+            memory
+                .write::<GenApiReg::EventNotificationFrameStart>(1)
+                .unwrap();
+            memory
+                .write::<GenApiReg::EventNotificationExposureEnd>(1)
+                .unwrap();
+        }
+
+        let (signal_tx, signal_rx) = channel::unbounded();
+        task::block_on(async {
+            module
+                .handle_signal(StreamSignal::StartAcquisition, &signal_tx)
+                .await;
+            module.on_timer(&signal_tx).await;
+        });
+
+        let events = match signal_rx.try_recv() {
+            Ok(InterfaceSignal::ToEvent(EventSignal::Events(events))) => events,
+            _ => panic!("events must be sent"),
+        };
+        assert_eq!(
+            events,
+            vec![
+                Event::new(genapi::EVENT_ID_FRAME_START, 0_u64.to_le_bytes()),
+                Event::new(genapi::EVENT_ID_EXPOSURE_END, 0_u64.to_le_bytes()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_payload_size() {
+        let mut memory = Memory::new();
+        assert!(update_payload_size(&mut memory));
+        assert_eq!(memory.read::<GenApiReg::PayloadSize>().unwrap(), 640 * 480);
+
+        memory.write::<GenApiReg::Width>(320).unwrap();
+        memory
+            .write::<GenApiReg::PixelFormat>(u32::from(PixelFormat::Mono16))
+            .unwrap();
+        memory.write::<GenApiReg::ChunkModeActive>(1).unwrap();
+        memory.write::<GenApiReg::ChunkEnableFrameID>(1).unwrap();
+        assert!(update_payload_size(&mut memory));
+        let expected = 320 * 480 * 2 + 8 + 16;
+        assert_eq!(memory.read::<GenApiReg::PayloadSize>().unwrap(), expected);
+        assert_eq!(
+            memory.read::<SIRM::RequiredPayloadSize>().unwrap(),
+            expected
+        );
+
+        // Invalid settings don't change payload size.
+        memory.write::<GenApiReg::Width>(1).unwrap();
+        assert!(!update_payload_size(&mut memory));
+        assert_eq!(memory.read::<GenApiReg::PayloadSize>().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_acquisition_settings() {
+        let mut memory = Memory::new();
+        let acquisition = Acquisition::read(&memory).unwrap();
+        assert_eq!(acquisition.settings.pixel_format, PixelFormat::Mono8);
+        assert_eq!(acquisition.frame_limit, None);
+        assert!(!acquisition.triggered);
+        assert!(acquisition.chunks.is_none());
+
+        // The frame period is extended by the exposure time.
+        memory.write::<GenApiReg::ExposureTime>(100_000.0).unwrap();
+        memory.write::<GenApiReg::AcquisitionMode>(2).unwrap();
+        memory.write::<GenApiReg::AcquisitionFrameCount>(5).unwrap();
+        memory.write::<GenApiReg::TriggerMode>(1).unwrap();
+        let acquisition = Acquisition::read(&memory).unwrap();
+        assert_eq!(acquisition.frame_period, Duration::from_millis(100));
+        assert_eq!(acquisition.frame_limit, Some(5));
+        assert!(acquisition.triggered);
+
+        memory.write::<GenApiReg::AcquisitionMode>(3).unwrap();
+        assert!(Acquisition::read(&memory).is_none());
+    }
+
+    #[test]
+    fn test_throughput_limit() {
+        let mut memory = Memory::new();
+        memory
+            .write::<GenApiReg::AcquisitionFrameRate>(100.0)
+            .unwrap();
+        memory
+            .write::<GenApiReg::DeviceLinkThroughputLimit>(640 * 480 * 20)
+            .unwrap();
+        let acquisition = Acquisition::read(&memory).unwrap();
+        assert_eq!(acquisition.frame_period, Duration::from_millis(10));
+
+        // 640x480 Mono8 images are limited to 20 frames per second.
+        memory
+            .write::<GenApiReg::DeviceLinkThroughputLimitMode>(1)
+            .unwrap();
+        let acquisition = Acquisition::read(&memory).unwrap();
+        assert_eq!(acquisition.frame_period, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_frame_limit() {
+        let mut module = module(TransferSizes {
+            payload_transfer_size: 640 * 480,
+            payload_transfer_count: 1,
+            payload_final_transfer1_size: 0,
+            payload_final_transfer2_size: 0,
+        });
+        module.enabled = true;
+        {
+            let mut memory = module.memory.try_lock().unwrap();
+            memory.write::<GenApiReg::AcquisitionMode>(1).unwrap();
+        }
+
+        task::block_on(async {
+            let (signal_tx, _signal_rx) = channel::unbounded();
+            module
+                .handle_signal(StreamSignal::StartAcquisition, &signal_tx)
+                .await;
+            module.on_timer(&signal_tx).await;
+        });
+        // Leader, payload, and trailer.
+        assert_eq!(module.pending.len(), 3);
+        // Single frame acquisition stops after the first frame.
+        assert!(module.acquisition.is_none());
     }
 }
@@ -0,0 +1,201 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use cameleon_impl::memory::{prelude::*, MemoryObserver, MemoryResult};
+
+use crate::u3v::protocol::ack::GenCpStatus;
+
+use super::memory::Memory;
+
+/// The memory of an emulated device accessed from write hooks.
+///
+/// Unlike requests from the host, access rights of registers are not checked.
+pub struct DeviceMemory<'a> {
+    memory: &'a mut Memory,
+}
+
+impl<'a> DeviceMemory<'a> {
+    pub(super) fn new(memory: &'a mut Memory) -> Self {
+        Self { memory }
+    }
+
+    /// Read the value of the register.
+    pub fn read<R: Register>(&self) -> MemoryResult<R::Ty> {
+        self.memory.peek(R::range())?;
+        self.memory.read::<R>()
+    }
+
+    /// Write `data` to the register.
+    pub fn write<R: Register>(&mut self, data: R::Ty) -> MemoryResult<()> {
+        self.memory.peek(R::range())?;
+        self.memory.write::<R>(data)
+    }
+
+    /// Read the raw bytes of the memory.
+    pub fn read_raw(&self, range: Range<usize>) -> MemoryResult<&[u8]> {
+        self.memory.peek(range)
+    }
+
+    /// Write `data` to the memory starting from `address`.
+    pub fn write_raw(&mut self, address: usize, data: &[u8]) -> MemoryResult<()> {
+        self.memory.poke(address, data)?;
+        self.memory.notify(address..address + data.len());
+        Ok(())
+    }
+}
+
+type Hook = Box<dyn FnMut(&mut DeviceMemory<'_>) -> Result<(), GenCpStatus> + Send>;
+
+struct WriteHook {
+    written: Arc<AtomicBool>,
+    hook: Hook,
+}
+
+/// Hooks which are run when the host writes to registers.
+#[derive(Clone, Default)]
+pub(super) struct WriteHooks {
+    inner: Arc<Mutex<Vec<WriteHook>>>,
+}
+
+impl WriteHooks {
+    /// Register `hook` which is run when the register `R` is written.
+    pub(super) fn register<R, F>(&self, memory: &mut Memory, hook: F)
+    where
+        R: Register,
+        F: FnMut(&mut DeviceMemory<'_>) -> Result<(), GenCpStatus> + Send + 'static,
+    {
+        let written = Arc::new(AtomicBool::new(false));
+        memory.register_observer::<R, _>(WrittenObserver(written.clone()));
+        self.with(|hooks| {
+            hooks.push(WriteHook {
+                written,
+                hook: Box::new(hook),
+            });
+        });
+    }
+
+    /// Forget all writes which happened before.
+    pub(super) fn reset(&self) {
+        self.with(|hooks| {
+            for hook in hooks {
+                hook.written.store(false, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Run hooks of registers written since the last call, returns the first error returned from
+    /// hooks.
+    pub(super) fn run(&self, memory: &mut Memory) -> Result<(), GenCpStatus> {
+        self.with(|hooks| {
+            let mut result = Ok(());
+            for hook in hooks {
+                if hook.written.load(Ordering::Relaxed) {
+                    let res = (hook.hook)(&mut DeviceMemory::new(memory));
+                    // Writes from the hook itself don't trigger the hook again.
+                    hook.written.store(false, Ordering::Relaxed);
+                    result = result.and(res);
+                }
+            }
+            result
+        })
+    }
+
+    fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Vec<WriteHook>) -> R,
+    {
+        f(&mut crate::lock(&self.inner))
+    }
+}
+
+struct WrittenObserver(Arc<AtomicBool>);
+
+impl MemoryObserver for WrittenObserver {
+    fn update(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cameleon_impl::memory::{register_map, AccessRight, MemoryFragment};
+
+    use super::*;
+
+    #[register_map(base = 0x10_0000, endianness = LE)]
+    enum VendorReg {
+        #[register(len = 4, access = RW, ty = u32)]
+        Gain = 0,
+
+        #[register(len = 4, access = RO, ty = u32)]
+        GainRaw = 0,
+    }
+
+    fn memory() -> Memory {
+        let mut memory = Memory::new();
+        assert!(memory.map_fragment(&VendorReg::fragment()));
+        memory
+    }
+
+    #[test]
+    fn test_map_fragment() {
+        let mut memory = memory();
+        assert_eq!(memory.size(), VendorReg::base() + VendorReg::size());
+        assert_eq!(memory.read::<VendorReg::Gain>().unwrap(), 0);
+        assert!(memory
+            .write_raw(VendorReg::GainRaw::ADDRESS, &[1, 0, 0, 0])
+            .is_err());
+
+        // Overlapped fragments are rejected.
+        let overlapped = MemoryFragment::new(VendorReg::base(), vec![0; 4], AccessRight::RW);
+        assert!(!memory.map_fragment(&overlapped));
+        // Unmapped gaps can be mapped later.
+        let gap = MemoryFragment::new(VendorReg::base() - 4, vec![1; 4], AccessRight::RO);
+        assert!(memory.map_fragment(&gap));
+        assert_eq!(memory.peek(gap.range()).unwrap(), &[1; 4]);
+    }
+
+    #[test]
+    fn test_hooks() {
+        let mut memory = memory();
+        let hooks = WriteHooks::default();
+        hooks.register::<VendorReg::Gain, _>(&mut memory, |memory| {
+            let gain = memory.read::<VendorReg::Gain>().unwrap();
+            if gain % 2 == 1 {
+                return Err(GenCpStatus::InvalidParameter);
+            }
+            memory.write::<VendorReg::GainRaw>(gain * 16).unwrap();
+            Ok(())
+        });
+
+        // Hooks are not run until the register is written.
+        assert!(hooks.run(&mut memory).is_ok());
+        assert_eq!(memory.read::<VendorReg::GainRaw>().unwrap(), 0);
+
+        memory
+            .write_raw(VendorReg::Gain::ADDRESS, &2_u32.to_le_bytes())
+            .unwrap();
+        assert!(hooks.run(&mut memory).is_ok());
+        assert_eq!(memory.read::<VendorReg::GainRaw>().unwrap(), 32);
+
+        memory
+            .write_raw(VendorReg::Gain::ADDRESS, &3_u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(hooks.run(&mut memory), Err(GenCpStatus::InvalidParameter));
+        // The hook is run only once per write.
+        assert!(hooks.run(&mut memory).is_ok());
+
+        memory.write::<VendorReg::Gain>(3).unwrap();
+        hooks.reset();
+        assert!(hooks.run(&mut memory).is_ok());
+    }
+}
@@ -2,27 +2,69 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-mod channel;
-mod device;
+//! This module provides U3V device emulators.
+//!
+//! Emulators are built by [`EmulatorBuilder`] and passed to the device pool. Emulators in the pool
+//! are found by [`enumerate_devices`] or [`enumerate_devices_with_filter`] as [`u3v::Device`], and
+//! controlled in the same way as real devices.
+//!
+//! Emulated devices aren't connected to a USB bus, so their bus numbers are `None`. The port
+//! number of each device is assigned in the order the device is built.
+//!
+//! # Examples
+//! ```rust
+//! use cameleon_device::emulator::{self, EmulatorBuilder};
+//!
+//! EmulatorBuilder::new().user_defined_name("My Camera").unwrap().build();
+//!
+//! let devices = emulator::enumerate_devices().unwrap();
+//! assert!(!devices.is_empty());
+//! ```
+
 mod emulator_impl;
 
-pub use channel::{ControlChannel, ReceiveChannel};
-pub use device::Device;
-pub use emulator_impl::{BuilderError, BuilderResult, EmulatorBuilder};
+pub use emulator_impl::{
+    AccessRight, BuilderError, BuilderResult, ControlFault, DeviceMemory, EmulatorBuilder,
+    EmulatorHandle, EventSender, FaultInjector, LoggedCommand, MemoryFragment, StreamFault,
+    TestPattern,
+};
+
+pub(crate) use emulator_impl::{DeviceHandle, CONTROL_IFACE, EVENT_IFACE, STREAM_IFACE};
 
-use crate::u3v::Result;
+use crate::u3v::{self, DeviceFilter, Result};
+
+/// Enumerate all emulated devices in the device pool.
+pub fn enumerate_devices() -> Result<Vec<u3v::Device>> {
+    enumerate_devices_with_filter(&DeviceFilter::new())
+}
 
-pub fn enumerate_devices() -> Result<Vec<Device>> {
+/// Enumerate emulated devices in the device pool which match the `filter`.
+///
+/// # Examples
+/// ```rust
+/// use cameleon_device::{
+///     emulator::{self, EmulatorBuilder},
+///     u3v::DeviceFilter,
+/// };
+///
+/// EmulatorBuilder::new().serial_number("FLT0001").unwrap().build();
+///
+/// let filter = DeviceFilter::new().serial_number("FLT0001");
+/// let devices = emulator::enumerate_devices_with_filter(&filter).unwrap();
+/// assert_eq!(devices.len(), 1);
+/// assert_eq!(devices[0].device_info.serial_number, "FLT0001");
+/// ```
+pub fn enumerate_devices_with_filter(filter: &DeviceFilter) -> Result<Vec<u3v::Device>> {
     let device_ids = emulator_impl::DevicePool::with(|pool| pool.device_ids());
     let mut devices = Vec::with_capacity(device_ids.len());
 
     for id in device_ids {
         let info = match emulator_impl::DevicePool::with(|pool| pool.device_info(id)) {
-            Ok(info) => info,
-            Err(_) => continue,
+            Ok(info) if filter.matches(&info) => info,
+            _ => continue,
         };
 
-        devices.push(Device::new(id, info));
+        devices.push(u3v::Device::emulated(id, info));
     }
 
     Ok(devices)
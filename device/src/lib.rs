@@ -10,12 +10,24 @@
     clippy::cast_possible_truncation
 )]
 
-#[cfg(feature = "libusb")]
+#[cfg(any(feature = "libusb", feature = "emulator"))]
 pub mod u3v;
 
-//// TODO: finish implementation.
-//mod emulator;
+#[cfg(feature = "emulator")]
+pub mod emulator;
 
 mod pixel_format;
 
 pub use pixel_format::PixelFormat;
+
+#[cfg(any(feature = "libusb", feature = "emulator"))]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Locks `mutex` even if it's poisoned.
+///
+/// A poisoned lock only means that a thread panicked while holding it, e.g. a user's hook
+/// panicked. States guarded by locks in this crate are kept consistent in such cases.
+#[cfg(any(feature = "libusb", feature = "emulator"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
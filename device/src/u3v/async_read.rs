@@ -9,50 +9,45 @@
 // The implementation in the module is written with heavily reference to
 // https://github.com/kevinmehall/rusb/blob/km-pipe-approach/src/device_handle/async_api.rs.
 
+use std::time::Duration;
+#[cfg(feature = "libusb")]
 use std::{
     collections::VecDeque,
     convert::TryInto,
     ptr::NonNull,
     sync::atomic::{AtomicBool, Ordering::SeqCst},
-    time::{Duration, Instant},
+    time::Instant,
 };
 
+#[cfg(feature = "libusb")]
 use super::{
-    channel::ReceiveIfaceInfo, device::RusbDeviceHandle, LibUsbError, ReceiveChannel, Result,
+    device::{LibUsbDeviceHandle, RusbDeviceHandle},
+    LibUsbError,
 };
+use super::{transport::AsyncBulkRead, ReceiveChannel, Result};
+#[cfg(feature = "libusb")]
 use rusb::UsbContext;
 
 #[doc(hidden)]
 /// Represents a pool of asynchronous transfers, that can be polled to completion.
 pub struct AsyncPool<'a> {
-    handle: AsyncHandle<'a>,
-    iface_info: ReceiveIfaceInfo,
-    pending: VecDeque<AsyncTransfer>,
+    inner: Box<dyn AsyncBulkRead + 'a>,
 }
 
 impl<'a> AsyncPool<'a> {
     #[doc(hidden)]
     pub fn new(channel: &'a ReceiveChannel) -> Self {
-        let iface_info = channel.iface_info.clone();
-        let handle = get_handle(channel);
-        Self {
-            handle,
-            iface_info,
-            pending: VecDeque::new(),
-        }
+        let inner = channel
+            .device_handle
+            .async_read(channel.iface_info.bulk_in_ep);
+        Self { inner }
     }
 
     #[doc(hidden)]
     pub fn submit(&mut self, buf: &mut [u8]) -> Result<()> {
-        // Safety: If transfer is submitted, it is pushed onto `pending` where it will be
-        // dropped before `device` is freed.
-        unsafe {
-            let mut transfer =
-                AsyncTransfer::new_bulk(self.handle.as_raw(), self.iface_info.bulk_in_ep, buf);
-            transfer.submit()?;
-            self.pending.push_back(transfer);
-            Ok(())
-        }
+        // Safety: The buffer must outlive the pool, which cancels and completes all pending
+        // transfers when it's dropped.
+        unsafe { self.inner.submit(buf) }
     }
 
     #[doc(hidden)]
@@ -60,6 +55,59 @@ impl<'a> AsyncPool<'a> {
     ///
     /// Panics if there is no pending transfer.
     pub fn poll(&mut self, timeout: Duration) -> Result<usize> {
+        self.inner.poll(timeout)
+    }
+
+    #[doc(hidden)]
+    pub fn cancel_all(&mut self) {
+        self.inner.cancel_all();
+    }
+
+    /// Returns the number of async transfers pending.
+    #[doc(hidden)]
+    pub fn pending(&self) -> usize {
+        self.inner.pending()
+    }
+
+    /// Returns `true` if there is no pending transfer.
+    #[doc(hidden)]
+    pub fn is_empty(&self) -> bool {
+        self.pending() == 0
+    }
+}
+
+/// Asynchronous reads with libusb asynchronous API.
+#[cfg(feature = "libusb")]
+pub(super) struct LibUsbPool<'a> {
+    handle: AsyncHandle<'a>,
+    endpoint: u8,
+    pending: VecDeque<AsyncTransfer>,
+}
+
+#[cfg(feature = "libusb")]
+impl<'a> LibUsbPool<'a> {
+    pub(super) fn new(handle: &'a LibUsbDeviceHandle, endpoint: u8) -> Self {
+        let handle = get_handle(handle);
+        Self {
+            handle,
+            endpoint,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+#[cfg(feature = "libusb")]
+impl<'a> AsyncBulkRead for LibUsbPool<'a> {
+    unsafe fn submit(&mut self, buf: &mut [u8]) -> Result<()> {
+        // Safety: If transfer is submitted, it is pushed onto `pending` where it will be
+        // dropped before `device` is freed.
+        let mut transfer = AsyncTransfer::new_bulk(self.handle.as_raw(), self.endpoint, buf);
+        transfer.submit()?;
+        self.pending.push_back(transfer);
+        Ok(())
+    }
+
+    fn poll(&mut self, timeout: Duration) -> Result<usize> {
         debug_assert!(!self.pending.is_empty());
         let next = self.pending.front().unwrap();
         if poll_completed(self.handle.context(), timeout, next.completed_flag())? {
@@ -70,8 +118,7 @@ impl<'a> AsyncPool<'a> {
         }
     }
 
-    #[doc(hidden)]
-    pub fn cancel_all(&mut self) {
+    fn cancel_all(&mut self) {
         // Cancel in reverse order to avoid a race condition in which one
         // transfer is cancelled but another submitted later makes its way onto
         // the bus.
@@ -80,17 +127,9 @@ impl<'a> AsyncPool<'a> {
         }
     }
 
-    /// Returns the number of async transfers pending.
-    #[doc(hidden)]
-    pub fn pending(&self) -> usize {
+    fn pending(&self) -> usize {
         self.pending.len()
     }
-
-    /// Returns `true` if there is no pending transfer.
-    #[doc(hidden)]
-    pub fn is_empty(&self) -> bool {
-        self.pending() == 0
-    }
 }
 
 impl<'a> Drop for AsyncPool<'a> {
@@ -102,10 +141,12 @@ impl<'a> Drop for AsyncPool<'a> {
     }
 }
 
+#[cfg(feature = "libusb")]
 struct AsyncTransfer {
     ptr: NonNull<libusb1_sys::libusb_transfer>,
 }
 
+#[cfg(feature = "libusb")]
 impl AsyncTransfer {
     /// Invariant: Caller must ensure `device` outlives this transfer.
     unsafe fn new_bulk(
@@ -200,6 +241,7 @@ impl AsyncTransfer {
 }
 
 /// Invariant: transfer must not be pending.
+#[cfg(feature = "libusb")]
 impl Drop for AsyncTransfer {
     fn drop(&mut self) {
         unsafe {
@@ -215,6 +257,7 @@ impl Drop for AsyncTransfer {
 ///
 /// This design is based on
 /// https://libusb.sourceforge.io/api-1.0/libusb_mtasync.html#threadwait
+#[cfg(feature = "libusb")]
 fn poll_completed(
     ctx: &impl UsbContext,
     timeout: Duration,
@@ -255,6 +298,7 @@ fn poll_completed(
     }
 }
 
+#[cfg(feature = "libusb")]
 impl LibUsbError {
     fn from_libusb_error(err: i32) -> std::result::Result<(), Self> {
         match err {
@@ -277,6 +321,7 @@ impl LibUsbError {
     }
 }
 
+#[cfg(feature = "libusb")]
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
         use std::sync::MutexGuard;
@@ -291,14 +336,14 @@ cfg_if::cfg_if! {
             }
         }
 
-        fn get_handle(channel: &ReceiveChannel) -> AsyncHandle {
-            AsyncHandle(channel.device_handle.handle.lock().unwrap())
+        fn get_handle(handle: &LibUsbDeviceHandle) -> AsyncHandle {
+            AsyncHandle(handle.handle.lock().unwrap())
         }
     } else {
         type AsyncHandle<'a> = &'a RusbDeviceHandle;
 
-        fn get_handle(channel: &ReceiveChannel) -> AsyncHandle {
-            &channel.device_handle
+        fn get_handle(handle: &LibUsbDeviceHandle) -> AsyncHandle {
+            handle
         }
     }
 }
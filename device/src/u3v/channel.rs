@@ -6,10 +6,12 @@ use std::time;
 
 use crate::u3v::Result;
 
-use super::device::LibUsbDeviceHandle;
+use super::transport::Transport;
+#[cfg(feature = "libusb")]
+use super::{async_read::LibUsbPool, device::LibUsbDeviceHandle, transport::AsyncBulkRead};
 
 pub struct ControlChannel {
-    pub(super) device_handle: LibUsbDeviceHandle,
+    pub(super) device_handle: Box<dyn Transport>,
     pub iface_info: ControlIfaceInfo,
     pub is_opened: bool,
 }
@@ -41,20 +43,20 @@ impl ControlChannel {
     }
 
     pub fn send(&self, buf: &[u8], timeout: time::Duration) -> Result<usize> {
-        Ok(self
-            .device_handle
-            .write_bulk(self.iface_info.bulk_out_ep, buf, timeout)?)
+        self.device_handle
+            .write_bulk(self.iface_info.bulk_out_ep, buf, timeout)
     }
 
     pub fn recv(&self, buf: &mut [u8], timeout: time::Duration) -> Result<usize> {
-        Ok(self
-            .device_handle
-            .read_bulk(self.iface_info.bulk_in_ep, buf, timeout)?)
+        self.device_handle
+            .read_bulk(self.iface_info.bulk_in_ep, buf, timeout)
     }
 
     pub fn set_halt(&self, timeout: time::Duration) -> Result<()> {
-        set_halt(&self.device_handle, self.iface_info.bulk_in_ep, timeout)?;
-        set_halt(&self.device_handle, self.iface_info.bulk_out_ep, timeout)?;
+        self.device_handle
+            .set_halt(self.iface_info.bulk_in_ep, timeout)?;
+        self.device_handle
+            .set_halt(self.iface_info.bulk_out_ep, timeout)?;
 
         Ok(())
     }
@@ -65,7 +67,7 @@ impl ControlChannel {
         Ok(())
     }
 
-    pub(super) fn new(device_handle: LibUsbDeviceHandle, iface_info: ControlIfaceInfo) -> Self {
+    pub(super) fn new(device_handle: Box<dyn Transport>, iface_info: ControlIfaceInfo) -> Self {
         Self {
             device_handle,
             iface_info,
@@ -75,7 +77,7 @@ impl ControlChannel {
 }
 
 pub struct ReceiveChannel {
-    pub(super) device_handle: LibUsbDeviceHandle,
+    pub(super) device_handle: Box<dyn Transport>,
     pub iface_info: ReceiveIfaceInfo,
     pub is_opened: bool,
 }
@@ -107,13 +109,13 @@ impl ReceiveChannel {
     }
 
     pub fn recv(&self, buf: &mut [u8], timeout: time::Duration) -> Result<usize> {
-        Ok(self
-            .device_handle
-            .read_bulk(self.iface_info.bulk_in_ep, buf, timeout)?)
+        self.device_handle
+            .read_bulk(self.iface_info.bulk_in_ep, buf, timeout)
     }
 
     pub fn set_halt(&self, timeout: time::Duration) -> Result<()> {
-        set_halt(&self.device_handle, self.iface_info.bulk_in_ep, timeout)?;
+        self.device_handle
+            .set_halt(self.iface_info.bulk_in_ep, timeout)?;
 
         Ok(())
     }
@@ -123,7 +125,7 @@ impl ReceiveChannel {
         Ok(())
     }
 
-    pub(super) fn new(device_handle: LibUsbDeviceHandle, iface_info: ReceiveIfaceInfo) -> Self {
+    pub(super) fn new(device_handle: Box<dyn Transport>, iface_info: ReceiveIfaceInfo) -> Self {
         Self {
             device_handle,
             iface_info,
@@ -145,28 +147,53 @@ pub struct ReceiveIfaceInfo {
     pub bulk_in_ep: u8,
 }
 
-fn set_halt(
-    handle: &LibUsbDeviceHandle,
-    endpoint_number: u8,
-    timeout: time::Duration,
-) -> Result<()> {
-    let request_type = rusb::request_type(
-        rusb::Direction::Out,
-        rusb::RequestType::Standard,
-        rusb::Recipient::Endpoint,
-    );
-    let request = 0x03; // SET_FEATURE.
-    let value = 0x00; // ENDPOINT_HALT.
-    let buf = vec![]; // NO DATA.
-
-    handle.write_control(
-        request_type,
-        request,
-        value,
-        u16::from(endpoint_number),
-        &buf,
-        timeout,
-    )?;
-
-    Ok(())
+#[cfg(feature = "libusb")]
+impl Transport for LibUsbDeviceHandle {
+    fn claim_interface(&mut self, iface: u8) -> Result<()> {
+        Ok(LibUsbDeviceHandle::claim_interface(self, iface)?)
+    }
+
+    fn release_interface(&mut self, iface: u8) -> Result<()> {
+        Ok(LibUsbDeviceHandle::release_interface(self, iface)?)
+    }
+
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], timeout: time::Duration) -> Result<usize> {
+        Ok(LibUsbDeviceHandle::read_bulk(self, endpoint, buf, timeout)?)
+    }
+
+    fn write_bulk(&self, endpoint: u8, buf: &[u8], timeout: time::Duration) -> Result<usize> {
+        Ok(LibUsbDeviceHandle::write_bulk(
+            self, endpoint, buf, timeout,
+        )?)
+    }
+
+    fn set_halt(&self, endpoint: u8, timeout: time::Duration) -> Result<()> {
+        let request_type = rusb::request_type(
+            rusb::Direction::Out,
+            rusb::RequestType::Standard,
+            rusb::Recipient::Endpoint,
+        );
+        let request = 0x03; // SET_FEATURE.
+        let value = 0x00; // ENDPOINT_HALT.
+        let buf = vec![]; // NO DATA.
+
+        self.write_control(
+            request_type,
+            request,
+            value,
+            u16::from(endpoint),
+            &buf,
+            timeout,
+        )?;
+
+        Ok(())
+    }
+
+    fn clear_halt(&mut self, endpoint: u8) -> Result<()> {
+        Ok(LibUsbDeviceHandle::clear_halt(self, endpoint)?)
+    }
+
+    fn async_read(&self, endpoint: u8) -> Box<dyn AsyncBulkRead + '_> {
+        Box::new(LibUsbPool::new(self, endpoint))
+    }
 }
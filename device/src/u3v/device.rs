@@ -2,16 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::sync::Arc;
+
 use crate::u3v::{DeviceInfo, Result};
 
-use super::channel::{ControlChannel, ControlIfaceInfo, ReceiveChannel, ReceiveIfaceInfo};
+use super::{
+    channel::{ControlChannel, ControlIfaceInfo, ReceiveChannel, ReceiveIfaceInfo},
+    transport::{Transport, TransportProvider},
+};
 
 /// Entry point to the connected device.
 /// This device itself doesn't communicate with the connected device but provide basic device
 /// information and channels to communicate with the connected device. So it's valid to use
 /// provided channels even after dropping this instance.
 pub struct Device {
-    device: LibUsbDevice,
+    device: DeviceKind,
 
     ctrl_iface_info: ControlIfaceInfo,
     event_iface_info: Option<ReceiveIfaceInfo>,
@@ -55,6 +60,7 @@ impl Device {
         &self.device_info
     }
 
+    #[cfg(feature = "libusb")]
     pub(super) fn new(
         device: RusbDevice,
         ctrl_iface_info: ControlIfaceInfo,
@@ -62,7 +68,7 @@ impl Device {
         stream_iface_info: Option<ReceiveIfaceInfo>,
         device_info: DeviceInfo,
     ) -> Self {
-        let device = get_device(device);
+        let device = DeviceKind::LibUsb(get_device(device));
 
         let device = Self {
             device,
@@ -76,6 +82,58 @@ impl Device {
         device
     }
 
+    /// Creates a device that communicates with the device through transports opened by
+    /// `provider`.
+    ///
+    /// See [`super::loopback`] for an example.
+    pub fn with_transport(
+        provider: impl TransportProvider + 'static,
+        ctrl_iface_info: ControlIfaceInfo,
+        event_iface_info: Option<ReceiveIfaceInfo>,
+        stream_iface_info: Option<ReceiveIfaceInfo>,
+        device_info: DeviceInfo,
+    ) -> Self {
+        let device = Self {
+            device: DeviceKind::Transport(Arc::new(provider)),
+            ctrl_iface_info,
+            event_iface_info,
+            stream_iface_info,
+            device_info,
+        };
+
+        log::info! {"{}: create device with a custom transport", device.log_name()};
+        device
+    }
+
+    /// Creates a device that communicates with an emulated device in the device pool of
+    /// [`crate::emulator`].
+    #[cfg(feature = "emulator")]
+    pub(crate) fn emulated(device_id: u32, device_info: DeviceInfo) -> Self {
+        use crate::emulator::{DeviceHandle, CONTROL_IFACE, EVENT_IFACE, STREAM_IFACE};
+
+        let provider = move || Ok(Box::new(DeviceHandle::new(device_id)) as _);
+        let device = Self {
+            device: DeviceKind::Transport(Arc::new(provider)),
+            ctrl_iface_info: ControlIfaceInfo {
+                iface_number: CONTROL_IFACE,
+                bulk_in_ep: 0,
+                bulk_out_ep: 0,
+            },
+            event_iface_info: Some(ReceiveIfaceInfo {
+                iface_number: EVENT_IFACE,
+                bulk_in_ep: 0,
+            }),
+            stream_iface_info: Some(ReceiveIfaceInfo {
+                iface_number: STREAM_IFACE,
+                bulk_in_ep: 0,
+            }),
+            device_info,
+        };
+
+        log::info! {"{}: create emulated device", device.log_name()};
+        device
+    }
+
     //TODO: We need logger.
     fn log_name(&self) -> String {
         format!(
@@ -87,15 +145,33 @@ impl Device {
     }
 }
 
+enum DeviceKind {
+    #[cfg(feature = "libusb")]
+    LibUsb(LibUsbDevice),
+
+    /// A device which communicates through custom transports, e.g. an emulated device.
+    Transport(Arc<dyn TransportProvider>),
+}
+
+impl DeviceKind {
+    fn open(&self) -> Result<Box<dyn Transport>> {
+        match self {
+            #[cfg(feature = "libusb")]
+            Self::LibUsb(device) => Ok(Box::new(device.open()?)),
+            Self::Transport(provider) => provider.open(),
+        }
+    }
+}
+
+#[cfg(feature = "libusb")]
 pub(super) type RusbDevice = rusb::Device<rusb::GlobalContext>;
+#[cfg(feature = "libusb")]
 pub(super) type RusbDeviceHandle = rusb::DeviceHandle<rusb::GlobalContext>;
 
+#[cfg(feature = "libusb")]
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
-        use std::{
-            sync::{Arc, Mutex},
-            time,
-        };
+        use std::{sync::Mutex, time};
 
         pub(super) struct LibUsbDevice {
             pub(super) handle: LibUsbDeviceHandle,
@@ -5,7 +5,7 @@
 use cameleon_impl::bytes_io::ReadBytes;
 use semver::Version;
 
-use crate::u3v::{BusSpeed, DeviceInfo, Error, Result};
+use crate::u3v::{BusSpeed, DeviceFilter, DeviceInfo, Error, Result};
 
 use super::{
     channel::{ControlIfaceInfo, ReceiveIfaceInfo},
@@ -23,13 +23,28 @@ const IAD_FUNCTION_PROTOCOL: u8 = 0x00;
 const USB3V_SUBCLASS: u8 = 0x05;
 
 pub fn enumerate_devices() -> Result<Vec<Device>> {
+    enumerate_devices_with_filter(&DeviceFilter::new())
+}
+
+/// Enumerate devices which match the filter.
+///
+/// Devices are filtered by their location before they are opened, so it's cheaper to filter
+/// devices by [`DeviceFilter::bus_number`] or [`DeviceFilter::port_numbers`] if possible.
+pub fn enumerate_devices_with_filter(filter: &DeviceFilter) -> Result<Vec<Device>> {
     let rusb_device_list = rusb::DeviceList::new()?;
     let builders = rusb_device_list
         .iter()
+        .filter(|dev| {
+            filter.matches_location(
+                Some(dev.bus_number()),
+                &dev.port_numbers().unwrap_or_default(),
+            )
+        })
         .filter_map(|dev| DeviceBuilder::new(dev).ok().flatten());
 
     Ok(builders
         .filter_map(|builder| builder.build().ok())
+        .filter(|dev| filter.matches(dev.device_info()))
         .collect())
 }
 
@@ -367,6 +382,16 @@ impl DeviceInfoDescriptor {
             return Err(Error::InvalidDevice);
         };
 
+        let device = channel.device();
+        let current_speed = match device.speed() {
+            rusb::Speed::Low => Some(BusSpeed::LowSpeed),
+            rusb::Speed::Full => Some(BusSpeed::FullSpeed),
+            rusb::Speed::High => Some(BusSpeed::HighSpeed),
+            rusb::Speed::Super => Some(BusSpeed::SuperSpeed),
+            rusb::Speed::SuperPlus => Some(BusSpeed::SuperSpeedPlus),
+            _ => None,
+        };
+
         Ok(DeviceInfo {
             gencp_version,
             u3v_version,
@@ -379,6 +404,9 @@ impl DeviceInfoDescriptor {
             serial_number,
             user_defined_name,
             supported_speed,
+            bus_number: Some(device.bus_number()),
+            port_numbers: device.port_numbers().unwrap_or_default(),
+            current_speed,
         })
     }
 }
@@ -0,0 +1,215 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use super::{BusSpeed, DeviceInfo};
+
+/// A filter to select devices to be enumerated.
+///
+/// A device matches the filter only if it satisfies all conditions set to the filter. A filter
+/// without any condition matches all devices.
+///
+/// # Examples
+///
+/// ```rust
+/// use cameleon_device::u3v::{BusSpeed, DeviceFilter};
+///
+/// // Select a camera by its serial number.
+/// let filter = DeviceFilter::new().serial_number("CAM1984");
+///
+/// // Select cameras of a specific model which are connected to USB3 ports of the bus 2.
+/// let filter = DeviceFilter::new()
+///     .vendor_name("Vendor")
+///     .model_name("Model")
+///     .bus_number(2)
+///     .min_speed(BusSpeed::SuperSpeed);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceFilter {
+    serial_number: Option<String>,
+    user_defined_name: Option<String>,
+    guid: Option<String>,
+    vendor_name: Option<String>,
+    model_name: Option<String>,
+    bus_number: Option<u8>,
+    port_numbers: Option<Vec<u8>>,
+    min_speed: Option<BusSpeed>,
+}
+
+impl DeviceFilter {
+    /// Construct a filter which matches all devices.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select devices with the serial number.
+    #[must_use]
+    pub fn serial_number(mut self, serial_number: impl Into<String>) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    /// Select devices with the user defined name.
+    #[must_use]
+    pub fn user_defined_name(mut self, name: impl Into<String>) -> Self {
+        self.user_defined_name = Some(name.into());
+        self
+    }
+
+    /// Select devices with the GUID.
+    #[must_use]
+    pub fn guid(mut self, guid: impl Into<String>) -> Self {
+        self.guid = Some(guid.into());
+        self
+    }
+
+    /// Select devices with the vendor name.
+    #[must_use]
+    pub fn vendor_name(mut self, vendor_name: impl Into<String>) -> Self {
+        self.vendor_name = Some(vendor_name.into());
+        self
+    }
+
+    /// Select devices with the model name.
+    #[must_use]
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.model_name = Some(model_name.into());
+        self
+    }
+
+    /// Select devices connected to the bus.
+    #[must_use]
+    pub fn bus_number(mut self, bus_number: u8) -> Self {
+        self.bus_number = Some(bus_number);
+        self
+    }
+
+    /// Select devices connected to the port. `port_numbers` is the port chain from the root hub
+    /// to the device, see [`DeviceInfo::port_numbers`].
+    #[must_use]
+    pub fn port_numbers(mut self, port_numbers: impl Into<Vec<u8>>) -> Self {
+        self.port_numbers = Some(port_numbers.into());
+        self
+    }
+
+    /// Select devices whose link speed is equal to or faster than `speed`.
+    ///
+    /// If the speed negotiated with the host is unknown, the speed supported by the device is
+    /// used instead.
+    #[must_use]
+    pub fn min_speed(mut self, speed: BusSpeed) -> Self {
+        self.min_speed = Some(speed);
+        self
+    }
+
+    /// Returns `true` if the device matches the filter.
+    #[must_use]
+    pub fn matches(&self, info: &DeviceInfo) -> bool {
+        fn matches_str(cond: &Option<String>, value: &str) -> bool {
+            !matches!(cond, Some(cond) if cond != value)
+        }
+
+        let speed = info.current_speed.unwrap_or(info.supported_speed);
+
+        matches_str(&self.serial_number, &info.serial_number)
+            && (self.user_defined_name.is_none()
+                || self.user_defined_name == info.user_defined_name)
+            && matches_str(&self.guid, &info.guid)
+            && matches_str(&self.vendor_name, &info.vendor_name)
+            && matches_str(&self.model_name, &info.model_name)
+            && !matches!(self.min_speed, Some(min_speed) if min_speed > speed)
+            && self.matches_location(info.bus_number, &info.port_numbers)
+    }
+
+    /// Returns `true` if the location matches the filter.
+    pub(crate) fn matches_location(&self, bus_number: Option<u8>, port_numbers: &[u8]) -> bool {
+        !matches!(self.bus_number, Some(bus) if Some(bus) != bus_number)
+            && !matches!(&self.port_numbers, Some(ports) if ports != port_numbers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::*;
+
+    fn device_info() -> DeviceInfo {
+        DeviceInfo {
+            gencp_version: Version::new(1, 0, 0),
+            u3v_version: Version::new(1, 0, 0),
+            guid: "ABCD00000001".into(),
+            vendor_name: "Vendor".into(),
+            model_name: "Model".into(),
+            family_name: None,
+            device_version: "1.0".into(),
+            manufacturer_info: "none".into(),
+            serial_number: "CAM1984".into(),
+            user_defined_name: Some("Left".into()),
+            supported_speed: BusSpeed::SuperSpeed,
+            bus_number: Some(2),
+            port_numbers: vec![1, 3],
+            current_speed: Some(BusSpeed::HighSpeed),
+        }
+    }
+
+    #[test]
+    fn test_match_all() {
+        assert!(DeviceFilter::new().matches(&device_info()));
+    }
+
+    #[test]
+    fn test_strings() {
+        let info = device_info();
+        assert!(DeviceFilter::new()
+            .serial_number("CAM1984")
+            .user_defined_name("Left")
+            .guid("ABCD00000001")
+            .vendor_name("Vendor")
+            .model_name("Model")
+            .matches(&info));
+        assert!(!DeviceFilter::new().serial_number("CAM1985").matches(&info));
+        assert!(!DeviceFilter::new()
+            .user_defined_name("Right")
+            .matches(&info));
+
+        let mut info = info;
+        info.user_defined_name = None;
+        assert!(!DeviceFilter::new().user_defined_name("Left").matches(&info));
+    }
+
+    #[test]
+    fn test_location() {
+        let info = device_info();
+        assert!(DeviceFilter::new()
+            .bus_number(2)
+            .port_numbers([1, 3])
+            .matches(&info));
+        assert!(!DeviceFilter::new().bus_number(1).matches(&info));
+        assert!(!DeviceFilter::new().port_numbers([1]).matches(&info));
+
+        // A device without a USB bus never matches a bus number.
+        let mut info = info;
+        info.bus_number = None;
+        assert!(!DeviceFilter::new().bus_number(2).matches(&info));
+        assert!(DeviceFilter::new().port_numbers([1, 3]).matches(&info));
+    }
+
+    #[test]
+    fn test_speed() {
+        let mut info = device_info();
+        assert!(DeviceFilter::new()
+            .min_speed(BusSpeed::HighSpeed)
+            .matches(&info));
+        assert!(!DeviceFilter::new()
+            .min_speed(BusSpeed::SuperSpeed)
+            .matches(&info));
+
+        // Supported speed is used if the current speed is unknown.
+        info.current_speed = None;
+        assert!(DeviceFilter::new()
+            .min_speed(BusSpeed::SuperSpeed)
+            .matches(&info));
+    }
+}
@@ -45,10 +45,27 @@ pub struct DeviceInfo {
 
     /// Bus speed supported by the device.
     pub supported_speed: BusSpeed,
+
+    /// Number of the bus that the device is connected to.
+    /// This field is `None` if the device isn't connected through a USB bus, e.g. an emulated
+    /// device or a device driven by a custom transport.
+    pub bus_number: Option<u8>,
+
+    /// Port numbers from the root hub to the device.
+    ///
+    /// Together with [`DeviceInfo::bus_number`], it identifies the device stably across reboots
+    /// as long as the device is connected to the same port.
+    pub port_numbers: Vec<u8>,
+
+    /// Bus speed negotiated with the host.
+    /// This field is `None` if the speed is unknown, e.g. the platform doesn't report it.
+    pub current_speed: Option<BusSpeed>,
 }
 
 /// Bus speed supported by each USB device.
-#[derive(Clone, Copy, Debug)]
+///
+/// Speeds are ordered from the slowest to the fastest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BusSpeed {
     /// USB 1.0/Low-Speed: 1.5 Mbps
     LowSpeed,
@@ -66,6 +83,20 @@ pub enum BusSpeed {
     SuperSpeedPlus,
 }
 
+impl BusSpeed {
+    /// Returns the signaling rate of the speed in bits per second.
+    #[must_use]
+    pub fn bits_per_second(self) -> u64 {
+        match self {
+            Self::LowSpeed => 1_500_000,
+            Self::FullSpeed => 12_000_000,
+            Self::HighSpeed => 480_000_000,
+            Self::SuperSpeed => 5_000_000_000,
+            Self::SuperSpeedPlus => 10_000_000_000,
+        }
+    }
+}
+
 impl fmt::Display for DeviceInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "### Device Information ###")?;
@@ -96,7 +127,22 @@ impl fmt::Display for DeviceInfo {
             writeln!(f, "User Defined Name: N/A")
         }?;
 
-        write!(f, "Supported Speed: {:?}", self.supported_speed)?;
+        writeln!(f, "Supported Speed: {:?}", self.supported_speed)?;
+
+        if let Some(bus_number) = self.bus_number {
+            writeln!(f, "Bus Number: {}", bus_number)
+        } else {
+            writeln!(f, "Bus Number: N/A")
+        }?;
+
+        let ports: Vec<String> = self.port_numbers.iter().map(u8::to_string).collect();
+        writeln!(f, "Port Numbers: {}", ports.join("."))?;
+
+        if let Some(current_speed) = &self.current_speed {
+            write!(f, "Current Speed: {:?}", current_speed)
+        } else {
+            write!(f, "Current Speed: N/A")
+        }?;
 
         Ok(())
     }
@@ -14,13 +14,21 @@ pub mod prelude {
 
 mod channel;
 mod device;
+#[cfg(feature = "libusb")]
 mod device_builder;
+mod device_filter;
 mod device_info;
+mod transport;
 
-pub use channel::{ControlChannel, ReceiveChannel};
+pub use channel::{ControlChannel, ControlIfaceInfo, ReceiveChannel, ReceiveIfaceInfo};
 pub use device::Device;
-pub use device_builder::enumerate_devices;
+#[cfg(feature = "libusb")]
+pub use device_builder::{enumerate_devices, enumerate_devices_with_filter};
+pub use device_filter::DeviceFilter;
 pub use device_info::{BusSpeed, DeviceInfo};
+pub use transport::{
+    loopback, AsyncBulkRead, LoopbackDevice, LoopbackTransport, Transport, TransportProvider,
+};
 
 use std::borrow::Cow;
 
@@ -76,6 +84,7 @@ pub enum LibUsbError {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+#[cfg(feature = "libusb")]
 impl From<rusb::Error> for Error {
     fn from(err: rusb::Error) -> Error {
         use LibUsbError::{
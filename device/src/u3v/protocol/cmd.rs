@@ -132,7 +132,8 @@ impl ReadMem {
 
     /// Returns maximum read length that corresponding ack length fit into `maximum_ack_len`.
     pub fn maximum_read_length(maximum_ack_len: usize) -> u16 {
-        (maximum_ack_len - CommandPacket::<ReadMem>::ACK_HEADER_LENGTH)
+        maximum_ack_len
+            .saturating_sub(CommandPacket::<ReadMem>::ACK_HEADER_LENGTH)
             .try_into()
             .unwrap_or(u16::MAX)
     }
@@ -242,6 +243,57 @@ impl ReadMemStacked {
         })
     }
 
+    /// Pack `entries` into as few [`ReadMemStacked`] as possible so that each command fits into
+    /// `maximum_cmd_len` and each corresponding ack fits into `maximum_ack_len`.
+    ///
+    /// The order of entries is preserved.
+    pub fn pack(
+        entries: impl IntoIterator<Item = ReadMem>,
+        maximum_cmd_len: usize,
+        maximum_ack_len: usize,
+    ) -> Result<Vec<Self>> {
+        let cmd_header_len = CommandPacket::<ReadMem>::header_len();
+        let ack_header_len = CommandPacket::<ReadMem>::ACK_HEADER_LENGTH;
+        // Address(8 bytes) + reserved(2bytes) + length(2 bytes)
+        let entry_len = 12;
+
+        let mut packed = vec![];
+        let mut current = vec![];
+        let mut cmd_len = cmd_header_len;
+        let mut ack_len = ack_header_len;
+        for ent in entries {
+            let read_length = ent.read_length as usize;
+            if cmd_header_len + entry_len > maximum_cmd_len
+                || ack_header_len + read_length > maximum_ack_len
+            {
+                return Err(Error::InvalidPacket(
+                    "an entry of ReadMemStacked doesn't fit into the maximum packet length".into(),
+                ));
+            }
+
+            if cmd_len + entry_len > maximum_cmd_len || ack_len + read_length > maximum_ack_len {
+                packed.push(Self::new(std::mem::take(&mut current))?);
+                cmd_len = cmd_header_len;
+                ack_len = ack_header_len;
+            }
+
+            cmd_len += entry_len;
+            ack_len += read_length;
+            current.push(ent);
+        }
+
+        if !current.is_empty() {
+            packed.push(Self::new(current)?);
+        }
+
+        Ok(packed)
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[ReadMem] {
+        &self.entries
+    }
+
     fn len(regs: &[ReadMem]) -> Result<u16> {
         let len = regs.iter().fold(0, |acc, reg| acc + reg.scd_len() as usize);
         into_scd_len(len)
@@ -277,6 +329,65 @@ impl<'a> WriteMemStacked<'a> {
         })
     }
 
+    /// Pack `entries` into as few [`WriteMemStacked`] as possible so that each command fits into
+    /// `maximum_cmd_len` and each corresponding ack fits into `maximum_ack_len`.
+    ///
+    /// The order of entries is preserved. Use [`WriteMemStacked::maximum_data_length`] to split
+    /// data that is too large to fit into a single command.
+    pub fn pack(
+        entries: impl IntoIterator<Item = WriteMem<'a>>,
+        maximum_cmd_len: usize,
+        maximum_ack_len: usize,
+    ) -> Result<Vec<Self>> {
+        let cmd_header_len = CommandPacket::<WriteMem>::header_len();
+        let ack_header_len = CommandPacket::<WriteMem>::ACK_HEADER_LENGTH;
+        // Reserved(2bytes)+ length written(2bytes);
+        let ack_entry_len = 4;
+
+        let mut packed = vec![];
+        let mut current = vec![];
+        let mut cmd_len = cmd_header_len;
+        let mut ack_len = ack_header_len;
+        for ent in entries {
+            // Address(8 bytes) + reserved(2bytes) + length(2 bytes) + data.
+            let entry_len = 12 + ent.data_len as usize;
+            if cmd_header_len + entry_len > maximum_cmd_len
+                || ack_header_len + ack_entry_len > maximum_ack_len
+            {
+                return Err(Error::InvalidPacket(
+                    "an entry of WriteMemStacked doesn't fit into the maximum packet length".into(),
+                ));
+            }
+
+            if cmd_len + entry_len > maximum_cmd_len || ack_len + ack_entry_len > maximum_ack_len {
+                packed.push(Self::new(std::mem::take(&mut current))?);
+                cmd_len = cmd_header_len;
+                ack_len = ack_header_len;
+            }
+
+            cmd_len += entry_len;
+            ack_len += ack_entry_len;
+            current.push(ent);
+        }
+
+        if !current.is_empty() {
+            packed.push(Self::new(current)?);
+        }
+
+        Ok(packed)
+    }
+
+    /// Returns maximum data length of an entry so that a [`WriteMemStacked`] containing the
+    /// entry fits into `maximum_cmd_len`.
+    pub fn maximum_data_length(maximum_cmd_len: usize) -> usize {
+        maximum_cmd_len.saturating_sub(CommandPacket::<WriteMem>::header_len() + 12)
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[WriteMem<'a>] {
+        &self.entries
+    }
+
     fn len(entries: &[WriteMem<'a>]) -> Result<u16> {
         let len = entries
             .iter()
@@ -631,6 +742,15 @@ mod tests {
         assert_eq!(last_chunk.read_length + read_len, read_mem.read_length);
     }
 
+    #[test]
+    fn test_read_mem_maximum_read_length() {
+        assert_eq!(ReadMem::maximum_read_length(24), 12);
+        assert_eq!(ReadMem::maximum_read_length(12), 0);
+        // Too small ack length must not underflow.
+        assert_eq!(ReadMem::maximum_read_length(4), 0);
+        assert_eq!(ReadMem::maximum_read_length(usize::MAX), u16::MAX);
+    }
+
     #[test]
     fn test_write_mem_chunks() {
         let data = vec![0; 128];
@@ -653,4 +773,62 @@ mod tests {
         assert_eq!(last_chunk.address, expected_addr);
         assert_eq!(last_chunk.data_len, data.len() as u16 - sent_data_len);
     }
+
+    #[test]
+    fn test_read_mem_stacked_pack() {
+        let read_mems: Vec<_> = (0..10).map(|i| ReadMem::new(i * 8, 8)).collect();
+        // Header(12 bytes) + 3 entries(12 bytes each).
+        let maximum_cmd_len = HEADER_LEN as usize + 12 * 3;
+        let maximum_ack_len = 1024;
+        let packed =
+            ReadMemStacked::pack(read_mems.clone(), maximum_cmd_len, maximum_ack_len).unwrap();
+
+        assert_eq!(packed.len(), 4);
+        for cmd in &packed {
+            assert!(cmd.clone().finalize(0).cmd_len() <= maximum_cmd_len);
+        }
+        let flattened: Vec<_> = packed
+            .iter()
+            .flat_map(|cmd| cmd.entries().to_vec())
+            .collect();
+        assert_eq!(flattened, read_mems);
+
+        // Ack length limits the number of entries.
+        let maximum_ack_len = HEADER_LEN as usize + 8 * 2;
+        let packed = ReadMemStacked::pack(read_mems.clone(), 1024, maximum_ack_len).unwrap();
+        assert_eq!(packed.len(), 5);
+        for cmd in &packed {
+            assert!(cmd.clone().finalize(0).maximum_ack_len() <= maximum_ack_len);
+        }
+
+        // An entry which doesn't fit into an ack.
+        assert!(ReadMemStacked::pack(vec![ReadMem::new(0, 64)], 1024, 32).is_err());
+    }
+
+    #[test]
+    fn test_write_mem_stacked_pack() {
+        let data = vec![0; 4];
+        let write_mems: Vec<_> = (0..10)
+            .map(|i| WriteMem::new(i * 4, &data).unwrap())
+            .collect();
+        // Header(12 bytes) + 2 entries(12 bytes + data each).
+        let maximum_cmd_len = HEADER_LEN as usize + (12 + 4) * 2;
+        let packed = WriteMemStacked::pack(write_mems.clone(), maximum_cmd_len, 1024).unwrap();
+
+        assert_eq!(packed.len(), 5);
+        for cmd in &packed {
+            assert!(cmd.clone().finalize(0).cmd_len() <= maximum_cmd_len);
+        }
+        let flattened: Vec<_> = packed
+            .iter()
+            .flat_map(|cmd| cmd.entries().to_vec())
+            .collect();
+        assert_eq!(flattened, write_mems);
+
+        let maximum_data_len = WriteMemStacked::maximum_data_length(maximum_cmd_len);
+        let data = vec![0; maximum_data_len];
+        let write_mem = WriteMem::new(0, &data).unwrap();
+        let packed = WriteMemStacked::pack(vec![write_mem], maximum_cmd_len, 1024).unwrap();
+        assert_eq!(packed[0].clone().finalize(0).cmd_len(), maximum_cmd_len);
+    }
 }
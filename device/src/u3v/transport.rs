@@ -0,0 +1,506 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module provides the transport abstraction through which channels communicate with a
+//! device.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ptr::NonNull,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use super::{LibUsbError, Result};
+
+/// A transport through which a channel communicates with a device.
+///
+/// A transport is opened for each channel by [`TransportProvider::open`], and bound to the
+/// interface claimed by the channel.
+pub trait Transport: Send + Sync {
+    /// Claim the interface.
+    fn claim_interface(&mut self, iface: u8) -> Result<()>;
+
+    /// Release the interface claimed by [`Transport::claim_interface`].
+    fn release_interface(&mut self, iface: u8) -> Result<()>;
+
+    /// Read data from the bulk in endpoint, returns the number of bytes read.
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], timeout: Duration) -> Result<usize>;
+
+    /// Write data to the bulk out endpoint, returns the number of bytes written.
+    fn write_bulk(&self, endpoint: u8, buf: &[u8], timeout: Duration) -> Result<usize>;
+
+    /// Halt the endpoint.
+    fn set_halt(&self, endpoint: u8, timeout: Duration) -> Result<()>;
+
+    /// Clear the halt state of the endpoint.
+    fn clear_halt(&mut self, endpoint: u8) -> Result<()>;
+
+    /// Start asynchronous reads from the bulk in endpoint.
+    ///
+    /// The default implementation doesn't read data in the background, each read is done by
+    /// [`Transport::read_bulk`] when it's polled. Transports which support asynchronous transfers
+    /// should override this, e.g. the libusb transport submits libusb asynchronous transfers.
+    fn async_read(&self, endpoint: u8) -> Box<dyn AsyncBulkRead + '_> {
+        Box::new(SyncBulkRead {
+            transport: self,
+            endpoint,
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+/// Asynchronous reads from a bulk in endpoint started by [`Transport::async_read`].
+///
+/// Reads are completed in the order they are submitted.
+pub trait AsyncBulkRead {
+    /// Submit a read into `buf`.
+    ///
+    /// # Safety
+    /// `buf` must be neither moved nor accessed until the read is completed by
+    /// [`AsyncBulkRead::poll`] or `self` is dropped.
+    unsafe fn submit(&mut self, buf: &mut [u8]) -> Result<()>;
+
+    /// Wait for the oldest pending read to complete, returns the number of bytes read.
+    ///
+    /// # Panics
+    /// Panics if there is no pending read.
+    fn poll(&mut self, timeout: Duration) -> Result<usize>;
+
+    /// Cancel all pending reads. A cancelled read either fails when it's polled or is removed
+    /// from the pending reads.
+    fn cancel_all(&mut self);
+
+    /// Returns the number of pending reads.
+    fn pending(&self) -> usize;
+}
+
+/// The default implementation of [`AsyncBulkRead`], submitted buffers are just queued and filled
+/// synchronously when polled.
+struct SyncBulkRead<'a, T: ?Sized> {
+    transport: &'a T,
+    endpoint: u8,
+    pending: VecDeque<(NonNull<u8>, usize)>,
+}
+
+impl<'a, T> AsyncBulkRead for SyncBulkRead<'a, T>
+where
+    T: Transport + ?Sized,
+{
+    unsafe fn submit(&mut self, buf: &mut [u8]) -> Result<()> {
+        let ptr = NonNull::new(buf.as_mut_ptr()).unwrap();
+        self.pending.push_back((ptr, buf.len()));
+        Ok(())
+    }
+
+    fn poll(&mut self, timeout: Duration) -> Result<usize> {
+        let (ptr, len) = self.pending.pop_front().unwrap();
+        // Safety: The caller of `submit` guarantees that the buffer is still valid.
+        let buf = unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), len) };
+        self.transport.read_bulk(self.endpoint, buf, timeout)
+    }
+
+    fn cancel_all(&mut self) {
+        self.pending.clear();
+    }
+
+    fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// A provider which opens transports to a device.
+pub trait TransportProvider: Send + Sync {
+    /// Open a new transport to the device.
+    fn open(&self) -> Result<Box<dyn Transport>>;
+}
+
+impl<F> TransportProvider for F
+where
+    F: Fn() -> Result<Box<dyn Transport>> + Send + Sync,
+{
+    fn open(&self) -> Result<Box<dyn Transport>> {
+        self()
+    }
+}
+
+/// Create an in-process loopback transport pair.
+///
+/// Data written to [`LoopbackTransport`] by the host is received by [`LoopbackDevice`], and data
+/// sent from [`LoopbackDevice`] is read from [`LoopbackTransport`]. This allows any device
+/// implementation, e.g. a recorded-traffic player, to drive the U3V host stack.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use cameleon_device::u3v::{self, ControlIfaceInfo, Device};
+/// # use cameleon_device::u3v::{BusSpeed, DeviceInfo};
+/// # use semver::Version;
+/// # let device_info = DeviceInfo {
+/// #     gencp_version: Version::new(1, 0, 0),
+/// #     u3v_version: Version::new(1, 0, 0),
+/// #     guid: "000000000000".into(),
+/// #     vendor_name: "vendor".into(),
+/// #     model_name: "model".into(),
+/// #     family_name: None,
+/// #     device_version: "1.0".into(),
+/// #     manufacturer_info: "none".into(),
+/// #     serial_number: "0".into(),
+/// #     user_defined_name: None,
+/// #     supported_speed: BusSpeed::SuperSpeed,
+/// #     bus_number: None,
+/// #     port_numbers: vec![],
+/// #     current_speed: None,
+/// # };
+///
+/// let (transport, device) = u3v::loopback();
+/// let ctrl_iface_info = ControlIfaceInfo {
+///     iface_number: 0,
+///     bulk_in_ep: 0x81,
+///     bulk_out_ep: 0x01,
+/// };
+/// let host = Device::with_transport(transport, ctrl_iface_info, None, None, device_info);
+///
+/// let mut channel = host.control_channel().unwrap();
+/// channel.open().unwrap();
+///
+/// // The device receives data sent from the host.
+/// channel.send(&[1, 2, 3], Duration::from_millis(100)).unwrap();
+/// let (endpoint, data) = device.recv(Duration::from_millis(100)).unwrap();
+/// assert_eq!((endpoint, data.as_slice()), (0x01, &[1, 2, 3][..]));
+///
+/// // The host receives data sent from the device.
+/// device.send(0x81, vec![4, 5]);
+/// let mut buf = [0; 8];
+/// let len = channel.recv(&mut buf, Duration::from_millis(100)).unwrap();
+/// assert_eq!(&buf[..len], &[4, 5]);
+/// ```
+#[must_use]
+pub fn loopback() -> (LoopbackTransport, LoopbackDevice) {
+    let shared = Arc::new(Shared::default());
+    (
+        LoopbackTransport {
+            shared: shared.clone(),
+            claimed: None,
+        },
+        LoopbackDevice { shared },
+    )
+}
+
+/// The host side of a loopback transport created by [`loopback`].
+///
+/// The transport also works as [`TransportProvider`], each opened transport shares the same
+/// device.
+pub struct LoopbackTransport {
+    shared: Arc<Shared>,
+    claimed: Option<u8>,
+}
+
+/// The device side of a loopback transport created by [`loopback`].
+#[derive(Clone)]
+pub struct LoopbackDevice {
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    /// Data sent from the device for each bulk in endpoint.
+    bulk_in: HashMap<u8, VecDeque<Vec<u8>>>,
+    /// Data sent from the host with its bulk out endpoint.
+    bulk_out: VecDeque<(u8, Vec<u8>)>,
+    halted: HashSet<u8>,
+    claimed: HashSet<u8>,
+    disconnected: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        crate::lock(&self.state)
+    }
+
+    /// Wait until `f` returns `Some` or the timeout elapses.
+    fn wait<F, T>(&self, timeout: Duration, mut f: F) -> Option<T>
+    where
+        F: FnMut(&mut State) -> Option<T>,
+    {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.lock();
+        loop {
+            if let Some(value) = f(&mut state) {
+                return Some(value);
+            }
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self
+                .cond
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn claim_interface(&mut self, iface: u8) -> Result<()> {
+        if self.claimed == Some(iface) {
+            return Ok(());
+        }
+
+        let mut state = self.shared.lock();
+        if state.disconnected {
+            return Err(LibUsbError::NoDevice.into());
+        }
+        if !state.claimed.insert(iface) {
+            return Err(LibUsbError::Busy.into());
+        }
+        self.claimed = Some(iface);
+        Ok(())
+    }
+
+    fn release_interface(&mut self, iface: u8) -> Result<()> {
+        if self.claimed == Some(iface) {
+            self.shared.lock().claimed.remove(&iface);
+            self.claimed = None;
+        }
+        Ok(())
+    }
+
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], timeout: Duration) -> Result<usize> {
+        let data = self
+            .shared
+            .wait(timeout, |state| {
+                if state.disconnected {
+                    Some(Err(LibUsbError::NoDevice))
+                } else if state.halted.contains(&endpoint) {
+                    Some(Err(LibUsbError::Pipe))
+                } else {
+                    state.bulk_in.get_mut(&endpoint)?.pop_front().map(Ok)
+                }
+            })
+            .ok_or(LibUsbError::Timeout)??;
+
+        let len = data.len();
+        if buf.len() < len {
+            return Err(LibUsbError::Overflow.into());
+        }
+        buf[..len].copy_from_slice(&data);
+        Ok(len)
+    }
+
+    fn write_bulk(&self, endpoint: u8, buf: &[u8], _timeout: Duration) -> Result<usize> {
+        let mut state = self.shared.lock();
+        if state.disconnected {
+            return Err(LibUsbError::NoDevice.into());
+        }
+        if state.halted.contains(&endpoint) {
+            return Err(LibUsbError::Pipe.into());
+        }
+        state.bulk_out.push_back((endpoint, buf.to_vec()));
+        self.shared.cond.notify_all();
+        Ok(buf.len())
+    }
+
+    fn set_halt(&self, endpoint: u8, _timeout: Duration) -> Result<()> {
+        self.shared.lock().halted.insert(endpoint);
+        self.shared.cond.notify_all();
+        Ok(())
+    }
+
+    fn clear_halt(&mut self, endpoint: u8) -> Result<()> {
+        self.shared.lock().halted.remove(&endpoint);
+        Ok(())
+    }
+}
+
+impl TransportProvider for LoopbackTransport {
+    fn open(&self) -> Result<Box<dyn Transport>> {
+        if self.shared.lock().disconnected {
+            return Err(LibUsbError::NoDevice.into());
+        }
+        Ok(Box::new(LoopbackTransport {
+            shared: self.shared.clone(),
+            claimed: None,
+        }))
+    }
+}
+
+impl Drop for LoopbackTransport {
+    fn drop(&mut self) {
+        if let Some(iface) = self.claimed {
+            self.release_interface(iface).ok();
+        }
+    }
+}
+
+impl LoopbackDevice {
+    /// Receive data sent from the host, returns the bulk out endpoint and the data.
+    ///
+    /// Returns `None` if no data is sent within the timeout.
+    #[must_use]
+    pub fn recv(&self, timeout: Duration) -> Option<(u8, Vec<u8>)> {
+        self.shared
+            .wait(timeout, |state| state.bulk_out.pop_front())
+    }
+
+    /// Send data to the host through the bulk in endpoint.
+    pub fn send(&self, endpoint: u8, data: Vec<u8>) {
+        self.shared
+            .lock()
+            .bulk_in
+            .entry(endpoint)
+            .or_default()
+            .push_back(data);
+        self.shared.cond.notify_all();
+    }
+
+    /// Halt the endpoint, the host receives [`LibUsbError::Pipe`] until it clears the halt state.
+    pub fn set_halt(&self, endpoint: u8) {
+        self.shared.lock().halted.insert(endpoint);
+        self.shared.cond.notify_all();
+    }
+
+    /// Returns `true` if the endpoint is halted.
+    #[must_use]
+    pub fn is_halted(&self, endpoint: u8) -> bool {
+        self.shared.lock().halted.contains(&endpoint)
+    }
+
+    /// Returns `true` if the interface is claimed by the host.
+    #[must_use]
+    pub fn is_claimed(&self, iface: u8) -> bool {
+        self.shared.lock().claimed.contains(&iface)
+    }
+
+    /// Emulate disconnection of the device, all requests from the host fail with
+    /// [`LibUsbError::NoDevice`] until [`LoopbackDevice::reconnect`] is called.
+    pub fn disconnect(&self) {
+        let mut state = self.shared.lock();
+        state.disconnected = true;
+        state.bulk_in.clear();
+        state.bulk_out.clear();
+        self.shared.cond.notify_all();
+    }
+
+    /// Reconnect the device disconnected by [`LoopbackDevice::disconnect`].
+    pub fn reconnect(&self) {
+        self.shared.lock().disconnected = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    fn transport() -> (Box<dyn Transport>, LoopbackDevice) {
+        let (provider, device) = loopback();
+        let mut transport = provider.open().unwrap();
+        transport.claim_interface(0).unwrap();
+        (transport, device)
+    }
+
+    #[test]
+    fn test_loopback() {
+        let (transport, device) = transport();
+        assert!(device.is_claimed(0));
+
+        assert_eq!(transport.write_bulk(1, &[1, 2], TIMEOUT).unwrap(), 2);
+        assert_eq!(device.recv(TIMEOUT), Some((1, vec![1, 2])));
+        assert!(device.recv(Duration::ZERO).is_none());
+
+        let mut buf = [0; 4];
+        device.send(0x81, vec![3, 4, 5]);
+        // Data sent to another endpoint is not received.
+        assert!(transport.read_bulk(0x82, &mut buf, TIMEOUT).is_err());
+        assert_eq!(transport.read_bulk(0x81, &mut buf, TIMEOUT).unwrap(), 3);
+        assert_eq!(&buf[..3], &[3, 4, 5]);
+
+        device.send(0x81, vec![0; 5]);
+        assert!(matches!(
+            transport.read_bulk(0x81, &mut buf, TIMEOUT),
+            Err(crate::u3v::Error::LibUsb(LibUsbError::Overflow))
+        ));
+    }
+
+    #[test]
+    fn test_async_read() {
+        let (transport, device) = transport();
+        let (mut first, mut second) = ([0; 4], [0; 4]);
+        device.send(0x81, vec![1, 2]);
+        device.send(0x81, vec![3]);
+
+        let mut reader = transport.async_read(0x81);
+        unsafe {
+            reader.submit(&mut first).unwrap();
+            reader.submit(&mut second).unwrap();
+        }
+        assert_eq!(reader.pending(), 2);
+        assert_eq!(reader.poll(TIMEOUT).unwrap(), 2);
+        assert_eq!(reader.poll(TIMEOUT).unwrap(), 1);
+        assert_eq!(reader.pending(), 0);
+
+        unsafe {
+            reader.submit(&mut first).unwrap();
+        }
+        reader.cancel_all();
+        assert_eq!(reader.pending(), 0);
+        drop(reader);
+
+        assert_eq!(&first[..2], &[1, 2]);
+        assert_eq!(second[0], 3);
+    }
+
+    #[test]
+    fn test_claim() {
+        let (provider, device) = loopback();
+        let (mut first, mut second) = (provider.open().unwrap(), provider.open().unwrap());
+        first.claim_interface(0).unwrap();
+        assert!(second.claim_interface(0).is_err());
+        second.claim_interface(1).unwrap();
+
+        drop(first);
+        assert!(!device.is_claimed(0));
+        assert!(device.is_claimed(1));
+    }
+
+    #[test]
+    fn test_halt() {
+        let (mut transport, device) = transport();
+        device.set_halt(0x81);
+        let mut buf = [0; 4];
+        assert!(matches!(
+            transport.read_bulk(0x81, &mut buf, TIMEOUT),
+            Err(crate::u3v::Error::LibUsb(LibUsbError::Pipe))
+        ));
+
+        transport.clear_halt(0x81).unwrap();
+        assert!(!device.is_halted(0x81));
+        transport.set_halt(0x01, TIMEOUT).unwrap();
+        assert!(device.is_halted(0x01));
+        assert!(transport.write_bulk(0x01, &[0], TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (transport, device) = transport();
+        device.disconnect();
+        let mut buf = [0; 4];
+        assert!(matches!(
+            transport.read_bulk(0x81, &mut buf, TIMEOUT),
+            Err(crate::u3v::Error::LibUsb(LibUsbError::NoDevice))
+        ));
+        assert!(transport.write_bulk(0x01, &[0], TIMEOUT).is_err());
+
+        device.reconnect();
+        assert!(transport.write_bulk(0x01, &[0], TIMEOUT).is_ok());
+    }
+}
@@ -0,0 +1,165 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::convert::TryInto;
+
+use super::{
+    interface::{IBoolean, INode},
+    node_base::{NodeAttributeBase, NodeBase},
+    register_base::RegisterBase,
+    store::{CacheStore, NodeStore, ValueStore},
+    Device, GenApiError, GenApiResult, ValueCtxt,
+};
+
+/// A DCAM specific node which controls the access control register of advanced features.
+///
+/// The lock is acquired by writing the feature ID and the timeout to the register, and it's
+/// regarded as acquired while the register holds the feature ID.
+#[derive(Debug, Clone)]
+pub struct AdvFeatureLockNode {
+    pub(crate) attr_base: NodeAttributeBase,
+    pub(crate) register_base: RegisterBase,
+
+    pub(crate) feature_id: i64,
+    pub(crate) timeout: i64,
+}
+
+impl AdvFeatureLockNode {
+    #[must_use]
+    pub fn register_base(&self) -> &RegisterBase {
+        &self.register_base
+    }
+
+    /// 48-bit ID of the advanced feature set.
+    #[must_use]
+    pub fn feature_id(&self) -> i64 {
+        self.feature_id
+    }
+
+    /// Timeout of the lock in milliseconds.
+    #[must_use]
+    pub fn timeout(&self) -> i64 {
+        self.timeout
+    }
+}
+
+impl INode for AdvFeatureLockNode {
+    fn node_base(&self) -> NodeBase {
+        let elem_base = &self.register_base.elem_base;
+        NodeBase::new(&self.attr_base, elem_base)
+    }
+
+    fn streamable(&self) -> bool {
+        self.register_base().streamable()
+    }
+}
+
+impl IBoolean for AdvFeatureLockNode {
+    #[tracing::instrument(skip(self, device, store, cx),
+                          level = "trace",
+                          fields(node = store.name_by_id(self.node_base().id()).unwrap()))]
+    fn value<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        let nid = self.node_base().id();
+        self.register_base()
+            .with_cache_or_read(nid, device, store, cx, |data| {
+                let data: [u8; 8] =
+                    data.get(..8)
+                        .and_then(|d| d.try_into().ok())
+                        .ok_or_else(|| {
+                            GenApiError::invalid_data("the register length must be 8".into())
+                        })?;
+                Ok(u64::from_be_bytes(data) >> 16 == self.feature_id as u64)
+            })
+    }
+
+    #[tracing::instrument(skip(self, device, store, cx),
+                          level = "trace",
+                          fields(node = store.name_by_id(self.node_base().id()).unwrap()))]
+    fn set_value<T: ValueStore, U: CacheStore>(
+        &self,
+        value: bool,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        let nid = self.node_base().id();
+        cx.invalidate_cache_by(nid);
+
+        let data = if value {
+            ((self.feature_id as u64) << 16) | (self.timeout as u64 & 0xffff)
+        } else {
+            0
+        };
+        self.register_base()
+            .write_and_cache(nid, &data.to_be_bytes(), device, store, cx)
+    }
+
+    fn is_readable<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        self.register_base().is_readable(device, store, cx)
+    }
+
+    fn is_writable<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        self.register_base().is_writable(device, store, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    #[test]
+    fn test_adv_feature_lock() {
+        let xml = register_description(
+            r#"<AdvFeatureLock Name="Lock">
+                   <Address>0</Address>
+                   <Length>8</Length>
+                   <pPort>Device</pPort>
+                   <FeatureID>0x0030533B73C3</FeatureID>
+                   <Timeout>1000</Timeout>
+                 </AdvFeatureLock>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        // The lock is held by another feature set.
+        let mut device = TestDevice::new((0x1234_u64 << 16).to_be_bytes().to_vec());
+
+        let lock = store
+            .id_by_name("Lock")
+            .unwrap()
+            .expect_iboolean_kind(&store)
+            .unwrap();
+        assert!(!lock.value(&mut device, &store, &mut cx).unwrap());
+
+        lock.set_value(true, &mut device, &store, &mut cx).unwrap();
+        assert_eq!(device.memory, 0x0030_533B_73C3_03E8_u64.to_be_bytes());
+        assert!(lock.value(&mut device, &store, &mut cx).unwrap());
+
+        lock.set_value(false, &mut device, &store, &mut cx).unwrap();
+        assert_eq!(device.memory, [0; 8]);
+        assert!(!lock.value(&mut device, &store, &mut cx).unwrap());
+    }
+}
@@ -0,0 +1,611 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! DCAM specific nodes which expose entries of the configuration ROM defined in IEEE 1212.
+
+use std::convert::TryInto;
+
+use super::{
+    elem_type::IntegerRepresentation,
+    interface::{IInteger, INode, IRegister, IString, IncrementMode},
+    node_base::{NodeAttributeBase, NodeBase, NodeElementBase},
+    register_base::RegisterBase,
+    store::{CacheStore, NodeId, NodeStore, ValueStore},
+    Device, GenApiError, GenApiResult, ValueCtxt,
+};
+
+/// Key of the textual descriptor leaf which follows the entry it describes.
+const TEXTUAL_DESCRIPTOR_LEAF: u8 = 0x81;
+/// Key of the unit directory entry in the root directory.
+const UNIT_DIRECTORY: u8 = 0xD1;
+
+#[derive(Debug, Clone)]
+pub struct ConfRomNode {
+    pub(crate) attr_base: NodeAttributeBase,
+    pub(crate) register_base: RegisterBase,
+
+    pub(crate) unit: i64,
+    pub(crate) entries: Vec<NodeId>,
+}
+
+impl ConfRomNode {
+    #[must_use]
+    pub fn register_base(&self) -> &RegisterBase {
+        &self.register_base
+    }
+
+    /// Index of the unit directory which contains keys. `0` means the root directory.
+    #[must_use]
+    pub fn unit_elem(&self) -> i64 {
+        self.unit
+    }
+
+    /// Returns `IntKey` and `TextDesc` nodes defined in the configuration ROM.
+    #[must_use]
+    pub fn entries(&self) -> &[NodeId] {
+        &self.entries
+    }
+
+    /// Read the configuration ROM and apply `f` to the directory which contains keys of the node.
+    fn with_directory<T: ValueStore, U: CacheStore, R>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+        f: impl FnOnce(Directory) -> GenApiResult<R>,
+    ) -> GenApiResult<R> {
+        let nid = self.node_base().id();
+        self.register_base
+            .with_cache_or_read(nid, device, store, cx, |rom| {
+                f(Directory::unit(rom, self.unit)?)
+            })
+    }
+}
+
+impl INode for ConfRomNode {
+    fn node_base(&self) -> NodeBase {
+        let elem_base = &self.register_base.elem_base;
+        NodeBase::new(&self.attr_base, elem_base)
+    }
+
+    fn streamable(&self) -> bool {
+        self.register_base().streamable()
+    }
+}
+
+impl IRegister for ConfRomNode {
+    fn read<T: ValueStore, U: CacheStore>(
+        &self,
+        buf: &mut [u8],
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        let address = self.address(device, store, cx)?;
+        let length = self.length(device, store, cx)?;
+        self.register_base().read_and_cache(
+            self.node_base().id(),
+            address,
+            length,
+            buf,
+            device,
+            store,
+            cx,
+        )
+    }
+
+    fn write<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &[u8],
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn address<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        self.register_base().address(device, store, cx)
+    }
+
+    fn length<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        self.register_base().length(device, store, cx)
+    }
+}
+
+/// An integer entry of the configuration ROM.
+///
+/// The value of an immediate or an offset entry is the 24-bit value of the entry, and the value
+/// of a leaf entry is the content of the leaf interpreted as a big-endian integer.
+#[derive(Debug, Clone)]
+pub struct IntKeyNode {
+    pub(crate) attr_base: NodeAttributeBase,
+    pub(crate) elem_base: NodeElementBase,
+
+    pub(crate) key: i64,
+    pub(crate) conf_rom: NodeId,
+}
+
+impl IntKeyNode {
+    #[must_use]
+    pub fn key(&self) -> i64 {
+        self.key
+    }
+
+    /// Returns [`NodeId`] of `ConfRom` node which the key belongs to.
+    #[must_use]
+    pub fn conf_rom(&self) -> NodeId {
+        self.conf_rom
+    }
+}
+
+impl INode for IntKeyNode {
+    fn node_base(&self) -> NodeBase {
+        NodeBase::new(&self.attr_base, &self.elem_base)
+    }
+
+    fn streamable(&self) -> bool {
+        false
+    }
+}
+
+impl IInteger for IntKeyNode {
+    #[tracing::instrument(skip(self, device, store, cx),
+                          level = "trace",
+                          fields(node = store.name_by_id(self.node_base().id()).unwrap()))]
+    fn value<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        let conf_rom = self.conf_rom.expect_conf_rom(store)?;
+        conf_rom.with_directory(device, store, cx, |dir| {
+            let entry = dir.entry(self.key)?;
+            if entry.key >> 6 == 0b10 {
+                let leaf = dir.leaf(&entry)?;
+                let len = leaf.len().min(8);
+                Ok(leaf[..len]
+                    .iter()
+                    .fold(0_u64, |acc, b| (acc << 8) | u64::from(*b)) as i64)
+            } else {
+                Ok(i64::from(entry.value))
+            }
+        })
+    }
+
+    fn set_value<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn min<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        Ok(i64::MIN)
+    }
+
+    fn max<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        Ok(i64::MAX)
+    }
+
+    fn inc_mode(&self, _: &impl NodeStore) -> Option<IncrementMode> {
+        None
+    }
+
+    fn inc<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<Option<i64>> {
+        Ok(None)
+    }
+
+    fn valid_value_set(&self, _: &impl NodeStore) -> &[i64] {
+        &[]
+    }
+
+    fn representation(&self, _: &impl NodeStore) -> IntegerRepresentation {
+        IntegerRepresentation::HexNumber
+    }
+
+    fn unit(&self, _: &impl NodeStore) -> Option<&str> {
+        None
+    }
+
+    fn set_min<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn set_max<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn is_readable<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        Ok(self.elem_base.is_readable(device, store, cx)?
+            && self
+                .conf_rom
+                .expect_conf_rom(store)?
+                .register_base()
+                .is_readable(device, store, cx)?)
+    }
+
+    fn is_writable<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        Ok(false)
+    }
+}
+
+/// A textual descriptor of an entry in the configuration ROM.
+///
+/// The value is the text of the textual descriptor leaf which immediately follows the entry
+/// specified by the key.
+#[derive(Debug, Clone)]
+pub struct TextDescNode {
+    pub(crate) attr_base: NodeAttributeBase,
+    pub(crate) elem_base: NodeElementBase,
+
+    pub(crate) key: i64,
+    pub(crate) conf_rom: NodeId,
+}
+
+impl TextDescNode {
+    #[must_use]
+    pub fn key(&self) -> i64 {
+        self.key
+    }
+
+    /// Returns [`NodeId`] of `ConfRom` node which the descriptor belongs to.
+    #[must_use]
+    pub fn conf_rom(&self) -> NodeId {
+        self.conf_rom
+    }
+}
+
+impl INode for TextDescNode {
+    fn node_base(&self) -> NodeBase {
+        NodeBase::new(&self.attr_base, &self.elem_base)
+    }
+
+    fn streamable(&self) -> bool {
+        false
+    }
+}
+
+impl IString for TextDescNode {
+    #[tracing::instrument(skip(self, device, store, cx),
+                          level = "trace",
+                          fields(node = store.name_by_id(self.node_base().id()).unwrap()))]
+    fn value<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<String> {
+        let conf_rom = self.conf_rom.expect_conf_rom(store)?;
+        conf_rom.with_directory(device, store, cx, |dir| {
+            let text = dir.text(self.key)?;
+            let str_end = text.iter().position(|b| *b == 0).unwrap_or(text.len());
+            Ok(String::from_utf8_lossy(&text[..str_end]).to_string())
+        })
+    }
+
+    fn set_value<T: ValueStore, U: CacheStore>(
+        &self,
+        _: String,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn max_length<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        let conf_rom = self.conf_rom.expect_conf_rom(store)?;
+        conf_rom.with_directory(
+            device,
+            store,
+            cx,
+            |dir| Ok(dir.text(self.key)?.len() as i64),
+        )
+    }
+
+    fn is_readable<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        Ok(self.elem_base.is_readable(device, store, cx)?
+            && self
+                .conf_rom
+                .expect_conf_rom(store)?
+                .register_base()
+                .is_readable(device, store, cx)?)
+    }
+
+    fn is_writable<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        Ok(false)
+    }
+}
+
+/// An entry of a directory.
+struct Entry {
+    /// Offset of the entry from the start of the configuration ROM.
+    offset: usize,
+    key: u8,
+    value: u32,
+}
+
+/// A directory in the configuration ROM.
+struct Directory<'a> {
+    rom: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Directory<'a> {
+    /// Returns the root directory if `unit` is 0, otherwise returns the `unit`-th unit directory
+    /// referred from the root directory.
+    fn unit(rom: &'a [u8], unit: i64) -> GenApiResult<Self> {
+        // The root directory follows the bus info block.
+        let info_length = quadlet(rom, 0)? >> 24;
+        let root = Self {
+            rom,
+            offset: (1 + info_length as usize) * 4,
+        };
+        if unit == 0 {
+            return Ok(root);
+        }
+
+        let entry = root
+            .entries()?
+            .filter(|entry| entry.key == UNIT_DIRECTORY)
+            .nth((unit - 1) as usize)
+            .ok_or_else(|| {
+                GenApiError::invalid_data(
+                    format!(
+                        "unit directory {} is not found in the configuration ROM",
+                        unit
+                    )
+                    .into(),
+                )
+            })?;
+        Ok(Self {
+            rom,
+            offset: entry.offset + entry.value as usize * 4,
+        })
+    }
+
+    fn entries(&self) -> GenApiResult<impl Iterator<Item = Entry> + 'a> {
+        let length = (quadlet(self.rom, self.offset)? >> 16) as usize;
+        let rom = self.rom;
+        let start = self.offset + 4;
+        Ok((0..length).map_while(move |i| {
+            let offset = start + i * 4;
+            let entry = quadlet(rom, offset).ok()?;
+            Some(Entry {
+                offset,
+                key: (entry >> 24) as u8,
+                value: entry & 0x00ff_ffff,
+            })
+        }))
+    }
+
+    fn entry(&self, key: i64) -> GenApiResult<Entry> {
+        self.entries()?
+            .find(|entry| i64::from(entry.key) == key)
+            .ok_or_else(|| {
+                GenApiError::invalid_data(
+                    format!("key {:#X} is not found in the configuration ROM", key).into(),
+                )
+            })
+    }
+
+    /// Returns the content of the leaf which the entry points to.
+    fn leaf(&self, entry: &Entry) -> GenApiResult<&'a [u8]> {
+        let offset = entry.offset + entry.value as usize * 4;
+        let length = (quadlet(self.rom, offset)? >> 16) as usize;
+        self.rom
+            .get(offset + 4..offset + 4 + length * 4)
+            .ok_or_else(|| {
+                GenApiError::invalid_data("the leaf exceeds the configuration ROM".into())
+            })
+    }
+
+    /// Returns the text of the textual descriptor which describes the entry of `key`.
+    fn text(&self, key: i64) -> GenApiResult<&'a [u8]> {
+        let entry = self.entry(key)?;
+        let descriptor = self
+            .entries()?
+            .find(|desc| desc.offset == entry.offset + 4)
+            .filter(|desc| desc.key == TEXTUAL_DESCRIPTOR_LEAF)
+            .ok_or_else(|| {
+                GenApiError::invalid_data(
+                    format!("key {:#X} doesn't have a textual descriptor", key).into(),
+                )
+            })?;
+        // The first two quadlets of the leaf are the descriptor type and the character set.
+        self.leaf(&descriptor)?
+            .get(8..)
+            .ok_or_else(|| GenApiError::invalid_data("the textual descriptor is too short".into()))
+    }
+}
+
+fn quadlet(rom: &[u8], offset: usize) -> GenApiResult<u32> {
+    rom.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| GenApiError::invalid_data("the offset exceeds the configuration ROM".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    fn rom() -> Vec<u8> {
+        let quadlets: &[u32] = &[
+            // Bus info block.
+            0x0404_0000,
+            0x3133_3934,
+            0x0000_0000,
+            0x0000_0000,
+            0x0000_0000,
+            // Root directory.
+            0x0004_0000,
+            0x0300_A0B0, // Vendor ID.
+            0x8100_0006, // Textual descriptor of vendor ID.
+            0xD100_000A, // Unit directory.
+            0x8D00_0001, // Node unique ID leaf.
+            // Node unique ID leaf.
+            0x0002_0000,
+            0x0000_0001,
+            0x0000_0002,
+            // Textual descriptor leaf.
+            0x0004_0000,
+            0x0000_0000,
+            0x0000_0000,
+            u32::from_be_bytes(*b"Came"),
+            u32::from_be_bytes(*b"leon"),
+            // Unit directory.
+            0x0001_0000,
+            0x1200_A02D, // Unit spec ID.
+        ];
+        quadlets.iter().flat_map(|q| q.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_root_directory() {
+        let rom = rom();
+        let dir = Directory::unit(&rom, 0).unwrap();
+        assert_eq!(dir.entries().unwrap().count(), 4);
+
+        let entry = dir.entry(0x03).unwrap();
+        assert_eq!(entry.value, 0xA0B0);
+        assert!(dir.entry(0x12).is_err());
+
+        let entry = dir.entry(0x8D).unwrap();
+        assert_eq!(dir.leaf(&entry).unwrap(), &[0, 0, 0, 1, 0, 0, 0, 2]);
+
+        assert_eq!(dir.text(0x03).unwrap(), b"Cameleon");
+        assert!(dir.text(0xD1).is_err());
+    }
+
+    #[test]
+    fn test_unit_directory() {
+        let rom = rom();
+        let dir = Directory::unit(&rom, 1).unwrap();
+        assert_eq!(dir.entry(0x12).unwrap().value, 0xA02D);
+        assert!(Directory::unit(&rom, 2).is_err());
+    }
+
+    #[test]
+    fn test_conf_rom_nodes() {
+        let xml = register_description(
+            r#"<ConfRom Name="RootDirectory">
+                   <Address>0</Address>
+                   <Length>80</Length>
+                   <pPort>Device</pPort>
+                   <IntKey Name="VendorID"><Key>0x03</Key></IntKey>
+                   <IntKey Name="NodeUniqueID"><Key>0x8D</Key></IntKey>
+                   <TextDesc Name="VendorName"><Key>0x03</Key></TextDesc>
+                 </ConfRom>
+                 <ConfRom Name="UnitDirectory">
+                   <Address>0</Address>
+                   <Length>80</Length>
+                   <pPort>Device</pPort>
+                   <Unit>1</Unit>
+                   <IntKey Name="UnitSpecID"><Key>0x12</Key></IntKey>
+                 </ConfRom>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = TestDevice::new(rom());
+
+        let mut int_value = |name: &str| {
+            store
+                .id_by_name(name)
+                .unwrap()
+                .expect_iinteger_kind(&store)
+                .unwrap()
+                .value(&mut device, &store, &mut cx)
+                .unwrap()
+        };
+        assert_eq!(int_value("VendorID"), 0xA0B0);
+        assert_eq!(int_value("NodeUniqueID"), 0x0000_0001_0000_0002);
+        assert_eq!(int_value("UnitSpecID"), 0xA02D);
+
+        let text_desc = store
+            .id_by_name("VendorName")
+            .unwrap()
+            .expect_istring_kind(&store)
+            .unwrap();
+        assert_eq!(
+            text_desc.value(&mut device, &store, &mut cx).unwrap(),
+            "Cameleon"
+        );
+        assert!(!text_desc.is_writable(&mut device, &store, &mut cx).unwrap());
+    }
+}
@@ -113,12 +113,17 @@ impl IFloat for ConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<f64> {
-        let mut collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
-        collector.insert("TO", self.p_value(), device, store, cx)?;
-        let var_env = collector.collect(device, store, cx)?;
-
-        let eval_result = self.formula_from.eval(&var_env)?;
+        let eval_result = cx.memoized(self.node_base().id(), |cx| {
+            let to = utils::eval_result_from_nid(self.p_value, device, store, cx)?;
+            utils::eval_formula(
+                &self.formula_from,
+                &self.p_variables,
+                Some(to),
+                device,
+                store,
+                cx,
+            )
+        })?;
         Ok(eval_result.as_float())
     }
 
@@ -134,12 +139,14 @@ impl IFloat for ConverterNode {
     ) -> GenApiResult<()> {
         cx.invalidate_cache_by(self.node_base().id());
 
-        let mut collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
-        collector.insert_imm("FROM", value);
-        let var_env = collector.collect(device, store, cx)?;
-
-        let eval_result = self.formula_to.eval(&var_env)?;
+        let eval_result = utils::eval_formula(
+            &self.formula_to,
+            &self.p_variables,
+            Some(value.into()),
+            device,
+            store,
+            cx,
+        )?;
         utils::set_eval_result(self.p_value, eval_result, device, store, cx)?;
         Ok(())
     }
@@ -175,6 +182,10 @@ impl IFloat for ConverterNode {
         Ok(None)
     }
 
+    fn valid_value_set(&self, _: &impl NodeStore) -> &[f64] {
+        &[]
+    }
+
     fn representation(&self, _: &impl NodeStore) -> FloatRepresentation {
         self.representation
     }
@@ -226,11 +237,9 @@ impl IFloat for ConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<bool> {
-        let collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
         Ok(self.elem_base.is_readable(device, store, cx)?
             && utils::is_nid_readable(self.p_value, device, store, cx)?
-            && collector.is_readable(device, store, cx)?)
+            && utils::is_variables_readable(&self.p_variables, device, store, cx)?)
     }
 
     #[tracing::instrument(skip(self, device, store, cx),
@@ -242,10 +251,9 @@ impl IFloat for ConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<bool> {
-        let collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
         Ok(self.elem_base.is_writable(device, store, cx)?
             && utils::is_nid_writable(self.p_value, device, store, cx)?
-            && collector.is_readable(device, store, cx)?) // Collector is needed to be readable to write a value.
+            && utils::is_variables_readable(&self.p_variables, device, store, cx)?)
+        // Variables need to be readable to write a value.
     }
 }
@@ -0,0 +1,749 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! This module contains the dependency graph of nodes, which is implicitly defined by node
+//! references such as `pValue`, `pMax` or `pIsAvailable`.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+    sync::{mpsc, Arc},
+};
+
+use super::{
+    elem_type::{AddressKind, ImmOrPNode, ValueKind},
+    node_base::NodeElementBase,
+    register_base::RegisterBase,
+    store::{NodeData, NodeId, NodeStore},
+};
+
+/// Kind of a dependency between nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    /// `pValue`, `pValueIndexed` or `pValueDefault`.
+    Value,
+    /// `pValueCopy`.
+    ValueCopy,
+    /// `pIndex`.
+    Index,
+    /// `pMin`.
+    Min,
+    /// `pMax`.
+    Max,
+    /// `pInc`.
+    Inc,
+    /// `pIsImplemented`.
+    IsImplemented,
+    /// `pIsAvailable`.
+    IsAvailable,
+    /// `pIsLocked`.
+    IsLocked,
+    /// `pBlockPolling`.
+    BlockPolling,
+    /// `pError`.
+    Error,
+    /// `pAlias`.
+    Alias,
+    /// `pCastAlias`.
+    CastAlias,
+    /// The dependency has the node in its `pSelected`, i.e. the node is selected by the
+    /// dependency.
+    Selector,
+    /// `pVariable` of a formula.
+    Variable,
+    /// `pInvalidator`.
+    Invalidator,
+    /// `pAddress`, `pOffset` or `IntSwissKnife` which computes the address of a register.
+    Address,
+    /// `pLength`.
+    Length,
+    /// `pPort`.
+    Port,
+    /// `EnumEntry` of an enumeration.
+    Entry,
+    /// `pFeature` of a category.
+    Feature,
+    /// `pCommandValue`.
+    CommandValue,
+    /// `pChunkID`.
+    ChunkId,
+    /// `pAdvFeatureLock`.
+    AdvFeatureLock,
+    /// The configuration ROM which an `IntKey` or a `TextDesc` is read from.
+    ConfRom,
+}
+
+impl DependencyKind {
+    /// Returns `true` if a change of the dependency may change the value, the access mode or the
+    /// range of the node.
+    #[must_use]
+    pub fn propagates_change(self) -> bool {
+        !matches!(
+            self,
+            Self::ValueCopy
+                | Self::BlockPolling
+                | Self::Error
+                | Self::Alias
+                | Self::CastAlias
+                | Self::Port
+                | Self::Feature
+                | Self::ChunkId
+        )
+    }
+
+    /// Returns `true` if the dependency is evaluated when the node is evaluated.
+    ///
+    /// `pInvalidator` only discards the cache, so registers commonly invalidate each other.
+    fn is_evaluated(self) -> bool {
+        self.propagates_change() && self != Self::Invalidator
+    }
+}
+
+/// Dependency graph of nodes in a [`NodeStore`].
+///
+/// Edges are directed from dependents to their dependencies, e.g. a node with `pMax` depends on
+/// the node referred by it.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    dependencies: HashMap<NodeId, Vec<(DependencyKind, NodeId)>>,
+    dependents: HashMap<NodeId, Vec<(DependencyKind, NodeId)>>,
+    dangling: Vec<(NodeId, DependencyKind, NodeId)>,
+}
+
+impl DependencyGraph {
+    /// Builds the dependency graph of all nodes in `store`.
+    pub fn new(store: &impl NodeStore) -> Self {
+        let mut graph = Self::default();
+        store.visit_nodes(|node| {
+            let nid = node.node_base().id();
+            visit_references(node, |kind, target| {
+                if store.node_opt(target).is_none() {
+                    graph.dangling.push((nid, kind, target));
+                }
+                // `pSelected` refers to the nodes which depend on the selector.
+                if kind == DependencyKind::Selector {
+                    graph.add(target, kind, nid);
+                } else {
+                    graph.add(nid, kind, target);
+                }
+            });
+        });
+        graph
+    }
+
+    /// Returns nodes which `nid` directly depends on.
+    #[must_use]
+    pub fn dependencies(&self, nid: NodeId) -> &[(DependencyKind, NodeId)] {
+        self.dependencies.get(&nid).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns nodes which directly depend on `nid`.
+    #[must_use]
+    pub fn dependents(&self, nid: NodeId) -> &[(DependencyKind, NodeId)] {
+        self.dependents.get(&nid).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns nodes which `nid` directly or indirectly depends on, excluding `nid` itself.
+    #[must_use]
+    pub fn transitive_dependencies(&self, nid: NodeId) -> Vec<NodeId> {
+        let mut nodes = self.traverse([nid], Self::dependencies, |_| true);
+        nodes.retain(|n| *n != nid);
+        nodes
+    }
+
+    /// Returns nodes which directly or indirectly depend on `nid`, excluding `nid` itself.
+    #[must_use]
+    pub fn transitive_dependents(&self, nid: NodeId) -> Vec<NodeId> {
+        let mut nodes = self.traverse([nid], Self::dependents, |_| true);
+        nodes.retain(|n| *n != nid);
+        nodes
+    }
+
+    /// Returns `changed` and all nodes whose value, access mode or range may change when nodes in
+    /// `changed` change.
+    #[must_use]
+    pub fn affected_by(&self, changed: impl IntoIterator<Item = NodeId>) -> Vec<NodeId> {
+        self.traverse(changed, Self::dependents, DependencyKind::propagates_change)
+    }
+
+    /// Returns references to nodes which are not defined in the store, as tuples of the
+    /// referring node, the kind of the reference and the undefined node.
+    ///
+    /// References are verified when the store is built from XML, but stores built or modified in
+    /// other ways may have dangling references.
+    ///
+    /// NOTE: A `pSelected` reference is reported as [`DependencyKind::Selector`] whose referring
+    /// node is the selector.
+    #[must_use]
+    pub fn dangling_references(&self) -> &[(NodeId, DependencyKind, NodeId)] {
+        &self.dangling
+    }
+
+    /// Returns cycles in the graph, i.e. strongly connected components which consist of more than
+    /// one node, or of a node which depends on itself.
+    ///
+    /// Only dependencies evaluated together with the node are considered, i.e. kinds which
+    /// [`DependencyKind::propagates_change`] except [`DependencyKind::Invalidator`]. Other
+    /// references such as mutual `pInvalidator`s or `pPort`s never make the evaluation loop.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            indices: HashMap::new(),
+            stack: vec![],
+            on_stack: HashSet::new(),
+            cycles: vec![],
+        };
+        for nid in self.dependencies.keys() {
+            if !tarjan.indices.contains_key(nid) {
+                tarjan.visit(*nid);
+            }
+        }
+        tarjan.cycles
+    }
+
+    /// Returns the whole graph in Graphviz DOT format.
+    ///
+    /// Nodes without any dependencies and dependents are omitted.
+    #[must_use]
+    pub fn to_dot(&self, store: &impl NodeStore) -> String {
+        let nodes = self.dependencies.keys().chain(self.dependents.keys());
+        self.subgraph_to_dot(store, nodes.copied())
+    }
+
+    /// Returns the subgraph which consists of `nodes` and dependencies between them in Graphviz
+    /// DOT format.
+    ///
+    /// Edges are directed from dependents to dependencies and labeled with
+    /// [`DependencyKind`], nodes which are not defined in the store are drawn with dashed lines.
+    #[must_use]
+    pub fn subgraph_to_dot(
+        &self,
+        store: &impl NodeStore,
+        nodes: impl IntoIterator<Item = NodeId>,
+    ) -> String {
+        let nodes: HashSet<_> = nodes.into_iter().collect();
+        let mut sorted: Vec<_> = nodes
+            .iter()
+            .map(|nid| (dot_id(store, *nid), *nid))
+            .collect();
+        sorted.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        let mut dot = String::from("digraph {\n");
+        for (id, nid) in &sorted {
+            if store.node_opt(*nid).is_some() {
+                writeln!(dot, "    {};", id).unwrap();
+            } else {
+                writeln!(dot, "    {} [style=dashed];", id).unwrap();
+            }
+        }
+        for (id, nid) in &sorted {
+            for (kind, dependency) in self.dependencies(*nid) {
+                if nodes.contains(dependency) {
+                    let dependency = dot_id(store, *dependency);
+                    writeln!(dot, "    {} -> {} [label=\"{:?}\"];", id, dependency, kind).unwrap();
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns nodes reachable from `start` through `edges` of kinds accepted by `filter`,
+    /// including `start`.
+    fn traverse<'a>(
+        &'a self,
+        start: impl IntoIterator<Item = NodeId>,
+        edges: impl Fn(&'a Self, NodeId) -> &'a [(DependencyKind, NodeId)],
+        filter: impl Fn(DependencyKind) -> bool,
+    ) -> Vec<NodeId> {
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<_> = start.into_iter().collect();
+        let mut nodes = vec![];
+        while let Some(nid) = queue.pop_front() {
+            if !visited.insert(nid) {
+                continue;
+            }
+            nodes.push(nid);
+            queue.extend(
+                edges(self, nid)
+                    .iter()
+                    .filter(|(kind, _)| filter(*kind))
+                    .map(|(_, next)| *next),
+            );
+        }
+        nodes
+    }
+
+    fn add(&mut self, dependent: NodeId, kind: DependencyKind, dependency: NodeId) {
+        self.dependencies
+            .entry(dependent)
+            .or_default()
+            .push((kind, dependency));
+        self.dependents
+            .entry(dependency)
+            .or_default()
+            .push((kind, dependent));
+    }
+}
+
+/// Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    graph: &'a DependencyGraph,
+    /// Index and lowlink of visited nodes.
+    indices: HashMap<NodeId, (usize, usize)>,
+    stack: Vec<NodeId>,
+    on_stack: HashSet<NodeId>,
+    cycles: Vec<Vec<NodeId>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn evaluated_dependencies(
+        &self,
+        nid: NodeId,
+    ) -> impl Iterator<Item = &'a (DependencyKind, NodeId)> {
+        self.graph
+            .dependencies(nid)
+            .iter()
+            .filter(|(kind, _)| kind.is_evaluated())
+    }
+
+    fn visit(&mut self, nid: NodeId) {
+        let index = self.indices.len();
+        let mut lowlink = index;
+        self.indices.insert(nid, (index, lowlink));
+        self.stack.push(nid);
+        self.on_stack.insert(nid);
+
+        for (_, dependency) in self.evaluated_dependencies(nid) {
+            if let Some((dependency_index, _)) = self.indices.get(dependency) {
+                if self.on_stack.contains(dependency) {
+                    lowlink = lowlink.min(*dependency_index);
+                }
+            } else {
+                self.visit(*dependency);
+                lowlink = lowlink.min(self.indices[dependency].1);
+            }
+        }
+        self.indices.insert(nid, (index, lowlink));
+
+        if lowlink == index {
+            let mut component = vec![];
+            loop {
+                let member = self.stack.pop().unwrap();
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == nid {
+                    break;
+                }
+            }
+            let is_self_loop = self
+                .evaluated_dependencies(nid)
+                .any(|(_, dependency)| *dependency == nid);
+            if component.len() > 1 || is_self_loop {
+                self.cycles.push(component);
+            }
+        }
+    }
+}
+
+fn dot_id(store: &impl NodeStore, nid: NodeId) -> String {
+    let name = store
+        .name_by_id(nid)
+        .map_or_else(|| format!("{:?}", nid), ToString::to_string);
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Records changed nodes and notifies subscribers of them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChangeNotifier {
+    changed: Vec<NodeId>,
+    subscribers: Vec<mpsc::Sender<NodeId>>,
+    /// Built lazily because it's not needed until someone subscribes.
+    graph: Option<Arc<DependencyGraph>>,
+}
+
+impl ChangeNotifier {
+    pub(crate) fn subscribe(&mut self) -> mpsc::Receiver<NodeId> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    pub(crate) fn mark_changed(&mut self, nid: NodeId) {
+        if !self.subscribers.is_empty() {
+            self.changed.push(nid);
+        }
+    }
+
+    pub(crate) fn notify(&mut self, store: &impl NodeStore) {
+        if self.changed.is_empty() {
+            return;
+        }
+        let graph = self
+            .graph
+            .get_or_insert_with(|| Arc::new(DependencyGraph::new(store)));
+        let affected = graph.affected_by(self.changed.drain(..));
+        self.subscribers
+            .retain(|tx| affected.iter().all(|nid| tx.send(*nid).is_ok()));
+    }
+}
+
+fn visit_references(node: &NodeData, mut f: impl FnMut(DependencyKind, NodeId)) {
+    use DependencyKind as K;
+
+    match node {
+        NodeData::Node(n) => visit_elem_base(&n.elem_base, &mut f),
+        NodeData::Category(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.p_features.iter().for_each(|nid| f(K::Feature, *nid));
+        }
+        NodeData::Integer(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_value_kind(&n.value_kind, &mut f);
+            visit_pnode(K::Min, n.min.pnode(), &mut f);
+            visit_pnode(K::Max, n.max.pnode(), &mut f);
+            visit_pnode(K::Inc, n.inc.pnode(), &mut f);
+            n.p_selected.iter().for_each(|nid| f(K::Selector, *nid));
+        }
+        NodeData::IntReg(n) => {
+            visit_register_base(&n.register_base, &mut f);
+            n.p_selected.iter().for_each(|nid| f(K::Selector, *nid));
+        }
+        NodeData::MaskedIntReg(n) => {
+            visit_register_base(&n.register_base, &mut f);
+            n.p_selected.iter().for_each(|nid| f(K::Selector, *nid));
+        }
+        NodeData::Boolean(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_pnode(K::Value, n.value.pnode(), &mut f);
+            n.p_selected.iter().for_each(|nid| f(K::Selector, *nid));
+        }
+        NodeData::Command(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_pnode(K::Value, n.value.pnode(), &mut f);
+            visit_pnode(K::CommandValue, n.command_value.pnode(), &mut f);
+        }
+        NodeData::Enumeration(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.entries.iter().for_each(|nid| f(K::Entry, *nid));
+            visit_pnode(K::Value, n.value.pnode(), &mut f);
+            n.p_selected.iter().for_each(|nid| f(K::Selector, *nid));
+        }
+        NodeData::EnumEntry(n) => visit_elem_base(&n.elem_base, &mut f),
+        NodeData::Float(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_value_kind(&n.value_kind, &mut f);
+            visit_pnode(K::Min, n.min.pnode(), &mut f);
+            visit_pnode(K::Max, n.max.pnode(), &mut f);
+            visit_pnode(K::Inc, n.inc.and_then(ImmOrPNode::pnode), &mut f);
+        }
+        NodeData::FloatReg(n) => visit_register_base(&n.register_base, &mut f),
+        NodeData::String(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_pnode(K::Value, n.value.pnode(), &mut f);
+        }
+        NodeData::StringReg(n) => visit_register_base(&n.register_base, &mut f),
+        NodeData::Register(n) => visit_register_base(&n.register_base, &mut f),
+        NodeData::Converter(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.p_variables.iter().for_each(|v| f(K::Variable, v.value()));
+            f(K::Value, n.p_value);
+        }
+        NodeData::IntConverter(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.p_variables.iter().for_each(|v| f(K::Variable, v.value()));
+            f(K::Value, n.p_value);
+        }
+        NodeData::SwissKnife(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.p_variables.iter().for_each(|v| f(K::Variable, v.value()));
+        }
+        NodeData::IntSwissKnife(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            n.p_variables.iter().for_each(|v| f(K::Variable, v.value()));
+        }
+        NodeData::Port(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            visit_pnode(K::ChunkId, n.chunk_id.and_then(ImmOrPNode::pnode), &mut f);
+        }
+        // Entries of the configuration ROM are visited from the entries themselves.
+        NodeData::ConfRom(n) => visit_register_base(&n.register_base, &mut f),
+        NodeData::TextDesc(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            f(K::ConfRom, n.conf_rom);
+        }
+        NodeData::IntKey(n) => {
+            visit_elem_base(&n.elem_base, &mut f);
+            f(K::ConfRom, n.conf_rom);
+        }
+        NodeData::AdvFeatureLock(n) => visit_register_base(&n.register_base, &mut f),
+        NodeData::SmartFeature(n) => {
+            visit_register_base(&n.register_base, &mut f);
+            visit_pnode(K::AdvFeatureLock, n.p_adv_feature_lock, &mut f);
+        }
+    }
+}
+
+fn visit_elem_base(elem_base: &NodeElementBase, f: &mut impl FnMut(DependencyKind, NodeId)) {
+    use DependencyKind as K;
+
+    let optional = [
+        (K::IsImplemented, elem_base.p_is_implemented),
+        (K::IsAvailable, elem_base.p_is_available),
+        (K::IsLocked, elem_base.p_is_locked),
+        (K::BlockPolling, elem_base.p_block_polling),
+        (K::Alias, elem_base.p_alias),
+        (K::CastAlias, elem_base.p_cast_alias),
+    ];
+    for (kind, p_node) in optional {
+        visit_pnode(kind, p_node, f);
+    }
+    elem_base.p_errors.iter().for_each(|nid| f(K::Error, *nid));
+}
+
+fn visit_register_base(register_base: &RegisterBase, f: &mut impl FnMut(DependencyKind, NodeId)) {
+    use DependencyKind as K;
+
+    visit_elem_base(&register_base.elem_base, f);
+    for address_kind in &register_base.address_kinds {
+        match address_kind {
+            AddressKind::Address(address) => visit_pnode(K::Address, address.pnode(), f),
+            AddressKind::IntSwissKnife(nid) => f(K::Address, *nid),
+            AddressKind::PIndex(p_index) => {
+                f(K::Index, p_index.p_index);
+                visit_pnode(K::Address, p_index.offset.and_then(ImmOrPNode::pnode), f);
+            }
+        }
+    }
+    visit_pnode(K::Length, register_base.length.pnode(), f);
+    f(K::Port, register_base.p_port);
+    register_base
+        .p_invalidators
+        .iter()
+        .for_each(|nid| f(K::Invalidator, *nid));
+}
+
+fn visit_value_kind<T: Copy>(
+    value_kind: &ValueKind<T>,
+    f: &mut impl FnMut(DependencyKind, NodeId),
+) {
+    use DependencyKind as K;
+
+    match value_kind {
+        ValueKind::Value(_) => {}
+        ValueKind::PValue(p_value) => {
+            f(K::Value, p_value.p_value);
+            p_value
+                .p_value_copies
+                .iter()
+                .for_each(|nid| f(K::ValueCopy, *nid));
+        }
+        ValueKind::PIndex(p_index) => {
+            f(K::Index, p_index.p_index);
+            for value_indexed in &p_index.value_indexed {
+                visit_pnode(K::Value, value_indexed.indexed.pnode(), f);
+            }
+            visit_pnode(K::Value, p_index.value_default.pnode(), f);
+        }
+    }
+}
+
+fn visit_pnode(
+    kind: DependencyKind,
+    p_node: Option<NodeId>,
+    f: &mut impl FnMut(DependencyKind, NodeId),
+) {
+    if let Some(nid) = p_node {
+        f(kind, nid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::{GenApiBuilder, NodeStoreBuilder},
+            interface::IInteger,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    fn xml() -> String {
+        register_description(
+            r#"<IntReg Name="BinningReg">
+                   <Address>0</Address>
+                   <Length>4</Length>
+                   <pPort>Device</pPort>
+                   <Cachable>WriteThrough</Cachable>
+                   <Endianess>LittleEndian</Endianess>
+                 </IntReg>
+                 <Integer Name="Binning">
+                   <pValue>BinningReg</pValue>
+                   <Min>1</Min>
+                   <Max>4</Max>
+                 </Integer>
+                 <IntSwissKnife Name="WidthMax">
+                   <pVariable Name="B">Binning</pVariable>
+                   <Formula>1024 / B</Formula>
+                 </IntSwissKnife>
+                 <Integer Name="Width">
+                   <Value>100</Value>
+                   <pMax>WidthMax</pMax>
+                 </Integer>
+                 <Integer Name="GainSelector">
+                   <Value>0</Value>
+                   <pSelected>Gain</pSelected>
+                 </Integer>
+                 <Integer Name="Gain">
+                   <Value>0</Value>
+                 </Integer>"#,
+        )
+    }
+
+    fn names(store: &DefaultNodeStore, nids: impl IntoIterator<Item = NodeId>) -> Vec<&str> {
+        let mut names: Vec<_> = nids
+            .into_iter()
+            .map(|nid| store.name_by_id(nid).unwrap())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_dependency_graph() {
+        let (_, store, _) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml())
+            .unwrap();
+        let graph = DependencyGraph::new(&store);
+        let nid = |name| store.id_by_name(name).unwrap();
+
+        assert_eq!(
+            graph.dependencies(nid("Width")),
+            &[(DependencyKind::Max, nid("WidthMax"))]
+        );
+        assert_eq!(
+            graph.dependencies(nid("Gain")),
+            &[(DependencyKind::Selector, nid("GainSelector"))]
+        );
+        assert_eq!(
+            graph.dependents(nid("Binning")),
+            &[(DependencyKind::Variable, nid("WidthMax"))]
+        );
+        assert_eq!(
+            names(&store, graph.affected_by([nid("BinningReg")])),
+            &["Binning", "BinningReg", "Width", "WidthMax"]
+        );
+    }
+
+    #[test]
+    fn test_transitive_dependencies() {
+        let (_, store, _) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml())
+            .unwrap();
+        let graph = DependencyGraph::new(&store);
+        let nid = |name| store.id_by_name(name).unwrap();
+
+        assert_eq!(
+            names(&store, graph.transitive_dependencies(nid("Width"))),
+            &["Binning", "BinningReg", "Device", "WidthMax"]
+        );
+        assert_eq!(
+            names(&store, graph.transitive_dependents(nid("BinningReg"))),
+            &["Binning", "Width", "WidthMax"]
+        );
+        assert!(graph.cycles().is_empty());
+        assert!(graph.dangling_references().is_empty());
+    }
+
+    #[test]
+    fn test_cycles_and_dangling_references() {
+        let xml = register_description(
+            r#"<Integer Name="A"><pValue>B</pValue></Integer>
+                 <Integer Name="B"><pValue>C</pValue></Integer>
+                 <Integer Name="C"><pValue>A</pValue></Integer>
+                 <Integer Name="Self"><pIsLocked>Self</pIsLocked><Value>0</Value></Integer>
+                 <Integer Name="D"><Value>0</Value><pMax>Max</pMax></Integer>
+                 <Integer Name="Max"><Value>10</Value></Integer>
+                 <IntReg Name="RegA">
+                   <Address>0</Address>
+                   <Length>4</Length>
+                   <pPort>Device</pPort>
+                   <pInvalidator>RegB</pInvalidator>
+                 </IntReg>
+                 <IntReg Name="RegB">
+                   <Address>4</Address>
+                   <Length>4</Length>
+                   <pPort>Device</pPort>
+                   <pInvalidator>RegA</pInvalidator>
+                 </IntReg>"#,
+        );
+        let (_, mut store, _) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        // References are verified on parsing, so make a dangling one by hand.
+        let d = store.id_by_name("D").unwrap();
+        let copied = store.get_or_intern("Copied");
+        let missing = store.get_or_intern("Missing");
+        let mut node = store.node(d).clone();
+        if let NodeData::Integer(n) = &mut node {
+            n.attr_base.id = copied;
+            n.max = ImmOrPNode::PNode(missing);
+        }
+        store.store_node(copied, node);
+
+        let graph = DependencyGraph::new(&store);
+        let nid = |name| store.id_by_name(name).unwrap();
+
+        let mut cycles: Vec<_> = graph
+            .cycles()
+            .into_iter()
+            .map(|cycle| names(&store, cycle))
+            .collect();
+        cycles.sort();
+        // Registers invalidating each other don't make a cycle.
+        assert_eq!(cycles, vec![vec!["A", "B", "C"], vec!["Self"]]);
+
+        assert_eq!(
+            graph.dangling_references(),
+            &[(nid("Copied"), DependencyKind::Max, nid("Missing"))]
+        );
+        assert_eq!(
+            graph.subgraph_to_dot(&store, [nid("Copied"), nid("Missing")]),
+            "digraph {\n    \"Copied\";\n    \"Missing\" [style=dashed];\n    \"Copied\" -> \"Missing\" [label=\"Max\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_notify_changes() {
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml())
+            .unwrap();
+        let mut device = TestDevice::new(vec![0; 4]);
+        let nid = |name| store.id_by_name(name).unwrap();
+        let rx = cx.subscribe();
+
+        let binning = nid("Binning").expect_iinteger_kind(&store).unwrap();
+        binning.set_value(2, &mut device, &store, &mut cx).unwrap();
+        // Nothing is sent until changes are notified.
+        assert!(rx.try_recv().is_err());
+        cx.notify_changes(&store);
+        // The port is also written.
+        assert_eq!(
+            names(&store, rx.try_iter()),
+            &["Binning", "BinningReg", "Device", "Width", "WidthMax"]
+        );
+
+        let selector = nid("GainSelector").expect_iinteger_kind(&store).unwrap();
+        selector.set_value(1, &mut device, &store, &mut cx).unwrap();
+        cx.notify_changes(&store);
+        assert_eq!(names(&store, rx.try_iter()), &["Gain", "GainSelector"]);
+
+        cx.mark_changed(nid("Gain"));
+        cx.notify_changes(&store);
+        assert_eq!(names(&store, rx.try_iter()), &["Gain"]);
+    }
+}
@@ -8,9 +8,15 @@ use super::{
     ivalue::IValue,
     node_base::{NodeAttributeBase, NodeBase, NodeElementBase},
     store::{CacheStore, FloatId, NodeStore, ValueStore},
-    Device, GenApiResult, ValueCtxt,
+    Device, GenApiError, GenApiResult, ValueCtxt,
 };
 
+/// Relative tolerance to match a value with an entry of `ValidValueSet`.
+///
+/// Values written by users often differ from the entries in the last few bits, e.g. when they are
+/// computed or parsed from strings.
+const VALID_VALUE_TOLERANCE: f64 = 1e-9;
+
 #[derive(Debug, Clone)]
 pub struct FloatNode {
     pub(crate) attr_base: NodeAttributeBase,
@@ -21,6 +27,7 @@ pub struct FloatNode {
     pub(crate) min: ImmOrPNode<FloatId>,
     pub(crate) max: ImmOrPNode<FloatId>,
     pub(crate) inc: Option<ImmOrPNode<f64>>,
+    pub(crate) valid_value_set: Vec<f64>,
     pub(crate) unit: Option<String>,
     pub(crate) representation: FloatRepresentation,
     pub(crate) display_notation: DisplayNotation,
@@ -48,6 +55,27 @@ impl FloatNode {
         self.inc.as_ref()
     }
 
+    #[must_use]
+    pub fn valid_value_set_elem(&self) -> &[f64] {
+        &self.valid_value_set
+    }
+
+    /// Returns the entry of `ValidValueSet` nearest to `value` if they match within
+    /// [`VALID_VALUE_TOLERANCE`] relative to the larger magnitude of them.
+    fn nearest_valid_value(&self, value: f64) -> Option<f64> {
+        let nearest = self
+            .valid_value_set
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))?;
+        let tolerance = VALID_VALUE_TOLERANCE * nearest.abs().max(value.abs());
+        if (nearest - value).abs() <= tolerance {
+            Some(nearest)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn unit_elem(&self) -> Option<&str> {
         self.unit.as_deref()
@@ -102,6 +130,15 @@ impl IFloat for FloatNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<()> {
+        let value = if self.valid_value_set.is_empty() {
+            value
+        } else {
+            self.nearest_valid_value(value).ok_or_else(|| {
+                GenApiError::invalid_data(
+                    format!("`{}` is not in the valid value set", value).into(),
+                )
+            })?
+        };
         cx.invalidate_cache_by(self.node_base().id());
         self.value_kind.set_value(value, device, store, cx)
     }
@@ -131,7 +168,11 @@ impl IFloat for FloatNode {
     }
 
     fn inc_mode(&self, _store: &impl NodeStore) -> Option<IncrementMode> {
-        Some(IncrementMode::FixedIncrement)
+        if self.valid_value_set.is_empty() {
+            Some(IncrementMode::FixedIncrement)
+        } else {
+            Some(IncrementMode::ListIncrement)
+        }
     }
 
     #[tracing::instrument(skip(self, device, store, cx),
@@ -146,6 +187,10 @@ impl IFloat for FloatNode {
         self.inc.map(|n| n.value(device, store, cx)).transpose()
     }
 
+    fn valid_value_set(&self, _store: &impl NodeStore) -> &[f64] {
+        &self.valid_value_set
+    }
+
     fn representation(&self, _store: &impl NodeStore) -> FloatRepresentation {
         self.representation_elem()
     }
@@ -214,3 +259,69 @@ impl IFloat for FloatNode {
             && self.value_kind.is_writable(device, store, cx)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    #[test]
+    fn test_valid_value_set() {
+        let xml = register_description(
+            r#"<Float Name="FrameRate">
+                   <Value>30.0</Value>
+                   <ValidValueSet>0.0;15.0;29.97;30.0</ValidValueSet>
+                 </Float>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = TestDevice::new(vec![]);
+
+        let frame_rate = store
+            .id_by_name("FrameRate")
+            .unwrap()
+            .expect_ifloat_kind(&store)
+            .unwrap();
+        assert!(matches!(
+            frame_rate.inc_mode(&store),
+            Some(IncrementMode::ListIncrement)
+        ));
+        assert_eq!(
+            frame_rate.valid_value_set(&store),
+            &[0.0, 15.0, 29.97, 30.0]
+        );
+        assert_eq!(frame_rate.inc(&mut device, &store, &mut cx).unwrap(), None);
+
+        frame_rate
+            .set_value(29.97, &mut device, &store, &mut cx)
+            .unwrap();
+        assert!(frame_rate
+            .set_value(25.0, &mut device, &store, &mut cx)
+            .is_err());
+
+        // Values which differ from an entry only by rounding errors are set to the entry.
+        let value: f64 = 29.97 + 1e-12;
+        assert_ne!(value.to_bits(), 29.97_f64.to_bits());
+        frame_rate
+            .set_value(value, &mut device, &store, &mut cx)
+            .unwrap();
+        let value = frame_rate.value(&mut device, &store, &mut cx).unwrap();
+        assert_eq!(value.to_bits(), 29.97_f64.to_bits());
+        frame_rate
+            .set_value(0.0, &mut device, &store, &mut cx)
+            .unwrap();
+        assert!(frame_rate
+            .set_value(29.98, &mut device, &store, &mut cx)
+            .is_err());
+        assert!(frame_rate
+            .set_value(1e-12, &mut device, &store, &mut cx)
+            .is_err());
+    }
+}
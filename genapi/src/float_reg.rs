@@ -135,6 +135,10 @@ impl IFloat for FloatRegNode {
         Ok(None)
     }
 
+    fn valid_value_set(&self, _: &impl NodeStore) -> &[f64] {
+        &[]
+    }
+
     fn representation(&self, _: &impl NodeStore) -> FloatRepresentation {
         self.representation
     }
@@ -10,6 +10,7 @@
 
 use std::{borrow::Borrow, collections::HashMap, fmt, hash::Hash, str::FromStr};
 
+use thiserror::Error;
 use tracing::debug;
 
 use super::{GenApiError, GenApiResult};
@@ -17,9 +18,40 @@ use super::{GenApiError, GenApiResult};
 #[derive(Debug, Clone, PartialEq)]
 pub struct Formula {
     pub(crate) expr: Expr,
+    compiled: CompiledExpr,
+    /// Sorted slots which the compiled formula refers to.
+    used_slots: Vec<usize>,
 }
 
 impl Formula {
+    /// Compiles `expr`.
+    ///
+    /// `slots` are names of variables whose values are given on evaluation, `definitions` are
+    /// constants and expressions which are inlined. Later definitions shadow earlier ones and
+    /// definitions shadow slots.
+    pub(crate) fn compile(
+        expr: Expr,
+        slots: &[&str],
+        definitions: &[(&str, Expr)],
+    ) -> Result<Self, CompileError> {
+        let mut compiler = Compiler {
+            slots,
+            definitions,
+            inlining: vec![],
+        };
+        let compiled = compiler.compile(&expr)?;
+        // Slots which are folded away aren't needed to be read.
+        let mut used_slots = vec![];
+        compiled.collect_slots(&mut used_slots);
+        used_slots.sort_unstable();
+        used_slots.dedup();
+        Ok(Self {
+            expr,
+            compiled,
+            used_slots,
+        })
+    }
+
     #[must_use]
     pub fn expr(&self) -> &Expr {
         &self.expr
@@ -32,6 +64,20 @@ impl Formula {
     {
         self.expr.eval(var_env)
     }
+
+    /// Evaluates the compiled formula, `slots` must contain values of all slots which the
+    /// formula refers to.
+    pub(crate) fn eval_compiled(
+        &self,
+        slots: &[EvaluationResult],
+    ) -> GenApiResult<EvaluationResult> {
+        self.compiled.eval(slots)
+    }
+
+    /// Returns sorted slots which the compiled formula refers to.
+    pub(crate) fn used_slots(&self) -> &[usize] {
+        &self.used_slots
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -95,6 +141,15 @@ impl From<f64> for EvaluationResult {
     }
 }
 
+impl From<EvaluationResult> for Expr {
+    fn from(res: EvaluationResult) -> Self {
+        match res {
+            EvaluationResult::Integer(i) => Self::Integer(i),
+            EvaluationResult::Float(f) => Self::Float(f),
+        }
+    }
+}
+
 impl From<bool> for EvaluationResult {
     fn from(b: bool) -> Self {
         if b {
@@ -165,6 +220,35 @@ impl Expr {
         }
     }
 
+    /// Returns names of variables which the expression refers to in order of appearance.
+    #[must_use]
+    pub fn variables(&self) -> Vec<&str> {
+        let mut vars = vec![];
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a str>) {
+        match self {
+            Self::BinOp { lhs, rhs, .. } => {
+                lhs.collect_variables(vars);
+                rhs.collect_variables(vars);
+            }
+            Self::UnOp { expr, .. } => expr.collect_variables(vars),
+            Self::If { cond, then, else_ } => {
+                cond.collect_variables(vars);
+                then.collect_variables(vars);
+                else_.collect_variables(vars);
+            }
+            Self::Ident(s) => {
+                if !vars.contains(&s.as_str()) {
+                    vars.push(s);
+                }
+            }
+            Self::Integer(_) | Self::Float(_) => {}
+        }
+    }
+
     fn eval_binop<K, V>(
         &self,
         op: BinOpKind,
@@ -175,80 +259,15 @@ impl Expr {
         K: Borrow<str> + Eq + Hash + fmt::Debug,
         V: Borrow<Expr> + fmt::Debug,
     {
-        use std::ops::{Add, Mul, Rem, Sub};
-
-        Ok(match op {
+        match op {
             BinOpKind::And => {
-                (self.eval(var_env)?.as_bool() && rhs.eval(var_env)?.as_bool()).into()
+                Ok((self.eval(var_env)?.as_bool() && rhs.eval(var_env)?.as_bool()).into())
             }
-            BinOpKind::Or => (self.eval(var_env)?.as_bool() || rhs.eval(var_env)?.as_bool()).into(),
-
-            _ => {
-                let lhs = self.eval(var_env)?;
-                let rhs = rhs.eval(var_env)?;
-
-                macro_rules! apply_arithmetic_op {
-                    ($fint:ident, $ffloat:ident) => {{
-                        if lhs.is_integer() && rhs.is_integer() {
-                            (lhs.as_integer().$fint(rhs.as_integer())).0.into()
-                        } else {
-                            (lhs.as_float().$ffloat(rhs.as_float())).into()
-                        }
-                    }};
-                }
-
-                macro_rules! apply_cmp_op {
-                    ($fint:ident, $ffloat:ident) => {{
-                        if lhs.is_integer() && rhs.is_integer() {
-                            (lhs.as_integer().$fint(&rhs.as_integer())).into()
-                        } else {
-                            (lhs.as_float().$ffloat(&rhs.as_float())).into()
-                        }
-                    }};
-                }
-                match op {
-                    BinOpKind::Add => apply_arithmetic_op!(overflowing_add, add),
-                    BinOpKind::Sub => apply_arithmetic_op!(overflowing_sub, sub),
-                    BinOpKind::Mul => apply_arithmetic_op!(overflowing_mul, mul),
-                    BinOpKind::Div => {
-                        // Division must be treated as floating points.
-                        // e.g. Converter node with `<FormulaFrom>TO/(1&lt;&lt;P1)</FormulaFrom>` where `P1` points to integer node are commonplace.
-                        (lhs.as_float() / rhs.as_float()).into()
-                    }
-                    BinOpKind::Rem => apply_arithmetic_op!(overflowing_rem, rem),
-                    BinOpKind::Pow => {
-                        if lhs.is_integer() && rhs.is_integer() && rhs.as_integer() >= 0 {
-                            lhs.as_integer()
-                                .overflowing_pow(rhs.as_integer() as u32)
-                                .0
-                                .into()
-                        } else {
-                            lhs.as_float().powf(rhs.as_float()).into()
-                        }
-                    }
-                    BinOpKind::Eq => apply_cmp_op!(eq, eq),
-                    BinOpKind::Ne => apply_cmp_op!(ne, ne),
-                    BinOpKind::Lt => apply_cmp_op!(lt, lt),
-                    BinOpKind::Le => apply_cmp_op!(le, le),
-                    BinOpKind::Gt => apply_cmp_op!(gt, gt),
-                    BinOpKind::Ge => apply_cmp_op!(ge, ge),
-                    BinOpKind::Shl => lhs
-                        .as_integer()
-                        .overflowing_shl(rhs.as_integer() as u32)
-                        .0
-                        .into(),
-                    BinOpKind::Shr => lhs
-                        .as_integer()
-                        .overflowing_shr(rhs.as_integer() as u32)
-                        .0
-                        .into(),
-                    BinOpKind::BitAnd => (lhs.as_integer() & rhs.as_integer()).into(),
-                    BinOpKind::BitOr => (lhs.as_integer() | rhs.as_integer()).into(),
-                    BinOpKind::Xor => (lhs.as_integer() ^ rhs.as_integer()).into(),
-                    _ => unreachable!(),
-                }
+            BinOpKind::Or => {
+                Ok((self.eval(var_env)?.as_bool() || rhs.eval(var_env)?.as_bool()).into())
             }
-        })
+            _ => apply_binop(op, self.eval(var_env)?, rhs.eval(var_env)?),
+        }
     }
 
     fn eval_unop<K, V>(
@@ -260,39 +279,292 @@ impl Expr {
         K: Borrow<str> + Eq + Hash + fmt::Debug,
         V: Borrow<Expr> + fmt::Debug,
     {
-        use std::ops::Neg;
-
-        let res = self.eval(var_env)?;
-        macro_rules! apply_op {
-            ($f:ident) => {
-                match res {
-                    EvaluationResult::Integer(i) => EvaluationResult::from(i.$f()),
-                    EvaluationResult::Float(f) => EvaluationResult::from(f.$f()),
+        Ok(apply_unop(op, self.eval(var_env)?))
+    }
+}
+
+/// A formula compiled for evaluation.
+///
+/// Constants and expressions are inlined, subtrees which don't depend on variables are folded,
+/// and variables are resolved to slots whose values are given at evaluation time.
+#[derive(Debug, Clone, PartialEq)]
+enum CompiledExpr {
+    BinOp {
+        kind: BinOpKind,
+        lhs: Box<CompiledExpr>,
+        rhs: Box<CompiledExpr>,
+    },
+    UnOp {
+        kind: UnOpKind,
+        expr: Box<CompiledExpr>,
+    },
+    If {
+        cond: Box<CompiledExpr>,
+        then: Box<CompiledExpr>,
+        else_: Box<CompiledExpr>,
+    },
+    Const(EvaluationResult),
+    Slot(usize),
+}
+
+/// An error which occurs when compiling a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CompileError {
+    /// The formula refers to a name which is neither a slot nor a definition.
+    Undefined(String),
+    /// The definition refers to itself directly or indirectly.
+    Cyclic(String),
+}
+
+impl CompiledExpr {
+    fn eval(&self, slots: &[EvaluationResult]) -> GenApiResult<EvaluationResult> {
+        match self {
+            Self::BinOp {
+                kind: BinOpKind::And,
+                lhs,
+                rhs,
+            } => Ok((lhs.eval(slots)?.as_bool() && rhs.eval(slots)?.as_bool()).into()),
+            Self::BinOp {
+                kind: BinOpKind::Or,
+                lhs,
+                rhs,
+            } => Ok((lhs.eval(slots)?.as_bool() || rhs.eval(slots)?.as_bool()).into()),
+            Self::BinOp { kind, lhs, rhs } => {
+                apply_binop(*kind, lhs.eval(slots)?, rhs.eval(slots)?)
+            }
+            Self::UnOp { kind, expr } => Ok(apply_unop(*kind, expr.eval(slots)?)),
+            Self::If { cond, then, else_ } => {
+                if cond.eval(slots)?.as_bool() {
+                    then.eval(slots)
+                } else {
+                    else_.eval(slots)
                 }
-            };
+            }
+            Self::Const(res) => Ok(*res),
+            Self::Slot(i) => Ok(slots[*i]),
         }
+    }
 
-        Ok(match op {
-            UnOpKind::Not => (!res.as_integer()).into(),
-            UnOpKind::Abs => apply_op!(abs),
-            UnOpKind::Sgn => apply_op!(signum),
-            UnOpKind::Neg => apply_op!(neg),
-            UnOpKind::Sin => res.as_float().sin().into(),
-            UnOpKind::Cos => res.as_float().cos().into(),
-            UnOpKind::Tan => res.as_float().tan().into(),
-            UnOpKind::Asin => res.as_float().asin().into(),
-            UnOpKind::Acos => res.as_float().acos().into(),
-            UnOpKind::Atan => res.as_float().atan().into(),
-            UnOpKind::Exp => res.as_float().exp().into(),
-            UnOpKind::Ln => res.as_float().ln().into(),
-            UnOpKind::Lg => res.as_float().log10().into(),
-            UnOpKind::Sqrt => res.as_float().sqrt().into(),
-            UnOpKind::Trunc => res.as_float().trunc().into(),
-            UnOpKind::Floor => res.as_float().floor().into(),
-            UnOpKind::Ceil => res.as_float().ceil().into(),
-            UnOpKind::Round => res.as_float().round().into(),
+    fn collect_slots(&self, slots: &mut Vec<usize>) {
+        match self {
+            Self::BinOp { lhs, rhs, .. } => {
+                lhs.collect_slots(slots);
+                rhs.collect_slots(slots);
+            }
+            Self::UnOp { expr, .. } => expr.collect_slots(slots),
+            Self::If { cond, then, else_ } => {
+                cond.collect_slots(slots);
+                then.collect_slots(slots);
+                else_.collect_slots(slots);
+            }
+            Self::Const(_) => {}
+            Self::Slot(i) => slots.push(*i),
+        }
+    }
+
+    fn const_value(&self) -> Option<EvaluationResult> {
+        match self {
+            Self::Const(res) => Some(*res),
+            _ => None,
+        }
+    }
+}
+
+/// Compiles `expr`, see [`Formula::compile`].
+struct Compiler<'a> {
+    slots: &'a [&'a str],
+    definitions: &'a [(&'a str, Expr)],
+    /// Definitions being inlined, used to detect cyclic definitions.
+    inlining: Vec<&'a str>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(&mut self, expr: &'a Expr) -> Result<CompiledExpr, CompileError> {
+        Ok(match expr {
+            Expr::BinOp { kind, lhs, rhs } => {
+                let lhs = self.compile(lhs)?;
+                let rhs = self.compile(rhs)?;
+                match (*kind, lhs.const_value(), rhs.const_value()) {
+                    // `rhs` is never evaluated in these cases.
+                    (BinOpKind::And, Some(l), _) if !l.as_bool() => {
+                        CompiledExpr::Const(false.into())
+                    }
+                    (BinOpKind::Or, Some(l), _) if l.as_bool() => CompiledExpr::Const(true.into()),
+                    // Leave the expression to report the error at evaluation time if it fails.
+                    (kind, Some(l), Some(r)) => match apply_binop(kind, l, r) {
+                        Ok(res) => CompiledExpr::Const(res),
+                        Err(_) => CompiledExpr::BinOp {
+                            kind,
+                            lhs: lhs.into(),
+                            rhs: rhs.into(),
+                        },
+                    },
+                    (kind, ..) => CompiledExpr::BinOp {
+                        kind,
+                        lhs: lhs.into(),
+                        rhs: rhs.into(),
+                    },
+                }
+            }
+            Expr::UnOp { kind, expr } => {
+                let expr = self.compile(expr)?;
+                match expr.const_value() {
+                    Some(res) => CompiledExpr::Const(apply_unop(*kind, res)),
+                    None => CompiledExpr::UnOp {
+                        kind: *kind,
+                        expr: expr.into(),
+                    },
+                }
+            }
+            Expr::If { cond, then, else_ } => {
+                // Both branches are compiled to validate them even if one of them is dead.
+                let cond = self.compile(cond)?;
+                let then = self.compile(then)?;
+                let else_ = self.compile(else_)?;
+                match cond.const_value() {
+                    Some(res) if res.as_bool() => then,
+                    Some(_) => else_,
+                    None => CompiledExpr::If {
+                        cond: cond.into(),
+                        then: then.into(),
+                        else_: else_.into(),
+                    },
+                }
+            }
+            &Expr::Integer(i) => CompiledExpr::Const(i.into()),
+            &Expr::Float(f) => CompiledExpr::Const(f.into()),
+            Expr::Ident(name) => self.compile_ident(name)?,
         })
     }
+
+    fn compile_ident(&mut self, name: &'a str) -> Result<CompiledExpr, CompileError> {
+        // Later definitions shadow earlier ones, and definitions shadow slots.
+        if let Some((_, def)) = self.definitions.iter().rev().find(|(n, _)| *n == name) {
+            if self.inlining.contains(&name) {
+                return Err(CompileError::Cyclic(name.into()));
+            }
+            self.inlining.push(name);
+            let compiled = self.compile(def)?;
+            self.inlining.pop();
+            Ok(compiled)
+        } else if let Some(slot) = self.slots.iter().rposition(|n| *n == name) {
+            Ok(CompiledExpr::Slot(slot))
+        } else {
+            Err(CompileError::Undefined(name.into()))
+        }
+    }
+}
+
+fn apply_binop(
+    op: BinOpKind,
+    lhs: EvaluationResult,
+    rhs: EvaluationResult,
+) -> GenApiResult<EvaluationResult> {
+    use std::ops::{Add, Mul, Rem, Sub};
+
+    macro_rules! apply_arithmetic_op {
+        ($fint:ident, $ffloat:ident) => {{
+            if lhs.is_integer() && rhs.is_integer() {
+                (lhs.as_integer().$fint(rhs.as_integer())).0.into()
+            } else {
+                (lhs.as_float().$ffloat(rhs.as_float())).into()
+            }
+        }};
+    }
+
+    macro_rules! apply_cmp_op {
+        ($fint:ident, $ffloat:ident) => {{
+            if lhs.is_integer() && rhs.is_integer() {
+                (lhs.as_integer().$fint(&rhs.as_integer())).into()
+            } else {
+                (lhs.as_float().$ffloat(&rhs.as_float())).into()
+            }
+        }};
+    }
+
+    Ok(match op {
+        BinOpKind::And => (lhs.as_bool() && rhs.as_bool()).into(),
+        BinOpKind::Or => (lhs.as_bool() || rhs.as_bool()).into(),
+        BinOpKind::Add => apply_arithmetic_op!(overflowing_add, add),
+        BinOpKind::Sub => apply_arithmetic_op!(overflowing_sub, sub),
+        BinOpKind::Mul => apply_arithmetic_op!(overflowing_mul, mul),
+        BinOpKind::Div => {
+            // Division must be treated as floating points.
+            // e.g. Converter node with `<FormulaFrom>TO/(1&lt;&lt;P1)</FormulaFrom>` where `P1` points to integer node are commonplace.
+            (lhs.as_float() / rhs.as_float()).into()
+        }
+        BinOpKind::Rem => {
+            if lhs.is_integer() && rhs.is_integer() && rhs.as_integer() == 0 {
+                return Err(GenApiError::invalid_data(
+                    "attempt to calculate the remainder with a divisor of zero".into(),
+                ));
+            }
+            apply_arithmetic_op!(overflowing_rem, rem)
+        }
+        BinOpKind::Pow => {
+            if lhs.is_integer() && rhs.is_integer() && rhs.as_integer() >= 0 {
+                lhs.as_integer()
+                    .overflowing_pow(rhs.as_integer() as u32)
+                    .0
+                    .into()
+            } else {
+                lhs.as_float().powf(rhs.as_float()).into()
+            }
+        }
+        BinOpKind::Eq => apply_cmp_op!(eq, eq),
+        BinOpKind::Ne => apply_cmp_op!(ne, ne),
+        BinOpKind::Lt => apply_cmp_op!(lt, lt),
+        BinOpKind::Le => apply_cmp_op!(le, le),
+        BinOpKind::Gt => apply_cmp_op!(gt, gt),
+        BinOpKind::Ge => apply_cmp_op!(ge, ge),
+        BinOpKind::Shl => lhs
+            .as_integer()
+            .overflowing_shl(rhs.as_integer() as u32)
+            .0
+            .into(),
+        BinOpKind::Shr => lhs
+            .as_integer()
+            .overflowing_shr(rhs.as_integer() as u32)
+            .0
+            .into(),
+        BinOpKind::BitAnd => (lhs.as_integer() & rhs.as_integer()).into(),
+        BinOpKind::BitOr => (lhs.as_integer() | rhs.as_integer()).into(),
+        BinOpKind::Xor => (lhs.as_integer() ^ rhs.as_integer()).into(),
+    })
+}
+
+fn apply_unop(op: UnOpKind, res: EvaluationResult) -> EvaluationResult {
+    use std::ops::Neg;
+
+    macro_rules! apply_op {
+        ($f:ident) => {
+            match res {
+                EvaluationResult::Integer(i) => EvaluationResult::from(i.$f()),
+                EvaluationResult::Float(f) => EvaluationResult::from(f.$f()),
+            }
+        };
+    }
+
+    match op {
+        UnOpKind::Not => (!res.as_integer()).into(),
+        UnOpKind::Abs => apply_op!(abs),
+        UnOpKind::Sgn => apply_op!(signum),
+        UnOpKind::Neg => apply_op!(neg),
+        UnOpKind::Sin => res.as_float().sin().into(),
+        UnOpKind::Cos => res.as_float().cos().into(),
+        UnOpKind::Tan => res.as_float().tan().into(),
+        UnOpKind::Asin => res.as_float().asin().into(),
+        UnOpKind::Acos => res.as_float().acos().into(),
+        UnOpKind::Atan => res.as_float().atan().into(),
+        UnOpKind::Exp => res.as_float().exp().into(),
+        UnOpKind::Ln => res.as_float().ln().into(),
+        UnOpKind::Lg => res.as_float().log10().into(),
+        UnOpKind::Sqrt => res.as_float().sqrt().into(),
+        UnOpKind::Trunc => res.as_float().trunc().into(),
+        UnOpKind::Floor => res.as_float().floor().into(),
+        UnOpKind::Ceil => res.as_float().ceil().into(),
+        UnOpKind::Round => res.as_float().round().into(),
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -340,12 +612,54 @@ pub enum UnOpKind {
     Round,
 }
 
-#[must_use]
+/// An error which occurs when parsing a formula.
+///
+/// `offset` is the byte offset in the formula text where the error is detected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FormulaError {
+    #[error("unexpected character `{ch}` at offset {offset}")]
+    UnexpectedChar { ch: char, offset: usize },
+
+    #[error("unexpected token `{token}` at offset {offset}")]
+    UnexpectedToken { token: String, offset: usize },
+
+    #[error("unexpected end of formula at offset {offset}")]
+    UnexpectedEnd { offset: usize },
+
+    #[error("unknown function `{name}` at offset {offset}")]
+    UnknownFunction { name: String, offset: usize },
+
+    #[error("invalid number `{number}` at offset {offset}")]
+    InvalidNumber { number: String, offset: usize },
+}
+
+impl FormulaError {
+    /// Returns the byte offset in the formula text where the error is detected.
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::UnexpectedChar { offset, .. }
+            | Self::UnexpectedToken { offset, .. }
+            | Self::UnexpectedEnd { offset }
+            | Self::UnknownFunction { offset, .. }
+            | Self::InvalidNumber { offset, .. } => *offset,
+        }
+    }
+}
+
+pub type FormulaResult<T> = std::result::Result<T, FormulaError>;
+
 #[tracing::instrument(level = "trace")]
-pub fn parse(s: &str) -> Expr {
+pub fn parse(s: &str) -> FormulaResult<Expr> {
     debug!("start parsing expression in `formula`");
     let lexer = Lexer::new(s);
-    Parser { lexer }.expr()
+    let mut parser = Parser { lexer };
+    let expr = parser.expr()?;
+    // The whole text must be consumed.
+    if parser.lexer.peek()?.is_some() {
+        return Err(parser.unexpected());
+    }
+    Ok(expr)
 }
 
 struct Parser<'a> {
@@ -355,12 +669,12 @@ struct Parser<'a> {
 macro_rules! parse_binop {
     ($self:ident.$f:ident, ($token:expr, $op:expr) $(,($token_rep:expr, $op_rep:expr))*) => {
         {
-        let mut expr = $self.$f();
+        let mut expr = $self.$f()?;
         loop {
-            let (op_kind, rhs) = if $self.eat(&$token) {
-                ($op, $self.$f())
-            } $(else if $self.eat(&$token_rep) {
-                ($op_rep, $self.$f())
+            let (op_kind, rhs) = if $self.eat(&$token)? {
+                ($op, $self.$f()?)
+            } $(else if $self.eat(&$token_rep)? {
+                ($op_rep, $self.$f()?)
             })* else {
                 break;
             };
@@ -370,49 +684,49 @@ macro_rules! parse_binop {
                 rhs: rhs.into(),
             };
         }
-        expr
+        Ok(expr)
         }
     }
 }
 
 impl<'a> Parser<'a> {
-    fn expr(&mut self) -> Expr {
-        let expr = self.logical_or();
-        if self.eat(&Token::Question) {
-            let then = self.expr();
-            self.expect(&Token::Colon);
-            let else_ = self.expr();
-            Expr::If {
+    fn expr(&mut self) -> FormulaResult<Expr> {
+        let expr = self.logical_or()?;
+        if self.eat(&Token::Question)? {
+            let then = self.expr()?;
+            self.expect(&Token::Colon)?;
+            let else_ = self.expr()?;
+            Ok(Expr::If {
                 cond: expr.into(),
                 then: then.into(),
                 else_: else_.into(),
-            }
+            })
         } else {
-            expr
+            Ok(expr)
         }
     }
 
-    fn logical_or(&mut self) -> Expr {
+    fn logical_or(&mut self) -> FormulaResult<Expr> {
         parse_binop!(self.logical_and, (Token::DoubleOr, BinOpKind::Or))
     }
 
-    fn logical_and(&mut self) -> Expr {
+    fn logical_and(&mut self) -> FormulaResult<Expr> {
         parse_binop!(self.bitwise_or, (Token::DoubleAnd, BinOpKind::And))
     }
 
-    fn bitwise_or(&mut self) -> Expr {
+    fn bitwise_or(&mut self) -> FormulaResult<Expr> {
         parse_binop!(self.bitwise_xor, (Token::Or, BinOpKind::BitOr))
     }
 
-    fn bitwise_xor(&mut self) -> Expr {
+    fn bitwise_xor(&mut self) -> FormulaResult<Expr> {
         parse_binop!(self.bitwise_and, (Token::Caret, BinOpKind::Xor))
     }
 
-    fn bitwise_and(&mut self) -> Expr {
+    fn bitwise_and(&mut self) -> FormulaResult<Expr> {
         parse_binop!(self.eq, (Token::And, BinOpKind::BitAnd))
     }
 
-    fn eq(&mut self) -> Expr {
+    fn eq(&mut self) -> FormulaResult<Expr> {
         parse_binop!(
             self.rel,
             (Token::Eq, BinOpKind::Eq),
@@ -420,7 +734,7 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn rel(&mut self) -> Expr {
+    fn rel(&mut self) -> FormulaResult<Expr> {
         parse_binop!(
             self.bit_shift,
             (Token::Lt, BinOpKind::Lt),
@@ -430,7 +744,7 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn bit_shift(&mut self) -> Expr {
+    fn bit_shift(&mut self) -> FormulaResult<Expr> {
         parse_binop!(
             self.term,
             (Token::Shl, BinOpKind::Shl),
@@ -438,7 +752,7 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn term(&mut self) -> Expr {
+    fn term(&mut self) -> FormulaResult<Expr> {
         parse_binop!(
             self.factor,
             (Token::Plus, BinOpKind::Add),
@@ -446,7 +760,7 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn factor(&mut self) -> Expr {
+    fn factor(&mut self) -> FormulaResult<Expr> {
         parse_binop!(
             self.unop,
             (Token::Star, BinOpKind::Mul),
@@ -455,52 +769,53 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn unop(&mut self) -> Expr {
-        if self.eat(&Token::Tilde) {
-            let expr = self.unop();
-            Expr::UnOp {
+    fn unop(&mut self) -> FormulaResult<Expr> {
+        if self.eat(&Token::Tilde)? {
+            let expr = self.unop()?;
+            Ok(Expr::UnOp {
                 kind: UnOpKind::Not,
                 expr: expr.into(),
-            }
-        } else if self.eat(&Token::Minus) {
-            let expr = self.unop();
-            Expr::UnOp {
+            })
+        } else if self.eat(&Token::Minus)? {
+            let expr = self.unop()?;
+            Ok(Expr::UnOp {
                 kind: UnOpKind::Neg,
                 expr: expr.into(),
-            }
+            })
         } else {
             // Eat unary `+` if exists.
-            self.eat(&Token::Plus);
+            self.eat(&Token::Plus)?;
             self.pow()
         }
     }
 
-    fn pow(&mut self) -> Expr {
-        let expr = self.primary();
-        if self.eat(&Token::DoubleStar) {
-            let rhs = self.unop();
-            Expr::BinOp {
+    fn pow(&mut self) -> FormulaResult<Expr> {
+        let expr = self.primary()?;
+        if self.eat(&Token::DoubleStar)? {
+            let rhs = self.unop()?;
+            Ok(Expr::BinOp {
                 kind: BinOpKind::Pow,
                 lhs: expr.into(),
                 rhs: rhs.into(),
-            }
+            })
         } else {
-            expr
+            Ok(expr)
         }
     }
 
-    fn primary(&mut self) -> Expr {
-        if self.eat(&Token::LParen) {
-            let expr = self.expr();
-            self.expect(&Token::RParen);
-            expr
-        } else if let Some(i) = self.next_integer() {
-            Expr::Integer(i)
-        } else if let Some(f) = self.next_float() {
-            Expr::Float(f)
+    fn primary(&mut self) -> FormulaResult<Expr> {
+        if self.eat(&Token::LParen)? {
+            let expr = self.expr()?;
+            self.expect(&Token::RParen)?;
+            Ok(expr)
+        } else if let Some(i) = self.next_integer()? {
+            Ok(Expr::Integer(i))
+        } else if let Some(f) = self.next_float()? {
+            Ok(Expr::Float(f))
         } else {
-            let s = self.next_ident().unwrap();
-            if self.eat(&Token::LParen) {
+            let offset = self.lexer.token_start;
+            let s = self.next_ident()?.ok_or_else(|| self.unexpected())?;
+            if self.eat(&Token::LParen)? {
                 let op = match s.as_str() {
                     "NEG" => UnOpKind::Neg,
                     "SIN" => UnOpKind::Sin,
@@ -518,68 +833,86 @@ impl<'a> Parser<'a> {
                     "FLOOR" => UnOpKind::Floor,
                     "CEIL" => UnOpKind::Ceil,
                     "ROUND" => UnOpKind::Round,
-                    other => panic!("{} is not a keyword or function name", other),
+                    _ => return Err(FormulaError::UnknownFunction { name: s, offset }),
                 };
-                let expr = self.expr();
-                self.expect(&Token::RParen);
-                Expr::UnOp {
+                let expr = self.expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(Expr::UnOp {
                     kind: op,
                     expr: expr.into(),
-                }
+                })
             } else {
-                Expr::Ident(s)
+                Ok(Expr::Ident(s))
             }
         }
     }
 
-    fn eat(&mut self, tok: &Token) -> bool {
-        match self.lexer.peek() {
+    fn eat(&mut self, tok: &Token) -> FormulaResult<bool> {
+        match self.lexer.peek()? {
             Some(peek) if peek == tok => {
-                self.lexer.next();
-                true
+                self.lexer.next()?;
+                Ok(true)
             }
-            _ => false,
+            _ => Ok(false),
         }
     }
 
-    fn next_integer(&mut self) -> Option<i64> {
-        if let Some(&Token::Integer(i)) = self.lexer.peek() {
-            self.lexer.next();
-            Some(i)
+    fn next_integer(&mut self) -> FormulaResult<Option<i64>> {
+        if let Some(&Token::Integer(i)) = self.lexer.peek()? {
+            self.lexer.next()?;
+            Ok(Some(i))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn next_float(&mut self) -> Option<f64> {
-        if let Some(&Token::Float(f)) = self.lexer.peek() {
-            self.lexer.next();
-            Some(f)
-        } else if let Some(Token::Ident(s)) = self.lexer.peek() {
+    fn next_float(&mut self) -> FormulaResult<Option<f64>> {
+        if let Some(&Token::Float(f)) = self.lexer.peek()? {
+            self.lexer.next()?;
+            Ok(Some(f))
+        } else if let Some(Token::Ident(s)) = self.lexer.peek()? {
             let f = match s.as_str() {
                 "PI" => std::f64::consts::PI,
                 "E" => std::f64::consts::E,
-                _ => return None,
+                _ => return Ok(None),
             };
-            self.lexer.next();
-            Some(f)
+            self.lexer.next()?;
+            Ok(Some(f))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn next_ident(&mut self) -> Option<String> {
-        if let Some(Token::Ident(s)) = self.lexer.peek() {
+    fn next_ident(&mut self) -> FormulaResult<Option<String>> {
+        if let Some(Token::Ident(s)) = self.lexer.peek()? {
             let s = s.to_string();
-            self.lexer.next();
-            Some(s)
+            self.lexer.next()?;
+            Ok(Some(s))
         } else {
-            None
+            Ok(None)
+        }
+    }
+
+    fn expect(&mut self, tok: &Token) -> FormulaResult<()> {
+        if self.eat(tok)? {
+            Ok(())
+        } else {
+            Err(self.unexpected())
         }
     }
 
-    fn expect(&mut self, tok: &Token) {
-        assert!(self.eat(tok))
+    /// Returns an error which indicates the peeked token is unexpected.
+    fn unexpected(&mut self) -> FormulaError {
+        match self.lexer.peek() {
+            Ok(Some(_)) => FormulaError::UnexpectedToken {
+                token: self.lexer.token_str().into(),
+                offset: self.lexer.token_start,
+            },
+            Ok(None) => FormulaError::UnexpectedEnd {
+                offset: self.lexer.cur,
+            },
+            Err(err) => err,
+        }
     }
 }
 
@@ -619,6 +952,8 @@ struct Lexer<'a> {
     peek: Option<Token>,
     cur: usize,
     peek_char: Option<(char, usize)>,
+    /// Start offset of the peeked token.
+    token_start: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -629,22 +964,28 @@ impl<'a> Lexer<'a> {
             peek: None,
             cur: 0,
             peek_char: None,
+            token_start: 0,
         }
     }
 
-    fn next(&mut self) -> Option<Token> {
-        self.peek();
-        self.peek.take()
+    fn next(&mut self) -> FormulaResult<Option<Token>> {
+        self.peek()?;
+        Ok(self.peek.take())
     }
 
-    fn peek(&mut self) -> Option<&Token> {
-        if let Some(ref peek) = self.peek {
-            return Some(peek);
+    fn peek(&mut self) -> FormulaResult<Option<&Token>> {
+        if self.peek.is_some() {
+            return Ok(self.peek.as_ref());
         }
 
         while self.eat_char(|c| c.is_whitespace() || c.is_ascii_control()) {}
 
-        self.peek = Some(match self.next_char()? {
+        self.token_start = self.cur;
+        let c = match self.next_char() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        self.peek = Some(match c {
             '(' => Token::LParen,
             ')' => Token::RParen,
             '+' => Token::Plus,
@@ -698,53 +1039,60 @@ impl<'a> Lexer<'a> {
                 }
             }
             '.' => {
-                let start_pos = self.cur - 1;
-                while self.eat_char(char::is_numeric) {}
-                let end_pos = self.cur;
-                let f = f64::from_str(self.sub_string(start_pos, end_pos)).unwrap();
-                Token::Float(f)
+                while self.eat_char(|c| c.is_ascii_digit()) {}
+                Token::Float(self.parse_number(f64::from_str)?)
             }
 
-            c if c.is_alphabetic() => {
-                let start_pos = self.cur - 1;
-                while self.eat_char(|c| c.is_alphanumeric() || c == '.' || c == '_') {}
-                let end_pos = self.cur;
-                Token::Ident(self.sub_string(start_pos, end_pos).into())
+            c if c.is_ascii_alphabetic() => {
+                while self.eat_char(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_') {}
+                Token::Ident(self.token_str().into())
             }
 
-            c if c.is_numeric() => {
+            c if c.is_ascii_digit() => {
                 if c == '0' && self.eat_char(|c| c == 'x') {
-                    let start_pos = self.cur;
                     while self.eat_char(|c| c.is_ascii_hexdigit()) {}
-                    let end_pos = self.cur;
-                    let i = i64::from_str_radix(self.sub_string(start_pos, end_pos), 16).unwrap();
-                    Token::Integer(i)
+                    Token::Integer(self.parse_number(|s| i64::from_str_radix(&s[2..], 16))?)
                 } else {
-                    let start_pos = self.cur - 1;
                     let mut is_integer = true;
                     let mut check_digit = |c: char| {
                         if c == '.' {
                             is_integer = false;
                             true
                         } else {
-                            c.is_numeric()
+                            c.is_ascii_digit()
                         }
                     };
                     while self.eat_char(&mut check_digit) {}
-                    let end_pos = self.cur;
-                    let s = self.sub_string(start_pos, end_pos);
                     if is_integer {
-                        Token::Integer(i64::from_str(s).unwrap())
+                        Token::Integer(self.parse_number(i64::from_str)?)
                     } else {
-                        Token::Float(f64::from_str(s).unwrap())
+                        Token::Float(self.parse_number(f64::from_str)?)
                     }
                 }
             }
 
-            c => panic!("unexpected character `{}` in formula", c),
+            ch => {
+                return Err(FormulaError::UnexpectedChar {
+                    ch,
+                    offset: self.token_start,
+                })
+            }
         });
 
-        self.peek.as_ref()
+        Ok(self.peek.as_ref())
+    }
+
+    /// Returns the source text of the token which is being lexed.
+    fn token_str(&self) -> &'a str {
+        self.sub_string(self.token_start, self.cur)
+    }
+
+    fn parse_number<T, E>(&self, f: impl FnOnce(&str) -> Result<T, E>) -> FormulaResult<T> {
+        let number = self.token_str();
+        f(number).map_err(|_| FormulaError::InvalidNumber {
+            number: number.into(),
+            offset: self.token_start,
+        })
     }
 
     fn next_char(&mut self) -> Option<char> {
@@ -807,7 +1155,8 @@ impl<'a> Lexer<'a> {
             .map_or(false, |next| c == *next as char)
     }
 
-    fn sub_string(&self, start_pos: usize, end_pos: usize) -> &str {
+    fn sub_string(&self, start_pos: usize, end_pos: usize) -> &'a str {
+        // Tokens consist of ASCII characters only.
         std::str::from_utf8(&self.src[start_pos..end_pos]).unwrap()
     }
 }
@@ -818,48 +1167,56 @@ mod tests {
 
     #[test]
     fn test_lexer() {
-        let t = Lexer::new("&amp;").next().unwrap();
+        let t = Lexer::new("&amp;").next().unwrap().unwrap();
         assert_eq!(Token::And, t);
 
-        let t = Lexer::new("&lt;").next().unwrap();
+        let t = Lexer::new("&lt;").next().unwrap().unwrap();
         assert_eq!(Token::Lt, t);
 
-        let t = Lexer::new("&gt;").next().unwrap();
+        let t = Lexer::new("&gt;").next().unwrap().unwrap();
         assert_eq!(Token::Gt, t);
 
-        let t = Lexer::new("Foo1.Max").next().unwrap();
+        let t = Lexer::new("Foo1.Max").next().unwrap().unwrap();
         assert_eq!(Token::Ident("Foo1.Max".into()), t);
 
-        let t = Lexer::new("0xa").next().unwrap();
+        let t = Lexer::new("0xa").next().unwrap().unwrap();
         assert_eq!(Token::Integer(0xa), t);
 
-        let t = Lexer::new("10").next().unwrap();
+        let t = Lexer::new("10").next().unwrap().unwrap();
         assert_eq!(Token::Integer(10), t);
 
-        let t = Lexer::new("0.1").next().unwrap();
+        let t = Lexer::new("0.1").next().unwrap().unwrap();
         assert!(matches!(t, Token::Float(_)));
 
-        let t = Lexer::new(".1").next().unwrap();
+        let t = Lexer::new(".1").next().unwrap().unwrap();
         assert!(matches!(t, Token::Float(_)));
 
-        let t = Lexer::new("  10 ").next().unwrap();
+        let t = Lexer::new("  10 ").next().unwrap().unwrap();
         assert_eq!(Token::Integer(10), t);
 
         let mut lexer = Lexer::new("&&||<>**>><<");
-        assert_eq!(Token::DoubleAnd, lexer.next().unwrap());
-        assert_eq!(Token::DoubleOr, lexer.next().unwrap());
-        assert_eq!(Token::Ne, lexer.next().unwrap());
-        assert_eq!(Token::DoubleStar, lexer.next().unwrap());
-        assert_eq!(Token::Shr, lexer.next().unwrap());
-        assert_eq!(Token::Shl, lexer.next().unwrap());
+        assert_eq!(Token::DoubleAnd, lexer.next().unwrap().unwrap());
+        assert_eq!(Token::DoubleOr, lexer.next().unwrap().unwrap());
+        assert_eq!(Token::Ne, lexer.next().unwrap().unwrap());
+        assert_eq!(Token::DoubleStar, lexer.next().unwrap().unwrap());
+        assert_eq!(Token::Shr, lexer.next().unwrap().unwrap());
+        assert_eq!(Token::Shl, lexer.next().unwrap().unwrap());
     }
 
     fn test_eval_impl(expr: &str, var_env: &HashMap<&str, Expr>) {
-        let expr = parse(expr);
+        let expr = parse(expr).unwrap();
         assert!(matches!(
             expr.eval(var_env).unwrap(),
             EvaluationResult::Integer(1)
         ));
+
+        // The compiled formula must agree with the tree walking evaluation.
+        let definitions: Vec<_> = var_env.iter().map(|(k, v)| (*k, v.clone())).collect();
+        let formula = Formula::compile(expr, &[], &definitions).unwrap();
+        assert!(matches!(
+            formula.eval_compiled(&[]).unwrap(),
+            EvaluationResult::Integer(1)
+        ));
     }
 
     fn test_eval_no_var_impl(expr: &str) {
@@ -902,4 +1259,106 @@ mod tests {
         test_eval_impl("ABS(VAR1 + 1 / 4 - 1.25) < EPS", &env);
         test_eval_impl("( EXP = 1 ) ? 1 : 0", &env);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = |s: &str| parse(s).unwrap_err();
+
+        assert_eq!(
+            err("1 + $"),
+            FormulaError::UnexpectedChar { ch: '$', offset: 4 }
+        );
+        assert_eq!(err("(1 + 2"), FormulaError::UnexpectedEnd { offset: 6 });
+        assert_eq!(
+            err("1 + 2)"),
+            FormulaError::UnexpectedToken {
+                token: ")".into(),
+                offset: 5
+            }
+        );
+        assert_eq!(err("1 ? 2"), FormulaError::UnexpectedEnd { offset: 5 });
+        assert_eq!(
+            err("1 + FOO(2)"),
+            FormulaError::UnknownFunction {
+                name: "FOO".into(),
+                offset: 4
+            }
+        );
+        assert_eq!(
+            err("0x + 1"),
+            FormulaError::InvalidNumber {
+                number: "0x".into(),
+                offset: 0
+            }
+        );
+        assert_eq!(
+            err("1.2.3"),
+            FormulaError::InvalidNumber {
+                number: "1.2.3".into(),
+                offset: 0
+            }
+        );
+        assert_eq!(err(""), FormulaError::UnexpectedEnd { offset: 0 });
+        assert_eq!(err("1 + $").offset(), 4);
+    }
+
+    #[test]
+    fn test_variables() {
+        let expr = parse("(VAR1 + VAR2.Max) * VAR1 > 0 ? SIN(PI) : EPS").unwrap();
+        assert_eq!(expr.variables(), vec!["VAR1", "VAR2.Max", "EPS"]);
+    }
+
+    #[test]
+    fn test_compile() {
+        let definitions = vec![
+            ("Const", Expr::Integer(3)),
+            ("Expr1", parse("Const + 1").unwrap()),
+        ];
+        let compile =
+            |s: &str| Formula::compile(parse(s).unwrap(), &["VAR1", "VAR2"], &definitions);
+
+        // Constants and expressions are inlined and folded.
+        let formula = compile("Const * 2 + VAR2 * Expr1").unwrap();
+        assert_eq!(formula.used_slots(), &[1]);
+        assert_eq!(
+            formula.compiled,
+            CompiledExpr::BinOp {
+                kind: BinOpKind::Add,
+                lhs: CompiledExpr::Const(6.into()).into(),
+                rhs: CompiledExpr::BinOp {
+                    kind: BinOpKind::Mul,
+                    lhs: CompiledExpr::Slot(1).into(),
+                    rhs: CompiledExpr::Const(4.into()).into(),
+                }
+                .into(),
+            }
+        );
+        let slots = [EvaluationResult::Integer(0), EvaluationResult::Integer(5)];
+        assert_eq!(
+            formula.eval_compiled(&slots).unwrap(),
+            EvaluationResult::Integer(26)
+        );
+
+        // Dead branches are folded away.
+        let formula = compile("(Const > 2 ? VAR1 : VAR2) + (0 && VAR2)").unwrap();
+        assert_eq!(formula.used_slots(), &[0]);
+
+        // Errors are left to evaluation time.
+        let formula = compile("VAR1 ? 1 % 0 : 1").unwrap();
+        assert!(formula.eval_compiled(&[1.into(), 0.into()]).is_err());
+        assert_eq!(
+            formula.eval_compiled(&[0.into(), 0.into()]).unwrap(),
+            EvaluationResult::Integer(1)
+        );
+
+        assert_eq!(
+            compile("VAR3 + 1").unwrap_err(),
+            CompileError::Undefined("VAR3".into())
+        );
+        let definitions = vec![("A", parse("B + 1").unwrap()), ("B", parse("A").unwrap())];
+        assert_eq!(
+            Formula::compile(parse("A").unwrap(), &[], &definitions).unwrap_err(),
+            CompileError::Cyclic("A".into())
+        );
+    }
 }
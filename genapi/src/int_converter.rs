@@ -95,12 +95,17 @@ impl IInteger for IntConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<i64> {
-        let mut collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
-        collector.insert("TO", self.p_value(), device, store, cx)?;
-        let var_env = collector.collect(device, store, cx)?;
-
-        let eval_result = self.formula_from.eval(&var_env)?;
+        let eval_result = cx.memoized(self.node_base().id(), |cx| {
+            let to = utils::eval_result_from_nid(self.p_value, device, store, cx)?;
+            utils::eval_formula(
+                &self.formula_from,
+                &self.p_variables,
+                Some(to),
+                device,
+                store,
+                cx,
+            )
+        })?;
         Ok(eval_result.as_integer())
     }
 
@@ -116,12 +121,14 @@ impl IInteger for IntConverterNode {
     ) -> GenApiResult<()> {
         cx.invalidate_cache_by(self.node_base().id());
 
-        let mut collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
-        collector.insert_imm("FROM", value);
-        let var_env = collector.collect(device, store, cx)?;
-
-        let eval_result = self.formula_to.eval(&var_env)?;
+        let eval_result = utils::eval_formula(
+            &self.formula_to,
+            &self.p_variables,
+            Some(value.into()),
+            device,
+            store,
+            cx,
+        )?;
         utils::set_eval_result(self.p_value, eval_result, device, store, cx)?;
         Ok(())
     }
@@ -204,11 +211,9 @@ impl IInteger for IntConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<bool> {
-        let collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
         Ok(self.elem_base.is_readable(device, store, cx)?
             && utils::is_nid_readable(self.p_value, device, store, cx)?
-            && collector.is_readable(device, store, cx)?)
+            && utils::is_variables_readable(&self.p_variables, device, store, cx)?)
     }
 
     #[tracing::instrument(skip(self, device, store, cx),
@@ -220,10 +225,9 @@ impl IInteger for IntConverterNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<bool> {
-        let collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
         Ok(self.elem_base.is_writable(device, store, cx)?
             && utils::is_nid_writable(self.p_value, device, store, cx)?
-            && collector.is_readable(device, store, cx)?) // Collector is needed to be readable to write a value.
+            && utils::is_variables_readable(&self.p_variables, device, store, cx)?)
+        // Variables need to be readable to write a value.
     }
 }
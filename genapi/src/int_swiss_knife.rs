@@ -77,10 +77,9 @@ impl IInteger for IntSwissKnifeNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<i64> {
-        let var_env =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions)
-                .collect(device, store, cx)?;
-        let eval_result = self.formula.eval(&var_env)?;
+        let eval_result = cx.memoized(self.node_base().id(), |cx| {
+            utils::eval_formula(&self.formula, &self.p_variables, None, device, store, cx)
+        })?;
         Ok(eval_result.as_integer())
     }
 
@@ -178,10 +177,8 @@ impl IInteger for IntSwissKnifeNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<bool> {
-        let collector =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions);
         Ok(self.elem_base.is_readable(device, store, cx)?
-            && collector.is_readable(device, store, cx)?)
+            && utils::is_variables_readable(&self.p_variables, device, store, cx)?)
     }
 
     fn is_writable<T: ValueStore, U: CacheStore>(
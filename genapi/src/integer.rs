@@ -8,7 +8,7 @@ use super::{
     ivalue::IValue,
     node_base::{NodeAttributeBase, NodeBase, NodeElementBase},
     store::{CacheStore, IntegerId, NodeId, NodeStore, ValueStore},
-    Device, GenApiResult, ValueCtxt,
+    Device, GenApiError, GenApiResult, ValueCtxt,
 };
 
 #[derive(Debug, Clone)]
@@ -21,6 +21,7 @@ pub struct IntegerNode {
     pub(crate) min: ImmOrPNode<IntegerId>,
     pub(crate) max: ImmOrPNode<IntegerId>,
     pub(crate) inc: ImmOrPNode<i64>,
+    pub(crate) valid_value_set: Vec<i64>,
     pub(crate) unit: Option<String>,
     pub(crate) representation: IntegerRepresentation,
     pub(crate) p_selected: Vec<NodeId>,
@@ -47,6 +48,11 @@ impl IntegerNode {
         self.inc
     }
 
+    #[must_use]
+    pub fn valid_value_set_elem(&self) -> &[i64] {
+        &self.valid_value_set
+    }
+
     #[must_use]
     pub fn unit_elem(&self) -> Option<&str> {
         self.unit.as_deref()
@@ -96,6 +102,11 @@ impl IInteger for IntegerNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<()> {
+        if !self.valid_value_set.is_empty() && !self.valid_value_set.contains(&value) {
+            return Err(GenApiError::invalid_data(
+                format!("`{}` is not in the valid value set", value).into(),
+            ));
+        }
         cx.invalidate_cache_by(self.node_base().id());
         self.value_kind().set_value(value, device, store, cx)
     }
@@ -125,7 +136,11 @@ impl IInteger for IntegerNode {
     }
 
     fn inc_mode(&self, _: &impl NodeStore) -> Option<IncrementMode> {
-        Some(IncrementMode::FixedIncrement)
+        if self.valid_value_set.is_empty() {
+            Some(IncrementMode::FixedIncrement)
+        } else {
+            Some(IncrementMode::ListIncrement)
+        }
     }
 
     #[tracing::instrument(skip(self, device, store, cx),
@@ -137,11 +152,15 @@ impl IInteger for IntegerNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<Option<i64>> {
-        Some(self.inc.value(device, store, cx)).transpose()
+        if self.valid_value_set.is_empty() {
+            Some(self.inc.value(device, store, cx)).transpose()
+        } else {
+            Ok(None)
+        }
     }
 
     fn valid_value_set(&self, _: &impl NodeStore) -> &[i64] {
-        &[]
+        &self.valid_value_set
     }
 
     fn representation(&self, _: &impl NodeStore) -> IntegerRepresentation {
@@ -210,3 +229,44 @@ impl ISelector for IntegerNode {
         Ok(self.p_selected())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    #[test]
+    fn test_valid_value_set() {
+        let xml = register_description(
+            r#"<Integer Name="Binning">
+                   <Value>1</Value>
+                   <ValidValueSet>1;2;4</ValidValueSet>
+                 </Integer>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = TestDevice::new(vec![]);
+
+        let binning = store
+            .id_by_name("Binning")
+            .unwrap()
+            .expect_iinteger_kind(&store)
+            .unwrap();
+        assert!(matches!(
+            binning.inc_mode(&store),
+            Some(IncrementMode::ListIncrement)
+        ));
+        assert_eq!(binning.valid_value_set(&store), &[1, 2, 4]);
+        assert_eq!(binning.inc(&mut device, &store, &mut cx).unwrap(), None);
+        binning.set_value(4, &mut device, &store, &mut cx).unwrap();
+        assert!(binning.set_value(3, &mut device, &store, &mut cx).is_err());
+        assert_eq!(binning.value(&mut device, &store, &mut cx).unwrap(), 4);
+    }
+}
@@ -14,7 +14,7 @@ use super::{
 #[derive(Clone, Debug)]
 pub enum IncrementMode {
     FixedIncrement,
-    /// NOTE: `ListIncrement` is not supported in `GenApiSchema Version 1.1` yet.
+    /// Valid values are listed by `valid_value_set`.
     ListIncrement,
 }
 
@@ -68,7 +68,8 @@ pub trait IInteger {
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<Option<i64>>;
 
-    /// NOTE: `ValidValueSet` is not supported in `GenApiSchema Version 1.1` yet.
+    /// Returns valid values if `inc_mode` returns `IncrementMode::ListIncrement`, otherwise returns
+    /// an empty slice.
     fn valid_value_set(&self, store: &impl NodeStore) -> &[i64];
 
     fn representation(&self, store: &impl NodeStore) -> IntegerRepresentation;
@@ -146,6 +147,13 @@ pub trait IFloat {
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<Option<f64>>;
 
+    /// Returns valid values if `inc_mode` returns `IncrementMode::ListIncrement`, otherwise returns
+    /// an empty slice.
+    ///
+    /// Floats are compared with a small relative tolerance, so `set_value` accepts a value which
+    /// differs from a valid value only by rounding errors and sets the valid value instead.
+    fn valid_value_set(&self, store: &impl NodeStore) -> &[f64];
+
     fn representation(&self, store: &impl NodeStore) -> FloatRepresentation;
 
     fn unit(&self, store: &impl NodeStore) -> Option<&str>;
@@ -434,6 +442,11 @@ pub enum INodeKind<'a> {
     Enumeration(&'a super::EnumerationNode),
     EnumEntry(&'a super::EnumEntryNode),
     Node(&'a super::Node),
+    ConfRom(&'a super::ConfRomNode),
+    TextDesc(&'a super::TextDescNode),
+    IntKey(&'a super::IntKeyNode),
+    AdvFeatureLock(&'a super::AdvFeatureLockNode),
+    SmartFeature(&'a super::SmartFeatureNode),
 }
 
 impl<'a> INodeKind<'a> {
@@ -458,7 +471,11 @@ impl<'a> INodeKind<'a> {
             NodeData::Enumeration(n) => Some(Self::Enumeration(n)),
             NodeData::EnumEntry(n) => Some(Self::EnumEntry(n)),
             NodeData::Node(n) => Some(Self::Node(n)),
-            _ => None,
+            NodeData::ConfRom(n) => Some(Self::ConfRom(n)),
+            NodeData::TextDesc(n) => Some(Self::TextDesc(n)),
+            NodeData::IntKey(n) => Some(Self::IntKey(n)),
+            NodeData::AdvFeatureLock(n) => Some(Self::AdvFeatureLock(n)),
+            NodeData::SmartFeature(n) => Some(Self::SmartFeature(n)),
         }
     }
 
@@ -484,6 +501,11 @@ impl<'a> INodeKind<'a> {
             Self::Enumeration(n) => n.node_base(),
             Self::EnumEntry(n) => n.node_base(),
             Self::Node(n) => n.node_base(),
+            Self::ConfRom(n) => n.node_base(),
+            Self::TextDesc(n) => n.node_base(),
+            Self::IntKey(n) => n.node_base(),
+            Self::AdvFeatureLock(n) => n.node_base(),
+            Self::SmartFeature(n) => n.node_base(),
         }
     }
 }
@@ -496,6 +518,8 @@ pub enum IIntegerKind<'a> {
     MaskedIntReg(&'a super::MaskedIntRegNode),
     IntConverter(&'a super::IntConverterNode),
     IntSwissKnife(&'a super::IntSwissKnifeNode),
+    IntKey(&'a super::IntKeyNode),
+    SmartFeature(&'a super::SmartFeatureNode),
 }
 
 impl<'a> IIntegerKind<'a> {
@@ -506,6 +530,8 @@ impl<'a> IIntegerKind<'a> {
             NodeData::MaskedIntReg(n) => Some(Self::MaskedIntReg(n)),
             NodeData::IntConverter(n) => Some(Self::IntConverter(n)),
             NodeData::IntSwissKnife(n) => Some(Self::IntSwissKnife(n)),
+            NodeData::IntKey(n) => Some(Self::IntKey(n)),
+            NodeData::SmartFeature(n) => Some(Self::SmartFeature(n)),
             _ => None,
         }
     }
@@ -537,6 +563,7 @@ impl<'a> IFloatKind<'a> {
 pub enum IStringKind<'a> {
     String(&'a super::StringNode),
     StringReg(&'a super::StringRegNode),
+    TextDesc(&'a super::TextDescNode),
 }
 
 impl<'a> IStringKind<'a> {
@@ -544,6 +571,7 @@ impl<'a> IStringKind<'a> {
         match store.node_opt(id)? {
             NodeData::String(n) => Some(Self::String(n)),
             NodeData::StringReg(n) => Some(Self::StringReg(n)),
+            NodeData::TextDesc(n) => Some(Self::TextDesc(n)),
             _ => None,
         }
     }
@@ -583,12 +611,14 @@ impl<'a> IEnumerationKind<'a> {
 #[delegate(IBoolean)]
 pub enum IBooleanKind<'a> {
     Boolean(&'a super::BooleanNode),
+    AdvFeatureLock(&'a super::AdvFeatureLockNode),
 }
 
 impl<'a> IBooleanKind<'a> {
     pub(super) fn maybe_from(id: NodeId, store: &'a impl NodeStore) -> Option<Self> {
         match store.node_opt(id)? {
             NodeData::Boolean(n) => Some(Self::Boolean(n)),
+            NodeData::AdvFeatureLock(n) => Some(Self::AdvFeatureLock(n)),
             _ => None,
         }
     }
@@ -602,6 +632,7 @@ pub enum IRegisterKind<'a> {
     MaskedIntReg(&'a super::MaskedIntRegNode),
     StringReg(&'a super::StringRegNode),
     FloatReg(&'a super::FloatRegNode),
+    ConfRom(&'a super::ConfRomNode),
 }
 
 impl<'a> IRegisterKind<'a> {
@@ -612,6 +643,7 @@ impl<'a> IRegisterKind<'a> {
             NodeData::MaskedIntReg(n) => Some(Self::MaskedIntReg(n)),
             NodeData::StringReg(n) => Some(Self::StringReg(n)),
             NodeData::FloatReg(n) => Some(Self::FloatReg(n)),
+            NodeData::ConfRom(n) => Some(Self::ConfRom(n)),
             _ => None,
         }
     }
@@ -108,6 +108,7 @@ macro_rules! impl_ivalue_for_vid {
                 cx: &mut ValueCtxt<U, S>,
             ) -> GenApiResult<()> {
                 cx.value_store_mut().update(*self, value);
+                cx.cache_store_mut().advance_epoch();
                 Ok(())
             }
 
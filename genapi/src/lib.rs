@@ -13,15 +13,18 @@
 )]
 
 pub mod builder;
+pub mod dependency;
 pub mod elem_type;
 pub mod formula;
 pub mod interface;
 pub mod parser;
 pub mod store;
 
+mod adv_feature_lock;
 mod boolean;
 mod category;
 mod command;
+mod conf_rom;
 mod converter;
 mod enumeration;
 mod float;
@@ -35,17 +38,21 @@ mod masked_int_reg;
 mod node;
 mod node_base;
 mod port;
+mod prefetch;
 mod register;
 mod register_base;
 mod register_description;
+mod smart_feature;
 mod string;
 mod string_reg;
 mod swiss_knife;
 mod utils;
 
+pub use adv_feature_lock::AdvFeatureLockNode;
 pub use boolean::BooleanNode;
 pub use category::CategoryNode;
 pub use command::CommandNode;
+pub use conf_rom::{ConfRomNode, IntKeyNode, TextDescNode};
 pub use converter::ConverterNode;
 pub use enumeration::{EnumEntryNode, EnumerationNode};
 pub use float::FloatNode;
@@ -61,6 +68,7 @@ pub use port::PortNode;
 pub use register::RegisterNode;
 pub use register_base::RegisterBase;
 pub use register_description::RegisterDescription;
+pub use smart_feature::SmartFeatureNode;
 pub use store::{CacheStore, NodeId, NodeStore, ValueStore};
 pub use string::StringNode;
 pub use string_reg::StringRegNode;
@@ -80,15 +88,57 @@ pub mod prelude {
 
 #[auto_impl(&mut, Box)]
 pub trait Device {
-    fn read_mem(&mut self, address: i64, buf: &mut [u8]) -> Result<(), Box<dyn std::error::Error>>;
+    fn read_mem(
+        &mut self,
+        address: i64,
+        buf: &mut [u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    fn write_mem(
+        &mut self,
+        address: i64,
+        data: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Reads multiple regions of the device memory, the length to read is same as the buffer
+    /// length of each entry.
+    ///
+    /// Node operations read registers one by one with [`Device::read_mem`], this is used when
+    /// registers are read in bulk, i.e. [`ValueCtxt::warm_up_cache`] and
+    /// [`ValueCtxt::warm_up_category`].
+    ///
+    /// The default implementation calls [`Device::read_mem`] for each entry.
+    fn read_mem_stacked(
+        &mut self,
+        entries: &mut [(i64, &mut [u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for (address, buf) in entries.iter_mut() {
+            self.read_mem(*address, buf)?;
+        }
+        Ok(())
+    }
 
-    fn write_mem(&mut self, address: i64, data: &[u8]) -> Result<(), Box<dyn std::error::Error>>;
+    /// Writes data to multiple regions of the device memory.
+    ///
+    /// Node operations don't use this because each write must be followed by the cache
+    /// invalidation of the node, it's provided for callers which write raw registers in bulk.
+    ///
+    /// The default implementation calls [`Device::write_mem`] for each entry.
+    fn write_mem_stacked(
+        &mut self,
+        entries: &[(i64, &[u8])],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for &(address, data) in entries {
+            self.write_mem(address, data)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum GenApiError {
     #[error("device I/O error: {0}")]
-    Device(Box<dyn std::error::Error>),
+    Device(Box<dyn std::error::Error + Send + Sync>),
 
     /// The node is not writable.
     #[error("attempt to write a value to non writable node")]
@@ -114,7 +164,7 @@ pub enum GenApiError {
 }
 
 impl GenApiError {
-    fn device(inner: Box<dyn std::error::Error>) -> Self {
+    fn device(inner: Box<dyn std::error::Error + Send + Sync>) -> Self {
         let err = GenApiError::Device(inner);
         error!("{}", err);
         err
@@ -157,6 +207,9 @@ pub type GenApiResult<T> = std::result::Result<T, GenApiError>;
 pub struct ValueCtxt<T, U> {
     pub value_store: T,
     pub cache_store: U,
+    notifier: dependency::ChangeNotifier,
+    /// `true` if an uncached register has been read in the innermost formula evaluation.
+    uncached_read: bool,
 }
 
 impl<T, U> ValueCtxt<T, U> {
@@ -164,6 +217,8 @@ impl<T, U> ValueCtxt<T, U> {
         Self {
             value_store,
             cache_store,
+            notifier: dependency::ChangeNotifier::default(),
+            uncached_read: false,
         }
     }
 
@@ -205,7 +260,8 @@ impl<T, U> ValueCtxt<T, U> {
     where
         U: store::CacheStore,
     {
-        self.cache_store.invalidate_by(nid)
+        self.cache_store.invalidate_by(nid);
+        self.notifier.mark_changed(nid);
     }
 
     pub fn invalidate_cache_of(&mut self, nid: store::NodeId)
@@ -221,4 +277,90 @@ impl<T, U> ValueCtxt<T, U> {
     {
         self.cache_store.clear()
     }
+
+    /// Returns a receiver of nodes whose value, access mode or range may have changed.
+    ///
+    /// Changes are recorded when nodes are written or [`Self::mark_changed`] is called, and are
+    /// sent with nodes affected by them through the dependency graph when
+    /// [`Self::notify_changes`] is called.
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<store::NodeId> {
+        self.notifier.subscribe()
+    }
+
+    /// Records that the value of `nid` may have changed without being written through the
+    /// context, e.g. it's notified by an event or found by polling.
+    pub fn mark_changed(&mut self, nid: store::NodeId) {
+        self.notifier.mark_changed(nid);
+    }
+
+    /// Sends recorded changes to subscribers.
+    ///
+    /// `store` must be the store which the context is built with.
+    pub fn notify_changes(&mut self, store: &impl store::NodeStore) {
+        self.notifier.notify(store);
+    }
+
+    /// Returns the memoized formula result of `nid`, or evaluates it with `f` and memoizes the
+    /// result unless a value the formula depends on may have changed during the evaluation.
+    ///
+    /// A result is not memoized either if the formula reads an uncached register, directly or
+    /// through nested formulas, because the register may change at any time.
+    pub(crate) fn memoized(
+        &mut self,
+        nid: store::NodeId,
+        f: impl FnOnce(&mut Self) -> GenApiResult<formula::EvaluationResult>,
+    ) -> GenApiResult<formula::EvaluationResult>
+    where
+        U: store::CacheStore,
+    {
+        if let Some(result) = self.cache_store.memo(nid) {
+            return Ok(result);
+        }
+        let epoch = self.cache_store.epoch();
+        let outer_uncached_read = std::mem::replace(&mut self.uncached_read, false);
+        let result = f(self);
+        let uncached_read = self.uncached_read;
+        // An outer formula depends on the uncached register through this formula.
+        self.uncached_read = outer_uncached_read || uncached_read;
+        let result = result?;
+        if !uncached_read {
+            self.cache_store.memoize(nid, epoch, result);
+        }
+        Ok(result)
+    }
+
+    /// Records that an uncached register is read, so that formulas evaluated at the moment are
+    /// not memoized.
+    pub(crate) fn mark_uncached_read(&mut self) {
+        self.uncached_read = true;
+    }
+
+    /// Reads all cacheable registers with as few transactions as possible and caches them.
+    ///
+    /// Registers that fail to be read are skipped, they are read on demand as usual.
+    pub fn warm_up_cache(&mut self, device: &mut impl Device, store: &impl store::NodeStore)
+    where
+        T: store::ValueStore,
+        U: store::CacheStore,
+    {
+        prefetch::warm_up_cache(device, store, self)
+    }
+
+    /// Reads cacheable registers which the features of `category` depend on with as few
+    /// transactions as possible and caches them. Features of nested categories are also covered.
+    ///
+    /// This is useful to read a whole feature page at once, e.g. when a UI shows the category.
+    /// Registers that fail to be read are skipped, they are read on demand as usual.
+    pub fn warm_up_category(
+        &mut self,
+        device: &mut impl Device,
+        store: &impl store::NodeStore,
+        category: store::NodeId,
+    ) where
+        T: store::ValueStore,
+        U: store::CacheStore,
+    {
+        let nids = dependency::DependencyGraph::new(store).transitive_dependencies(category);
+        prefetch::warm_up_registers(device, store, self, nids)
+    }
 }
@@ -0,0 +1,73 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use tracing::debug;
+
+use crate::{
+    builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
+    AdvFeatureLockNode,
+};
+
+use super::{
+    elem_name::{ADV_FEATURE_LOCK, FEATURE_ID, TIMEOUT},
+    elem_type::convert_to_hex,
+    xml, Parse, ParseResult,
+};
+
+impl Parse for AdvFeatureLockNode {
+    #[tracing::instrument(level = "trace", skip(node_builder, value_builder, cache_builder))]
+    fn parse(
+        node: &mut xml::Node,
+        node_builder: &mut impl NodeStoreBuilder,
+        value_builder: &mut impl ValueStoreBuilder,
+        cache_builder: &mut impl CacheStoreBuilder,
+    ) -> ParseResult<Self> {
+        debug!("start parsing `AdvFeatureLockNode`");
+        debug_assert_eq!(node.tag_name(), ADV_FEATURE_LOCK);
+
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let register_base = node.parse(node_builder, value_builder, cache_builder)?;
+
+        let feature_id_node = node
+            .next_if(FEATURE_ID)
+            .ok_or_else(|| node.missing_element(FEATURE_ID))?;
+        let text = feature_id_node.text();
+        let feature_id = convert_to_hex(&text.view()).ok_or_else(|| text.invalid_value())?;
+        let timeout = node
+            .parse_if(TIMEOUT, node_builder, value_builder, cache_builder)?
+            .unwrap_or_default();
+
+        let node = Self {
+            attr_base,
+            register_base,
+            feature_id,
+            timeout,
+        };
+        node.register_base
+            .store_invalidators(node.attr_base.id, cache_builder);
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::utils::tests::parse_default, *};
+
+    #[test]
+    fn test_adv_feature_lock() {
+        let xml = r#"
+        <AdvFeatureLock Name="TestNode">
+          <Address>0x480</Address>
+          <Length>8</Length>
+          <pPort>Device</pPort>
+          <FeatureID>0x0030533B73C3</FeatureID>
+          <Timeout>1000</Timeout>
+        </AdvFeatureLock>
+        "#;
+
+        let (node, ..): (AdvFeatureLockNode, _, _, _) = parse_default(xml);
+        assert_eq!(node.feature_id(), 0x0030_533B_73C3);
+        assert_eq!(node.timeout(), 1000);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
 
 use super::{
     elem_name::{CATEGORY, P_FEATURE},
-    xml, Parse,
+    xml, Parse, ParseResult,
 };
 
 impl Parse for CategoryNode {
@@ -21,20 +21,20 @@ impl Parse for CategoryNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `CategoryNode`");
         debug_assert_eq!(node.tag_name(), CATEGORY);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
-        let p_features = node.parse_while(P_FEATURE, node_builder, value_builder, cache_builder);
+        let p_features = node.parse_while(P_FEATURE, node_builder, value_builder, cache_builder)?;
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             p_features,
-        }
+        })
     }
 }
 
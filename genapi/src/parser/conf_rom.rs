@@ -0,0 +1,178 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use tracing::debug;
+
+use crate::{
+    builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
+    node_base::NodeAttributeBase,
+    store::{NodeData, NodeId},
+    ConfRomNode, IntKeyNode, TextDescNode,
+};
+
+use super::{
+    elem_name::{CONF_ROM, INT_KEY, KEY, TEXT_DESC, UNIT},
+    elem_type::convert_to_hex,
+    xml, Parse, ParseResult,
+};
+
+impl Parse for ConfRomNode {
+    #[tracing::instrument(level = "trace", skip(node_builder, value_builder, cache_builder))]
+    fn parse(
+        node: &mut xml::Node,
+        node_builder: &mut impl NodeStoreBuilder,
+        value_builder: &mut impl ValueStoreBuilder,
+        cache_builder: &mut impl CacheStoreBuilder,
+    ) -> ParseResult<Self> {
+        debug!("start parsing `ConfRomNode`");
+        debug_assert_eq!(node.tag_name(), CONF_ROM);
+
+        let attr_base: NodeAttributeBase =
+            node.parse(node_builder, value_builder, cache_builder)?;
+        let register_base = node.parse(node_builder, value_builder, cache_builder)?;
+
+        let unit = node
+            .parse_if(UNIT, node_builder, value_builder, cache_builder)?
+            .unwrap_or_default();
+        let mut entries = vec![];
+        while let Some(mut entry_node) = node.peek() {
+            let entry = match entry_node.tag_name() {
+                INT_KEY => {
+                    let entry = parse_int_key(
+                        &mut entry_node,
+                        attr_base.id,
+                        node_builder,
+                        value_builder,
+                        cache_builder,
+                    )?;
+                    NodeData::IntKey(entry.into())
+                }
+                TEXT_DESC => {
+                    let entry = parse_text_desc(
+                        &mut entry_node,
+                        attr_base.id,
+                        node_builder,
+                        value_builder,
+                        cache_builder,
+                    )?;
+                    NodeData::TextDesc(entry.into())
+                }
+                _ => break,
+            };
+            node.next();
+            let nid = entry.node_base().id();
+            node_builder.store_node(nid, entry);
+            entries.push(nid);
+        }
+
+        let node = Self {
+            attr_base,
+            register_base,
+            unit,
+            entries,
+        };
+        node.register_base
+            .store_invalidators(node.attr_base.id, cache_builder);
+        Ok(node)
+    }
+}
+
+fn parse_int_key(
+    node: &mut xml::Node,
+    conf_rom: NodeId,
+    node_builder: &mut impl NodeStoreBuilder,
+    value_builder: &mut impl ValueStoreBuilder,
+    cache_builder: &mut impl CacheStoreBuilder,
+) -> ParseResult<IntKeyNode> {
+    debug!("start parsing `IntKeyNode`");
+    debug_assert_eq!(node.tag_name(), INT_KEY);
+
+    let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+    let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
+    let key = parse_key(node)?;
+    node.finish()?;
+
+    Ok(IntKeyNode {
+        attr_base,
+        elem_base,
+        key,
+        conf_rom,
+    })
+}
+
+fn parse_text_desc(
+    node: &mut xml::Node,
+    conf_rom: NodeId,
+    node_builder: &mut impl NodeStoreBuilder,
+    value_builder: &mut impl ValueStoreBuilder,
+    cache_builder: &mut impl CacheStoreBuilder,
+) -> ParseResult<TextDescNode> {
+    debug!("start parsing `TextDescNode`");
+    debug_assert_eq!(node.tag_name(), TEXT_DESC);
+
+    let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+    let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
+    let key = parse_key(node)?;
+    node.finish()?;
+
+    Ok(TextDescNode {
+        attr_base,
+        elem_base,
+        key,
+        conf_rom,
+    })
+}
+
+fn parse_key(node: &mut xml::Node) -> ParseResult<i64> {
+    let key_node = node.next_if(KEY).ok_or_else(|| node.missing_element(KEY))?;
+    let text = key_node.text();
+    convert_to_hex(&text.view()).ok_or_else(|| text.invalid_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{interface::INode, store::NodeStore};
+
+    use super::{super::utils::tests::parse_default, *};
+
+    #[test]
+    fn test_conf_rom() {
+        let xml = r#"
+        <ConfRom Name="TestNode">
+          <Address>0x400</Address>
+          <Length>0x400</Length>
+          <pPort>Device</pPort>
+          <Unit>1</Unit>
+          <IntKey Name="VendorID">
+            <Key>0x03</Key>
+          </IntKey>
+          <TextDesc Name="VendorName">
+            <ToolTip>Name of the vendor</ToolTip>
+            <Key>03</Key>
+          </TextDesc>
+        </ConfRom>
+        "#;
+
+        let (node, mut node_builder, ..): (ConfRomNode, _, _, _) = parse_default(xml);
+        assert_eq!(node.unit_elem(), 1);
+        assert_eq!(node.entries().len(), 2);
+
+        let int_key_id = node_builder.get_or_intern("VendorID");
+        let int_key = match node_builder.node(int_key_id) {
+            NodeData::IntKey(n) => n,
+            _ => panic!(),
+        };
+        assert_eq!(int_key.key(), 0x03);
+        assert_eq!(int_key.conf_rom(), node.node_base().id());
+
+        let text_desc_id = node_builder.get_or_intern("VendorName");
+        let text_desc = match node_builder.node(text_desc_id) {
+            NodeData::TextDesc(n) => n,
+            _ => panic!(),
+        };
+        assert_eq!(text_desc.key(), 0x03);
+        assert_eq!(text_desc.node_base().tooltip(), Some("Name of the vendor"));
+        assert_eq!(node.entries(), &[int_key_id, text_desc_id]);
+    }
+}
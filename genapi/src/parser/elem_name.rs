@@ -53,8 +53,12 @@ pub(super) const MAX: &str = "Max";
 pub(super) const P_MAX: &str = "pMax";
 pub(super) const INC: &str = "Inc";
 pub(super) const P_INC: &str = "pInc";
+pub(super) const VALID_VALUE_SET: &str = "ValidValueSet";
 pub(super) const CONSTANT: &str = "Constant";
 pub(super) const EXPRESSION: &str = "Expression";
+pub(super) const FORMULA: &str = "Formula";
+pub(super) const FORMULA_TO: &str = "FormulaTo";
+pub(super) const FORMULA_FROM: &str = "FormulaFrom";
 pub(super) const SIGN: &str = "Sign";
 pub(super) const UNIT: &str = "Unit";
 pub(super) const REPRESENTATION: &str = "Representation";
@@ -81,12 +85,18 @@ pub(super) const P_VALUE_COPY: &str = "pValueCopy";
 pub(super) const VALUE_INDEXED: &str = "ValueIndexed";
 pub(super) const P_VALUE_INDEXED: &str = "pValueIndexed";
 pub(super) const BIT: &str = "Bit";
+pub(super) const LSB: &str = "LSB";
+pub(super) const MSB: &str = "MSB";
 pub(super) const SLOPE: &str = "Slope";
 pub(super) const IS_LINEAR: &str = "IsLinear";
 pub(super) const CHUNK_ID: &str = "ChunkID";
 pub(super) const P_CHUNK_ID: &str = "pChunkID";
 pub(super) const SWAP_ENDIANNESS: &str = "SwapEndianess"; // Schema typos "Endianness" to "Endianess".
 pub(super) const CACHE_CHUNK_DATA: &str = "CacheChunkData";
+pub(super) const KEY: &str = "Key";
+pub(super) const FEATURE_ID: &str = "FeatureID";
+pub(super) const TIMEOUT: &str = "Timeout";
+pub(super) const P_ADV_FEATURE_LOCK: &str = "pAdvFeatureLock";
 
 pub(super) const NAME: &str = "Name";
 pub(super) const NAME_SPACE: &str = "NameSpace";
@@ -19,10 +19,10 @@ use crate::{
 
 use super::{
     elem_name::{
-        ADDRESS, BIT, INDEX, INT_SWISS_KNIFE, NAME, OFFSET, P_ADDRESS, P_INDEX, P_OFFSET, P_VALUE,
-        P_VALUE_COPY, P_VALUE_INDEXED, VALUE, VALUE_INDEXED,
+        ADDRESS, BIT, INDEX, INT_SWISS_KNIFE, LSB, MSB, NAME, OFFSET, P_ADDRESS, P_INDEX, P_OFFSET,
+        P_VALUE, P_VALUE_COPY, P_VALUE_INDEXED, VALID_VALUE_SET, VALUE, VALUE_INDEXED,
     },
-    xml, Parse,
+    xml, Parse, ParseResult,
 };
 
 macro_rules! match_text_view{
@@ -31,11 +31,11 @@ macro_rules! match_text_view{
         $($s:expr => $var:expr,)*
     ) => {
         if $text == $s1 {
-            $var1
+            Ok($var1)
         } $(else if $text == $s {
-            $var
+            Ok($var)
         })* else {
-            unreachable!()
+            Err($text.invalid_value())
         }
     }
 }
@@ -46,13 +46,11 @@ impl Default for NameSpace {
     }
 }
 
-impl From<&str> for NameSpace {
-    fn from(value: &str) -> Self {
-        match value {
-            "Standard" => Self::Standard,
-            "Custom" => Self::Custom,
-            _ => unreachable!(),
-        }
+pub(super) fn convert_to_name_space(value: &str) -> Option<NameSpace> {
+    match value {
+        "Standard" => Some(NameSpace::Standard),
+        "Custom" => Some(NameSpace::Custom),
+        _ => None,
     }
 }
 
@@ -62,8 +60,8 @@ impl Parse for NameSpace {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view!(text,
             "Standard" => Self::Standard,
             "Custom" => Self::Custom,
@@ -83,8 +81,8 @@ impl Parse for Visibility {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view!(text,
             "Beginner" => Self::Beginner,
             "Expert" => Self::Expert,
@@ -94,14 +92,12 @@ impl Parse for Visibility {
     }
 }
 
-impl From<&str> for MergePriority {
-    fn from(value: &str) -> Self {
-        match value {
-            "1" => Self::High,
-            "0" => Self::Mid,
-            "-1" => Self::Low,
-            _ => unreachable!(),
-        }
+pub(super) fn convert_to_merge_priority(value: &str) -> Option<MergePriority> {
+    match value {
+        "1" => Some(MergePriority::High),
+        "0" => Some(MergePriority::Mid),
+        "-1" => Some(MergePriority::Low),
+        _ => None,
     }
 }
 
@@ -117,8 +113,8 @@ impl Parse for MergePriority {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view!(text,
             "1" => Self::High,
             "0" => Self::Mid,
@@ -133,8 +129,8 @@ impl Parse for AccessMode {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view!(text,
             "RO" => Self::RO,
             "WO" => Self::WO,
@@ -149,12 +145,20 @@ impl Parse for ImmOrPNode<i64> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let peeked_text = node.peek().unwrap().text();
-        if peeked_text.view().chars().next().unwrap().is_alphabetic() {
-            Self::PNode(node.parse(node_builder, value_builder, cache_builder))
+    ) -> ParseResult<Self> {
+        let peeked_text = node.peek_required(VALUE)?.text();
+        if peeked_text.view().starts_with(char::is_alphabetic) {
+            Ok(Self::PNode(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         } else {
-            Self::Imm(node.parse(node_builder, value_builder, cache_builder))
+            Ok(Self::Imm(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         }
     }
 }
@@ -165,17 +169,25 @@ impl Parse for ImmOrPNode<f64> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let peeked_text = node.peek().unwrap().text();
+    ) -> ParseResult<Self> {
+        let peeked_text = node.peek_required(VALUE)?.text();
 
         if peeked_text == "INF"
             || peeked_text == "-INF"
             || peeked_text == "NaN"
-            || !peeked_text.view().chars().next().unwrap().is_alphabetic()
+            || !peeked_text.view().starts_with(char::is_alphabetic)
         {
-            Self::Imm(node.parse(node_builder, value_builder, cache_builder))
+            Ok(Self::Imm(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         } else {
-            Self::PNode(node.parse(node_builder, value_builder, cache_builder))
+            Ok(Self::PNode(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         }
     }
 }
@@ -186,11 +198,19 @@ impl Parse for ImmOrPNode<bool> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        if convert_to_bool_opt(&node.peek().unwrap().text().view()).is_some() {
-            Self::Imm(node.parse(node_builder, value_builder, cache_builder))
+    ) -> ParseResult<Self> {
+        if convert_to_bool(&node.peek_required(VALUE)?.text().view()).is_some() {
+            Ok(Self::Imm(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         } else {
-            Self::PNode(node.parse(node_builder, value_builder, cache_builder))
+            Ok(Self::PNode(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))
         }
     }
 }
@@ -203,16 +223,16 @@ macro_rules! impl_parse_for_imm_or_pnode_id {
                 node_builder: &mut impl NodeStoreBuilder,
                 value_builder: &mut impl ValueStoreBuilder,
                 cache_builder: &mut impl CacheStoreBuilder,
-            ) -> Self {
+            ) -> ParseResult<Self> {
                 let node: ImmOrPNode<$value_ty> =
-                    node.parse(node_builder, value_builder, cache_builder);
-                match node {
+                    node.parse(node_builder, value_builder, cache_builder)?;
+                Ok(match node {
                     ImmOrPNode::Imm(i) => {
                         let id = value_builder.store(i);
                         ImmOrPNode::Imm(id)
                     }
                     ImmOrPNode::PNode(id) => ImmOrPNode::PNode(id),
-                }
+                })
             }
         }
     };
@@ -233,12 +253,12 @@ impl Parse for IntegerRepresentation {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         use IntegerRepresentation::{
             Boolean, HexNumber, IpV4Address, Linear, Logarithmic, MacAddress, PureNumber,
         };
 
-        let value = node.next_text().unwrap();
+        let value = node.next_text()?;
         match_text_view!(value,
             "Linear" => Linear,
             "Logarithmic" => Logarithmic,
@@ -257,8 +277,8 @@ impl Parse for FloatRepresentation {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "Linear" => Self::Linear,
             "Logarithmic" => Self::Logarithmic,
@@ -279,8 +299,8 @@ impl Parse for Slope {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "Increasing" => Self::Increasing,
             "Decreasing" => Self::Decreasing,
@@ -308,8 +328,8 @@ impl Parse for DisplayNotation {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "Automatic" => Self::Automatic,
             "Fixed" => Self::Fixed,
@@ -318,16 +338,14 @@ impl Parse for DisplayNotation {
     }
 }
 
-impl From<&str> for StandardNameSpace {
-    fn from(value: &str) -> Self {
-        match value {
-            "None" => Self::None,
-            "IIDC" => Self::IIDC,
-            "GEV" => Self::GEV,
-            "CL" => Self::CL,
-            "USB" => Self::USB,
-            _ => unreachable!(),
-        }
+pub(super) fn convert_to_standard_name_space(value: &str) -> Option<StandardNameSpace> {
+    match value {
+        "None" => Some(StandardNameSpace::None),
+        "IIDC" => Some(StandardNameSpace::IIDC),
+        "GEV" => Some(StandardNameSpace::GEV),
+        "CL" => Some(StandardNameSpace::CL),
+        "USB" => Some(StandardNameSpace::USB),
+        _ => None,
     }
 }
 
@@ -343,8 +361,8 @@ impl Parse for CachingMode {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "WriteThrough" => Self::WriteThrough,
             "WriteAround" => Self::WriteAround,
@@ -362,21 +380,17 @@ where
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let name = node.peek().unwrap().attribute_of(NAME).unwrap().into();
-        let value = node.parse(node_builder, value_builder, cache_builder);
-        Self { name, value }
+    ) -> ParseResult<Self> {
+        let name = node
+            .peek_required(VALUE)?
+            .required_attribute_of(NAME)?
+            .into();
+        let value = node.parse(node_builder, value_builder, cache_builder)?;
+        Ok(Self { name, value })
     }
 }
 
-pub(super) fn convert_to_bool(value: &str) -> bool {
-    match convert_to_bool_opt(value) {
-        Some(b) => b,
-        _ => unreachable!(),
-    }
-}
-
-pub(super) fn convert_to_bool_opt(value: &str) -> Option<bool> {
+pub(super) fn convert_to_bool(value: &str) -> Option<bool> {
     match value {
         "Yes" | "true" => Some(true),
         "No" | "false" => Some(false),
@@ -390,25 +404,34 @@ impl Parse for bool {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
-        convert_to_bool(&text.view())
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
+        convert_to_bool(&text.view()).ok_or_else(|| text.invalid_value())
     }
 }
 
-pub(super) fn convert_to_int(value: &str) -> i64 {
+pub(super) fn convert_to_int(value: &str) -> Option<i64> {
     if value.starts_with("0x") || value.starts_with("0X") {
-        i64::from_str_radix(&value[2..], 16).unwrap()
+        i64::from_str_radix(&value[2..], 16).ok()
     } else {
-        value.parse().unwrap()
+        value.parse().ok()
     }
 }
 
-pub(super) fn convert_to_uint(value: &str) -> u64 {
+/// Convert a hexadecimal number which may be prefixed with `0x`.
+pub(super) fn convert_to_hex(value: &str) -> Option<i64> {
+    let value = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    i64::from_str_radix(value, 16).ok()
+}
+
+pub(super) fn convert_to_uint(value: &str) -> Option<u64> {
     if value.starts_with("0x") || value.starts_with("0X") {
-        u64::from_str_radix(&value[2..], 16).unwrap()
+        u64::from_str_radix(&value[2..], 16).ok()
     } else {
-        value.parse().unwrap()
+        value.parse().ok()
     }
 }
 
@@ -418,9 +441,9 @@ impl Parse for i64 {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let value = node.next_text().unwrap();
-        convert_to_int(&value.view())
+    ) -> ParseResult<Self> {
+        let value = node.next_text()?;
+        convert_to_int(&value.view()).ok_or_else(|| value.invalid_value())
     }
 }
 
@@ -430,9 +453,17 @@ impl Parse for u64 {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let value = node.next_text().unwrap();
-        convert_to_uint(&value.view())
+    ) -> ParseResult<Self> {
+        let value = node.next_text()?;
+        convert_to_uint(&value.view()).ok_or_else(|| value.invalid_value())
+    }
+}
+
+pub(super) fn convert_to_float(value: &str) -> Option<f64> {
+    match value {
+        "INF" => Some(f64::INFINITY),
+        "-INF" => Some(f64::NEG_INFINITY),
+        _ => value.parse().ok(),
     }
 }
 
@@ -442,15 +473,28 @@ impl Parse for f64 {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let value = node.next_text().unwrap().view();
-        if value == "INF" {
-            f64::INFINITY
-        } else if value == "-INF" {
-            f64::NEG_INFINITY
-        } else {
-            value.parse().unwrap()
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
+        convert_to_float(&text.view()).ok_or_else(|| text.invalid_value())
+    }
+}
+
+/// Parse the optional `ValidValueSet` element, a list of values separated by `;`.
+pub(super) fn parse_valid_value_set<T>(
+    node: &mut xml::Node,
+    convert: impl Fn(&str) -> Option<T>,
+) -> ParseResult<Vec<T>> {
+    match node.next_if(VALID_VALUE_SET) {
+        Some(elem) => {
+            let text = elem.text();
+            let view = text.view();
+            view.split(';')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| convert(value).ok_or_else(|| text.invalid_value()))
+                .collect()
         }
+        None => Ok(vec![]),
     }
 }
 
@@ -460,8 +504,8 @@ impl Parse for String {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        node.next_text().unwrap().view().into()
+    ) -> ParseResult<Self> {
+        Ok(node.next_text()?.view().into())
     }
 }
 
@@ -471,9 +515,9 @@ impl Parse for NodeId {
         node_builder: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
-        node_builder.get_or_intern(&text.view())
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
+        Ok(node_builder.get_or_intern(&text.view()))
     }
 }
 
@@ -485,10 +529,9 @@ macro_rules! impl_parse_for_value_id {
                 node_builder: &mut impl NodeStoreBuilder,
                 value_builder: &mut impl ValueStoreBuilder,
                 cache_builder: &mut impl CacheStoreBuilder,
-            ) -> Self {
-                let value: $value_ty = node.parse(node_builder, value_builder, cache_builder);
-                let id = value_builder.store(value);
-                id
+            ) -> ParseResult<Self> {
+                let value: $value_ty = node.parse(node_builder, value_builder, cache_builder)?;
+                Ok(value_builder.store(value))
             }
         }
     };
@@ -507,20 +550,20 @@ where
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let peek = node.peek().unwrap();
-        match peek.tag_name() {
-            VALUE => ValueKind::Value(node.parse(node_builder, value_builder, cache_builder)),
+    ) -> ParseResult<Self> {
+        let peek = node.peek_required(VALUE)?;
+        Ok(match peek.tag_name() {
+            VALUE => ValueKind::Value(node.parse(node_builder, value_builder, cache_builder)?),
             P_VALUE_COPY | P_VALUE => {
-                let p_value = node.parse(node_builder, value_builder, cache_builder);
+                let p_value = node.parse(node_builder, value_builder, cache_builder)?;
                 ValueKind::PValue(p_value)
             }
             P_INDEX => {
-                let p_index = node.parse(node_builder, value_builder, cache_builder);
+                let p_index = node.parse(node_builder, value_builder, cache_builder)?;
                 ValueKind::PIndex(p_index)
             }
-            _ => unreachable!(),
-        }
+            _ => return Err(peek.unknown_element()),
+        })
     }
 }
 
@@ -530,22 +573,22 @@ impl<T> Parse for PValue<T> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         // NOTE: The pValue can be sandwiched between two pValueCopy sequence.
         let mut p_value_copies =
-            node.parse_while(P_VALUE_COPY, node_builder, value_builder, cache_builder);
+            node.parse_while(P_VALUE_COPY, node_builder, value_builder, cache_builder)?;
 
-        let p_value = node.parse(node_builder, value_builder, cache_builder);
+        let p_value = node.parse_required(P_VALUE, node_builder, value_builder, cache_builder)?;
 
         let node_ids: Vec<NodeId> =
-            node.parse_while(P_VALUE_COPY, node_builder, value_builder, cache_builder);
+            node.parse_while(P_VALUE_COPY, node_builder, value_builder, cache_builder)?;
         p_value_copies.extend(node_ids);
 
-        Self {
+        Ok(Self {
             p_value,
             p_value_copies,
             phantom: PhantomData,
-        }
+        })
     }
 }
 
@@ -559,24 +602,26 @@ where
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let p_index = node.parse(node_builder, value_builder, cache_builder);
+    ) -> ParseResult<Self> {
+        let p_index = node.parse_required(P_INDEX, node_builder, value_builder, cache_builder)?;
 
         let mut value_indexed = vec![];
-        while let Some(indexed) = node
-            .parse_if(VALUE_INDEXED, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_VALUE_INDEXED, node_builder, value_builder, cache_builder))
-        {
+        while let Some(indexed) = node.parse_if_any(
+            &[VALUE_INDEXED, P_VALUE_INDEXED],
+            node_builder,
+            value_builder,
+            cache_builder,
+        )? {
             value_indexed.push(indexed);
         }
 
-        let value_default = node.parse(node_builder, value_builder, cache_builder);
+        let value_default = node.parse(node_builder, value_builder, cache_builder)?;
 
-        Self {
+        Ok(Self {
             p_index,
             value_indexed,
             value_default,
-        }
+        })
     }
 }
 
@@ -590,10 +635,11 @@ where
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let index = convert_to_int(node.peek().unwrap().attribute_of(INDEX).unwrap());
-        let indexed = node.parse(node_builder, value_builder, cache_builder);
-        Self { index, indexed }
+    ) -> ParseResult<Self> {
+        let peeked_node = node.peek_required(VALUE_INDEXED)?;
+        let index = peeked_node.convert_required_attribute(INDEX, convert_to_int)?;
+        let indexed = node.parse(node_builder, value_builder, cache_builder)?;
+        Ok(Self { index, indexed })
     }
 }
 
@@ -603,24 +649,23 @@ impl Parse for AddressKind {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let peeked_node = node.peek().unwrap();
-        match peeked_node.tag_name() {
+    ) -> ParseResult<Self> {
+        let mut peeked_node = node.peek_required(ADDRESS)?;
+        Ok(match peeked_node.tag_name() {
             ADDRESS | P_ADDRESS => {
-                Self::Address(node.parse(node_builder, value_builder, cache_builder))
+                Self::Address(node.parse(node_builder, value_builder, cache_builder)?)
             }
             INT_SWISS_KNIFE => {
+                node.next();
                 let swiss_knife: IntSwissKnifeNode =
-                    node.next()
-                        .unwrap()
-                        .parse(node_builder, value_builder, cache_builder);
+                    peeked_node.parse(node_builder, value_builder, cache_builder)?;
                 let id = swiss_knife.node_base().id();
                 node_builder.store_node(id, NodeData::IntSwissKnife(swiss_knife.into()));
                 Self::IntSwissKnife(id)
             }
-            P_INDEX => Self::PIndex(node.parse(node_builder, value_builder, cache_builder)),
-            _ => unreachable!(),
-        }
+            P_INDEX => Self::PIndex(node.parse(node_builder, value_builder, cache_builder)?),
+            _ => return Err(peeked_node.unknown_element()),
+        })
     }
 }
 
@@ -630,20 +675,20 @@ impl Parse for RegPIndex {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let next_node = node.peek().unwrap();
+    ) -> ParseResult<Self> {
+        let next_node = node.peek_required(P_INDEX)?;
 
         let imm_offset = next_node
-            .attribute_of(OFFSET)
-            .map(|s| ImmOrPNode::Imm(convert_to_int(s)));
+            .convert_attribute(OFFSET, convert_to_int)?
+            .map(ImmOrPNode::Imm);
         let pnode_offset = next_node
             .attribute_of(P_OFFSET)
             .map(|s| ImmOrPNode::PNode(node_builder.get_or_intern(s)));
         let offset = imm_offset.xor(pnode_offset);
 
-        let p_index = node.parse(node_builder, value_builder, cache_builder);
+        let p_index = node.parse(node_builder, value_builder, cache_builder)?;
 
-        Self { offset, p_index }
+        Ok(Self { offset, p_index })
     }
 }
 
@@ -659,8 +704,8 @@ impl Parse for Endianness {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "LittleEndian" => Self::LE,
             "BigEndian" => Self::BE,
@@ -680,8 +725,8 @@ impl Parse for Sign {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
         match_text_view! {text,
             "Signed" => Self::Signed,
             "Unsigned" => Self::Unsigned,
@@ -695,15 +740,13 @@ impl Parse for BitMask {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        node.parse_if(BIT, node_builder, value_builder, cache_builder)
-            .map_or_else(
-                || {
-                    let lsb = node.parse(node_builder, value_builder, cache_builder);
-                    let msb = node.parse(node_builder, value_builder, cache_builder);
-                    Self::Range { lsb, msb }
-                },
-                Self::SingleBit,
-            )
+    ) -> ParseResult<Self> {
+        if let Some(bit) = node.parse_if(BIT, node_builder, value_builder, cache_builder)? {
+            Ok(Self::SingleBit(bit))
+        } else {
+            let lsb = node.parse_required(LSB, node_builder, value_builder, cache_builder)?;
+            let msb = node.parse_required(MSB, node_builder, value_builder, cache_builder)?;
+            Ok(Self::Range { lsb, msb })
+        }
     }
 }
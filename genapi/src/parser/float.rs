@@ -15,7 +15,8 @@ use super::{
         DISPLAY_NOTATION, DISPLAY_PRECISION, FLOAT, INC, MAX, MIN, P_INC, P_MAX, P_MIN,
         REPRESENTATION, STREAMABLE, UNIT,
     },
-    xml, Parse,
+    elem_type::{convert_to_float, parse_valid_value_set},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for FloatNode {
@@ -25,40 +26,37 @@ impl Parse for FloatNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `FloatNode`");
         debug_assert_eq!(node.tag_name(), FLOAT);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
         let streamable = node
-            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)
+            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let value_kind = node.parse(node_builder, value_builder, cache_builder);
+        let value_kind = node.parse(node_builder, value_builder, cache_builder)?;
         let min = node
-            .parse_if(MIN, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_MIN, node_builder, value_builder, cache_builder))
+            .parse_if_any(&[MIN, P_MIN], node_builder, value_builder, cache_builder)?
             .unwrap_or_else(|| {
                 let id = value_builder.store(f64::MIN);
                 ImmOrPNode::Imm(id)
             });
         let max = node
-            .parse_if(MAX, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_MAX, node_builder, value_builder, cache_builder))
+            .parse_if_any(&[MAX, P_MAX], node_builder, value_builder, cache_builder)?
             .unwrap_or_else(|| {
                 let id = value_builder.store(f64::MAX);
                 ImmOrPNode::Imm(id)
             });
-        let inc = node
-            .parse_if(INC, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_INC, node_builder, value_builder, cache_builder));
-        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder);
+        let inc = node.parse_if_any(&[INC, P_INC], node_builder, value_builder, cache_builder)?;
+        let valid_value_set = parse_valid_value_set(node, convert_to_float)?;
+        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder)?;
         let representation = node
-            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)
+            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
         let display_notation = node
-            .parse_if(DISPLAY_NOTATION, node_builder, value_builder, cache_builder)
+            .parse_if(DISPLAY_NOTATION, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
         let display_precision = node
             .parse_if(
@@ -66,10 +64,10 @@ impl Parse for FloatNode {
                 node_builder,
                 value_builder,
                 cache_builder,
-            )
+            )?
             .unwrap_or(6);
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             streamable,
@@ -77,11 +75,12 @@ impl Parse for FloatNode {
             min,
             max,
             inc,
+            valid_value_set,
             unit,
             representation,
             display_notation,
             display_precision,
-        }
+        })
     }
 }
 
@@ -129,4 +128,17 @@ mod test {
         assert_eq!(node.display_notation_elem(), DisplayNotation::Fixed);
         assert_eq!(node.display_precision_elem(), 10);
     }
+
+    #[test]
+    fn test_float_node_with_valid_value_set() {
+        let xml = r#"
+            <Float Name = "TestNode">
+                <Value>30.0</Value>
+                <ValidValueSet>15.0;29.97;30</ValidValueSet>
+            </Float>
+            "#;
+
+        let (node, ..): (FloatNode, _, _, _) = parse_default(xml);
+        assert_eq!(node.valid_value_set_elem(), &[15.0, 29.97, 30.0]);
+    }
 }
@@ -4,22 +4,12 @@
 
 use crate::{
     builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
-    formula::{parse, Expr, Formula},
+    elem_type::NamedValue,
+    formula::{parse, CompileError, Expr, Formula},
+    store::NodeId,
 };
 
-use super::{xml, Parse};
-
-impl Parse for Formula {
-    fn parse(
-        node: &mut xml::Node,
-        node_builder: &mut impl NodeStoreBuilder,
-        value_builder: &mut impl ValueStoreBuilder,
-        cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let expr = node.parse(node_builder, value_builder, cache_builder);
-        Formula { expr }
-    }
-}
+use super::{elem_name::EXPRESSION, xml, Parse, ParseError, ParseResult};
 
 impl Parse for Expr {
     fn parse(
@@ -27,8 +17,86 @@ impl Parse for Expr {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let text = node.next_text().unwrap();
-        parse(&text.view())
+    ) -> ParseResult<Self> {
+        let text = node.next_text()?;
+        parse(&text.view()).map_err(|err| text.invalid_formula(err))
     }
 }
+
+/// Parse the mandatory formula element `tag` and compile it.
+///
+/// Slots of the compiled formula are `p_variables` in order followed by `special`, i.e. `TO` or
+/// `FROM` of converters. Returns an error if the formula refers to an undefined variable.
+pub(super) fn parse_formula<T: Copy + Into<Expr>>(
+    node: &mut xml::Node,
+    tag: &'static str,
+    p_variables: &[NamedValue<NodeId>],
+    constants: &[NamedValue<T>],
+    expressions: &[NamedValue<Expr>],
+    special: Option<&'static str>,
+) -> ParseResult<Formula> {
+    let formula_node = node.next_if(tag).ok_or_else(|| node.missing_element(tag))?;
+    let text = formula_node.text();
+    let expr = parse(&text.view()).map_err(|err| text.invalid_formula(err))?;
+
+    let mut slots: Vec<&str> = p_variables.iter().map(NamedValue::name).collect();
+    // A variable of the same name shadows the special variable.
+    if let Some(special) = special.filter(|special| !slots.contains(special)) {
+        slots.push(special);
+    }
+    let definitions: Vec<(&str, Expr)> = constants
+        .iter()
+        .map(|c| (c.name(), c.value().into()))
+        .chain(
+            expressions
+                .iter()
+                .map(|e| (e.name(), e.value_ref().clone())),
+        )
+        .collect();
+
+    Formula::compile(expr, &slots, &definitions).map_err(|err| match err {
+        CompileError::Undefined(var) => text.undefined_variable(&var),
+        CompileError::Cyclic(var) => ParseError::CyclicExpression {
+            node: node.node_name(),
+            var,
+            pos: node.position(),
+        },
+    })
+}
+
+/// Returns an error if `expressions` refer to a variable which isn't in `vars`.
+pub(super) fn check_expressions(
+    node: &xml::Node,
+    expressions: &[NamedValue<Expr>],
+    vars: &[&str],
+) -> ParseResult<()> {
+    let undefined = expressions.iter().find_map(|expr| {
+        expr.value_ref()
+            .variables()
+            .into_iter()
+            .find(|var| !vars.contains(var))
+    });
+    match undefined {
+        Some(var) => Err(ParseError::UndefinedVariable {
+            node: node.node_name(),
+            elem: EXPRESSION.into(),
+            var: var.into(),
+            pos: node.position(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Returns names of variables, constants and expressions which formulas can refer to.
+pub(super) fn declared_variables<'a, T>(
+    p_variables: &'a [NamedValue<NodeId>],
+    constants: &'a [NamedValue<T>],
+    expressions: &'a [NamedValue<Expr>],
+) -> Vec<&'a str> {
+    p_variables
+        .iter()
+        .map(NamedValue::name)
+        .chain(constants.iter().map(NamedValue::name))
+        .chain(expressions.iter().map(NamedValue::name))
+        .collect()
+}
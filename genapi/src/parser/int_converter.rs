@@ -11,9 +11,11 @@ use crate::{
 
 use super::{
     elem_name::{
-        CONSTANT, EXPRESSION, INT_CONVERTER, P_VARIABLE, REPRESENTATION, SLOPE, STREAMABLE, UNIT,
+        CONSTANT, EXPRESSION, FORMULA_FROM, FORMULA_TO, INT_CONVERTER, P_VARIABLE, REPRESENTATION,
+        SLOPE, STREAMABLE, UNIT,
     },
-    xml, Parse,
+    formula::{check_expressions, declared_variables, parse_formula},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for IntConverterNode {
@@ -23,31 +25,50 @@ impl Parse for IntConverterNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `IntConverterNode`");
         debug_assert_eq!(node.tag_name(), INT_CONVERTER);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
         let streamable = node
-            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)
+            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let p_variables = node.parse_while(P_VARIABLE, node_builder, value_builder, cache_builder);
-        let constants = node.parse_while(CONSTANT, node_builder, value_builder, cache_builder);
-        let expressions = node.parse_while(EXPRESSION, node_builder, value_builder, cache_builder);
-        let formula_to = node.parse(node_builder, value_builder, cache_builder);
-        let formula_from = node.parse(node_builder, value_builder, cache_builder);
-        let p_value = node.parse(node_builder, value_builder, cache_builder);
-        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder);
+        let p_variables =
+            node.parse_while(P_VARIABLE, node_builder, value_builder, cache_builder)?;
+        let constants = node.parse_while(CONSTANT, node_builder, value_builder, cache_builder)?;
+        let expressions =
+            node.parse_while(EXPRESSION, node_builder, value_builder, cache_builder)?;
+        let vars = declared_variables(&p_variables, &constants, &expressions);
+        // `FormulaTo` is evaluated with `FROM` and `FormulaFrom` is evaluated with `TO`.
+        check_expressions(node, &expressions, &[&vars[..], &["FROM", "TO"]].concat())?;
+        let formula_to = parse_formula(
+            node,
+            FORMULA_TO,
+            &p_variables,
+            &constants,
+            &expressions,
+            Some("FROM"),
+        )?;
+        let formula_from = parse_formula(
+            node,
+            FORMULA_FROM,
+            &p_variables,
+            &constants,
+            &expressions,
+            Some("TO"),
+        )?;
+        let p_value = node.parse(node_builder, value_builder, cache_builder)?;
+        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder)?;
         let representation = node
-            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)
+            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
         let slope = node
-            .parse_if(SLOPE, node_builder, value_builder, cache_builder)
+            .parse_if(SLOPE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             streamable,
@@ -60,7 +81,7 @@ impl Parse for IntConverterNode {
             unit,
             representation,
             slope,
-        }
+        })
     }
 }
 
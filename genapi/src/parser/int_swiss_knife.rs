@@ -11,9 +11,11 @@ use crate::{
 
 use super::{
     elem_name::{
-        CONSTANT, EXPRESSION, INT_SWISS_KNIFE, P_VARIABLE, REPRESENTATION, STREAMABLE, UNIT,
+        CONSTANT, EXPRESSION, FORMULA, INT_SWISS_KNIFE, P_VARIABLE, REPRESENTATION, STREAMABLE,
+        UNIT,
     },
-    xml, Parse,
+    formula::{check_expressions, declared_variables, parse_formula},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for IntSwissKnifeNode {
@@ -23,26 +25,30 @@ impl Parse for IntSwissKnifeNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `IntSwissKnifeNode`");
         debug_assert_eq!(node.tag_name(), INT_SWISS_KNIFE);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
         let streamable = node
-            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)
+            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let p_variables = node.parse_while(P_VARIABLE, node_builder, value_builder, cache_builder);
-        let constants = node.parse_while(CONSTANT, node_builder, value_builder, cache_builder);
-        let expressions = node.parse_while(EXPRESSION, node_builder, value_builder, cache_builder);
-        let formula = node.parse(node_builder, value_builder, cache_builder);
-        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder);
+        let p_variables =
+            node.parse_while(P_VARIABLE, node_builder, value_builder, cache_builder)?;
+        let constants = node.parse_while(CONSTANT, node_builder, value_builder, cache_builder)?;
+        let expressions =
+            node.parse_while(EXPRESSION, node_builder, value_builder, cache_builder)?;
+        let vars = declared_variables(&p_variables, &constants, &expressions);
+        check_expressions(node, &expressions, &vars)?;
+        let formula = parse_formula(node, FORMULA, &p_variables, &constants, &expressions, None)?;
+        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder)?;
         let representation = node
-            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)
+            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             streamable,
@@ -52,7 +58,7 @@ impl Parse for IntSwissKnifeNode {
             formula,
             unit,
             representation,
-        }
+        })
     }
 }
 
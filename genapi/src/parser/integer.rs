@@ -15,7 +15,8 @@ use super::{
     elem_name::{
         INC, INTEGER, MAX, MIN, P_INC, P_MAX, P_MIN, P_SELECTED, REPRESENTATION, STREAMABLE, UNIT,
     },
-    xml, Parse,
+    elem_type::{convert_to_int, parse_valid_value_set},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for IntegerNode {
@@ -25,33 +26,29 @@ impl Parse for IntegerNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `IntegerNode`");
         debug_assert_eq!(node.tag_name(), INTEGER);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
         let streamable = node
-            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)
+            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let value_kind = node.parse(node_builder, value_builder, cache_builder);
-        let min = node
-            .parse_if(MIN, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_MIN, node_builder, value_builder, cache_builder));
-        let max = node
-            .parse_if(MAX, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_MAX, node_builder, value_builder, cache_builder));
+        let value_kind = node.parse(node_builder, value_builder, cache_builder)?;
+        let min = node.parse_if_any(&[MIN, P_MIN], node_builder, value_builder, cache_builder)?;
+        let max = node.parse_if_any(&[MAX, P_MAX], node_builder, value_builder, cache_builder)?;
         let inc = node
-            .parse_if(INC, node_builder, value_builder, cache_builder)
-            .or_else(|| node.parse_if(P_INC, node_builder, value_builder, cache_builder))
+            .parse_if_any(&[INC, P_INC], node_builder, value_builder, cache_builder)?
             .unwrap_or(ImmOrPNode::Imm(10));
-        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder);
+        let valid_value_set = parse_valid_value_set(node, convert_to_int)?;
+        let unit = node.parse_if(UNIT, node_builder, value_builder, cache_builder)?;
         let representation: IntegerRepresentation = node
-            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)
+            .parse_if(REPRESENTATION, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
         let p_selected: Vec<NodeId> =
-            node.parse_while(P_SELECTED, node_builder, value_builder, cache_builder);
+            node.parse_while(P_SELECTED, node_builder, value_builder, cache_builder)?;
 
         // Deduce min and max value based on representation if not specified.
         let min = min.unwrap_or_else(|| {
@@ -63,7 +60,7 @@ impl Parse for IntegerNode {
             ImmOrPNode::Imm(id)
         });
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             streamable,
@@ -71,10 +68,11 @@ impl Parse for IntegerNode {
             min,
             max,
             inc,
+            valid_value_set,
             unit,
             representation,
             p_selected,
-        }
+        })
     }
 }
 
@@ -129,6 +127,19 @@ mod tests {
         assert_eq!(p_selected[1], node_builder.get_or_intern("Selected1"));
     }
 
+    #[test]
+    fn test_integer_node_with_valid_value_set() {
+        let xml = r#"
+            <Integer Name = "TestNode">
+                <Value>1</Value>
+                <ValidValueSet>1;2; 4;0x8</ValidValueSet>
+            </Integer>
+            "#;
+
+        let (node, ..): (IntegerNode, _, _, _) = parse_default(xml);
+        assert_eq!(node.valid_value_set_elem(), &[1, 2, 4, 8]);
+    }
+
     #[test]
     fn test_integer_node_with_p_value() {
         let xml = r#"
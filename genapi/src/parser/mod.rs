@@ -2,9 +2,11 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+mod adv_feature_lock;
 mod boolean;
 mod category;
 mod command;
+mod conf_rom;
 mod converter;
 mod elem_name;
 mod elem_type;
@@ -24,27 +26,32 @@ mod port;
 mod register;
 mod register_base;
 mod register_description;
+mod smart_feature;
 mod string;
 mod string_reg;
 mod struct_reg;
 mod swiss_knife;
-mod utils;
+pub(crate) mod utils;
 mod xml;
 
+use std::collections::HashSet;
+
 use group::GroupNode;
 use struct_reg::StructRegNode;
 use thiserror::Error;
 
 use crate::{
     builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
+    formula::FormulaError,
     store::NodeData,
     RegisterDescription,
 };
 
 use elem_name::{
-    ADV_FEATURE_LOCK, BOOLEAN, CATEGORY, COMMAND, CONF_ROM, CONVERTER, ENUMERATION, FLOAT,
-    FLOAT_REG, GROUP, INTEGER, INT_CONVERTER, INT_KEY, INT_REG, INT_SWISS_KNIFE, MASKED_INT_REG,
-    NODE, PORT, REGISTER, SMART_FEATURE, STRING, STRING_REG, STRUCT_REG, SWISS_KNIFE, TEXT_DESC,
+    ADV_FEATURE_LOCK, BOOLEAN, CATEGORY, COMMAND, CONF_ROM, CONSTANT, CONVERTER, ENUMERATION,
+    EXPRESSION, FLOAT, FLOAT_REG, GROUP, INTEGER, INT_CONVERTER, INT_REG, INT_SWISS_KNIFE,
+    MASKED_INT_REG, NAME, NODE, PORT, REGISTER, SMART_FEATURE, STRING, STRING_REG, STRUCT_REG,
+    SWISS_KNIFE,
 };
 
 #[derive(Debug, Error)]
@@ -54,6 +61,78 @@ pub enum ParseError {
 
     #[error("invalid XML syntax: {0}")]
     InvalidSyntax(#[from] roxmltree::Error),
+
+    #[error("unknown element `{elem}` in `{node}` at {pos}")]
+    UnknownElement {
+        node: String,
+        elem: String,
+        pos: TextPosition,
+    },
+
+    #[error("missing element `{elem}` in `{node}` at {pos}")]
+    MissingElement {
+        node: String,
+        elem: &'static str,
+        pos: TextPosition,
+    },
+
+    #[error("missing attribute `{attr}` in `{node}` at {pos}")]
+    MissingAttribute {
+        node: String,
+        attr: &'static str,
+        pos: TextPosition,
+    },
+
+    #[error("invalid value `{value}` of `{elem}` in `{node}` at {pos}")]
+    InvalidValue {
+        node: String,
+        elem: String,
+        value: String,
+        pos: TextPosition,
+    },
+
+    #[error("invalid formula `{formula}` in `{node}` at {pos}: {source}")]
+    InvalidFormula {
+        node: String,
+        formula: String,
+        source: FormulaError,
+        pos: TextPosition,
+    },
+
+    #[error("undefined variable `{var}` in `{elem}` of `{node}` at {pos}")]
+    UndefinedVariable {
+        node: String,
+        elem: String,
+        var: String,
+        pos: TextPosition,
+    },
+
+    #[error("expression `{var}` refers to itself in `{node}` at {pos}")]
+    CyclicExpression {
+        node: String,
+        var: String,
+        pos: TextPosition,
+    },
+
+    #[error("unresolved reference to `{target}` in `{node}` at {pos}")]
+    UnresolvedReference {
+        node: String,
+        target: String,
+        pos: TextPosition,
+    },
+}
+
+/// A position in the XML source, both `line` and `column` start from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl std::fmt::Display for TextPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
 }
 
 pub type ParseResult<T> = std::result::Result<T, ParseError>;
@@ -66,25 +145,58 @@ pub fn parse(
 ) -> ParseResult<RegisterDescription> {
     let document = xml::Document::from_str(xml.as_ref())?;
     let mut node = document.root_node();
-    let reg_desc = node.parse(node_builder, value_builder, cache_builder);
+    let reg_desc = node.parse(node_builder, value_builder, cache_builder)?;
     while let Some(ref mut child) = node.next() {
-        let children: Vec<NodeData> = child.parse(node_builder, value_builder, cache_builder);
+        let children: Vec<NodeData> = child.parse(node_builder, value_builder, cache_builder)?;
         for child in children {
             let id = child.node_base().id();
             node_builder.store_node(id, child);
         }
     }
+    check_references(&document)?;
 
     Ok(reg_desc)
 }
 
+/// Verify all `p*` elements refer to nodes defined in the document.
+fn check_references(document: &xml::Document) -> ParseResult<()> {
+    fn is_reference(node: &xml::Node) -> bool {
+        let tag_name = node.tag_name();
+        tag_name.starts_with('p') && tag_name[1..].starts_with(char::is_uppercase)
+    }
+
+    // `Name` attributes of references and formula constants are variable names, not node names.
+    let defined: HashSet<_> = document
+        .elements()
+        .filter(|node| {
+            !is_reference(node) && node.tag_name() != CONSTANT && node.tag_name() != EXPRESSION
+        })
+        .filter_map(|node| node.attribute_of(NAME).map(ToString::to_string))
+        .collect();
+
+    for node in document.elements().filter(is_reference) {
+        let target = node.text().view();
+        if !defined.contains(target.as_ref()) {
+            return Err(ParseError::UnresolvedReference {
+                node: node.node_name(),
+                target: target.into(),
+                pos: node.position(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 trait Parse {
     fn parse(
         node: &mut xml::Node,
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self;
+    ) -> ParseResult<Self>
+    where
+        Self: Sized;
 }
 
 impl Parse for Vec<NodeData> {
@@ -94,112 +206,346 @@ impl Parse for Vec<NodeData> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        match node.tag_name() {
+    ) -> ParseResult<Self> {
+        let nodes = match node.tag_name() {
             NODE => vec![NodeData::Node(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             CATEGORY => vec![NodeData::Category(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             INTEGER => vec![NodeData::Integer(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             INT_REG => vec![NodeData::IntReg(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             MASKED_INT_REG => vec![NodeData::MaskedIntReg(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             BOOLEAN => vec![NodeData::Boolean(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             COMMAND => vec![NodeData::Command(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             ENUMERATION => vec![NodeData::Enumeration(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             FLOAT => vec![NodeData::Float(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             FLOAT_REG => vec![NodeData::FloatReg(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             STRING => vec![NodeData::String(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             STRING_REG => vec![NodeData::StringReg(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             REGISTER => vec![NodeData::Register(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             CONVERTER => vec![NodeData::Converter(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             INT_CONVERTER => vec![NodeData::IntConverter(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             SWISS_KNIFE => vec![NodeData::SwissKnife(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             INT_SWISS_KNIFE => vec![NodeData::IntSwissKnife(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             PORT => vec![NodeData::Port(Box::new(node.parse(
                 node_builder,
                 value_builder,
                 cache_builder,
-            )))],
+            )?))],
             STRUCT_REG => {
-                let node: StructRegNode = node.parse(node_builder, value_builder, cache_builder);
+                let node: StructRegNode = node.parse(node_builder, value_builder, cache_builder)?;
                 node.into_masked_int_regs(cache_builder)
                     .into_iter()
                     .map(|node| NodeData::MaskedIntReg(node.into()))
                     .collect()
             }
             GROUP => {
-                let node: GroupNode = node.parse(node_builder, value_builder, cache_builder);
+                let node: GroupNode = node.parse(node_builder, value_builder, cache_builder)?;
                 node.nodes
             }
-            // TODO: Implement DCAM specific ndoes.
-            CONF_ROM | TEXT_DESC | INT_KEY | ADV_FEATURE_LOCK | SMART_FEATURE => todo!(),
-            _ => unreachable!(),
-        }
+            CONF_ROM => vec![NodeData::ConfRom(Box::new(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))],
+            ADV_FEATURE_LOCK => vec![NodeData::AdvFeatureLock(Box::new(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))],
+            SMART_FEATURE => vec![NodeData::SmartFeature(Box::new(node.parse(
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?))],
+            _ => return Err(node.unknown_element()),
+        };
+
+        // All child elements must be consumed by the node parser.
+        node.finish()?;
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{DefaultCacheStore, DefaultNodeStore, DefaultValueStore};
+
+    use super::*;
+
+    fn parse_xml(nodes: &str) -> ParseResult<RegisterDescription> {
+        let xml = format!(
+            r#"<RegisterDescription
+ ModelName="CameleonModel"
+ VendorName="CameleonVendor"
+ StandardNameSpace="None"
+ SchemaMajorVersion="1"
+ SchemaMinorVersion="1"
+ SchemaSubMinorVersion="0"
+ MajorVersion="1"
+ MinorVersion="2"
+ SubMinorVersion="3"
+ ProductGuid="01234567-0123-0123-0123-0123456789ab"
+ VersionGuid="76543210-3210-3210-3210-ba9876543210">
+<Port Name="Device"></Port>
+{}
+</RegisterDescription>"#,
+            nodes
+        );
+        parse(
+            &xml,
+            &mut DefaultNodeStore::new(),
+            &mut DefaultValueStore::new(),
+            &mut DefaultCacheStore::new(),
+        )
+    }
+
+    #[test]
+    fn test_valid() {
+        let nodes = r#"<IntReg Name="MyIntReg">
+  <Address>0x100</Address>
+  <Length>4</Length>
+  <pPort>Device</pPort>
+</IntReg>"#;
+        assert!(parse_xml(nodes).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_element() {
+        let nodes = r#"<Integer Name="MyInt">
+  <Value>1</Value>
+  <Foo>1</Foo>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnknownElement { ref node, ref elem, pos }
+            if node == "MyInt" && elem == "Foo" && pos == TextPosition { line: 16, column: 3 }
+        ));
+
+        let err = parse_xml("<Foo Name=\"MyFoo\"></Foo>").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnknownElement { ref elem, pos, .. }
+            if elem == "Foo" && pos == TextPosition { line: 14, column: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_missing_element() {
+        let nodes = r#"<MaskedIntReg Name="MyMaskedIntReg">
+  <Address>0x100</Address>
+  <Length>4</Length>
+  <pPort>Device</pPort>
+  <LSB>3</LSB>
+</MaskedIntReg>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::MissingElement { ref node, elem: "MSB", pos }
+            if node == "MyMaskedIntReg" && pos.line == 19
+        ));
+    }
+
+    #[test]
+    fn test_missing_attribute() {
+        let err = parse_xml("<Integer><Value>1</Value></Integer>").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::MissingAttribute { attr: "Name", pos, .. }
+            if pos == TextPosition { line: 14, column: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let nodes = r#"<Integer Name="MyInt">
+  <Value>0xZZ</Value>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::InvalidValue { ref node, ref elem, ref value, pos }
+            if node == "MyInt"
+                && elem == "Value"
+                && value == "0xZZ"
+                && pos == TextPosition { line: 15, column: 3 }
+        ));
+
+        let nodes = r#"<Integer Name="MyInt">
+  <Visibility>Master</Visibility>
+  <Value>1</Value>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::InvalidValue { ref elem, ref value, .. }
+            if elem == "Visibility" && value == "Master"
+        ));
+    }
+
+    #[test]
+    fn test_unresolved_reference() {
+        let nodes = r#"<Integer Name="MyInt">
+  <pValue>MyIntReg</pValue>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnresolvedReference { ref node, ref target, pos }
+            if node == "MyInt" && target == "MyIntReg" && pos == TextPosition { line: 15, column: 3 }
+        ));
+
+        // Variables of formulas must not be treated as nodes.
+        let nodes = r#"<IntSwissKnife Name="MySwissKnife">
+  <pVariable Name="VAR">VAR</pVariable>
+  <Formula>VAR + 1</Formula>
+</IntSwissKnife>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnresolvedReference { ref target, .. } if target == "VAR"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_formula() {
+        let nodes = r#"<IntSwissKnife Name="MySwissKnife">
+  <pVariable Name="VAR">MyInt</pVariable>
+  <Formula>(VAR + 1</Formula>
+</IntSwissKnife>
+<Integer Name="MyInt">
+  <Value>1</Value>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::InvalidFormula { ref node, source: FormulaError::UnexpectedEnd { offset: 8 }, pos, .. }
+            if node == "MySwissKnife" && pos == TextPosition { line: 16, column: 3 }
+        ));
+    }
+
+    #[test]
+    fn test_undefined_variable() {
+        let nodes = r#"<IntSwissKnife Name="MySwissKnife">
+  <pVariable Name="VAR">MyInt</pVariable>
+  <Formula>VAR + VAR2</Formula>
+</IntSwissKnife>
+<Integer Name="MyInt">
+  <Value>1</Value>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UndefinedVariable { ref elem, ref var, pos, .. }
+            if elem == "Formula" && var == "VAR2" && pos == TextPosition { line: 16, column: 3 }
+        ));
+
+        let nodes = r#"<IntConverter Name="MyConverter">
+  <Expression Name="HALF">FROM / 2</Expression>
+  <FormulaTo>HALF</FormulaTo>
+  <FormulaFrom>FROM * 2</FormulaFrom>
+  <pValue>MyInt</pValue>
+</IntConverter>
+<Integer Name="MyInt">
+  <Value>1</Value>
+</Integer>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UndefinedVariable { ref elem, ref var, .. }
+            if elem == "FormulaFrom" && var == "FROM"
+        ));
+
+        let nodes = r#"<SwissKnife Name="MySwissKnife">
+  <Expression Name="EXPR">CONST + 1</Expression>
+  <Formula>EXPR</Formula>
+</SwissKnife>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UndefinedVariable { ref node, ref elem, ref var, .. }
+            if node == "MySwissKnife" && elem == "Expression" && var == "CONST"
+        ));
+    }
+    #[test]
+    fn test_cyclic_expression() {
+        let nodes = r#"<SwissKnife Name="MySwissKnife">
+  <Expression Name="A">B + 1</Expression>
+  <Expression Name="B">A * 2</Expression>
+  <Formula>A</Formula>
+</SwissKnife>"#;
+        let err = parse_xml(nodes).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::CyclicExpression { ref node, ref var, .. }
+            if node == "MySwissKnife" && var == "A"
+        ));
     }
 }
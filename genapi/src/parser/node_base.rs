@@ -15,8 +15,8 @@ use super::{
         P_BLOCK_POLLING, P_CAST_ALIAS, P_ERROR, P_IS_AVAILABLE, P_IS_IMPLEMENTED, P_IS_LOCKED,
         TOOL_TIP, VISIBILITY,
     },
-    elem_type::convert_to_bool,
-    xml, Parse,
+    elem_type::{convert_to_bool, convert_to_merge_priority, convert_to_name_space},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for NodeAttributeBase {
@@ -25,25 +25,23 @@ impl Parse for NodeAttributeBase {
         node_builder: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
-        let name = node.attribute_of(NAME).unwrap();
+    ) -> ParseResult<Self> {
+        let name = node.required_attribute_of(NAME)?;
         let id = node_builder.get_or_intern(&name);
         let name_space = node
-            .attribute_of(NAME_SPACE)
-            .map(|text| text.into())
+            .convert_attribute(NAME_SPACE, convert_to_name_space)?
             .unwrap_or_default();
         let merge_priority = node
-            .attribute_of(MERGE_PRIORITY)
-            .map(|text| text.into())
+            .convert_attribute(MERGE_PRIORITY, convert_to_merge_priority)?
             .unwrap_or_default();
-        let expose_static = node.attribute_of(EXPOSE_STATIC).map(convert_to_bool);
+        let expose_static = node.convert_attribute(EXPOSE_STATIC, convert_to_bool)?;
 
-        Self {
+        Ok(Self {
             id,
             name_space,
             merge_priority,
             expose_static,
-        }
+        })
     }
 }
 
@@ -53,44 +51,50 @@ impl Parse for NodeElementBase {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         // Ignore Extension element.
         let _extension: Option<String> =
-            node.parse_if(EXTENSION, node_builder, value_builder, cache_builder);
+            node.parse_if(EXTENSION, node_builder, value_builder, cache_builder)?;
 
-        let tooltip = node.parse_if(TOOL_TIP, node_builder, value_builder, cache_builder);
-        let description = node.parse_if(DESCRIPTION, node_builder, value_builder, cache_builder);
-        let display_name = node.parse_if(DISPLAY_NAME, node_builder, value_builder, cache_builder);
+        let tooltip = node.parse_if(TOOL_TIP, node_builder, value_builder, cache_builder)?;
+        let description = node.parse_if(DESCRIPTION, node_builder, value_builder, cache_builder)?;
+        let display_name =
+            node.parse_if(DISPLAY_NAME, node_builder, value_builder, cache_builder)?;
         let visibility = node
-            .parse_if(VISIBILITY, node_builder, value_builder, cache_builder)
+            .parse_if(VISIBILITY, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let docu_url = node.parse_if(DOCU_URL, node_builder, value_builder, cache_builder);
+        let docu_url = node.parse_if(DOCU_URL, node_builder, value_builder, cache_builder)?;
         let is_deprecated = node
-            .parse_if(IS_DEPRECATED, node_builder, value_builder, cache_builder)
+            .parse_if(IS_DEPRECATED, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
         let event_id = node
             .next_if(EVENT_ID)
-            .map(|n| u64::from_str_radix(&n.text().view(), 16).unwrap());
+            .map(|n| {
+                let text = n.text();
+                u64::from_str_radix(&text.view(), 16).map_err(|_| text.invalid_value())
+            })
+            .transpose()?;
         let p_is_implemented =
-            node.parse_if(P_IS_IMPLEMENTED, node_builder, value_builder, cache_builder);
+            node.parse_if(P_IS_IMPLEMENTED, node_builder, value_builder, cache_builder)?;
         let p_is_available =
-            node.parse_if(P_IS_AVAILABLE, node_builder, value_builder, cache_builder);
-        let p_is_locked = node.parse_if(P_IS_LOCKED, node_builder, value_builder, cache_builder);
+            node.parse_if(P_IS_AVAILABLE, node_builder, value_builder, cache_builder)?;
+        let p_is_locked = node.parse_if(P_IS_LOCKED, node_builder, value_builder, cache_builder)?;
         let p_block_polling =
-            node.parse_if(P_BLOCK_POLLING, node_builder, value_builder, cache_builder);
+            node.parse_if(P_BLOCK_POLLING, node_builder, value_builder, cache_builder)?;
         let imposed_access_mode = node
             .parse_if(
                 IMPOSED_ACCESS_MODE,
                 node_builder,
                 value_builder,
                 cache_builder,
-            )
+            )?
             .unwrap_or(AccessMode::RW);
-        let p_errors = node.parse_while(P_ERROR, node_builder, value_builder, cache_builder);
-        let p_alias = node.parse_if(P_ALIAS, node_builder, value_builder, cache_builder);
-        let p_cast_alias = node.parse_if(P_CAST_ALIAS, node_builder, value_builder, cache_builder);
+        let p_errors = node.parse_while(P_ERROR, node_builder, value_builder, cache_builder)?;
+        let p_alias = node.parse_if(P_ALIAS, node_builder, value_builder, cache_builder)?;
+        let p_cast_alias =
+            node.parse_if(P_CAST_ALIAS, node_builder, value_builder, cache_builder)?;
 
-        Self {
+        Ok(Self {
             tooltip,
             description,
             display_name,
@@ -106,6 +110,6 @@ impl Parse for NodeElementBase {
             p_errors,
             p_alias,
             p_cast_alias,
-        }
+        })
     }
 }
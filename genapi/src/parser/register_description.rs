@@ -15,8 +15,8 @@ use super::{
         SCHEMA_MAJOR_VERSION, SCHEMA_MINOR_VERSION, SCHEMA_SUB_MINOR_VERSION, STANDARD_NAME_SPCACE,
         SUB_MINOR_VERSION, TOOL_TIP, VENDOR_NAME, VERSION_GUID,
     },
-    elem_type::convert_to_uint,
-    xml, Parse,
+    elem_type::{convert_to_standard_name_space, convert_to_uint},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for RegisterDescription {
@@ -26,27 +26,29 @@ impl Parse for RegisterDescription {
         _: &mut impl NodeStoreBuilder,
         _: &mut impl ValueStoreBuilder,
         _: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `RegisterDescription`");
         debug_assert_eq!(node.tag_name(), REGISTER_DESCRIPTION);
 
-        let model_name = node.attribute_of(MODEL_NAME).unwrap().into();
-        let vendor_name = node.attribute_of(VENDOR_NAME).unwrap().into();
+        let model_name = node.required_attribute_of(MODEL_NAME)?.into();
+        let vendor_name = node.required_attribute_of(VENDOR_NAME)?.into();
         let tooltip = node.attribute_of(TOOL_TIP).map(Into::into);
-        let standard_name_space = node.attribute_of(STANDARD_NAME_SPCACE).unwrap().into();
+        let standard_name_space =
+            node.convert_required_attribute(STANDARD_NAME_SPCACE, convert_to_standard_name_space)?;
         let schema_major_version =
-            convert_to_uint(node.attribute_of(SCHEMA_MAJOR_VERSION).unwrap());
+            node.convert_required_attribute(SCHEMA_MAJOR_VERSION, convert_to_uint)?;
         let schema_minor_version =
-            convert_to_uint(node.attribute_of(SCHEMA_MINOR_VERSION).unwrap());
+            node.convert_required_attribute(SCHEMA_MINOR_VERSION, convert_to_uint)?;
         let schema_subminor_version =
-            convert_to_uint(node.attribute_of(SCHEMA_SUB_MINOR_VERSION).unwrap());
-        let major_version = convert_to_uint(node.attribute_of(MAJOR_VERSION).unwrap());
-        let minor_version = convert_to_uint(node.attribute_of(MINOR_VERSION).unwrap());
-        let subminor_version = convert_to_uint(node.attribute_of(SUB_MINOR_VERSION).unwrap());
-        let product_guid = node.attribute_of(PRODUCT_GUID).unwrap().into();
-        let version_guid = node.attribute_of(VERSION_GUID).unwrap().into();
-
-        Self {
+            node.convert_required_attribute(SCHEMA_SUB_MINOR_VERSION, convert_to_uint)?;
+        let major_version = node.convert_required_attribute(MAJOR_VERSION, convert_to_uint)?;
+        let minor_version = node.convert_required_attribute(MINOR_VERSION, convert_to_uint)?;
+        let subminor_version =
+            node.convert_required_attribute(SUB_MINOR_VERSION, convert_to_uint)?;
+        let product_guid = node.required_attribute_of(PRODUCT_GUID)?.into();
+        let version_guid = node.required_attribute_of(VERSION_GUID)?.into();
+
+        Ok(Self {
             model_name,
             vendor_name,
             tooltip,
@@ -59,7 +61,7 @@ impl Parse for RegisterDescription {
             subminor_version,
             product_guid,
             version_guid,
-        }
+        })
     }
 }
 
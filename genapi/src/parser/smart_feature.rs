@@ -0,0 +1,101 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use tracing::debug;
+
+use crate::{
+    builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
+    SmartFeatureNode,
+};
+
+use super::{
+    elem_name::{FEATURE_ID, P_ADV_FEATURE_LOCK, SMART_FEATURE},
+    xml, Parse, ParseResult,
+};
+
+impl Parse for SmartFeatureNode {
+    #[tracing::instrument(level = "trace", skip(node_builder, value_builder, cache_builder))]
+    fn parse(
+        node: &mut xml::Node,
+        node_builder: &mut impl NodeStoreBuilder,
+        value_builder: &mut impl ValueStoreBuilder,
+        cache_builder: &mut impl CacheStoreBuilder,
+    ) -> ParseResult<Self> {
+        debug!("start parsing `SmartFeatureNode`");
+        debug_assert_eq!(node.tag_name(), SMART_FEATURE);
+
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let register_base = node.parse(node_builder, value_builder, cache_builder)?;
+
+        let feature_id_node = node
+            .next_if(FEATURE_ID)
+            .ok_or_else(|| node.missing_element(FEATURE_ID))?;
+        let text = feature_id_node.text();
+        let feature_id = convert_to_guid(&text.view()).ok_or_else(|| text.invalid_value())?;
+        let p_adv_feature_lock = node.parse_if(
+            P_ADV_FEATURE_LOCK,
+            node_builder,
+            value_builder,
+            cache_builder,
+        )?;
+
+        let node = Self {
+            attr_base,
+            register_base,
+            feature_id,
+            p_adv_feature_lock,
+        };
+        node.register_base
+            .store_invalidators(node.attr_base.id, cache_builder);
+        Ok(node)
+    }
+}
+
+/// Convert a GUID in the form of `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`.
+fn convert_to_guid(value: &str) -> Option<u128> {
+    let value = value.strip_prefix('{')?.strip_suffix('}')?;
+    let groups: Vec<_> = value.split('-').collect();
+    let is_valid = groups.len() == 5
+        && groups
+            .iter()
+            .zip(&[8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == *len);
+    if !is_valid {
+        return None;
+    }
+    u128::from_str_radix(&groups.concat(), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::utils::tests::parse_default, *};
+
+    #[test]
+    fn test_smart_feature() {
+        let xml = r#"
+        <SmartFeature Name="TestNode">
+          <Address>0x1000</Address>
+          <Length>20</Length>
+          <pPort>Device</pPort>
+          <FeatureID>{E1D9D6C2-3F9A-4B5C-8D7E-0123456789AB}</FeatureID>
+          <pAdvFeatureLock>Lock</pAdvFeatureLock>
+        </SmartFeature>
+        "#;
+
+        let (node, mut node_builder, ..): (SmartFeatureNode, _, _, _) = parse_default(xml);
+        assert_eq!(node.feature_id(), 0xE1D9_D6C2_3F9A_4B5C_8D7E_0123_4567_89AB);
+        assert_eq!(
+            node.p_adv_feature_lock(),
+            Some(node_builder.get_or_intern("Lock"))
+        );
+    }
+
+    #[test]
+    fn test_convert_to_guid() {
+        assert!(convert_to_guid("{E1D9D6C2-3F9A-4B5C-8D7E-0123456789AB}").is_some());
+        assert!(convert_to_guid("E1D9D6C2-3F9A-4B5C-8D7E-0123456789AB").is_none());
+        assert!(convert_to_guid("{E1D9D6C2-3F9A4B5C-8D7E-0123456789AB}").is_none());
+        assert!(convert_to_guid("{E1D9D6C2-3F9A-4B5C-8D7E-0123456789AG}").is_none());
+    }
+}
@@ -11,8 +11,8 @@ use crate::{
 };
 
 use super::{
-    elem_name::{STREAMABLE, STRING, VALUE},
-    xml, Parse,
+    elem_name::{P_VALUE, STREAMABLE, STRING, VALUE},
+    xml, Parse, ParseResult,
 };
 
 impl Parse for StringNode {
@@ -22,30 +22,34 @@ impl Parse for StringNode {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Self {
+    ) -> ParseResult<Self> {
         debug!("start parsing `StringNode`");
         debug_assert_eq!(node.tag_name(), STRING);
 
-        let attr_base = node.parse(node_builder, value_builder, cache_builder);
-        let elem_base = node.parse(node_builder, value_builder, cache_builder);
+        let attr_base = node.parse(node_builder, value_builder, cache_builder)?;
+        let elem_base = node.parse(node_builder, value_builder, cache_builder)?;
 
         let streamable = node
-            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)
+            .parse_if(STREAMABLE, node_builder, value_builder, cache_builder)?
             .unwrap_or_default();
-        let value = node.next_if(VALUE).map_or_else(
-            || ImmOrPNode::PNode(node_builder.get_or_intern(&node.next_text().unwrap().view())),
-            |next_node| {
-                let id = value_builder.store(next_node.text().view().into_owned());
-                ImmOrPNode::Imm(id)
-            },
-        );
+        let value = if let Some(next_node) = node.next_if(VALUE) {
+            let id = value_builder.store(next_node.text().view().into_owned());
+            ImmOrPNode::Imm(id)
+        } else {
+            ImmOrPNode::PNode(node.parse_required(
+                P_VALUE,
+                node_builder,
+                value_builder,
+                cache_builder,
+            )?)
+        };
 
-        Self {
+        Ok(Self {
             attr_base,
             elem_base,
             streamable,
             value,
-        }
+        })
     }
 }
 
@@ -3,9 +3,12 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 #[cfg(test)]
-pub(super) mod tests {
+pub(crate) mod tests {
     use super::super::{xml, Parse};
-    use crate::store::{DefaultCacheStore, DefaultNodeStore, DefaultValueStore};
+    use crate::{
+        store::{DefaultCacheStore, DefaultNodeStore, DefaultValueStore},
+        Device,
+    };
 
     pub(in super::super) fn parse_default<T: Parse>(
         xml: &str,
@@ -18,10 +21,66 @@ pub(super) mod tests {
         (
             document
                 .root_node()
-                .parse(&mut node_builder, &mut value_builder, &mut cache_builder),
+                .parse(&mut node_builder, &mut value_builder, &mut cache_builder)
+                .unwrap(),
             node_builder,
             value_builder,
             cache_builder,
         )
     }
+
+    /// Wrap `nodes` in `RegisterDescription` which has `Device` port.
+    pub(crate) fn register_description(nodes: &str) -> String {
+        format!(
+            r#"<RegisterDescription
+                 ModelName="CameleonModel"
+                 VendorName="CameleonVendor"
+                 StandardNameSpace="None"
+                 SchemaMajorVersion="1"
+                 SchemaMinorVersion="1"
+                 SchemaSubMinorVersion="0"
+                 MajorVersion="1"
+                 MinorVersion="0"
+                 SubMinorVersion="0"
+                 ProductGuid="01234567-0123-0123-0123-0123456789ab"
+                 VersionGuid="76543210-3210-3210-3210-ba9876543210">
+                 <Port Name="Device"></Port>
+                 {}
+               </RegisterDescription>"#,
+            nodes
+        )
+    }
+
+    /// A device backed by `memory` which starts at address `0`.
+    pub(crate) struct TestDevice {
+        pub(crate) memory: Vec<u8>,
+    }
+
+    impl TestDevice {
+        pub(crate) fn new(memory: Vec<u8>) -> Self {
+            Self { memory }
+        }
+    }
+
+    impl Device for TestDevice {
+        fn read_mem(
+            &mut self,
+            address: i64,
+            buf: &mut [u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let address = address as usize;
+            buf.copy_from_slice(&self.memory[address..address + buf.len()]);
+            Ok(())
+        }
+
+        fn write_mem(
+            &mut self,
+            address: i64,
+            data: &[u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let address = address as usize;
+            self.memory[address..address + data.len()].copy_from_slice(data);
+            Ok(())
+        }
+    }
 }
@@ -4,9 +4,12 @@
 
 use std::{fmt, iter::Peekable};
 
-use crate::builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder};
+use crate::{
+    builder::{CacheStoreBuilder, NodeStoreBuilder, ValueStoreBuilder},
+    formula::FormulaError,
+};
 
-use super::{Parse, ParseResult};
+use super::{elem_name::NAME, Parse, ParseError, ParseResult, TextPosition};
 
 pub(super) struct Document<'input> {
     document: roxmltree::Document<'input>,
@@ -26,6 +29,16 @@ impl<'input> Document<'input> {
     pub(super) fn inner_str(&self) -> &'input str {
         self.document.input_text()
     }
+
+    /// Returns all elements in the document in document order.
+    pub(super) fn elements(&self) -> impl Iterator<Item = Node<'_, 'input>> {
+        let src = self.inner_str();
+        self.document
+            .root()
+            .descendants()
+            .filter(roxmltree::Node::is_element)
+            .map(move |node| Node::from_xmltree_node(node, src))
+    }
 }
 
 pub(super) struct Node<'a, 'input> {
@@ -41,7 +54,7 @@ impl<'a, 'input> Node<'a, 'input> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> T {
+    ) -> ParseResult<T> {
         T::parse(self, node_builder, value_builder, cache_builder)
     }
 
@@ -51,27 +64,58 @@ impl<'a, 'input> Node<'a, 'input> {
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Option<T> {
-        if self.peek()?.tag_name() == tag_name {
-            Some(self.parse(node_builder, value_builder, cache_builder))
-        } else {
-            None
+    ) -> ParseResult<Option<T>> {
+        match self.peek() {
+            Some(peeked) if peeked.tag_name() == tag_name => self
+                .parse(node_builder, value_builder, cache_builder)
+                .map(Some),
+            _ => Ok(None),
         }
     }
 
+    /// Parse the next element if its tag name is one of `tag_names`.
+    pub(super) fn parse_if_any<T: Parse>(
+        &mut self,
+        tag_names: &[&str],
+        node_builder: &mut impl NodeStoreBuilder,
+        value_builder: &mut impl ValueStoreBuilder,
+        cache_builder: &mut impl CacheStoreBuilder,
+    ) -> ParseResult<Option<T>> {
+        match self.peek() {
+            Some(peeked) if tag_names.contains(&peeked.tag_name()) => self
+                .parse(node_builder, value_builder, cache_builder)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse the next element which is mandatory, returns an error if the tag name of the next
+    /// element isn't `tag_name`.
+    pub(super) fn parse_required<T: Parse>(
+        &mut self,
+        tag_name: &'static str,
+        node_builder: &mut impl NodeStoreBuilder,
+        value_builder: &mut impl ValueStoreBuilder,
+        cache_builder: &mut impl CacheStoreBuilder,
+    ) -> ParseResult<T> {
+        self.parse_if(tag_name, node_builder, value_builder, cache_builder)?
+            .ok_or_else(|| self.missing_element(tag_name))
+    }
+
     pub(super) fn parse_while<T: Parse>(
         &mut self,
         tag_name: &str,
         node_builder: &mut impl NodeStoreBuilder,
         value_builder: &mut impl ValueStoreBuilder,
         cache_builder: &mut impl CacheStoreBuilder,
-    ) -> Vec<T> {
+    ) -> ParseResult<Vec<T>> {
         let mut res = vec![];
-        while let Some(parsed) = self.parse_if(tag_name, node_builder, value_builder, cache_builder)
+        while let Some(parsed) =
+            self.parse_if(tag_name, node_builder, value_builder, cache_builder)?
         {
             res.push(parsed);
         }
-        res
+        Ok(res)
     }
 
     pub(super) fn next(&mut self) -> Option<Self> {
@@ -89,8 +133,25 @@ impl<'a, 'input> Node<'a, 'input> {
         }
     }
 
-    pub(super) fn next_text(&mut self) -> Option<TextView<'a, 'input>> {
-        Some(self.next()?.text())
+    /// Returns the text of the next element, returns an error if there is no element left.
+    pub(super) fn next_text(&mut self) -> ParseResult<TextView<'a, 'input>> {
+        match self.next() {
+            Some(next) => Ok(next.text()),
+            None => Err(self.missing_element("text element")),
+        }
+    }
+
+    /// Returns the next element, returns an error if there is no element left.
+    pub(super) fn peek_required(&mut self, expected: &'static str) -> ParseResult<Self> {
+        self.peek().ok_or_else(|| self.missing_element(expected))
+    }
+
+    /// Returns an error if the element has child elements which are not parsed yet.
+    pub(super) fn finish(&mut self) -> ParseResult<()> {
+        match self.peek() {
+            Some(unknown) => Err(unknown.unknown_element()),
+            None => Ok(()),
+        }
     }
 
     pub(super) fn peek(&mut self) -> Option<Self> {
@@ -115,10 +176,95 @@ impl<'a, 'input> Node<'a, 'input> {
         self.attributes.attribute_of(name)
     }
 
+    /// Returns the value of the mandatory attribute.
+    pub(super) fn required_attribute_of(&self, name: &'static str) -> ParseResult<&str> {
+        self.attribute_of(name)
+            .ok_or_else(|| self.missing_attribute(name))
+    }
+
+    /// Convert the value of the attribute with `f`, returns an error if `f` returns `None`.
+    pub(super) fn convert_attribute<T>(
+        &self,
+        name: &'static str,
+        f: impl FnOnce(&str) -> Option<T>,
+    ) -> ParseResult<Option<T>> {
+        self.attribute_of(name)
+            .map(|value| {
+                f(value).ok_or_else(|| ParseError::InvalidValue {
+                    node: node_name(self.inner),
+                    elem: name.into(),
+                    value: value.into(),
+                    pos: position(self.inner),
+                })
+            })
+            .transpose()
+    }
+
+    /// Convert the value of the mandatory attribute with `f`.
+    pub(super) fn convert_required_attribute<T>(
+        &self,
+        name: &'static str,
+        f: impl FnOnce(&str) -> Option<T>,
+    ) -> ParseResult<T> {
+        self.convert_attribute(name, f)?
+            .ok_or_else(|| self.missing_attribute(name))
+    }
+
     pub(super) fn text(&self) -> TextView<'a, 'input> {
         TextView { inner: self.inner }
     }
 
+    /// Returns the name of the node which the element belongs to.
+    pub(super) fn node_name(&self) -> String {
+        node_name(self.inner)
+    }
+
+    pub(super) fn position(&self) -> TextPosition {
+        position(self.inner)
+    }
+
+    /// Returns an error which indicates the element is unexpected in its parent.
+    pub(super) fn unknown_element(&self) -> ParseError {
+        ParseError::UnknownElement {
+            node: self
+                .inner
+                .parent_element()
+                .map_or_else(|| self.tag_name().into(), |parent| node_name(parent)),
+            elem: self.tag_name().into(),
+            pos: position(self.inner),
+        }
+    }
+
+    /// Returns an error which indicates the mandatory attribute `attr` is missing.
+    pub(super) fn missing_attribute(&self, attr: &'static str) -> ParseError {
+        ParseError::MissingAttribute {
+            node: node_name(self.inner),
+            attr,
+            pos: position(self.inner),
+        }
+    }
+
+    /// Returns an error which indicates the mandatory child element `elem` is missing.
+    pub(super) fn missing_element(&self, elem: &'static str) -> ParseError {
+        // Point to the unexpected element if exists, otherwise point to the end of the element.
+        let pos = match self.children.clone().find(roxmltree::Node::is_element) {
+            Some(next) => position(next),
+            None => {
+                let doc = self.inner.document();
+                let pos = doc.text_pos_at(self.inner.range().end);
+                TextPosition {
+                    line: pos.row,
+                    column: pos.col,
+                }
+            }
+        };
+        ParseError::MissingElement {
+            node: node_name(self.inner),
+            elem,
+            pos,
+        }
+    }
+
     fn from_xmltree_node(node: roxmltree::Node<'a, 'input>, src: &'input str) -> Self {
         debug_assert!(node.node_type() == roxmltree::NodeType::Element);
         let children = node.children().peekable();
@@ -167,7 +313,10 @@ pub(super) struct TextView<'a, 'input> {
 
 impl<'a, 'input> TextView<'a, 'input> {
     pub(super) fn view(&self) -> std::borrow::Cow<'a, str> {
-        let first_child = self.inner.first_child().unwrap();
+        let first_child = match self.inner.first_child() {
+            Some(child) => child,
+            None => return "".into(),
+        };
         if first_child.has_siblings() {
             let mut s = String::new();
             for child in self.inner.children() {
@@ -180,7 +329,38 @@ impl<'a, 'input> TextView<'a, 'input> {
             }
             s.into()
         } else {
-            first_child.text().unwrap().into()
+            first_child.text().unwrap_or_default().into()
+        }
+    }
+
+    /// Returns an error which indicates the text of the element is invalid.
+    pub(super) fn invalid_value(&self) -> ParseError {
+        ParseError::InvalidValue {
+            node: node_name(self.inner),
+            elem: self.inner.tag_name().name().into(),
+            value: self.view().into(),
+            pos: position(self.inner),
+        }
+    }
+
+    /// Returns an error which indicates the text of the element is an invalid formula.
+    pub(super) fn invalid_formula(&self, source: FormulaError) -> ParseError {
+        ParseError::InvalidFormula {
+            node: node_name(self.inner),
+            formula: self.view().into(),
+            source,
+            pos: position(self.inner),
+        }
+    }
+
+    /// Returns an error which indicates the formula of the element refers to an undefined
+    /// variable.
+    pub(super) fn undefined_variable(&self, var: &str) -> ParseError {
+        ParseError::UndefinedVariable {
+            node: node_name(self.inner),
+            elem: self.inner.tag_name().name().into(),
+            var: var.into(),
+            pos: position(self.inner),
         }
     }
 }
@@ -190,3 +370,23 @@ impl<'a, 'input> PartialEq<&str> for TextView<'a, 'input> {
         &self.view() == rhs
     }
 }
+
+/// Returns the name of the nearest node which contains `node`, or the tag name of the root
+/// element if there is no such node.
+fn node_name(node: roxmltree::Node) -> String {
+    node.ancestors()
+        .filter(roxmltree::Node::is_element)
+        .find_map(|node| node.attribute(NAME))
+        .map_or_else(
+            || node.document().root_element().tag_name().name().into(),
+            Into::into,
+        )
+}
+
+fn position(node: roxmltree::Node) -> TextPosition {
+    let pos = node.document().text_pos_at(node.range().start);
+    TextPosition {
+        line: pos.row,
+        column: pos.col,
+    }
+}
@@ -0,0 +1,312 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use tracing::debug;
+
+use super::{
+    elem_type::{AccessMode, CachingMode},
+    interface::INode,
+    register_base::RegisterBase,
+    store::{CacheStore, NodeData, NodeId, NodeStore, ValueStore},
+    Device, ValueCtxt,
+};
+
+/// A register region which is cached by a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Region {
+    nid: NodeId,
+    address: i64,
+    length: i64,
+}
+
+/// Reads all cacheable registers in the store with as few transactions as possible, then caches
+/// the read data.
+///
+/// Registers whose address or length can't be resolved, or whose data can't be read are just
+/// skipped. They will be read on demand as usual.
+pub(super) fn warm_up_cache<T: ValueStore, U: CacheStore>(
+    device: &mut impl Device,
+    store: &impl NodeStore,
+    cx: &mut ValueCtxt<T, U>,
+) {
+    let mut nids = vec![];
+    store.visit_nodes(|data| nids.push(data.node_base().id()));
+    warm_up_registers(device, store, cx, nids);
+}
+
+/// Same as [`warm_up_cache`], but only registers in `nids` are read. Nodes in `nids` which are
+/// not cacheable registers are ignored.
+pub(super) fn warm_up_registers<T: ValueStore, U: CacheStore>(
+    device: &mut impl Device,
+    store: &impl NodeStore,
+    cx: &mut ValueCtxt<T, U>,
+    nids: impl IntoIterator<Item = NodeId>,
+) {
+    let mut regions = vec![];
+    for nid in nids {
+        let reg = match cacheable_register(store.node(nid), store) {
+            Some((_, reg)) => reg,
+            None => continue,
+        };
+        let (address, length) = match (
+            reg.address(device, store, cx),
+            reg.length(device, store, cx),
+        ) {
+            (Ok(address), Ok(length)) if length > 0 => (address, length),
+            _ => continue,
+        };
+        if cx.get_cache(nid, address, length).is_none() {
+            regions.push(Region {
+                nid,
+                address,
+                length,
+            });
+        }
+    }
+    regions.sort_by_key(|r| (r.address, r.length));
+
+    let segments = merge_regions(&regions);
+    let mut bufs: Vec<Vec<u8>> = segments
+        .iter()
+        .map(|(start, end)| vec![0; (end - start) as usize])
+        .collect();
+    let mut is_read = vec![true; segments.len()];
+
+    let mut entries: Vec<(i64, &mut [u8])> = segments
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|((start, _), buf)| (*start, buf.as_mut_slice()))
+        .collect();
+    if let Err(e) = device.read_mem_stacked(&mut entries) {
+        // Some of the segments may not be readable, fall back to reading each segment.
+        debug!(
+            "failed to read registers at once, falls back to reading each segment: {}",
+            e
+        );
+        for ((address, buf), is_read) in entries.iter_mut().zip(is_read.iter_mut()) {
+            *is_read = device.read_mem(*address, buf).is_ok();
+        }
+    }
+
+    let mut seg_idx = 0;
+    for region in regions {
+        while segments[seg_idx].1 <= region.address {
+            seg_idx += 1;
+        }
+        if !is_read[seg_idx] {
+            continue;
+        }
+
+        let offset = (region.address - segments[seg_idx].0) as usize;
+        let data = &bufs[seg_idx][offset..offset + region.length as usize];
+        cx.cache_data(region.nid, region.address, region.length, data);
+    }
+}
+
+fn cacheable_register<'a>(
+    data: &'a NodeData,
+    store: &impl NodeStore,
+) -> Option<(NodeId, &'a RegisterBase)> {
+    let (nid, reg) = match data {
+        NodeData::IntReg(n) => (n.node_base().id(), n.register_base()),
+        NodeData::MaskedIntReg(n) => (n.node_base().id(), n.register_base()),
+        NodeData::FloatReg(n) => (n.node_base().id(), n.register_base()),
+        NodeData::StringReg(n) => (n.node_base().id(), n.register_base()),
+        _ => return None,
+    };
+
+    // Registers on a chunk port can't be read via the device.
+    let is_device_port = matches!(store.node_opt(reg.p_port()), Some(NodeData::Port(port)) if port.chunk_id().is_none());
+    if reg.cacheable() == CachingMode::NoCache
+        || reg.access_mode() == AccessMode::WO
+        || !is_device_port
+    {
+        None
+    } else {
+        Some((nid, reg))
+    }
+}
+
+/// Merges overlapping or adjacent regions into segments, `regions` must be sorted by address.
+fn merge_regions(regions: &[Region]) -> Vec<(i64, i64)> {
+    let mut segments: Vec<(i64, i64)> = vec![];
+    for region in regions {
+        let end = region.address + region.length;
+        match segments.last_mut() {
+            Some(last) if region.address <= last.1 => last.1 = last.1.max(end),
+            _ => segments.push((region.address, end)),
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::{GenApiBuilder, NodeStoreBuilder},
+            interface::IInteger,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    /// Counts reads which are not stacked.
+    struct CountingDevice {
+        inner: TestDevice,
+        read_count: usize,
+        stacked_reads: Vec<Vec<(i64, usize)>>,
+    }
+
+    impl Device for CountingDevice {
+        fn read_mem(
+            &mut self,
+            address: i64,
+            buf: &mut [u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.read_count += 1;
+            self.inner.read_mem(address, buf)
+        }
+
+        fn write_mem(
+            &mut self,
+            address: i64,
+            data: &[u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.write_mem(address, data)
+        }
+
+        fn read_mem_stacked(
+            &mut self,
+            entries: &mut [(i64, &mut [u8])],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.stacked_reads
+                .push(entries.iter().map(|(a, b)| (*a, b.len())).collect());
+            for (address, buf) in entries.iter_mut() {
+                self.inner.read_mem(*address, buf)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn int_reg(name: &str, address: i64, cachable: &str) -> String {
+        format!(
+            r#"<IntReg Name="{}">
+                 <Address>{:#X}</Address>
+                 <Length>4</Length>
+                 <pPort>Device</pPort>
+                 <Cachable>{}</Cachable>
+                 <Endianess>LittleEndian</Endianess>
+               </IntReg>"#,
+            name, address, cachable
+        )
+    }
+
+    #[test]
+    fn test_merge_regions() {
+        let nid = DefaultNodeStore::new().get_or_intern("Node");
+        let region = |address, length| Region {
+            nid,
+            address,
+            length,
+        };
+        let regions = vec![
+            region(0, 4),
+            region(4, 4),
+            region(6, 4),
+            region(16, 8),
+            region(16, 4),
+        ];
+        assert_eq!(merge_regions(&regions), vec![(0, 10), (16, 24)]);
+    }
+
+    #[test]
+    fn test_warm_up_cache() {
+        let xml = register_description(
+            &[
+                int_reg("Reg0", 0x100, "WriteThrough"),
+                int_reg("Reg1", 0x104, "WriteAround"),
+                int_reg("Reg2", 0x200, "WriteThrough"),
+                int_reg("NoCacheReg", 0x108, "NoCache"),
+            ]
+            .join("\n"),
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = CountingDevice {
+            inner: TestDevice::new(vec![0; 0x204]),
+            read_count: 0,
+            stacked_reads: vec![],
+        };
+        device.inner.memory[0x104] = 10;
+        device.inner.memory[0x200] = 20;
+
+        warm_up_cache(&mut device, &store, &mut cx);
+        assert_eq!(device.stacked_reads, vec![vec![(0x100, 8), (0x200, 4)]]);
+        assert_eq!(device.read_count, 0);
+
+        let value = |name: &str, device: &mut CountingDevice, cx: &mut ValueCtxt<_, _>| {
+            store
+                .id_by_name(name)
+                .unwrap()
+                .expect_iinteger_kind(&store)
+                .unwrap()
+                .value(device, &store, cx)
+                .unwrap()
+        };
+        assert_eq!(value("Reg0", &mut device, &mut cx), 0);
+        assert_eq!(value("Reg1", &mut device, &mut cx), 10);
+        assert_eq!(value("Reg2", &mut device, &mut cx), 20);
+        assert_eq!(device.read_count, 0);
+
+        value("NoCacheReg", &mut device, &mut cx);
+        assert_eq!(device.read_count, 1);
+    }
+
+    #[test]
+    fn test_warm_up_category() {
+        let xml = register_description(
+            &[
+                r#"<Category Name="Root">
+                     <pFeature>Page</pFeature>
+                   </Category>
+                   <Category Name="Page">
+                     <pFeature>Value</pFeature>
+                     <pFeature>Reg1</pFeature>
+                   </Category>
+                   <Integer Name="Value">
+                     <pValue>Reg0</pValue>
+                   </Integer>"#
+                    .to_string(),
+                int_reg("Reg0", 0x100, "WriteThrough"),
+                int_reg("Reg1", 0x104, "WriteThrough"),
+                int_reg("OtherReg", 0x200, "WriteThrough"),
+            ]
+            .join("\n"),
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = CountingDevice {
+            inner: TestDevice::new(vec![0; 0x204]),
+            read_count: 0,
+            stacked_reads: vec![],
+        };
+
+        let root = store.id_by_name("Root").unwrap();
+        cx.warm_up_category(&mut device, &store, root);
+        assert_eq!(device.stacked_reads, vec![vec![(0x100, 8)]]);
+        assert_eq!(device.read_count, 0);
+
+        let value = store
+            .id_by_name("Value")
+            .unwrap()
+            .expect_iinteger_kind(&store)
+            .unwrap();
+        value.value(&mut device, &store, &mut cx).unwrap();
+        assert_eq!(device.read_count, 0);
+    }
+}
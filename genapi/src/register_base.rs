@@ -76,7 +76,7 @@ impl RegisterBase {
     ) -> GenApiResult<R> {
         let length = self.length(device, store, cx)?;
         let address = self.address(device, store, cx)?;
-        if let Some(cache) = cx.get_cache(nid, length, address) {
+        if let Some(cache) = cx.get_cache(nid, address, length) {
             f(cache)
         } else {
             let mut buf = vec![0; length as usize];
@@ -104,7 +104,10 @@ impl RegisterBase {
         self.p_port
             .expect_iport_kind(store)?
             .read(address, buf, device, store, cx)?;
-        if self.cacheable != CachingMode::NoCache {
+        if self.cacheable == CachingMode::NoCache {
+            // The register may change at any time, so formulas depending on it can't be memoized.
+            cx.mark_uncached_read();
+        } else {
             cx.cache_data(nid, address, length, buf);
         }
 
@@ -135,6 +138,7 @@ impl RegisterBase {
         if self.cacheable == CachingMode::WriteThrough {
             cx.cache_data(nid, address, length, buf);
         }
+        cx.mark_changed(nid);
         Ok(())
     }
 
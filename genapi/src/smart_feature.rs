@@ -0,0 +1,318 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::convert::TryInto;
+
+use super::{
+    elem_type::IntegerRepresentation,
+    interface::{IBoolean, IInteger, INode, IncrementMode},
+    node_base::{NodeAttributeBase, NodeBase},
+    register_base::RegisterBase,
+    store::{CacheStore, NodeId, NodeStore, ValueStore},
+    Device, GenApiError, GenApiResult, ValueCtxt,
+};
+
+/// Length of the GUID which identifies the smart feature.
+const GUID_LENGTH: usize = 16;
+
+/// A DCAM specific node which inquires the address of a smart feature.
+///
+/// The value is the address of the feature which is returned by the device after writing the
+/// feature ID to the inquiry register. `0` means the device doesn't support the feature.
+/// `AdvFeatureLock` is acquired before the inquiry and released after it.
+#[derive(Debug, Clone)]
+pub struct SmartFeatureNode {
+    pub(crate) attr_base: NodeAttributeBase,
+    pub(crate) register_base: RegisterBase,
+
+    pub(crate) feature_id: u128,
+    pub(crate) p_adv_feature_lock: Option<NodeId>,
+}
+
+impl SmartFeatureNode {
+    #[must_use]
+    pub fn register_base(&self) -> &RegisterBase {
+        &self.register_base
+    }
+
+    /// GUID of the smart feature.
+    #[must_use]
+    pub fn feature_id(&self) -> u128 {
+        self.feature_id
+    }
+
+    /// Returns [`NodeId`] of `AdvFeatureLock` node which is locked during the inquiry.
+    #[must_use]
+    pub fn p_adv_feature_lock(&self) -> Option<NodeId> {
+        self.p_adv_feature_lock
+    }
+
+    /// Write the feature ID to the inquiry register, and then read back the address.
+    fn inquire<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        let nid = self.node_base().id();
+        let reg = self.register_base();
+        let length = reg.length(device, store, cx)?;
+        if (length as usize) < GUID_LENGTH + 4 {
+            return Err(GenApiError::invalid_data(
+                "the register is too short to inquire the smart feature".into(),
+            ));
+        }
+
+        // The inquiry result depends on the written ID, so the cache is never reused.
+        cx.invalidate_cache_by(nid);
+        let mut buf = vec![0; length as usize];
+        buf[..GUID_LENGTH].copy_from_slice(&self.feature_id.to_be_bytes());
+        reg.write_and_cache(nid, &buf, device, store, cx)?;
+
+        let address = reg.address(device, store, cx)?;
+        reg.read_and_cache(nid, address, length, &mut buf, device, store, cx)?;
+        let addr_bytes = buf[buf.len() - 4..].try_into().unwrap();
+        Ok(i64::from(u32::from_be_bytes(addr_bytes)))
+    }
+}
+
+impl INode for SmartFeatureNode {
+    fn node_base(&self) -> NodeBase {
+        let elem_base = &self.register_base.elem_base;
+        NodeBase::new(&self.attr_base, elem_base)
+    }
+
+    fn streamable(&self) -> bool {
+        self.register_base().streamable()
+    }
+}
+
+impl IInteger for SmartFeatureNode {
+    #[tracing::instrument(skip(self, device, store, cx),
+                          level = "trace",
+                          fields(node = store.name_by_id(self.node_base().id()).unwrap()))]
+    fn value<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        let lock = self
+            .p_adv_feature_lock
+            .map(|lock| lock.expect_iboolean_kind(store))
+            .transpose()?;
+        if let Some(lock) = &lock {
+            lock.set_value(true, device, store, cx)?;
+        }
+
+        let res = self.inquire(device, store, cx);
+
+        // Release the lock even if the inquiry fails, the error of the inquiry takes precedence.
+        match lock {
+            Some(lock) => {
+                let released = lock.set_value(false, device, store, cx);
+                res.and_then(|address| released.map(|_| address))
+            }
+            None => res,
+        }
+    }
+
+    fn set_value<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn min<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        Ok(0)
+    }
+
+    fn max<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<i64> {
+        Ok(i64::from(u32::MAX))
+    }
+
+    fn inc_mode(&self, _: &impl NodeStore) -> Option<IncrementMode> {
+        None
+    }
+
+    fn inc<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<Option<i64>> {
+        Ok(None)
+    }
+
+    fn valid_value_set(&self, _: &impl NodeStore) -> &[i64] {
+        &[]
+    }
+
+    fn representation(&self, _: &impl NodeStore) -> IntegerRepresentation {
+        IntegerRepresentation::HexNumber
+    }
+
+    fn unit(&self, _: &impl NodeStore) -> Option<&str> {
+        None
+    }
+
+    fn set_min<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn set_max<T: ValueStore, U: CacheStore>(
+        &self,
+        _: i64,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<()> {
+        Err(GenApiError::not_writable())
+    }
+
+    fn is_readable<T: ValueStore, U: CacheStore>(
+        &self,
+        device: &mut impl Device,
+        store: &impl NodeStore,
+        cx: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        // The inquiry needs to write the feature ID to the register.
+        Ok(self.register_base().is_readable(device, store, cx)?
+            && self.register_base().is_writable(device, store, cx)?)
+    }
+
+    fn is_writable<T: ValueStore, U: CacheStore>(
+        &self,
+        _: &mut impl Device,
+        _: &impl NodeStore,
+        _: &mut ValueCtxt<T, U>,
+    ) -> GenApiResult<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    const FEATURE_ID: u128 = 0xE1D9_D6C2_3F9A_4B5C_8D7E_0123_4567_89AB;
+    const FEATURE_ADDRESS: u32 = 0x0001_0000;
+
+    /// A device which returns `FEATURE_ADDRESS` for the inquiry of `FEATURE_ID`.
+    struct InquiryDevice {
+        inner: TestDevice,
+        locked_on_inquiry: bool,
+    }
+
+    impl Device for InquiryDevice {
+        fn read_mem(
+            &mut self,
+            address: i64,
+            buf: &mut [u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.read_mem(address, buf)
+        }
+
+        fn write_mem(
+            &mut self,
+            address: i64,
+            data: &[u8],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.write_mem(address, data)?;
+            let memory = &mut self.inner.memory;
+            if address == 0x100 {
+                self.locked_on_inquiry = memory[..8] != [0; 8];
+                if data[..GUID_LENGTH] == FEATURE_ID.to_be_bytes() {
+                    memory[0x110..0x114].copy_from_slice(&FEATURE_ADDRESS.to_be_bytes());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_smart_feature() {
+        let xml = register_description(
+            r#"<AdvFeatureLock Name="Lock">
+                   <Address>0</Address>
+                   <Length>8</Length>
+                   <pPort>Device</pPort>
+                   <FeatureID>0x0030533B73C3</FeatureID>
+                   <Timeout>1000</Timeout>
+                 </AdvFeatureLock>
+                 <SmartFeature Name="Feature">
+                   <Address>0x100</Address>
+                   <Length>20</Length>
+                   <pPort>Device</pPort>
+                   <FeatureID>{E1D9D6C2-3F9A-4B5C-8D7E-0123456789AB}</FeatureID>
+                   <pAdvFeatureLock>Lock</pAdvFeatureLock>
+                 </SmartFeature>
+                 <SmartFeature Name="ShortFeature">
+                   <Address>0x100</Address>
+                   <Length>16</Length>
+                   <pPort>Device</pPort>
+                   <FeatureID>{E1D9D6C2-3F9A-4B5C-8D7E-0123456789AB}</FeatureID>
+                   <pAdvFeatureLock>Lock</pAdvFeatureLock>
+                 </SmartFeature>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = InquiryDevice {
+            inner: TestDevice::new(vec![0; 0x200]),
+            locked_on_inquiry: false,
+        };
+
+        let feature = store
+            .id_by_name("Feature")
+            .unwrap()
+            .expect_iinteger_kind(&store)
+            .unwrap();
+        assert_eq!(
+            feature.value(&mut device, &store, &mut cx).unwrap(),
+            i64::from(FEATURE_ADDRESS)
+        );
+        assert_eq!(device.inner.memory[0x100..0x110], FEATURE_ID.to_be_bytes());
+        assert!(device.locked_on_inquiry);
+        // The lock is released after the inquiry.
+        assert_eq!(device.inner.memory[..8], [0; 8]);
+
+        let short_feature = store
+            .id_by_name("ShortFeature")
+            .unwrap()
+            .expect_iinteger_kind(&store)
+            .unwrap();
+        device.inner.memory[..8].copy_from_slice(&[0xff; 8]);
+        assert!(short_feature.value(&mut device, &store, &mut cx).is_err());
+        // The lock is released even if the inquiry fails.
+        assert_eq!(device.inner.memory[..8], [0; 8]);
+    }
+}
@@ -9,15 +9,17 @@ use string_interner::{DefaultBackend, StringInterner, Symbol};
 
 use super::{
     builder,
+    formula::EvaluationResult,
     interface::{
         IBooleanKind, ICategoryKind, ICommandKind, IEnumerationKind, IFloatKind, IIntegerKind,
         INode, INodeKind, IPortKind, IRegisterKind, ISelectorKind, IStringKind,
     },
     node_base::NodeBase,
-    BooleanNode, CategoryNode, CommandNode, ConverterNode, EnumEntryNode, EnumerationNode,
-    FloatNode, FloatRegNode, GenApiError, GenApiResult, IntConverterNode, IntRegNode,
-    IntSwissKnifeNode, IntegerNode, MaskedIntRegNode, Node, PortNode, RegisterNode, StringNode,
-    StringRegNode, SwissKnifeNode,
+    AdvFeatureLockNode, BooleanNode, CategoryNode, CommandNode, ConfRomNode, ConverterNode,
+    EnumEntryNode, EnumerationNode, FloatNode, FloatRegNode, GenApiError, GenApiResult,
+    IntConverterNode, IntKeyNode, IntRegNode, IntSwissKnifeNode, IntegerNode, MaskedIntRegNode,
+    Node, PortNode, RegisterNode, SmartFeatureNode, StringNode, StringRegNode, SwissKnifeNode,
+    TextDescNode,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -44,13 +46,11 @@ pub enum NodeData {
     SwissKnife(Box<SwissKnifeNode>),
     IntSwissKnife(Box<IntSwissKnifeNode>),
     Port(Box<PortNode>),
-
-    // TODO: Implement DCAM specific ndoes.
-    ConfRom(()),
-    TextDesc(()),
-    IntKey(()),
-    AdvFeatureLock(()),
-    SmartFeature(()),
+    ConfRom(Box<ConfRomNode>),
+    TextDesc(Box<TextDescNode>),
+    IntKey(Box<IntKeyNode>),
+    AdvFeatureLock(Box<AdvFeatureLockNode>),
+    SmartFeature(Box<SmartFeatureNode>),
 }
 
 #[auto_impl(&, &mut, Box, Rc, Arc)]
@@ -123,6 +123,26 @@ pub trait CacheStore {
     fn invalidate_of(&mut self, nid: NodeId);
 
     fn clear(&mut self);
+
+    /// Memoizes the evaluation result of the formula of `nid`.
+    ///
+    /// `epoch` is the value of [`Self::epoch`] before the evaluation started, the result must be
+    /// discarded if the epoch has advanced since then.
+    fn memoize(&mut self, _nid: NodeId, _epoch: u64, _result: EvaluationResult) {}
+
+    /// Returns the memoized evaluation result of the formula of `nid` if it's still valid.
+    fn memo(&self, _nid: NodeId) -> Option<EvaluationResult> {
+        None
+    }
+
+    /// Returns the current epoch, which advances whenever a value which formulas depend on may
+    /// have changed.
+    fn epoch(&self) -> u64 {
+        0
+    }
+
+    /// Advances the epoch, which discards all memoized results.
+    fn advance_epoch(&mut self) {}
 }
 
 impl Symbol for NodeId {
@@ -266,10 +286,21 @@ impl NodeId {
         self.as_enum_entry(store)
             .ok_or_else(|| GenApiError::invalid_node("the node doesn't `EnumEntryNode`".into()))
     }
+
+    pub fn as_conf_rom(self, store: &impl NodeStore) -> Option<&ConfRomNode> {
+        match store.node_opt(self)? {
+            NodeData::ConfRom(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn expect_conf_rom(self, store: &impl NodeStore) -> GenApiResult<&ConfRomNode> {
+        self.as_conf_rom(store)
+            .ok_or_else(|| GenApiError::invalid_node("the node doesn't `ConfRomNode`".into()))
+    }
 }
 
 impl NodeData {
-    #[allow(clippy::missing_panics_doc)]
     #[must_use]
     pub fn node_base(&self) -> NodeBase<'_> {
         match self {
@@ -291,7 +322,31 @@ impl NodeData {
             Self::SwissKnife(node) => node.node_base(),
             Self::IntSwissKnife(node) => node.node_base(),
             Self::Port(node) => node.node_base(),
-            _ => todo!(),
+            Self::EnumEntry(node) => node.node_base(),
+            Self::ConfRom(node) => node.node_base(),
+            Self::TextDesc(node) => node.node_base(),
+            Self::IntKey(node) => node.node_base(),
+            Self::AdvFeatureLock(node) => node.node_base(),
+            Self::SmartFeature(node) => node.node_base(),
+        }
+    }
+
+    /// Returns `PollingTime` of the node in milliseconds, the value of the node may change
+    /// without notice and should be re-read at the interval.
+    #[must_use]
+    pub fn polling_time(&self) -> Option<u64> {
+        match self {
+            Self::IntReg(node) => node.register_base().polling_time(),
+            Self::MaskedIntReg(node) => node.register_base().polling_time(),
+            Self::FloatReg(node) => node.register_base().polling_time(),
+            Self::StringReg(node) => node.register_base().polling_time(),
+            Self::Register(node) => node.register_base().polling_time(),
+            Self::ConfRom(node) => node.register_base().polling_time(),
+            Self::AdvFeatureLock(node) => node.register_base().polling_time(),
+            Self::SmartFeature(node) => node.register_base().polling_time(),
+            Self::Command(node) => node.polling_time(),
+            Self::Enumeration(node) => node.polling_time(),
+            _ => None,
         }
     }
 }
@@ -484,6 +539,8 @@ impl ValueStore for DefaultValueStore {
 pub struct DefaultCacheStore {
     store: HashMap<NodeId, HashMap<(i64, i64), Vec<u8>>>,
     invalidators: HashMap<NodeId, Vec<NodeId>>,
+    memo: HashMap<NodeId, EvaluationResult>,
+    epoch: u64,
 }
 
 impl DefaultCacheStore {
@@ -508,19 +565,17 @@ impl builder::CacheStoreBuilder for DefaultCacheStore {
 
 impl CacheStore for DefaultCacheStore {
     fn cache(&mut self, nid: NodeId, address: i64, length: i64, data: &[u8]) {
-        self.store
-            .entry(nid)
-            .and_modify(|level1| {
-                level1
-                    .entry((address, length))
-                    .and_modify(|level2| *level2 = data.to_owned())
-                    .or_insert_with(|| data.to_owned());
-            })
-            .or_insert_with(|| {
-                let mut level1 = HashMap::new();
+        let level1 = self.store.entry(nid).or_default();
+        match level1.get_mut(&(address, length)) {
+            Some(level2) if level2 == data => {}
+            Some(level2) => {
+                *level2 = data.to_owned();
+                self.advance_epoch();
+            }
+            None => {
                 level1.insert((address, length), data.to_owned());
-                level1
-            });
+            }
+        }
     }
 
     fn get_cache(&self, nid: NodeId, address: i64, length: i64) -> Option<&[u8]> {
@@ -535,16 +590,39 @@ impl CacheStore for DefaultCacheStore {
                 }
             }
         }
+        // The value of `nid` itself may have changed even if it invalidates nothing.
+        self.advance_epoch();
     }
 
     fn invalidate_of(&mut self, nid: NodeId) {
         if let Some(cache) = self.store.get_mut(&nid) {
             *cache = HashMap::new();
         }
+        self.advance_epoch();
     }
 
     fn clear(&mut self) {
-        self.store.clear()
+        self.store.clear();
+        self.advance_epoch();
+    }
+
+    fn memoize(&mut self, nid: NodeId, epoch: u64, result: EvaluationResult) {
+        if epoch == self.epoch {
+            self.memo.insert(nid, result);
+        }
+    }
+
+    fn memo(&self, nid: NodeId) -> Option<EvaluationResult> {
+        self.memo.get(&nid).copied()
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn advance_epoch(&mut self) {
+        self.epoch = self.epoch.wrapping_add(1);
+        self.memo.clear();
     }
 }
 
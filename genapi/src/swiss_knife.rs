@@ -89,10 +89,9 @@ impl IFloat for SwissKnifeNode {
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
     ) -> GenApiResult<f64> {
-        let var_env =
-            utils::FormulaEnvCollector::new(&self.p_variables, &self.constants, &self.expressions)
-                .collect(device, store, cx)?;
-        let eval_result = self.formula.eval(&var_env)?;
+        let eval_result = cx.memoized(self.node_base().id(), |cx| {
+            utils::eval_formula(&self.formula, &self.p_variables, None, device, store, cx)
+        })?;
         Ok(eval_result.as_float())
     }
 
@@ -146,6 +145,10 @@ impl IFloat for SwissKnifeNode {
         Ok(None)
     }
 
+    fn valid_value_set(&self, _: &impl NodeStore) -> &[f64] {
+        &[]
+    }
+
     fn representation(&self, _: &impl NodeStore) -> FloatRepresentation {
         self.representation
     }
@@ -209,3 +212,88 @@ impl IFloat for SwissKnifeNode {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            builder::GenApiBuilder,
+            interface::IInteger,
+            parser::utils::tests::{register_description, TestDevice},
+            store::DefaultNodeStore,
+        },
+        *,
+    };
+
+    #[test]
+    fn test_memoized_value() {
+        let xml = register_description(
+            r#"<Integer Name="Factor"><Value>2</Value></Integer>
+                 <IntReg Name="Reg">
+                   <Address>0</Address>
+                   <Length>4</Length>
+                   <pPort>Device</pPort>
+                   <Cachable>WriteThrough</Cachable>
+                   <Endianess>LittleEndian</Endianess>
+                 </IntReg>
+                 <IntReg Name="VolatileReg">
+                   <Address>4</Address>
+                   <Length>4</Length>
+                   <pPort>Device</pPort>
+                   <Cachable>NoCache</Cachable>
+                   <Endianess>LittleEndian</Endianess>
+                 </IntReg>
+                 <SwissKnife Name="Scaled">
+                   <pVariable Name="R">Reg</pVariable>
+                   <pVariable Name="F">Factor</pVariable>
+                   <Formula>R * F</Formula>
+                 </SwissKnife>
+                 <SwissKnife Name="VolatileScaled">
+                   <pVariable Name="R">VolatileReg</pVariable>
+                   <Formula>R * 2</Formula>
+                 </SwissKnife>
+                 <SwissKnife Name="NestedVolatile">
+                   <pVariable Name="V">VolatileScaled</pVariable>
+                   <Formula>V + 1</Formula>
+                 </SwissKnife>"#,
+        );
+        let (_, store, mut cx) = GenApiBuilder::<DefaultNodeStore>::default()
+            .build(&xml)
+            .unwrap();
+        let mut device = TestDevice::new(vec![3, 0, 0, 0, 5, 0, 0, 0]);
+        let nid = |name| store.id_by_name(name).unwrap();
+        let scaled = nid("Scaled").expect_ifloat_kind(&store).unwrap();
+
+        assert!((scaled.value(&mut device, &store, &mut cx).unwrap() - 6.0).abs() < f64::EPSILON);
+        assert!(cx.cache_store.memo(nid("Scaled")).is_some());
+
+        // Writing a variable discards the memoized result.
+        let factor = nid("Factor").expect_iinteger_kind(&store).unwrap();
+        factor.set_value(3, &mut device, &store, &mut cx).unwrap();
+        assert!(cx.cache_store.memo(nid("Scaled")).is_none());
+        assert!((scaled.value(&mut device, &store, &mut cx).unwrap() - 9.0).abs() < f64::EPSILON);
+
+        let reg = nid("Reg").expect_iinteger_kind(&store).unwrap();
+        reg.set_value(4, &mut device, &store, &mut cx).unwrap();
+        assert!((scaled.value(&mut device, &store, &mut cx).unwrap() - 12.0).abs() < f64::EPSILON);
+
+        // Results depending on uncached registers are never memoized.
+        let volatile = nid("VolatileScaled").expect_ifloat_kind(&store).unwrap();
+        assert!(
+            (volatile.value(&mut device, &store, &mut cx).unwrap() - 10.0).abs() < f64::EPSILON
+        );
+        assert!(cx.cache_store.memo(nid("VolatileScaled")).is_none());
+        device.memory[4] = 6;
+        assert!(
+            (volatile.value(&mut device, &store, &mut cx).unwrap() - 12.0).abs() < f64::EPSILON
+        );
+        // Formulas depending on them through other formulas are not memoized either.
+        let nested = nid("NestedVolatile").expect_ifloat_kind(&store).unwrap();
+        assert!((nested.value(&mut device, &store, &mut cx).unwrap() - 13.0).abs() < f64::EPSILON);
+        assert!(cx.cache_store.memo(nid("NestedVolatile")).is_none());
+        device.memory[4] = 7;
+        assert!((nested.value(&mut device, &store, &mut cx).unwrap() - 15.0).abs() < f64::EPSILON);
+        // Reading uncached registers doesn't discard other memoized results.
+        assert!(cx.cache_store.memo(nid("Scaled")).is_some());
+    }
+}
@@ -2,12 +2,11 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::{borrow::Cow, collections::HashMap, convert::TryInto};
+use std::convert::TryInto;
 
 use super::{
     elem_type::{Endianness, NamedValue, Sign},
-    formula::EvaluationResult,
-    formula::Expr,
+    formula::{EvaluationResult, Formula},
     interface::{IBoolean, IEnumeration, IFloat, IInteger},
     store::{CacheStore, NodeId, NodeStore, ValueStore},
     Device, GenApiError, GenApiResult, ValueCtxt,
@@ -115,96 +114,47 @@ pub(super) fn bytes_from_float(
     }
 }
 
-pub(super) struct FormulaEnvCollector<'a, T> {
-    p_variables: &'a [NamedValue<NodeId>],
-    constants: &'a [NamedValue<T>],
-    expressions: &'a [NamedValue<Expr>],
-    var_env: HashMap<&'a str, Cow<'a, Expr>>,
-}
-
-impl<'a, T: Copy + Into<Expr>> FormulaEnvCollector<'a, T> {
-    pub(super) fn new(
-        p_variables: &'a [NamedValue<NodeId>],
-        constants: &'a [NamedValue<T>],
-        expressions: &'a [NamedValue<Expr>],
-    ) -> Self {
-        Self {
-            p_variables,
-            constants,
-            expressions,
-            var_env: HashMap::new(),
-        }
-    }
-    pub(super) fn collect<U: ValueStore, S: CacheStore>(
-        mut self,
-        device: &mut impl Device,
-        store: &impl NodeStore,
-        cx: &mut ValueCtxt<U, S>,
-    ) -> GenApiResult<HashMap<&'a str, Cow<'a, Expr>>> {
-        // Collect variables.
-        self.collect_variables(device, store, cx)?;
-
-        // Collect constatns.
-        for constant in self.constants {
-            let name = constant.name();
-            let value: Expr = (constant.value()).into();
-            self.var_env.insert(name, Cow::Owned(value));
-        }
-
-        // Collect expressions.
-        for expr in self.expressions {
-            let name = expr.name();
-            let value = expr.value_ref();
-            self.var_env.insert(name, Cow::Borrowed(value));
-        }
-
-        Ok(self.var_env)
-    }
-
-    pub(super) fn insert<U: ValueStore, S: CacheStore>(
-        &mut self,
-        name: &'a str,
-        nid: NodeId,
-        device: &mut impl Device,
-        store: &impl NodeStore,
-        cx: &mut ValueCtxt<U, S>,
-    ) -> GenApiResult<()> {
-        let expr = expr_from_nid(nid, device, store, cx)?;
-        self.insert_imm(name, expr);
-        Ok(())
-    }
-
-    pub(super) fn insert_imm(&mut self, name: &'a str, imm: impl Into<Expr>) {
-        self.var_env.insert(name, Cow::Owned(imm.into()));
-    }
-
-    pub(super) fn is_readable<U: ValueStore, S: CacheStore>(
-        &self,
-        device: &mut impl Device,
-        store: &impl NodeStore,
-        cx: &mut ValueCtxt<U, S>,
-    ) -> GenApiResult<bool> {
-        let mut res = true;
-        for variable in self.p_variables {
-            res &= is_nid_readable(variable.value(), device, store, cx)?;
-        }
-        Ok(res)
+/// Evaluates the compiled `formula`, only variables which the formula refers to are read.
+///
+/// `special` is the value of the slot following `p_variables`, i.e. `TO` or `FROM` of converters.
+pub(super) fn eval_formula<T: ValueStore, U: CacheStore>(
+    formula: &Formula,
+    p_variables: &[NamedValue<NodeId>],
+    special: Option<EvaluationResult>,
+    device: &mut impl Device,
+    store: &impl NodeStore,
+    cx: &mut ValueCtxt<T, U>,
+) -> GenApiResult<EvaluationResult> {
+    let mut slots = vec![EvaluationResult::Integer(0); p_variables.len() + 1];
+    for &slot in formula.used_slots() {
+        slots[slot] = if let Some(variable) = p_variables.get(slot) {
+            VariableKind::from_str(variable.name())?.get_value(
+                variable.value(),
+                device,
+                store,
+                cx,
+            )?
+        } else {
+            special.ok_or_else(|| {
+                GenApiError::invalid_node("the formula refers to an unbound variable".into())
+            })?
+        };
     }
+    formula.eval_compiled(&slots)
+}
 
-    fn collect_variables<U: ValueStore, S: CacheStore>(
-        &mut self,
-        device: &mut impl Device,
-        store: &impl NodeStore,
-        cx: &mut ValueCtxt<U, S>,
-    ) -> GenApiResult<()> {
-        for variable in self.p_variables {
-            let name = variable.name();
-            let nid = variable.value();
-            let expr = VariableKind::from_str(name)?.get_value(nid, device, store, cx)?;
-            self.var_env.insert(name, Cow::Owned(expr));
-        }
-        Ok(())
+/// Returns `true` if all `p_variables` are readable.
+pub(super) fn is_variables_readable<T: ValueStore, U: CacheStore>(
+    p_variables: &[NamedValue<NodeId>],
+    device: &mut impl Device,
+    store: &impl NodeStore,
+    cx: &mut ValueCtxt<T, U>,
+) -> GenApiResult<bool> {
+    let mut res = true;
+    for variable in p_variables {
+        res &= is_nid_readable(variable.value(), device, store, cx)?;
     }
+    Ok(res)
 }
 
 #[derive(Debug)]
@@ -239,13 +189,13 @@ impl<'a> VariableKind<'a> {
         device: &mut impl Device,
         store: &impl NodeStore,
         cx: &mut ValueCtxt<T, U>,
-    ) -> GenApiResult<Expr> {
+    ) -> GenApiResult<EvaluationResult> {
         fn error(nid: NodeId, store: &impl NodeStore) -> GenApiError {
             GenApiError::invalid_node(format!("invalid `pVariable: {}`", nid.name(store)).into())
         }
 
-        let expr: Expr = match self {
-            Self::Value => expr_from_nid(nid, device, store, cx)?,
+        let result: EvaluationResult = match self {
+            Self::Value => eval_result_from_nid(nid, device, store, cx)?,
             Self::Min => {
                 if let Some(node) = nid.as_iinteger_kind(store) {
                     node.min(device, store, cx)?.into()
@@ -290,7 +240,7 @@ impl<'a> VariableKind<'a> {
             }
         };
 
-        Ok(expr)
+        Ok(result)
     }
 }
 
@@ -359,12 +309,12 @@ pub(super) fn set_eval_result<T: ValueStore, U: CacheStore>(
     Ok(())
 }
 
-fn expr_from_nid<T: ValueStore, U: CacheStore>(
+pub(super) fn eval_result_from_nid<T: ValueStore, U: CacheStore>(
     nid: NodeId,
     device: &mut impl Device,
     store: &impl NodeStore,
     cx: &mut ValueCtxt<T, U>,
-) -> GenApiResult<Expr> {
+) -> GenApiResult<EvaluationResult> {
     Ok(if let Some(node) = nid.as_iinteger_kind(store) {
         node.value(device, store, cx)?.into()
     } else if let Some(node) = nid.as_ifloat_kind(store) {
@@ -9,7 +9,7 @@ pub(super) mod system;
 
 mod genapi_common;
 
-use cameleon::ControlError;
+use cameleon::{ControlError, DeviceStatus};
 use cameleon_impl::memory::MemoryError;
 
 use super::GenTlError;
@@ -27,7 +27,8 @@ impl From<MemoryError> for GenTlError {
 impl From<ControlError> for GenTlError {
     fn from(err: ControlError) -> Self {
         use GenTlError::{
-            BufferTooSmall, InvalidValue, Io, NotInitialized, ResourceInUse, Timeout,
+            AccessDenied, BufferTooSmall, InvalidAddress, InvalidParameter, InvalidValue, Io,
+            NotImplemented, NotInitialized, ResourceInUse, Timeout,
         };
 
         match err {
@@ -39,6 +40,15 @@ impl From<ControlError> for GenTlError {
             ControlError::InvalidData(..) => InvalidValue(format!("{}", err).into()),
             ControlError::Timeout => Timeout,
             ControlError::BufferTooSmall => BufferTooSmall,
+            ControlError::DeviceStatus(status) => match status {
+                DeviceStatus::NotImplemented => NotImplemented,
+                DeviceStatus::InvalidParameter => InvalidParameter,
+                DeviceStatus::InvalidAddress => InvalidAddress,
+                DeviceStatus::WriteProtect | DeviceStatus::AccessDenied => AccessDenied,
+                DeviceStatus::Busy => ResourceInUse,
+                DeviceStatus::Timeout => Timeout,
+                _ => Io(err.into()),
+            },
         }
     }
 }
@@ -85,6 +85,7 @@ impl RegisterMap {
         let init_memory_protection = self.impl_init_memory_protection()?;
         let base = self.const_base()?;
         let size = self.const_size()?;
+        let fragment = self.fn_fragment()?;
         let impl_register = self.impl_register(&vis_inside_mod);
 
         let impls = quote! {
@@ -97,6 +98,7 @@ impl RegisterMap {
 
             #base
             #size
+            #fragment
             #init_raw_memory
             #init_memory_protection
             #impl_register
@@ -157,6 +159,15 @@ impl RegisterMap {
         })
     }
 
+    fn fn_fragment(&self) -> Result<TokenStream> {
+        let vis = modify_visibility(&self.vis)?;
+        Ok(quote! {
+            #vis fn fragment() -> MemoryFragment {
+                MemoryFragment::from_register_map(base(), size(), init_raw_memory, init_memory_protection)
+            }
+        })
+    }
+
     fn const_base(&self) -> Result<TokenStream> {
         let base = &self.args.base;
         let vis = modify_visibility(&self.vis)?;
@@ -195,6 +195,18 @@ impl MemoryProtection {
             .for_each(|i| self.set_access_right(i, access_right));
     }
 
+    /// Resize the memory size. Access rights of the extended addresses are set to
+    /// [`AccessRight::NA`].
+    pub fn resize(&mut self, memory_size: usize) {
+        let len = if memory_size == 0 {
+            0
+        } else {
+            (memory_size - 1) / 4 + 1
+        };
+        self.inner.resize(len, 0);
+        self.memory_size = memory_size;
+    }
+
     pub fn verify_address(&self, address: usize) -> MemoryResult<()> {
         if self.memory_size <= address {
             Err(MemoryError::InvalidAddress)
@@ -214,6 +226,77 @@ impl MemoryProtection {
     }
 }
 
+/// A contiguous fragment of a memory, which consists of its initial data and access rights.
+///
+/// A fragment is constructed from a raw memory image by [`MemoryFragment::new`], or from
+/// registers defined by [`register_map`] via `fragment()` function of the register map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryFragment {
+    address: usize,
+    data: Vec<u8>,
+    access_rights: Vec<AccessRight>,
+}
+
+impl MemoryFragment {
+    /// Construct a fragment which starts from `address`, all addresses of the fragment have the
+    /// same `access_right`.
+    pub fn new(address: usize, data: impl Into<Vec<u8>>, access_right: AccessRight) -> Self {
+        let data = data.into();
+        let access_rights = vec![access_right; data.len()];
+        Self {
+            address,
+            data,
+            access_rights,
+        }
+    }
+
+    #[doc(hidden)]
+    #[must_use]
+    pub fn from_register_map(
+        base: usize,
+        size: usize,
+        init_raw_memory: fn(&mut [u8]),
+        init_memory_protection: fn(&mut MemoryProtection),
+    ) -> Self {
+        let mut raw = vec![0; base + size];
+        init_raw_memory(&mut raw);
+        let mut protection = MemoryProtection::new(base + size);
+        init_memory_protection(&mut protection);
+
+        Self {
+            address: base,
+            data: raw.split_off(base),
+            access_rights: (base..base + size)
+                .map(|i| protection.access_right(i))
+                .collect(),
+        }
+    }
+
+    /// Start address of the fragment.
+    #[must_use]
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Initial data of the fragment.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Access rights of each address of the fragment.
+    #[must_use]
+    pub fn access_rights(&self) -> &[AccessRight] {
+        &self.access_rights
+    }
+
+    /// Address range of the fragment.
+    #[must_use]
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.address..self.address + self.data.len()
+    }
+}
+
 pub trait Register {
     type Ty;
 
@@ -279,4 +362,26 @@ mod tests {
         assert!(protection.verify_address_with_range(2..5).is_ok());
         assert!(protection.verify_address_with_range(2..6).is_err());
     }
+
+    #[test]
+    fn test_resize() {
+        let mut protection = MemoryProtection::new(5);
+        protection.set_access_right(4, RW);
+
+        protection.resize(10);
+        assert!(protection.verify_address(9).is_ok());
+        assert_eq!(protection.access_right(4), RW);
+        assert_eq!(protection.access_right(9), NA);
+
+        protection.resize(4);
+        assert!(protection.verify_address(4).is_err());
+    }
+
+    #[test]
+    fn test_fragment() {
+        let fragment = MemoryFragment::new(0x10, vec![1, 2, 3], RO);
+        assert_eq!(fragment.range(), 0x10..0x13);
+        assert_eq!(fragment.data(), &[1, 2, 3]);
+        assert_eq!(fragment.access_rights(), &[RO, RO, RO]);
+    }
 }
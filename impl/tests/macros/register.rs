@@ -72,4 +72,13 @@ fn main() {
 
     let addr = ABRM::TestOffset2::ADDRESS;
     assert_eq!(addr, 0x1008);
+
+    let fragment = SBRM::fragment();
+    assert_eq!(fragment.address(), SBRM_ADDRESS as usize);
+    assert_eq!(fragment.data().len(), SBRM::size());
+    assert_eq!(&fragment.data()[..8], b"Cameleon");
+    assert!(fragment
+        .access_rights()
+        .iter()
+        .all(|access_right| *access_right == AccessRight::RW));
 }